@@ -1,9 +1,8 @@
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
-use miniclaw::agent::AgentLoop;
 use miniclaw::agent::tools::ToolRegistry;
+use miniclaw::agent::{AgentLoop, ContextBuilder, ContextBuilderConfig, ContextBuilderImpl};
 use miniclaw::chat::ChatHub;
 use miniclaw::chat::InboundMessage;
 use miniclaw::session::SessionManager;
@@ -36,6 +35,16 @@ impl miniclaw::providers::LlmProvider for MockLlmProvider {
     fn provider_name(&self) -> &'static str {
         "MockLlmProvider"
     }
+
+    async fn list_models(
+        &self,
+    ) -> std::result::Result<Vec<miniclaw::providers::ModelInfo>, miniclaw::providers::ProviderError>
+    {
+        Ok(vec![miniclaw::providers::ModelInfo::new(
+            "benchmark-model",
+            false,
+        )])
+    }
 }
 
 /// Benchmark helper to create a test AgentLoop
@@ -44,17 +53,16 @@ fn create_test_agent_loop() -> AgentLoop {
     let llm_provider: Arc<dyn miniclaw::providers::LlmProvider> = Arc::new(MockLlmProvider);
     let context_builder: Arc<dyn miniclaw::agent::ContextBuilder> = Arc::new(MockContextBuilder);
     let tool_registry = Arc::new(ToolRegistry::new());
-    let session_manager = Arc::new(RwLock::new(SessionManager::new(PathBuf::from(
-        "/tmp/sessions",
-    ))));
+    let session_manager = Arc::new(SessionManager::new(PathBuf::from("/tmp/sessions")));
 
-    AgentLoop::new(
+    AgentLoop::builder(
         chat_hub,
         llm_provider,
         context_builder,
         tool_registry,
         session_manager,
     )
+    .build()
 }
 
 /// Mock context builder for benchmarks
@@ -86,6 +94,7 @@ fn benchmark_message_processing_setup(c: &mut Criterion) {
 
         b.iter(|| {
             // Benchmark the message validation and session lookup setup
+            black_box(&agent);
             black_box(&message);
         });
     });
@@ -127,10 +136,65 @@ fn benchmark_context_build(c: &mut Criterion) {
     });
 }
 
+/// Creates a workspace with the files the context builder reads on every turn
+fn create_bench_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path();
+    for (filename, content) in miniclaw::workspace::templates::WORKSPACE_FILES {
+        std::fs::write(path.join(filename), content).unwrap();
+    }
+
+    let memory_dir = path.join("memory");
+    std::fs::create_dir(&memory_dir).unwrap();
+    let memories = (1..=100)
+        .map(|i| format!("- Benchmark memory entry {}", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(memory_dir.join("MEMORY.md"), memories).unwrap();
+
+    for i in 1..=10 {
+        let skill_dir = path.join("skills").join(format!("skill-{}", i));
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("# Skill {}\nDoes benchmark things", i),
+        )
+        .unwrap();
+    }
+
+    temp_dir
+}
+
+/// Benchmark the workspace context builder with and without the file cache
+fn benchmark_workspace_context_build(c: &mut Criterion) {
+    let workspace = create_bench_workspace();
+    let session = miniclaw::session::Session::new("test".to_string(), "123".to_string());
+    let message = InboundMessage::new("test", "123", "Which memory entry mentions 42?");
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("context_build_workspace");
+    for cached in [false, true] {
+        let config = ContextBuilderConfig {
+            cache_workspace_files: cached,
+            ..Default::default()
+        };
+        let builder = ContextBuilderImpl::with_config(workspace.path(), config).unwrap();
+        let name = if cached { "cached" } else { "uncached" };
+
+        group.bench_function(name, |b| {
+            b.to_async(&rt).iter(|| async {
+                let _ = builder.build_context(&session, &message).await;
+            });
+        });
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     benchmark_message_processing_setup,
     benchmark_session_operations,
-    benchmark_context_build
+    benchmark_context_build,
+    benchmark_workspace_context_build
 );
 criterion_main!(benches);
@@ -1,20 +1,27 @@
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 use crate::agent::metrics::ResponseMetrics;
-use crate::agent::tools::ToolRegistry;
+use crate::agent::tools::{ToolExecutionContext, ToolRegistry};
+use crate::agent::trace::{ToolExecutionTrace, TraceStore, TurnTrace};
 use crate::chat::{ChatHub, InboundMessage};
-use crate::providers::{LlmMessage, LlmProvider, LlmResponse, LlmRole, LlmToolCall};
+use crate::config::AgentConfig;
+pub use crate::config::schema::{MAX_ITERATIONS, MAX_LLM_RETRIES};
+use crate::providers::{LlmMessage, LlmProvider, LlmResponse, LlmRole, LlmToolCall, Tokenizer};
 use crate::session::{Session, SessionManager};
 
-/// Maximum number of iterations before terminating to prevent infinite loops
-pub const MAX_ITERATIONS: u32 = 200;
-
 /// Timeout for LLM API calls in seconds
 pub const LLM_TIMEOUT_SECS: u64 = 30;
 
-/// Maximum number of retries for transient LLM errors
-pub const MAX_LLM_RETRIES: u32 = 3;
+/// Number of identical tool calls (same name and arguments) that triggers a corrective nudge
+pub const REPEATED_TOOL_CALL_THRESHOLD: u32 = 2;
+
+/// Instruction sent with the final tool-less LLM call when max iterations are exhausted
+const MAX_ITERATIONS_SUMMARY_PROMPT: &str = "You have reached the maximum number of tool iterations for this request. \
+     Do not call any more tools. Summarize for the user what you have accomplished so far, \
+     what results the tools produced, and what remains to be done.";
 
 /// Target startup time in milliseconds (NFR-P3)
 pub const TARGET_STARTUP_TIME_MS: u128 = 100;
@@ -75,6 +82,10 @@ pub struct AgentLoopBuilder {
     tool_registry: Arc<ToolRegistry>,
     session_manager: Arc<SessionManager>,
     model: Option<String>,
+    max_iterations: Option<u32>,
+    max_llm_retries: Option<u32>,
+    tool_timeout: Option<Duration>,
+    trace_store: Option<TraceStore>,
     inbound_rx: Option<mpsc::Receiver<InboundMessage>>,
 }
 
@@ -87,6 +98,46 @@ impl AgentLoopBuilder {
         self
     }
 
+    /// Overrides the maximum number of LLM→Tools iterations per message.
+    ///
+    /// Defaults to [`MAX_ITERATIONS`] if not set.
+    pub fn with_max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Overrides the maximum number of retries for a failed LLM call.
+    ///
+    /// Defaults to [`MAX_LLM_RETRIES`] if not set.
+    pub fn with_max_llm_retries(mut self, max_llm_retries: u32) -> Self {
+        self.max_llm_retries = Some(max_llm_retries);
+        self
+    }
+
+    /// Overrides the timeout for a single tool execution.
+    ///
+    /// Defaults to [`DEFAULT_TOOL_TIMEOUT_SECS`](crate::agent::tools::DEFAULT_TOOL_TIMEOUT_SECS) if not set.
+    pub fn with_tool_timeout(mut self, timeout: Duration) -> Self {
+        self.tool_timeout = Some(timeout);
+        self
+    }
+
+    /// Applies the loop limits from the `agent` config section
+    /// (max iterations, LLM retries and tool timeout).
+    pub fn with_agent_config(self, agent_config: &AgentConfig) -> Self {
+        self.with_max_iterations(agent_config.max_iterations)
+            .with_max_llm_retries(agent_config.max_llm_retries)
+            .with_tool_timeout(agent_config.tool_timeout())
+    }
+
+    /// Enables per-turn execution traces, written to the given store.
+    ///
+    /// Traces are not recorded to disk if not set.
+    pub fn with_trace_store(mut self, trace_store: TraceStore) -> Self {
+        self.trace_store = Some(trace_store);
+        self
+    }
+
     /// Sets the inbound message receiver for the agent loop.
     ///
     /// Required when calling [`AgentLoop::run`].
@@ -106,8 +157,13 @@ impl AgentLoopBuilder {
             context_builder: self.context_builder,
             tool_registry: self.tool_registry,
             session_manager: self.session_manager,
-            max_iterations: MAX_ITERATIONS,
+            max_iterations: self.max_iterations.unwrap_or(MAX_ITERATIONS),
+            max_llm_retries: self.max_llm_retries.unwrap_or(MAX_LLM_RETRIES),
+            tool_timeout: self.tool_timeout.unwrap_or(Duration::from_secs(
+                crate::agent::tools::DEFAULT_TOOL_TIMEOUT_SECS,
+            )),
             model,
+            trace_store: self.trace_store,
             response_metrics: Arc::new(ResponseMetrics::new()),
             inbound_rx: Mutex::new(self.inbound_rx),
         }
@@ -122,7 +178,10 @@ pub struct AgentLoop {
     tool_registry: Arc<ToolRegistry>,
     session_manager: Arc<SessionManager>,
     max_iterations: u32,
+    max_llm_retries: u32,
+    tool_timeout: Duration,
     model: String,
+    trace_store: Option<TraceStore>,
     response_metrics: Arc<ResponseMetrics>,
     inbound_rx: Mutex<Option<mpsc::Receiver<InboundMessage>>>,
 }
@@ -151,6 +210,10 @@ impl AgentLoop {
             tool_registry,
             session_manager,
             model: None,
+            max_iterations: None,
+            max_llm_retries: None,
+            tool_timeout: None,
+            trace_store: None,
             inbound_rx: None,
         }
     }
@@ -160,6 +223,16 @@ impl AgentLoop {
         self.max_iterations
     }
 
+    /// Returns the maximum number of retries for a failed LLM call
+    pub fn max_llm_retries(&self) -> u32 {
+        self.max_llm_retries
+    }
+
+    /// Returns the timeout for a single tool execution
+    pub fn tool_timeout(&self) -> Duration {
+        self.tool_timeout
+    }
+
     /// Returns the current model being used
     pub fn model(&self) -> &str {
         &self.model
@@ -173,7 +246,25 @@ impl AgentLoop {
     /// 3. Builds the conversation context
     /// 4. Runs the LLM→Tools→Reply cycle
     /// 5. Returns the final response
+    ///
+    /// A [`TurnTrace`] is recorded for every message and saved if a trace store is set.
     pub async fn process_message(&self, message: InboundMessage) -> Result<String> {
+        let mut trace = TurnTrace::new(&message.channel, &message.chat_id, &self.model);
+
+        let result = self.process_message_traced(message, &mut trace).await;
+
+        trace.finish(result.as_deref().map_err(|e| e.to_string()));
+        self.save_trace(&trace).await;
+
+        result
+    }
+
+    /// Processes a message while recording its execution into `trace`
+    async fn process_message_traced(
+        &self,
+        message: InboundMessage,
+        trace: &mut TurnTrace,
+    ) -> Result<String> {
         let session_id = format!("{}_{}", message.channel, message.chat_id);
 
         // Start timing for response measurement
@@ -204,6 +295,7 @@ impl AgentLoop {
             .await
             .map_err(|e| AgentError::ContextBuildError(e.to_string()))?;
         let context_time = context_start.elapsed();
+        trace.record_context(&context, &Tokenizer::for_model(&self.model));
 
         tracing::debug!(
             session_id = %session_id,
@@ -219,8 +311,9 @@ impl AgentLoop {
         );
 
         // Run the main agent loop
+        let tool_ctx = ToolExecutionContext::for_message(&message);
         let response = self
-            .run_agent_loop(&session_id, &mut session, context)
+            .run_agent_loop(&session_id, &mut session, context, &tool_ctx, trace)
             .await?;
 
         // Calculate and log response time
@@ -282,11 +375,14 @@ impl AgentLoop {
         session_id: &str,
         session: &mut Session,
         mut context: Vec<LlmMessage>,
+        tool_ctx: &ToolExecutionContext,
+        trace: &mut TurnTrace,
     ) -> Result<String> {
         let mut iteration: u32 = 0;
         let loop_start = std::time::Instant::now();
         let mut llm_time_ms: u128 = 0;
         let mut tool_time_ms: u128 = 0;
+        let mut tool_call_counts: HashMap<(String, String), u32> = HashMap::new();
 
         loop {
             // Check max iterations
@@ -294,12 +390,15 @@ impl AgentLoop {
                 tracing::warn!(
                     session_id = %session_id,
                     iterations = iteration,
-                    "Max iterations reached, terminating loop"
+                    "Max iterations reached, requesting final summary"
                 );
-                return Err(AgentError::MaxIterationsReached(iteration));
+                return self
+                    .finish_with_summary(session_id, session, context, iteration, trace)
+                    .await;
             }
 
             iteration += 1;
+            trace.iterations = iteration;
 
             tracing::debug!(
                 session_id = %session_id,
@@ -314,9 +413,15 @@ impl AgentLoop {
             let llm_start = std::time::Instant::now();
 
             // Call LLM
-            let llm_response = self.call_llm_with_retry(&context, &tools).await?;
+            let llm_result = self.call_llm_with_retry(&context, &tools).await;
             let llm_elapsed = llm_start.elapsed().as_millis();
             llm_time_ms += llm_elapsed;
+            trace.record_llm_call(
+                iteration,
+                llm_elapsed as u64,
+                llm_result.as_ref().map_err(|e| e.to_string()),
+            );
+            let llm_response = llm_result?;
 
             tracing::trace!(
                 session_id = %session_id,
@@ -350,9 +455,12 @@ impl AgentLoop {
                 .with_tool_calls(session_tool_calls);
                 session.add_message(assistant_message);
 
+                // Track identical calls before the batch is consumed by execution
+                let repeated_calls = Self::record_tool_calls(&mut tool_call_counts, &tool_calls);
+
                 // Execute tools with timing
                 let tool_start = std::time::Instant::now();
-                let tool_results = self.execute_tools(tool_calls).await;
+                let tool_results = self.execute_tools(tool_calls, iteration, tool_ctx).await;
                 let tool_elapsed = tool_start.elapsed().as_millis();
                 tool_time_ms += tool_elapsed;
 
@@ -364,7 +472,10 @@ impl AgentLoop {
                 );
 
                 // Add tool results to context AND session
-                for (tool_id, result) in tool_results {
+                for (execution, result) in tool_results {
+                    let tool_id = execution.tool_call_id.clone();
+                    trace.record_tool_execution(execution);
+
                     let result_content = format!("Tool {} result: {}", tool_id, result);
 
                     // Add to LLM context with tool_call_id set so the API can correlate
@@ -384,6 +495,21 @@ impl AgentLoop {
                     session.add_message(tool_result_message);
                 }
 
+                // Nudge the model away from loops of identical tool calls. The nudge
+                // goes after the tool results so assistant/tool pairing stays intact.
+                if !repeated_calls.is_empty() {
+                    tracing::warn!(
+                        session_id = %session_id,
+                        iteration = iteration,
+                        repeated = ?repeated_calls,
+                        "Repeated identical tool calls detected, injecting corrective nudge"
+                    );
+                    context.push(LlmMessage::new(
+                        LlmRole::System,
+                        Self::repeated_tool_call_nudge(&repeated_calls),
+                    ));
+                }
+
                 // Continue to next iteration
                 continue;
             } else {
@@ -414,6 +540,106 @@ impl AgentLoop {
         }
     }
 
+    /// Records a batch of tool calls and returns the names of those that have now been
+    /// issued with identical arguments at least [`REPEATED_TOOL_CALL_THRESHOLD`] times.
+    fn record_tool_calls(
+        counts: &mut HashMap<(String, String), u32>,
+        tool_calls: &[LlmToolCall],
+    ) -> Vec<(String, u32)> {
+        let mut repeated = Vec::new();
+
+        for tool_call in tool_calls {
+            // Normalize JSON so whitespace and key order don't hide a repeat
+            let arguments = serde_json::from_str::<serde_json::Value>(&tool_call.arguments)
+                .map(|v| v.to_string())
+                .unwrap_or_else(|_| tool_call.arguments.clone());
+
+            let count = counts
+                .entry((tool_call.name.clone(), arguments))
+                .or_insert(0);
+            *count += 1;
+
+            if *count >= REPEATED_TOOL_CALL_THRESHOLD {
+                repeated.push((tool_call.name.clone(), *count));
+            }
+        }
+
+        repeated
+    }
+
+    /// Builds the corrective message injected after repeated identical tool calls
+    fn repeated_tool_call_nudge(repeated: &[(String, u32)]) -> String {
+        let calls = repeated
+            .iter()
+            .map(|(name, count)| format!("'{}' ({} times)", name, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "You have called {} with identical arguments. Repeating the same call will \
+             return the same result. Use the results you already have, try a different \
+             approach or arguments, or answer the user directly.",
+            calls
+        )
+    }
+
+    /// Handles max-iteration exhaustion: asks the LLM for a tool-less summary of the
+    /// progress so far and saves the partial session so tool results are not lost.
+    ///
+    /// Falls back to [`AgentError::MaxIterationsReached`] if the summary call fails.
+    async fn finish_with_summary(
+        &self,
+        session_id: &str,
+        session: &mut Session,
+        mut context: Vec<LlmMessage>,
+        iteration: u32,
+        trace: &mut TurnTrace,
+    ) -> Result<String> {
+        context.push(LlmMessage::new(
+            LlmRole::System,
+            MAX_ITERATIONS_SUMMARY_PROMPT,
+        ));
+
+        // No tools offered, so the model must answer with text
+        let llm_start = std::time::Instant::now();
+        let llm_result = self.call_llm_with_retry(&context, &[]).await;
+        trace.record_llm_call(
+            iteration + 1,
+            llm_start.elapsed().as_millis() as u64,
+            llm_result.as_ref().map_err(|e| e.to_string()),
+        );
+
+        match llm_result {
+            Ok(response) => {
+                tracing::info!(
+                    session_id = %session_id,
+                    iterations = iteration,
+                    "Final summary produced after max iterations"
+                );
+
+                session.add_message(crate::session::Message::new(
+                    "assistant".to_string(),
+                    response.content.clone(),
+                ));
+                self.save_session(session).await?;
+
+                Ok(response.content)
+            }
+            Err(e) => {
+                tracing::error!(
+                    session_id = %session_id,
+                    error = %e,
+                    "Final summary call failed after max iterations"
+                );
+
+                // Keep whatever the tools produced before giving up
+                self.save_session(session).await?;
+
+                Err(AgentError::MaxIterationsReached(iteration))
+            }
+        }
+    }
+
     /// Calls the LLM with exponential backoff retry logic
     async fn call_llm_with_retry(
         &self,
@@ -431,7 +657,7 @@ impl AgentLoop {
             {
                 Ok(response) => return Ok(response),
                 Err(e) => {
-                    if retry_count >= MAX_LLM_RETRIES {
+                    if retry_count >= self.max_llm_retries {
                         tracing::error!(
                             retries = retry_count,
                             error = %e,
@@ -456,8 +682,14 @@ impl AgentLoop {
     }
 
     /// Executes a batch of tool calls in parallel
-    /// Returns tuples of (tool_call_id, result_message) for proper correlation
-    async fn execute_tools(&self, tool_calls: Vec<LlmToolCall>) -> Vec<(String, String)> {
+    /// Returns the trace of each execution (carrying the tool_call_id for correlation)
+    /// together with the result message
+    async fn execute_tools(
+        &self,
+        tool_calls: Vec<LlmToolCall>,
+        iteration: u32,
+        tool_ctx: &ToolExecutionContext,
+    ) -> Vec<(ToolExecutionTrace, String)> {
         use futures::stream::{FuturesUnordered, StreamExt};
 
         let mut futures = FuturesUnordered::new();
@@ -465,20 +697,38 @@ impl AgentLoop {
         for tool_call in tool_calls {
             let tool_registry = Arc::clone(&self.tool_registry);
             let tool_call_id = tool_call.id.clone();
+            let timeout = self.tool_timeout;
 
             futures.push(async move {
                 let tool_name = tool_call.name.clone();
-
-                match Self::execute_single_tool(tool_call, &tool_registry).await {
-                    Ok(result) => {
-                        tracing::info!(tool = %tool_name, tool_id = %tool_call_id, "Tool executed successfully");
-                        (tool_call_id, result)
-                    }
-                    Err(e) => {
-                        tracing::error!(tool = %tool_name, tool_id = %tool_call_id, error = %e, "Tool execution failed");
-                        (tool_call_id, format!("Error executing tool '{}': {}", tool_name, e))
-                    }
-                }
+                let arguments = tool_call.arguments.clone();
+                let start = std::time::Instant::now();
+
+                let (result, error) =
+                    match Self::execute_single_tool(tool_call, &tool_registry, tool_ctx, timeout).await {
+                        Ok(result) => {
+                            tracing::info!(tool = %tool_name, tool_id = %tool_call_id, "Tool executed successfully");
+                            (result, None)
+                        }
+                        Err(e) => {
+                            tracing::error!(tool = %tool_name, tool_id = %tool_call_id, error = %e, "Tool execution failed");
+                            (
+                                format!("Error executing tool '{}': {}", tool_name, e),
+                                Some(e.to_string()),
+                            )
+                        }
+                    };
+
+                let execution = ToolExecutionTrace {
+                    iteration,
+                    tool_call_id,
+                    name: tool_name,
+                    arguments,
+                    duration_ms: start.elapsed().as_millis() as u64,
+                    result_bytes: result.len(),
+                    error,
+                };
+                (execution, result)
             });
         }
 
@@ -494,6 +744,8 @@ impl AgentLoop {
     async fn execute_single_tool(
         tool_call: LlmToolCall,
         tool_registry: &ToolRegistry,
+        ctx: &ToolExecutionContext,
+        timeout: Duration,
     ) -> Result<String> {
         // Parse arguments from JSON string
         let args: std::collections::HashMap<String, serde_json::Value> =
@@ -502,13 +754,8 @@ impl AgentLoop {
             })?;
 
         // Execute the tool (includes validation and timeout)
-        let ctx = crate::agent::tools::ToolExecutionContext {
-            channel: None,
-            chat_id: None,
-        };
-
         tool_registry
-            .execute_tool(&tool_call.name, args, &ctx)
+            .execute_tool_with_timeout(&tool_call.name, args, ctx, timeout)
             .await
             .map_err(|e| AgentError::ToolExecutionError(e.to_string()))
     }
@@ -539,6 +786,20 @@ impl AgentLoop {
         Ok(())
     }
 
+    /// Writes a finished trace to the trace store, if configured
+    ///
+    /// Failures are logged but never fail the request.
+    async fn save_trace(&self, trace: &TurnTrace) {
+        let Some(store) = &self.trace_store else {
+            return;
+        };
+
+        match store.save(trace).await {
+            Ok(path) => tracing::debug!(trace_id = %trace.id, path = ?path, "Turn trace saved"),
+            Err(e) => tracing::warn!(trace_id = %trace.id, error = %e, "Failed to save turn trace"),
+        }
+    }
+
     /// Runs the agent loop continuously, processing messages from the chat hub
     ///
     /// This method is designed to run as a background task and will:
@@ -721,11 +982,220 @@ mod tests {
         assert_eq!(agent.model(), "custom-model");
     }
 
+    #[test]
+    fn test_agent_loop_with_agent_config() {
+        let agent_config = AgentConfig {
+            max_iterations: 12,
+            max_llm_retries: 1,
+            tool_timeout_secs: 90,
+            ..Default::default()
+        };
+
+        let agent = AgentLoop::builder(
+            Arc::new(ChatHub::new()),
+            Arc::new(MockLlmProvider),
+            Arc::new(MockContextBuilder),
+            Arc::new(ToolRegistry::new()),
+            Arc::new(SessionManager::new(std::path::PathBuf::from(
+                "/tmp/sessions",
+            ))),
+        )
+        .with_agent_config(&agent_config)
+        .build();
+
+        assert_eq!(agent.max_iterations(), 12);
+        assert_eq!(agent.max_llm_retries(), 1);
+        assert_eq!(agent.tool_timeout(), Duration::from_secs(90));
+    }
+
     #[test]
     fn test_max_iterations_constant() {
         assert_eq!(MAX_ITERATIONS, 200);
     }
 
+    /// Provider that keeps requesting the same tool call while tools are offered,
+    /// and answers with text once the tool list is empty.
+    struct LoopingLlmProvider {
+        calls: Mutex<Vec<Vec<LlmMessage>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for LoopingLlmProvider {
+        async fn chat(
+            &self,
+            messages: Vec<LlmMessage>,
+            tools: Vec<serde_json::Value>,
+            _model: &str,
+        ) -> std::result::Result<LlmResponse, ProviderError> {
+            let call_index = {
+                let mut calls = self.calls.lock().unwrap();
+                calls.push(messages);
+                calls.len()
+            };
+
+            if tools.is_empty() {
+                return Ok(LlmResponse::new("Summary of progress"));
+            }
+
+            Ok(LlmResponse::new("").with_tool_calls(vec![LlmToolCall::new(
+                format!("call_{}", call_index),
+                "noop",
+                r#"{"path": "/tmp", "operation": "list"}"#,
+            )]))
+        }
+
+        fn default_model(&self) -> String {
+            "test-model".to_string()
+        }
+
+        fn provider_name(&self) -> &'static str {
+            "LoopingLlmProvider"
+        }
+
+        async fn list_models(
+            &self,
+        ) -> std::result::Result<Vec<crate::providers::ModelInfo>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    struct NoopTool;
+
+    #[async_trait::async_trait]
+    impl crate::agent::tools::Tool for NoopTool {
+        fn name(&self) -> &str {
+            "noop"
+        }
+
+        fn description(&self) -> &str {
+            "Does nothing"
+        }
+
+        fn parameters(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(
+            &self,
+            _args: HashMap<String, serde_json::Value>,
+            _ctx: &crate::agent::tools::ToolExecutionContext,
+        ) -> crate::agent::tools::ToolResult<String> {
+            Ok("nothing".to_string())
+        }
+    }
+
+    #[test]
+    fn test_record_tool_calls_detects_identical_arguments() {
+        let mut counts = HashMap::new();
+
+        let first = vec![LlmToolCall::new("c1", "exec", r#"{"a": 1, "b": 2}"#)];
+        assert!(AgentLoop::record_tool_calls(&mut counts, &first).is_empty());
+
+        // Same arguments with different key order and whitespace count as a repeat
+        let second = vec![LlmToolCall::new("c2", "exec", r#"{"b":2,"a":1}"#)];
+        let repeated = AgentLoop::record_tool_calls(&mut counts, &second);
+        assert_eq!(repeated, vec![("exec".to_string(), 2)]);
+
+        // Different arguments are not a repeat
+        let third = vec![LlmToolCall::new("c3", "exec", r#"{"a": 3}"#)];
+        assert!(AgentLoop::record_tool_calls(&mut counts, &third).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_max_iterations_returns_summary_and_saves_session() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let provider = Arc::new(LoopingLlmProvider {
+            calls: Mutex::new(Vec::new()),
+        });
+        let session_manager = Arc::new(SessionManager::new(temp_dir.path().to_path_buf()));
+        let tool_registry = Arc::new(ToolRegistry::new());
+        tool_registry.register(Box::new(NoopTool)).await.unwrap();
+
+        let agent = AgentLoop::builder(
+            Arc::new(ChatHub::new()),
+            provider.clone(),
+            Arc::new(MockContextBuilder),
+            tool_registry,
+            Arc::clone(&session_manager),
+        )
+        .with_max_iterations(3)
+        .build();
+
+        let response = agent
+            .process_message(InboundMessage::new("cli", "loop", "List /tmp"))
+            .await
+            .unwrap();
+        assert_eq!(response, "Summary of progress");
+
+        // 3 tool iterations + 1 tool-less summary call
+        let calls = provider.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 4);
+
+        // The repeated identical call was nudged before the third iteration
+        assert!(
+            calls[2].iter().any(|m| {
+                m.role == LlmRole::System && m.content.contains("identical arguments")
+            })
+        );
+        assert!(calls[3].iter().any(|m| {
+            m.role == LlmRole::System && m.content.contains("maximum number of tool iterations")
+        }));
+
+        // Partial session (tool results + summary) was saved
+        let session = session_manager
+            .get_or_create_session("cli", "loop")
+            .await
+            .unwrap();
+        assert!(session.messages.iter().any(|m| m.is_tool_result()));
+        assert_eq!(
+            session.messages.back().unwrap().content,
+            "Summary of progress"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_message_saves_turn_trace() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let provider = Arc::new(LoopingLlmProvider {
+            calls: Mutex::new(Vec::new()),
+        });
+        let tool_registry = Arc::new(ToolRegistry::new());
+        tool_registry.register(Box::new(NoopTool)).await.unwrap();
+
+        let agent = AgentLoop::builder(
+            Arc::new(ChatHub::new()),
+            provider,
+            Arc::new(MockContextBuilder),
+            tool_registry,
+            Arc::new(SessionManager::new(temp_dir.path().to_path_buf())),
+        )
+        .with_model("trace-model")
+        .with_max_iterations(2)
+        .with_trace_store(TraceStore::new(temp_dir.path()))
+        .build();
+
+        agent
+            .process_message(InboundMessage::new("cli", "trace", "List /tmp"))
+            .await
+            .unwrap();
+
+        let traces = TraceStore::new(temp_dir.path()).list(10).await.unwrap();
+        assert_eq!(traces.len(), 1);
+
+        let trace = &traces[0];
+        assert_eq!(trace.session_id, "cli_trace");
+        assert_eq!(trace.iterations, 2);
+        // 2 tool iterations + 1 summary call
+        assert_eq!(trace.llm_calls.len(), 3);
+        assert!(trace.llm_calls.iter().all(|c| c.model == "trace-model"));
+        assert_eq!(trace.llm_calls[0].tool_calls, vec!["noop".to_string()]);
+        assert_eq!(trace.tool_executions.len(), 2);
+        assert_eq!(trace.tool_executions[1].iteration, 2);
+        assert!(trace.tool_executions.iter().all(|t| t.error.is_none()));
+        assert_eq!(trace.response_chars, Some("Summary of progress".len()));
+        assert!(trace.error.is_none());
+    }
+
     #[test]
     fn test_llm_role_as_str() {
         assert_eq!(LlmRole::System.as_str(), "system");
@@ -930,7 +1400,8 @@ mod tests {
         assert!(assistant_msg.is_assistant());
         assert!(!assistant_msg.is_tool_result());
 
-        let tool_result_msg = crate::session::Message::tool_result("call_1".to_string(), "Result".to_string());
+        let tool_result_msg =
+            crate::session::Message::tool_result("call_1".to_string(), "Result".to_string());
         assert!(!tool_result_msg.is_user());
         assert!(!tool_result_msg.is_assistant());
         assert!(tool_result_msg.is_tool_result());
@@ -9,53 +9,91 @@
 //! with proper priority and freshness:
 //! 1. **System**: SOUL.md + AGENTS.md combined (personality and behavior)
 //! 2. **Bootstrap**: Agent capabilities, current date/time, environment info
-//! 3. **Memory**: Long-term memories from MEMORY.md (ranked by relevance)
-//! 4. **Skills**: Available skills from workspace/skills/ directory
-//! 5. **Tools**: Tool documentation from TOOLS.md
-//! 6. **History**: Most recent conversation messages (max 50, newest first)
-//! 7. **Current**: The user's current message (always last, never truncated)
+//! 3. **User**: User profile from USER.md
+//! 4. **Memory**: Memories ranked against the current message, plus recent daily notes
+//! 5. **Skills**: Index of workspace skills, with matching and always-on skills in full
+//! 6. **Tools**: Tool documentation from TOOLS.md
+//! 7. **History**: Most recent conversation messages (max 50, newest first)
+//! 8. **Current**: The user's current message (always last, never truncated)
 //!
 //! This ordering ensures system instructions are never truncated, recent context
 //! is prioritized, and the current message always reaches the LLM.
+//!
+//! Workspace files (system, user, skills and tools layers) are rendered with
+//! [`TemplateVariables`] for each turn, so `{{date}}`, `{{channel}}`, `{{user.name}}`,
+//! `{{hostname}}` and `{{tools}}` reflect the current message.
 
 use chrono;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
 
 use crate::agent::agent_loop::{AgentError, ContextBuilder, Result};
 use crate::chat::InboundMessage;
-use crate::providers::{LlmMessage, LlmRole, LlmToolCall};
+use crate::memory::{DEFAULT_HIGHLIGHT_DAYS, MemoryRanker, MemorySource, RankedMemory};
+use crate::providers::{LlmMessage, LlmRole, LlmToolCall, Tokenizer};
 use crate::session::Session;
+use crate::skills::{DEFAULT_MAX_ACTIVE_SKILLS, SkillIndexEntry, select_skills};
+use crate::workspace::templates::DEFAULT_USER;
+use crate::workspace::{TemplateVariables, WorkspaceFileCache};
 
 /// Configuration for context building
 #[derive(Debug, Clone)]
 pub struct ContextBuilderConfig {
-    /// Maximum tokens allowed in context (approximate)
+    /// Maximum tokens allowed in context, counted with the builder's tokenizer
     pub max_context_tokens: usize,
     /// Maximum history messages to include
     pub max_history_messages: usize,
     /// Maximum memory entries to include
     pub max_memory_entries: usize,
-    /// Whether to use cached TOOLS.md content
+    /// Token budget for injected memories and daily-note highlights
+    pub max_memory_tokens: usize,
+    /// Days of daily notes surfaced as highlights (0 disables them)
+    pub daily_note_highlight_days: usize,
+    /// Maximum matching skills injected in full per turn (always-on skills excluded)
+    pub max_active_skills: usize,
+    /// Whether to cache workspace files and their parsed form (skills, daily
+    /// notes, memories) in memory, re-reading them only when their mtime or
+    /// size changes
+    pub cache_workspace_files: bool,
+    /// Former name of `cache_workspace_files`; setting it to `false` still
+    /// disables the cache
+    #[deprecated(note = "use `cache_workspace_files`")]
     pub cache_tools_content: bool,
 }
 
 impl Default for ContextBuilderConfig {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             max_context_tokens: 4000,
             max_history_messages: 50,
             max_memory_entries: 20,
+            max_memory_tokens: 1000,
+            daily_note_highlight_days: DEFAULT_HIGHLIGHT_DAYS,
+            max_active_skills: DEFAULT_MAX_ACTIVE_SKILLS,
+            cache_workspace_files: true,
             cache_tools_content: true,
         }
     }
 }
 
+impl ContextBuilderConfig {
+    /// Whether workspace files go through the file cache, honouring the
+    /// deprecated `cache_tools_content` flag
+    #[allow(deprecated)]
+    pub fn caches_workspace_files(&self) -> bool {
+        self.cache_workspace_files && self.cache_tools_content
+    }
+}
+
 /// Implementation of ContextBuilder that assembles context from workspace files
 pub struct ContextBuilderImpl {
     workspace_path: PathBuf,
     config: ContextBuilderConfig,
-    // Note: cached_tools_content removed - use static cache if needed in future
+    tokenizer: Tokenizer,
+    tool_names: Vec<String>,
+    file_cache: Arc<WorkspaceFileCache>,
 }
 
 impl ContextBuilderImpl {
@@ -73,6 +111,9 @@ impl ContextBuilderImpl {
         Ok(Self {
             workspace_path,
             config: ContextBuilderConfig::default(),
+            tokenizer: Tokenizer::default(),
+            tool_names: Vec::new(),
+            file_cache: Arc::new(WorkspaceFileCache::new()),
         })
     }
 
@@ -93,9 +134,24 @@ impl ContextBuilderImpl {
         Ok(Self {
             workspace_path,
             config,
+            tokenizer: Tokenizer::default(),
+            tool_names: Vec::new(),
+            file_cache: Arc::new(WorkspaceFileCache::new()),
         })
     }
 
+    /// Sets the tokenizer used for context budgeting (default: cl100k_base)
+    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// Sets the tool names exposed to templates as `{{tools}}`
+    pub fn with_tool_names(mut self, tool_names: Vec<String>) -> Self {
+        self.tool_names = tool_names;
+        self
+    }
+
     /// Returns the workspace path
     pub fn workspace_path(&self) -> &Path {
         &self.workspace_path
@@ -106,15 +162,51 @@ impl ContextBuilderImpl {
         &self.config
     }
 
-    /// Estimates token count for a string (simple heuristic: chars / 4)
-    fn estimate_tokens(&self, text: &str) -> usize {
-        text.len() / 4
+    /// Returns the tokenizer used for context budgeting
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
+    /// Returns the workspace file cache
+    pub fn file_cache(&self) -> &WorkspaceFileCache {
+        &self.file_cache
+    }
+
+    /// Reads a workspace file, through the file cache when enabled
+    async fn read_workspace_file(&self, path: &Path) -> std::io::Result<String> {
+        if self.config.caches_workspace_files() {
+            self.file_cache
+                .read(path)
+                .await
+                .map(|content| content.to_string())
+        } else {
+            fs::read_to_string(path).await
+        }
+    }
+
+    /// Reads and parses a workspace file, caching the parsed value when enabled
+    async fn read_parsed_workspace_file<T, F>(
+        &self,
+        path: &Path,
+        parse: F,
+    ) -> std::io::Result<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(&str) -> T,
+    {
+        if self.config.caches_workspace_files() {
+            self.file_cache.read_parsed(path, parse).await
+        } else {
+            fs::read_to_string(path)
+                .await
+                .map(|content| Arc::new(parse(&content)))
+        }
     }
 
     /// Loads SOUL.md from workspace
     async fn load_soul_md(&self) -> Option<String> {
         let path = self.workspace_path.join("SOUL.md");
-        match fs::read_to_string(&path).await {
+        match self.read_workspace_file(&path).await {
             Ok(content) => {
                 tracing::debug!("Loaded SOUL.md");
                 Some(content)
@@ -129,7 +221,7 @@ impl ContextBuilderImpl {
     /// Loads AGENTS.md from workspace
     async fn load_agents_md(&self) -> Option<String> {
         let path = self.workspace_path.join("AGENTS.md");
-        match fs::read_to_string(&path).await {
+        match self.read_workspace_file(&path).await {
             Ok(content) => {
                 tracing::debug!("Loaded AGENTS.md");
                 Some(content)
@@ -181,44 +273,98 @@ impl ContextBuilderImpl {
         }
     }
 
-    /// Loads MEMORY.md from workspace/memory/
-    async fn load_memory_md(&self) -> Option<String> {
-        let path = self.workspace_path.join("memory").join("MEMORY.md");
-        match fs::read_to_string(&path).await {
+    /// Loads USER.md from workspace
+    async fn load_user_md(&self) -> Option<String> {
+        let path = self.workspace_path.join("USER.md");
+        match self.read_workspace_file(&path).await {
             Ok(content) => {
-                tracing::debug!("Loaded MEMORY.md");
+                tracing::debug!("Loaded USER.md");
                 Some(content)
             }
             Err(e) => {
-                tracing::debug!(path = %path.display(), error = %e, "MEMORY.md not found");
+                tracing::debug!(path = %path.display(), error = %e, "USER.md not found");
                 None
             }
         }
     }
 
-    /// Builds memory context message with limit enforcement (AC #3)
-    async fn build_memory_message(&self) -> Option<LlmMessage> {
-        let memory_content = self.load_memory_md().await?;
+    /// Builds the user profile message from USER.md
+    ///
+    /// Skipped when the file is empty or still the untouched onboarding template.
+    async fn build_user_message(&self) -> Option<LlmMessage> {
+        let user_content = self.load_user_md().await?;
+        if user_content.trim().is_empty() || user_content == DEFAULT_USER {
+            return None;
+        }
 
-        // Split by lines and limit to max_memory_entries to avoid context overflow (AC #3)
-        let limited_memory = memory_content
-            .lines()
-            .take(self.config.max_memory_entries)
-            .collect::<Vec<_>>()
-            .join("\n");
+        Some(LlmMessage {
+            role: LlmRole::System,
+            content: format!("User profile:\n{}", user_content),
+            tool_calls: None,
+            tool_call_id: None,
+        })
+    }
 
-        let content = format!("Relevant memories:\n{}", limited_memory);
+    /// Builds the memory message for the current message (AC #3)
+    ///
+    /// Memories are ranked against the message, followed by recent daily-note
+    /// highlights. Entries are added in priority order until `max_memory_tokens` or
+    /// `max_memory_entries` is reached; entries that do not fit are skipped.
+    async fn build_memory_message(&self, current_message: &InboundMessage) -> Option<LlmMessage> {
+        let mut ranker = MemoryRanker::new(self.workspace_path.clone());
+        if self.config.caches_workspace_files() {
+            ranker = ranker.with_file_cache(Arc::clone(&self.file_cache));
+        }
+        let ranked = match ranker
+            .rank_for_context(
+                &current_message.content,
+                self.config.daily_note_highlight_days,
+            )
+            .await
+        {
+            Ok(ranked) => ranked,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to rank memories");
+                return None;
+            }
+        };
+
+        let mut budget = MemoryBudget {
+            tokenizer: &self.tokenizer,
+            remaining_tokens: self.config.max_memory_tokens,
+            remaining_entries: self.config.max_memory_entries,
+        };
+        let sections: Vec<String> = [
+            ("Relevant memories:", ranked.memories),
+            ("Recent daily notes:", ranked.daily_highlights),
+        ]
+        .into_iter()
+        .filter_map(|(header, entries)| budget.fill_section(header, &entries))
+        .collect();
+
+        if sections.is_empty() {
+            return None;
+        }
+
+        tracing::debug!(
+            entries = self.config.max_memory_entries - budget.remaining_entries,
+            tokens = self.config.max_memory_tokens - budget.remaining_tokens,
+            "Built memory context"
+        );
 
         Some(LlmMessage {
             role: LlmRole::System,
-            content,
+            content: sections.join("\n\n"),
             tool_calls: None,
             tool_call_id: None,
         })
     }
 
-    /// Scans skills directory and loads all SKILL.md files
-    async fn load_skills(&self) -> Vec<(String, String)> {
+    /// Scans skills directory and indexes all SKILL.md files
+    ///
+    /// Hidden (disabled) skill directories are skipped. Skills are sorted by name
+    /// so the index is stable across turns.
+    async fn load_skills(&self) -> Vec<SkillIndexEntry> {
         let skills_dir = self.workspace_path.join("skills");
         let mut skills = Vec::new();
 
@@ -226,22 +372,29 @@ impl ContextBuilderImpl {
             Ok(mut entries) => {
                 while let Ok(Some(entry)) = entries.next_entry().await {
                     let path = entry.path();
-                    if path.is_dir() {
-                        let skill_md = path.join("SKILL.md");
-                        if skill_md.exists() {
-                            match fs::read_to_string(&skill_md).await {
-                                Ok(content) => {
-                                    let skill_name = path
-                                        .file_name()
-                                        .and_then(|n| n.to_str())
-                                        .unwrap_or("unknown")
-                                        .to_string();
-                                    tracing::debug!(skill = %skill_name, "Loaded skill");
-                                    skills.push((skill_name, content));
-                                }
-                                Err(e) => {
-                                    tracing::warn!(path = %skill_md.display(), error = %e, "Failed to read skill");
-                                }
+                    let skill_name = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    if !path.is_dir() || skill_name.starts_with('.') {
+                        continue;
+                    }
+
+                    let skill_md = path.join("SKILL.md");
+                    if skill_md.exists() {
+                        let parsed = self
+                            .read_parsed_workspace_file(&skill_md, |content| {
+                                SkillIndexEntry::parse(&skill_name, content)
+                            })
+                            .await;
+                        match parsed {
+                            Ok(skill) => {
+                                tracing::debug!(skill = %skill_name, "Loaded skill");
+                                skills.push(skill.as_ref().clone());
+                            }
+                            Err(e) => {
+                                tracing::warn!(path = %skill_md.display(), error = %e, "Failed to read skill");
                             }
                         }
                     }
@@ -252,35 +405,50 @@ impl ContextBuilderImpl {
             }
         }
 
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
         skills
     }
 
-    /// Builds skills context message with efficient formatting (AC #4)
-    async fn build_skills_message(&self) -> Option<LlmMessage> {
+    /// Builds skills context message (AC #4)
+    ///
+    /// Every skill is listed as a one-line index entry. Only always-on skills and
+    /// up to `max_active_skills` skills matching the current message are injected
+    /// in full; the model can load any other skill with `read_skill`.
+    async fn build_skills_message(&self, current_message: &InboundMessage) -> Option<LlmMessage> {
         let skills = self.load_skills().await;
 
         if skills.is_empty() {
             return None;
         }
 
-        // Extract first line/title from each skill for efficiency (AC #4)
-        // Avoid dumping entire SKILL.md content which creates context bloat
-        let skills_text = skills
-            .into_iter()
-            .map(|(name, content)| {
-                // Get just the first non-empty line as the skill description
-                let description = content
-                    .lines()
-                    .find(|line| !line.trim().is_empty())
-                    .unwrap_or("(no description)")
-                    .trim_start_matches("#")
-                    .trim();
-                format!("- {}: {}", name, description)
-            })
+        let index = skills
+            .iter()
+            .map(|skill| format!("- {}: {}", skill.name, skill.description))
             .collect::<Vec<_>>()
             .join("\n");
+        let mut content = format!(
+            "Available skills (call read_skill with a skill name for its full instructions):\n{}",
+            index
+        );
+
+        let active = select_skills(
+            &skills,
+            &current_message.content,
+            self.config.max_active_skills,
+        );
+        for skill in &active {
+            content.push_str(&format!(
+                "\n\nActive skill '{}':\n{}",
+                skill.name,
+                skill.content.trim()
+            ));
+        }
 
-        let content = format!("Available skills:\n{}", skills_text);
+        tracing::debug!(
+            skills = skills.len(),
+            active = ?active.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            "Built skills context"
+        );
 
         Some(LlmMessage {
             role: LlmRole::System,
@@ -293,7 +461,7 @@ impl ContextBuilderImpl {
     /// Loads TOOLS.md from workspace
     async fn load_tools_md(&self) -> Option<String> {
         let path = self.workspace_path.join("TOOLS.md");
-        match fs::read_to_string(&path).await {
+        match self.read_workspace_file(&path).await {
             Ok(content) => {
                 tracing::debug!("Loaded TOOLS.md");
                 Some(content)
@@ -378,8 +546,12 @@ impl ContextBuilderImpl {
     /// or session-eviction logic might have left orphaned tool results in the context.
     ///
     /// The OpenAI API rejects requests where a `role: "tool"` message does not follow a
-    /// `role: "assistant"` message that contains `tool_calls`.
-    fn sanitize_tool_pairs(messages: Vec<LlmMessage>) -> Vec<LlmMessage> {
+    /// `role: "assistant"` message that contains `tool_calls`. Removed messages are
+    /// appended to `dropped`.
+    fn sanitize_tool_pairs(
+        messages: Vec<LlmMessage>,
+        dropped: &mut Vec<LlmMessage>,
+    ) -> Vec<LlmMessage> {
         let mut result: Vec<LlmMessage> = Vec::with_capacity(messages.len());
 
         for msg in messages {
@@ -388,15 +560,14 @@ impl ContextBuilderImpl {
                 // message that has tool_calls.
                 let preceded_by_tool_calls = result
                     .last()
-                    .map(|prev| {
-                        prev.role == LlmRole::Assistant && prev.tool_calls.is_some()
-                    })
+                    .map(|prev| prev.role == LlmRole::Assistant && prev.tool_calls.is_some())
                     .unwrap_or(false);
 
                 if !preceded_by_tool_calls {
                     tracing::warn!(
                         "Dropping orphaned tool result message (no preceding assistant+tool_calls)"
                     );
+                    dropped.push(msg);
                     continue;
                 }
             }
@@ -411,14 +582,13 @@ impl ContextBuilderImpl {
             .map(|m| m.role == LlmRole::Assistant && m.tool_calls.is_some())
             .unwrap_or(false)
         {
-            let dropped = result.pop().unwrap();
             tracing::warn!(
                 "Dropped trailing assistant+tool_calls message with no following tool result"
             );
             // If the message before the dropped one is now an orphaned tool result, keep
             // going — the while condition will catch it on the next iteration only if it
             // itself also has tool_calls (which would be unusual).
-            let _ = dropped;
+            dropped.extend(result.pop());
         }
 
         result
@@ -427,20 +597,23 @@ impl ContextBuilderImpl {
     /// Strategy: Remove oldest history messages first, never remove system or current message.
     /// Tool-interaction groups (assistant+tool_calls followed by one or more tool results) are
     /// always removed together to avoid orphaned tool result messages that would be rejected by
-    /// the OpenAI API.
+    /// the OpenAI API. Removed messages are appended to `dropped`, oldest first.
     fn truncate_context(
         &self,
         mut messages: Vec<LlmMessage>,
         current_message: &LlmMessage,
+        dropped: &mut Vec<LlmMessage>,
     ) -> Vec<LlmMessage> {
         let max_tokens = self.config.max_context_tokens;
 
-        loop {
-            let total_tokens: usize = messages
-                .iter()
-                .map(|m| self.estimate_tokens(&m.content))
-                .sum();
+        // Count each message once and keep the counts aligned with `messages`
+        let mut token_counts: Vec<usize> = messages
+            .iter()
+            .map(|m| self.tokenizer.count_message(m))
+            .collect();
+        let mut total_tokens: usize = token_counts.iter().sum();
 
+        loop {
             if total_tokens <= max_tokens {
                 break;
             }
@@ -457,11 +630,14 @@ impl ContextBuilderImpl {
 
             if let Some(idx) = removable_index {
                 let removed = messages.remove(idx);
+                let removed_tokens = token_counts.remove(idx);
+                total_tokens -= removed_tokens;
                 tracing::debug!(
                     role = ?removed.role,
-                    tokens = self.estimate_tokens(&removed.content),
+                    tokens = removed_tokens,
                     "Truncated message from context"
                 );
+                dropped.push(removed);
 
                 // After removing a message, also remove any immediately following tool
                 // result messages that are now orphaned (i.e. their preceding
@@ -484,10 +660,12 @@ impl ContextBuilderImpl {
                     }
 
                     let orphan = messages.remove(idx);
+                    total_tokens -= token_counts.remove(idx);
                     tracing::debug!(
                         role = ?orphan.role,
                         "Removed orphaned tool result after truncation"
                     );
+                    dropped.push(orphan);
                 }
             } else {
                 // Can't remove any more messages
@@ -504,53 +682,129 @@ impl ContextBuilderImpl {
     }
 }
 
-#[async_trait::async_trait]
-impl ContextBuilder for ContextBuilderImpl {
-    async fn build_context(
+/// Remaining token and entry allowance while assembling the memory message
+struct MemoryBudget<'a> {
+    tokenizer: &'a Tokenizer,
+    remaining_tokens: usize,
+    remaining_entries: usize,
+}
+
+impl MemoryBudget<'_> {
+    /// Formats the entries that fit under `header`, or `None` if none fit
+    fn fill_section(&mut self, header: &str, entries: &[RankedMemory]) -> Option<String> {
+        // The header (and the blank line before it) is only paid for once an entry fits
+        let header_tokens = self.tokenizer.count(header) + 2;
+        let mut lines = Vec::new();
+        let mut used = header_tokens;
+
+        for entry in entries {
+            if self.remaining_entries == 0 {
+                break;
+            }
+
+            let line = format_memory_line(entry);
+            let tokens = self.tokenizer.count(&line) + 1;
+            if used + tokens > self.remaining_tokens {
+                continue;
+            }
+
+            used += tokens;
+            self.remaining_entries -= 1;
+            lines.push(line);
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        self.remaining_tokens -= used;
+        Some(format!("{}\n{}", header, lines.join("\n")))
+    }
+}
+
+/// Formats a memory as a single bullet line; daily notes are prefixed with their date
+fn format_memory_line(entry: &RankedMemory) -> String {
+    let content = entry
+        .content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    match (&entry.source, entry.date) {
+        (MemorySource::DailyNote, Some(date)) => {
+            format!("- [{}] {}", date.format("%Y-%m-%d %H:%M"), content)
+        }
+        _ => format!("- {}", content),
+    }
+}
+
+/// The assembled context along with the messages removed to fit the budget
+#[derive(Debug, Clone, Default)]
+pub struct ContextReport {
+    /// Messages sent to the LLM, in order
+    pub messages: Vec<LlmMessage>,
+    /// Messages removed by truncation or tool-pair sanitizing, in removal order
+    pub dropped: Vec<LlmMessage>,
+}
+
+impl ContextBuilderImpl {
+    /// Builds the context exactly as [`ContextBuilder::build_context`] does, and also
+    /// reports which messages were dropped to stay within `max_context_tokens`
+    pub async fn build_context_report(
         &self,
         session: &Session,
         current_message: &InboundMessage,
-    ) -> Result<Vec<LlmMessage>> {
+    ) -> Result<ContextReport> {
         tracing::info!("Building conversation context with parallel file loading");
 
         let mut context = Vec::new();
+        let variables = TemplateVariables::for_message(current_message, &self.tool_names);
+        let render = |mut msg: LlmMessage| {
+            msg.content = variables.render(&msg.content);
+            msg
+        };
 
         // 1. System message (SOUL.md + AGENTS.md loaded in parallel)
         let system_msg = self.build_system_message().await;
-        context.push(system_msg);
+        context.push(render(system_msg));
 
         // 2. Bootstrap context (no I/O)
         let bootstrap_msg = self.build_bootstrap_message();
         context.push(bootstrap_msg);
 
-        // 3 & 4. Load Memory and Skills in parallel while assembling bootstrap
+        // 3-6. Load User, Memory, Skills and Tools in parallel
         // (Bootstrap is added first since it's in-memory and doesn't depend on I/O)
-        let (memory_msg, skills_msg, tools_msg) = tokio::join!(
-            self.build_memory_message(),
-            self.build_skills_message(),
+        let (user_msg, memory_msg, skills_msg, tools_msg) = tokio::join!(
+            self.build_user_message(),
+            self.build_memory_message(current_message),
+            self.build_skills_message(current_message),
             self.build_tools_message()
         );
 
-        // 3. Memory layer (if available)
+        // 3. User profile layer (if available)
+        if let Some(user_msg) = user_msg {
+            context.push(render(user_msg));
+        }
+
+        // 4. Memory layer (if available, memories are not templates)
         if let Some(memory_msg) = memory_msg {
             context.push(memory_msg);
         }
 
-        // 4. Skills layer (if available)
+        // 5. Skills layer (if available)
         if let Some(skills_msg) = skills_msg {
-            context.push(skills_msg);
+            context.push(render(skills_msg));
         }
 
-        // 5. Tools layer (if available)
+        // 6. Tools layer (if available)
         if let Some(tools_msg) = tools_msg {
-            context.push(tools_msg);
+            context.push(render(tools_msg));
         }
 
-        // 6. Conversation history (already in memory, FIFO order with most recent last)
+        // 7. Conversation history (already in memory, FIFO order with most recent last)
         let history = self.build_history_messages(session);
         context.extend(history);
 
-        // 7. Current user message
+        // 8. Current user message
         let current_msg = LlmMessage {
             role: LlmRole::User,
             content: current_message.content.clone(),
@@ -560,24 +814,44 @@ impl ContextBuilder for ContextBuilderImpl {
         context.push(current_msg.clone());
 
         // Truncate if necessary, protecting system and current messages
-        let context = self.truncate_context(context, &current_msg);
+        let mut dropped = Vec::new();
+        let context = self.truncate_context(context, &current_msg, &mut dropped);
 
         // Final safety pass: remove any orphaned tool messages that may have slipped
         // through (e.g. from sessions persisted before this fix was deployed).
-        let context = Self::sanitize_tool_pairs(context);
+        let context = Self::sanitize_tool_pairs(context, &mut dropped);
 
         tracing::info!(
             message_count = context.len(),
+            dropped_count = dropped.len(),
             "Context built successfully with parallel I/O"
         );
 
-        Ok(context)
+        Ok(ContextReport {
+            messages: context,
+            dropped,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ContextBuilder for ContextBuilderImpl {
+    async fn build_context(
+        &self,
+        session: &Session,
+        current_message: &InboundMessage,
+    ) -> Result<Vec<LlmMessage>> {
+        Ok(self
+            .build_context_report(session, current_message)
+            .await?
+            .messages)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::TokenizerKind;
     use tempfile::TempDir;
 
     fn create_test_session() -> Session {
@@ -652,7 +926,8 @@ mod tests {
             .unwrap();
 
         let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
-        let msg = builder.build_memory_message().await;
+        let inbound = InboundMessage::new("cli", "1", "Hello");
+        let msg = builder.build_memory_message(&inbound).await;
 
         assert!(msg.is_some());
         let msg = msg.unwrap();
@@ -664,7 +939,8 @@ mod tests {
     async fn test_build_memory_message_missing() {
         let temp_dir = TempDir::new().unwrap();
         let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
-        let msg = builder.build_memory_message().await;
+        let inbound = InboundMessage::new("cli", "1", "Hello");
+        let msg = builder.build_memory_message(&inbound).await;
 
         assert!(msg.is_none());
     }
@@ -685,8 +961,51 @@ mod tests {
         let skills = builder.load_skills().await;
 
         assert_eq!(skills.len(), 1);
-        assert_eq!(skills[0].0, "weather");
-        assert!(skills[0].1.contains("weather information"));
+        assert_eq!(skills[0].name, "weather");
+        assert_eq!(skills[0].description, "Get weather information.");
+    }
+
+    #[tokio::test]
+    async fn test_build_skills_message_injects_matching_skills() {
+        let temp_dir = TempDir::new().unwrap();
+        let skills_dir = temp_dir.path().join("skills");
+        for (name, content) in [
+            (
+                "weather",
+                "# Skill: weather\n\n## Description\nGet weather forecasts\n\n## Implementation\nCall wttr.in",
+            ),
+            (
+                "git_helper",
+                "# Skill: git_helper\n\n## Description\nCommit changes\n\n## Implementation\nRun git commit",
+            ),
+            (
+                "style",
+                "# Skill: style\n\n## Description\nHouse style\n\n## Metadata\n- **Always-on**: true",
+            ),
+            (
+                ".disabled",
+                "# Skill: disabled\n\n## Description\nNever shown",
+            ),
+        ] {
+            fs::create_dir_all(skills_dir.join(name)).await.unwrap();
+            fs::write(skills_dir.join(name).join("SKILL.md"), content)
+                .await
+                .unwrap();
+        }
+
+        let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
+        let inbound = InboundMessage::new("cli", "1", "What's the weather tomorrow?");
+        let msg = builder.build_skills_message(&inbound).await.unwrap();
+
+        // Every enabled skill is indexed
+        assert!(msg.content.contains("- git_helper: Commit changes"));
+        assert!(msg.content.contains("- weather: Get weather forecasts"));
+        assert!(!msg.content.contains("disabled"));
+        // Only the matching and always-on skills are injected in full
+        assert!(msg.content.contains("Active skill 'weather'"));
+        assert!(msg.content.contains("Call wttr.in"));
+        assert!(msg.content.contains("Active skill 'style'"));
+        assert!(!msg.content.contains("Run git commit"));
     }
 
     #[tokio::test]
@@ -713,13 +1032,17 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_estimate_tokens() {
+    async fn test_default_tokenizer() {
         let temp_dir = TempDir::new().unwrap();
         let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
 
-        // Simple heuristic: chars / 4
-        assert_eq!(builder.estimate_tokens("aaaa"), 1);
-        assert_eq!(builder.estimate_tokens("aaaaaaaa"), 2);
+        // Counted with cl100k_base by default
+        assert_eq!(builder.tokenizer().kind(), TokenizerKind::Cl100k);
+        assert_eq!(builder.tokenizer().count("hello world"), 2);
+        assert_eq!(builder.tokenizer().count(""), 0);
+
+        let builder = builder.with_tokenizer(Tokenizer::for_model("gpt-4o"));
+        assert_eq!(builder.tokenizer().kind(), TokenizerKind::O200k);
     }
 
     #[tokio::test]
@@ -761,11 +1084,82 @@ mod tests {
             current.clone(),
         ];
 
-        let truncated = builder.truncate_context(messages, &current);
+        let mut dropped = Vec::new();
+        let truncated = builder.truncate_context(messages, &current, &mut dropped);
 
         // Should keep system and current, remove oldest non-system
         assert!(truncated.iter().any(|m| m.role == LlmRole::System));
         assert!(truncated.iter().any(|m| m.content == "Current"));
+        assert_eq!(dropped[0].content, "Old message");
+    }
+
+    #[tokio::test]
+    async fn test_truncate_context_counts_tool_call_arguments() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = ContextBuilderConfig {
+            max_context_tokens: 50,
+            ..Default::default()
+        };
+        let builder = ContextBuilderImpl::with_config(temp_dir.path(), config).unwrap();
+
+        let current = LlmMessage::new(LlmRole::User, "Current");
+        let mut tool_request = LlmMessage::new(LlmRole::Assistant, "");
+        tool_request.tool_calls = Some(vec![LlmToolCall::new(
+            "call_1",
+            "write_file",
+            format!(r#"{{"content":"{}"}}"#, "lorem ipsum ".repeat(50)),
+        )]);
+        let mut tool_result = LlmMessage::new(LlmRole::Tool, "ok");
+        tool_result.tool_call_id = Some("call_1".to_string());
+
+        let messages = vec![
+            LlmMessage::new(LlmRole::System, "System"),
+            tool_request,
+            tool_result,
+            current.clone(),
+        ];
+
+        // The assistant message has no content, but its arguments exceed the budget
+        let mut dropped = Vec::new();
+        let truncated = builder.truncate_context(messages, &current, &mut dropped);
+        assert_eq!(truncated.len(), 2);
+        assert_eq!(dropped.len(), 2);
+        assert_eq!(truncated[0].role, LlmRole::System);
+        assert_eq!(truncated[1].content, "Current");
+    }
+
+    #[tokio::test]
+    async fn test_build_context_report_lists_dropped_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = ContextBuilderConfig {
+            max_context_tokens: 100,
+            ..Default::default()
+        };
+        let builder = ContextBuilderImpl::with_config(temp_dir.path(), config).unwrap();
+
+        let mut session = create_test_session();
+        session.add_message(crate::session::Message::new(
+            "user".to_string(),
+            "Remember that my locker code is 4321 ".repeat(10),
+        ));
+        session.add_message(crate::session::Message::new(
+            "assistant".to_string(),
+            "Noted".to_string(),
+        ));
+
+        let inbound = InboundMessage::new("telegram", "123", "What is my locker code?");
+        let report = builder
+            .build_context_report(&session, &inbound)
+            .await
+            .unwrap();
+
+        assert_eq!(report.dropped.len(), 1);
+        assert!(report.dropped[0].content.contains("locker code is 4321"));
+        assert!(report.messages.iter().any(|m| m.content == "Noted"));
+        assert_eq!(
+            builder.build_context(&session, &inbound).await.unwrap(),
+            report.messages
+        );
     }
 
     #[tokio::test]
@@ -856,6 +1250,122 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_build_context_includes_user_profile() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("SOUL.md"), "Personality")
+            .await
+            .unwrap();
+        fs::write(temp_dir.path().join("USER.md"), "Name: Alice")
+            .await
+            .unwrap();
+
+        let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
+        let inbound = InboundMessage::new("telegram", "123", "Hello");
+        let context = builder
+            .build_context(&create_test_session(), &inbound)
+            .await
+            .unwrap();
+
+        // User profile follows System and Bootstrap
+        assert_eq!(context[2].role, LlmRole::System);
+        assert_eq!(context[2].content, "User profile:\nName: Alice");
+    }
+
+    #[tokio::test]
+    async fn test_build_user_message_skips_default_template() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("USER.md"), DEFAULT_USER)
+            .await
+            .unwrap();
+
+        let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
+        assert!(builder.build_user_message().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_build_context_renders_template_variables() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("SOUL.md"),
+            "You talk to {{user.name}} on {{channel}}.",
+        )
+        .await
+        .unwrap();
+        fs::write(temp_dir.path().join("TOOLS.md"), "Enabled: {{tools}}")
+            .await
+            .unwrap();
+
+        let builder = ContextBuilderImpl::new(temp_dir.path())
+            .unwrap()
+            .with_tool_names(vec!["web".to_string(), "filesystem".to_string()]);
+        let inbound = InboundMessage::new("telegram", "123", "Hello {{channel}}")
+            .with_metadata("username", serde_json::json!("alice"));
+        let context = builder
+            .build_context(&create_test_session(), &inbound)
+            .await
+            .unwrap();
+
+        assert_eq!(context[0].content, "You talk to alice on telegram.");
+        assert!(
+            context
+                .iter()
+                .any(|m| m.content == "Available tools:\nEnabled: filesystem, web")
+        );
+        // The user's own message is never rendered
+        assert_eq!(context.last().unwrap().content, "Hello {{channel}}");
+    }
+
+    #[tokio::test]
+    async fn test_workspace_files_cached_until_changed() {
+        let temp_dir = TempDir::new().unwrap();
+        let soul_path = temp_dir.path().join("SOUL.md");
+        fs::write(&soul_path, "Soul v1").await.unwrap();
+
+        let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
+        assert_eq!(builder.build_system_message().await.content, "Soul v1");
+        assert_eq!(builder.file_cache().len(), 1);
+
+        fs::write(&soul_path, "Soul version 2").await.unwrap();
+        assert_eq!(
+            builder.build_system_message().await.content,
+            "Soul version 2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workspace_file_cache_disabled() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("SOUL.md"), "Soul")
+            .await
+            .unwrap();
+
+        let config = ContextBuilderConfig {
+            cache_workspace_files: false,
+            ..Default::default()
+        };
+        let builder = ContextBuilderImpl::with_config(temp_dir.path(), config).unwrap();
+        assert_eq!(builder.build_system_message().await.content, "Soul");
+        assert!(builder.file_cache().is_empty());
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_deprecated_cache_tools_content_disables_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("SOUL.md"), "Soul")
+            .await
+            .unwrap();
+
+        let config = ContextBuilderConfig {
+            cache_tools_content: false,
+            ..Default::default()
+        };
+        let builder = ContextBuilderImpl::with_config(temp_dir.path(), config).unwrap();
+        assert_eq!(builder.build_system_message().await.content, "Soul");
+        assert!(builder.file_cache().is_empty());
+    }
+
     #[tokio::test]
     async fn test_history_selects_most_recent() {
         let mut session = create_test_session();
@@ -941,13 +1451,98 @@ mod tests {
         };
 
         let builder = ContextBuilderImpl::with_config(temp_dir.path(), config).unwrap();
-        let msg = builder.build_memory_message().await.unwrap();
+        let inbound = InboundMessage::new("cli", "1", "Hello");
+        let msg = builder.build_memory_message(&inbound).await.unwrap();
+
+        // Nothing matches, so the 5 newest lines win (limited by max_memory_entries)
+        let lines: Vec<&str> = msg.content.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "- Memory line 10",
+                "- Memory line 9",
+                "- Memory line 8",
+                "- Memory line 7",
+                "- Memory line 6"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_ranked_against_current_message() {
+        let temp_dir = TempDir::new().unwrap();
+        let memory_dir = temp_dir.path().join("memory");
+        fs::create_dir(&memory_dir).await.unwrap();
+
+        let mut memory_content = vec!["The staging server runs on port 8443".to_string()];
+        memory_content.extend((1..=30).map(|i| format!("Unrelated fact number {}", i)));
+        fs::write(memory_dir.join("MEMORY.md"), memory_content.join("\n"))
+            .await
+            .unwrap();
+
+        let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
+        let inbound = InboundMessage::new("cli", "1", "Which port does staging use?");
+        let msg = builder.build_memory_message(&inbound).await.unwrap();
+
+        // The oldest line is relevant, so it is injected first
+        assert_eq!(
+            msg.content.lines().nth(1),
+            Some("- The staging server runs on port 8443")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_fits_token_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let memory_dir = temp_dir.path().join("memory");
+        fs::create_dir(&memory_dir).await.unwrap();
+
+        let memory_content = (1..=20)
+            .map(|i| format!("Memory entry {} {}", i, "filler ".repeat(20)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(memory_dir.join("MEMORY.md"), memory_content)
+            .await
+            .unwrap();
+
+        let config = ContextBuilderConfig {
+            max_memory_tokens: 100,
+            ..Default::default()
+        };
+        let builder = ContextBuilderImpl::with_config(temp_dir.path(), config).unwrap();
+        let inbound = InboundMessage::new("cli", "1", "Hello");
+        let msg = builder.build_memory_message(&inbound).await.unwrap();
+
+        assert!(builder.tokenizer().count(&msg.content) <= 100);
+        assert!(msg.content.contains("Memory entry 20 "));
+        assert!(!msg.content.contains("Memory entry 1 "));
+    }
+
+    #[tokio::test]
+    async fn test_memory_includes_daily_note_highlights() {
+        let temp_dir = TempDir::new().unwrap();
+        let memory_dir = temp_dir.path().join("memory");
+        fs::create_dir(&memory_dir).await.unwrap();
+
+        let today = chrono::Utc::now().format("%Y-%m-%d");
+        fs::write(
+            memory_dir.join(format!("{}.md", today)),
+            "# Daily Note\n\n## 08:15:00 UTC\n\nDeployed v2 to production\n\n---\n",
+        )
+        .await
+        .unwrap();
 
-        // Should only contain first 5 lines (limited by max_memory_entries)
-        assert!(msg.content.contains("Memory line 1"));
-        assert!(msg.content.contains("Memory line 5"));
-        // Lines 6-10 should not be present
-        assert!(!msg.content.contains("Memory line 10"));
+        let builder = ContextBuilderImpl::new(temp_dir.path()).unwrap();
+        let inbound = InboundMessage::new("cli", "1", "Hello");
+        let msg = builder.build_memory_message(&inbound).await.unwrap();
+
+        assert_eq!(
+            msg.content,
+            format!(
+                "Recent daily notes:\n- [{} 08:15] Deployed v2 to production",
+                today
+            )
+        );
     }
 
     #[tokio::test]
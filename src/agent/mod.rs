@@ -3,12 +3,14 @@ pub mod context;
 pub mod metrics;
 pub mod oneshot;
 pub mod tools;
+pub mod trace;
 
 // Re-export from providers module
 pub use crate::providers::{
     LlmMessage, LlmProvider, LlmResponse, LlmRole, LlmToolCall, ProviderError,
 };
 pub use agent_loop::{AgentError, AgentLoop, ContextBuilder};
-pub use context::{ContextBuilderConfig, ContextBuilderImpl};
+pub use context::{ContextBuilderConfig, ContextBuilderImpl, ContextReport};
 pub use metrics::ResponseMetrics;
 pub use oneshot::execute_one_shot;
+pub use trace::{TraceStore, TurnTrace};
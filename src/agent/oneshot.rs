@@ -73,21 +73,19 @@ pub async fn execute_one_shot(
         .map(|home| home.join(".miniclaw").join("workspace"))
         .unwrap_or_else(std::env::temp_dir);
 
-    let context_builder: Arc<dyn ContextBuilder> = if workspace_path.exists() {
-        Arc::new(
-            crate::agent::ContextBuilderImpl::new(&workspace_path)
-                .map_err(|e| anyhow::anyhow!("Failed to create context builder: {}", e))?,
-        )
-    } else {
-        // Fallback: create a minimal context builder that doesn't depend on workspace files
-        Arc::new(MinimalContextBuilder::new())
-    };
+    let workspace_exists = workspace_path.exists();
 
     // Create a tool registry with all default tools
     // Use the configured default channel (defaults to "telegram" if not set)
+    // Tools need an existing base directory, so fall back to the temp dir before onboarding
+    let tools_path = if workspace_exists {
+        workspace_path.clone()
+    } else {
+        std::env::temp_dir()
+    };
     let tool_registry = Arc::new(
         ToolRegistry::with_all_default_tools(
-            workspace_path,
+            tools_path,
             Arc::clone(&chat_hub),
             config,
             &config.default_channel,
@@ -95,12 +93,34 @@ pub async fn execute_one_shot(
         .await,
     );
 
+    let tool_names = tool_registry
+        .list_tools()
+        .await
+        .into_iter()
+        .map(|(name, _, _)| name)
+        .collect();
+
+    let context_builder: Arc<dyn ContextBuilder> = if workspace_exists {
+        Arc::new(
+            crate::agent::ContextBuilderImpl::with_config(
+                &workspace_path,
+                config.agent.context_builder_config(),
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to create context builder: {}", e))?
+            .with_tokenizer(crate::providers::Tokenizer::for_model(&model))
+            .with_tool_names(tool_names),
+        )
+    } else {
+        // Fallback: create a minimal context builder that doesn't depend on workspace files
+        Arc::new(MinimalContextBuilder::new())
+    };
+
     // Create a temporary session manager (not persisted)
     let temp_dir = std::env::temp_dir();
     let session_manager = Arc::new(crate::session::SessionManager::new(temp_dir));
 
     // Create the agent loop with model override
-    let agent_loop = AgentLoop::builder(
+    let mut builder = AgentLoop::builder(
         chat_hub,
         provider,
         context_builder,
@@ -108,7 +128,14 @@ pub async fn execute_one_shot(
         session_manager,
     )
     .with_model(model)
-    .build();
+    .with_agent_config(&config.agent);
+
+    // Only record traces once the workspace has been onboarded
+    if workspace_exists {
+        builder = builder.with_trace_store(crate::agent::TraceStore::new(&workspace_path));
+    }
+
+    let agent_loop = builder.build();
 
     // Create the inbound message
     let inbound_message = InboundMessage::new("cli", "oneshot", message);
@@ -204,6 +231,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: Some(crate::providers::ProviderConfig::openai("test-key")),
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -223,6 +253,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: None,
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -265,6 +298,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: None,
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -289,6 +325,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: Some(ProviderConfig::OpenAi(OpenAiConfig::new("test-key"))),
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -319,6 +358,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: Some(ProviderConfig::OpenAi(OpenAiConfig::new("test-key"))),
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -5,16 +5,19 @@
 //! or recurring tasks (Interval).
 //!
 //! # Security
-//! Commands are validated against the same blacklist as the exec tool
-//! to prevent execution of dangerous system commands.
+//! Commands are checked against the same command policy as the exec tool,
+//! both when a job is scheduled and when it runs.
 
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
+use crate::agent::tools::policy::PolicyScope;
+use crate::agent::tools::security;
 use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
 use crate::cron::CronScheduler;
+use crate::cron::types::JobNotification;
 
 /// Tool for scheduling tasks
 ///
@@ -77,8 +80,14 @@ impl CronTool {
         command: String,
         time: String,
         args: Option<Vec<String>>,
+        scope: PolicyScope,
+        notify: Option<JobNotification>,
     ) -> ToolResult<String> {
-        match self.scheduler.schedule_fire_at(command, time, args).await {
+        match self
+            .scheduler
+            .schedule_fire_at(command, time, args, scope, notify)
+            .await
+        {
             Ok(result) => {
                 let response = ScheduleResponse {
                     success: true,
@@ -101,10 +110,12 @@ impl CronTool {
         command: String,
         minutes: u32,
         args: Option<Vec<String>>,
+        scope: PolicyScope,
+        notify: Option<JobNotification>,
     ) -> ToolResult<String> {
         match self
             .scheduler
-            .schedule_interval(command, minutes, args)
+            .schedule_interval(command, minutes, args, scope, notify)
             .await
         {
             Ok(result) => {
@@ -179,7 +190,8 @@ impl Tool for CronTool {
         "Schedule tasks for later execution. Supports one-time tasks (FireAt) and recurring tasks (Interval). \
          Use 'schedule' action to create jobs, 'list' to view scheduled jobs, and 'cancel' to remove jobs. \
          FireAt requires an ISO 8601 datetime (e.g., '2026-02-16T10:00:00Z'). \
-         Interval requires minutes >= 2. Commands are executed with the same security restrictions as exec tool."
+         Interval requires minutes >= 2. Commands are executed with the same security restrictions as exec tool. \
+         Set 'notify' to have each run's output (if any) sent to this chat."
     }
 
     fn parameters(&self) -> Value {
@@ -217,6 +229,10 @@ impl Tool for CronTool {
                 "job_id": {
                     "type": "string",
                     "description": "Job ID to cancel (required when action='cancel')"
+                },
+                "notify": {
+                    "type": "boolean",
+                    "description": "Send the command's output to this chat after each run; empty output sends nothing (optional)"
                 }
             },
             "required": ["action"]
@@ -226,7 +242,7 @@ impl Tool for CronTool {
     async fn execute(
         &self,
         args: HashMap<String, Value>,
-        _ctx: &ToolExecutionContext,
+        ctx: &ToolExecutionContext,
     ) -> ToolResult<String> {
         // Get action parameter
         let action = args.get("action").and_then(|v| v.as_str()).ok_or_else(|| {
@@ -258,11 +274,44 @@ impl Tool for CronTool {
 
                 let command = command.to_string();
 
-                let cmd_args = args.get("args").and_then(|v| v.as_array()).map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect()
-                });
+                let cmd_args: Option<Vec<String>> =
+                    args.get("args").and_then(|v| v.as_array()).map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    });
+
+                // Reject denied commands now rather than when the job fires
+                let scope = PolicyScope::from(ctx);
+                security::check_command(
+                    self.name(),
+                    self.scheduler.policy(),
+                    &command,
+                    cmd_args.as_deref().unwrap_or_default(),
+                    &scope,
+                )?;
+
+                let notify = match (
+                    args.get("notify")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                    &ctx.channel,
+                    &ctx.chat_id,
+                ) {
+                    (true, Some(channel), Some(chat_id)) if self.scheduler.can_notify() => {
+                        Some(JobNotification {
+                            channel: channel.clone(),
+                            chat_id: chat_id.clone(),
+                        })
+                    }
+                    (true, ..) => {
+                        return Err(ToolError::InvalidArguments {
+                            tool: self.name().to_string(),
+                            message: "'notify' needs a chat to send messages to".to_string(),
+                        });
+                    }
+                    _ => None,
+                };
 
                 match job_type {
                     "fire_at" => {
@@ -274,7 +323,7 @@ impl Tool for CronTool {
                             }
                         })?;
 
-                        self.schedule_fire_at(command, time.to_string(), cmd_args)
+                        self.schedule_fire_at(command, time.to_string(), cmd_args, scope, notify)
                             .await
                     }
                     "interval" => {
@@ -288,7 +337,8 @@ impl Tool for CronTool {
                                     .to_string(),
                             })?;
 
-                        self.schedule_interval(command, minutes, cmd_args).await
+                        self.schedule_interval(command, minutes, cmd_args, scope, notify)
+                            .await
                     }
                     _ => Err(ToolError::InvalidArguments {
                         tool: self.name().to_string(),
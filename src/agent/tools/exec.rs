@@ -1,15 +1,19 @@
 //! Exec tool for the agent
 //!
 //! This tool provides command execution with security constraints.
-//! Commands are validated against a blacklist to prevent dangerous operations.
+//! Commands are validated against the shared command policy to prevent
+//! dangerous operations.
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 use serde_json::Value;
 use tokio::process::Command;
 
+use crate::agent::tools::policy::{CommandPolicy, PolicyScope};
+use crate::agent::tools::sandbox::Sandbox;
 use crate::agent::tools::security;
 use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
 
@@ -18,18 +22,22 @@ const DEFAULT_EXEC_TIMEOUT_SECS: u64 = 30;
 
 /// Tool for executing shell commands
 ///
-/// Provides command execution with security constraints including a blacklist
-/// of dangerous commands and path validation for working directories.
+/// Provides command execution with security constraints including the command
+/// policy and path validation for working directories.
 ///
 /// # Security
-/// This tool implements NFR-S4: Commands are validated against a blacklist
-/// to prevent execution of dangerous system commands.
+/// This tool implements NFR-S4: Commands are validated against the
+/// [`CommandPolicy`] to prevent execution of dangerous system commands.
 #[derive(Debug)]
 pub struct ExecTool {
     /// The canonicalized base directory that all cwd operations are restricted to
     base_dir: PathBuf,
     /// Default timeout for command execution
     default_timeout: Duration,
+    /// Policy deciding which commands may run
+    policy: Arc<CommandPolicy>,
+    /// Confinement applied to started commands
+    sandbox: Arc<Sandbox>,
 }
 
 impl ExecTool {
@@ -55,20 +63,21 @@ impl ExecTool {
         Ok(Self {
             base_dir: canonical_base,
             default_timeout: Duration::from_secs(DEFAULT_EXEC_TIMEOUT_SECS),
+            policy: Arc::new(CommandPolicy::default()),
+            sandbox: Arc::new(Sandbox::default()),
         })
     }
 
-    /// Checks if a command is in the blacklist
-    ///
-    /// Delegates to the shared security module's [`security::is_blacklisted`].
-    ///
-    /// # Arguments
-    /// * `command` - The command to check
-    ///
-    /// # Returns
-    /// `true` if the command is blacklisted, `false` otherwise
-    fn is_blacklisted(&self, command: &str) -> bool {
-        security::is_blacklisted(command)
+    /// Replaces the default command policy
+    pub fn with_policy(mut self, policy: Arc<CommandPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replaces the default sandbox
+    pub fn with_sandbox(mut self, sandbox: Arc<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
     }
 
     /// Validates a working directory path
@@ -89,6 +98,7 @@ impl ExecTool {
     /// * `command` - The command to execute
     /// * `args` - Command arguments as an array
     /// * `cwd` - Optional working directory
+    /// * `scope` - Caller the command policy is evaluated for
     ///
     /// # Returns
     /// JSON string with stdout, stderr, and exit_code
@@ -97,14 +107,41 @@ impl ExecTool {
         command: &str,
         args: &[String],
         cwd: Option<&Path>,
+        scope: &PolicyScope,
     ) -> ToolResult<String> {
-        // Check blacklist
-        if self.is_blacklisted(command) {
-            return Err(ToolError::PermissionDenied {
-                tool: self.name().to_string(),
-                message: format!("Command not allowed: {}", command),
-            });
-        }
+        let runner = CommandRunner {
+            tool: self.name(),
+            policy: &self.policy,
+            sandbox: &self.sandbox,
+            timeout: self.default_timeout,
+        };
+        runner.run(command, args, cwd, scope).await
+    }
+}
+
+/// Runs commands under the command policy and sandbox on behalf of a tool
+pub(crate) struct CommandRunner<'a> {
+    /// Name of the tool reported in errors
+    pub tool: &'a str,
+    pub policy: &'a CommandPolicy,
+    pub sandbox: &'a Sandbox,
+    /// The process is killed when this elapses
+    pub timeout: Duration,
+}
+
+impl CommandRunner<'_> {
+    /// Checks the command against the policy, then runs it in the sandbox
+    ///
+    /// # Returns
+    /// JSON string with stdout, stderr, and exit_code
+    pub(crate) async fn run(
+        &self,
+        command: &str,
+        args: &[String],
+        cwd: Option<&Path>,
+        scope: &PolicyScope,
+    ) -> ToolResult<String> {
+        security::check_command(self.tool, self.policy, command, args, scope)?;
 
         // Build the command - spawn instead of output to get a handle for killing
         let mut cmd = Command::new(command);
@@ -117,6 +154,15 @@ impl ExecTool {
             cmd.current_dir(dir);
         }
 
+        // Keep the guard until the command is done: it owns the private temp dir
+        let _sandbox_guard =
+            self.sandbox
+                .prepare(&mut cmd)
+                .map_err(|e| ToolError::ExecutionFailed {
+                    tool: self.tool.to_string(),
+                    message: format!("Failed to prepare sandbox for '{}': {}", command, e),
+                })?;
+
         // Spawn the process to get a child handle
         let mut child = cmd.spawn().map_err(|e| {
             // Classify error types for better debugging
@@ -125,11 +171,11 @@ impl ExecTool {
                     ToolError::NotFound(format!("Command '{}' not found in system PATH", command))
                 }
                 std::io::ErrorKind::PermissionDenied => ToolError::PermissionDenied {
-                    tool: self.name().to_string(),
+                    tool: self.tool.to_string(),
                     message: format!("Permission denied executing command '{}'", command),
                 },
                 _ => ToolError::ExecutionFailed {
-                    tool: self.name().to_string(),
+                    tool: self.tool.to_string(),
                     message: format!("Failed to spawn command '{}': {}", command, e),
                 },
             }
@@ -173,19 +219,19 @@ impl ExecTool {
                         });
 
                         serde_json::to_string(&result).map_err(|e| ToolError::ExecutionFailed {
-                            tool: self.name().to_string(),
+                            tool: self.tool.to_string(),
                             message: format!("Failed to serialize command output: {}", e),
                         })
                     }
                     Err(e) => {
                         Err(ToolError::ExecutionFailed {
-                            tool: self.name().to_string(),
+                            tool: self.tool.to_string(),
                             message: format!("Failed to wait for command '{}': {}", command, e),
                         })
                     }
                 }
             }
-            _ = tokio::time::sleep(self.default_timeout) => {
+            _ = tokio::time::sleep(self.timeout) => {
                 // Timeout occurred - kill the process gracefully, then forcefully if needed
                 // Try SIGTERM first (graceful) via start_kill
                 let _ = child.start_kill();
@@ -197,8 +243,8 @@ impl ExecTool {
                 let _ = child.kill().await;
 
                 Err(ToolError::Timeout {
-                    tool: self.name().to_string(),
-                    duration: self.default_timeout.as_secs(),
+                    tool: self.tool.to_string(),
+                    duration: self.timeout.as_secs(),
                 })
             }
         }
@@ -212,10 +258,12 @@ impl Tool for ExecTool {
     }
 
     fn description(&self) -> &str {
-        "Execute shell commands with security constraints. Commands are validated against a blacklist of dangerous operations. \
+        "Execute shell commands with security constraints. Commands and their arguments are checked against the command policy, \
+         which blocks dangerous operations such as rm, sudo, dd, mkfs, shutdown, inline interpreter code and find -delete. \
          Arguments must be provided as an array to prevent shell injection. Optional 'cwd' parameter to set working directory. \
+         Commands run sandboxed: CPU, memory and file limits apply, the environment holds no secrets and $TMPDIR is a private temp directory. \
          Returns stdout, stderr, and exit code as JSON. \
-         Timeout: 30 seconds (process killed if exceeded). Blacklist: rm, sudo, dd, mkfs, shutdown, reboot, passwd, visudo"
+         Timeout: 30 seconds (process killed if exceeded)."
     }
 
     fn parameters(&self) -> Value {
@@ -245,7 +293,7 @@ impl Tool for ExecTool {
     async fn execute(
         &self,
         args: HashMap<String, Value>,
-        _ctx: &ToolExecutionContext,
+        ctx: &ToolExecutionContext,
     ) -> ToolResult<String> {
         // Extract command
         let command = args
@@ -288,8 +336,13 @@ impl Tool for ExecTool {
         };
 
         // Execute the command
-        self.execute_command(command, &args_strings, cwd_path.as_deref())
-            .await
+        self.execute_command(
+            command,
+            &args_strings,
+            cwd_path.as_deref(),
+            &PolicyScope::from(ctx),
+        )
+        .await
     }
 }
 
@@ -298,6 +351,13 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    /// Returns true if the tool's policy denies the command without arguments
+    fn is_denied(tool: &ExecTool, command: &str) -> bool {
+        !tool
+            .policy
+            .is_allowed(command, &[], &PolicyScope::default())
+    }
+
     /// Creates an ExecTool with a temporary directory as base
     fn create_test_tool() -> (ExecTool, TempDir) {
         let temp_dir = TempDir::new().unwrap();
@@ -316,7 +376,7 @@ mod tests {
         let (tool, _temp) = create_test_tool();
         let desc = tool.description();
         assert!(desc.contains("Execute shell commands"));
-        assert!(desc.contains("blacklist"));
+        assert!(desc.contains("command policy"));
         assert!(desc.contains("rm"));
         assert!(desc.contains("sudo"));
     }
@@ -348,45 +408,45 @@ mod tests {
     fn test_blacklist_simple_commands() {
         let (tool, _temp) = create_test_tool();
 
-        assert!(tool.is_blacklisted("rm"));
-        assert!(tool.is_blacklisted("sudo"));
-        assert!(tool.is_blacklisted("dd"));
-        assert!(tool.is_blacklisted("mkfs"));
-        assert!(tool.is_blacklisted("shutdown"));
-        assert!(tool.is_blacklisted("reboot"));
-        assert!(tool.is_blacklisted("passwd"));
-        assert!(tool.is_blacklisted("visudo"));
+        assert!(is_denied(&tool, "rm"));
+        assert!(is_denied(&tool, "sudo"));
+        assert!(is_denied(&tool, "dd"));
+        assert!(is_denied(&tool, "mkfs"));
+        assert!(is_denied(&tool, "shutdown"));
+        assert!(is_denied(&tool, "reboot"));
+        assert!(is_denied(&tool, "passwd"));
+        assert!(is_denied(&tool, "visudo"));
     }
 
     #[test]
     fn test_blacklist_with_paths() {
         let (tool, _temp) = create_test_tool();
 
-        assert!(tool.is_blacklisted("/bin/rm"));
-        assert!(tool.is_blacklisted("/usr/bin/sudo"));
-        assert!(tool.is_blacklisted("./rm"));
+        assert!(is_denied(&tool, "/bin/rm"));
+        assert!(is_denied(&tool, "/usr/bin/sudo"));
+        assert!(is_denied(&tool, "./rm"));
     }
 
     #[test]
     fn test_blacklist_case_insensitive() {
         let (tool, _temp) = create_test_tool();
 
-        assert!(tool.is_blacklisted("RM"));
-        assert!(tool.is_blacklisted("SUDO"));
-        assert!(tool.is_blacklisted("Rm"));
-        assert!(tool.is_blacklisted("SuDo"));
+        assert!(is_denied(&tool, "RM"));
+        assert!(is_denied(&tool, "SUDO"));
+        assert!(is_denied(&tool, "Rm"));
+        assert!(is_denied(&tool, "SuDo"));
     }
 
     #[test]
     fn test_non_blacklisted_commands() {
         let (tool, _temp) = create_test_tool();
 
-        assert!(!tool.is_blacklisted("ls"));
-        assert!(!tool.is_blacklisted("cat"));
-        assert!(!tool.is_blacklisted("echo"));
-        assert!(!tool.is_blacklisted("git"));
-        assert!(!tool.is_blacklisted("cargo"));
-        assert!(!tool.is_blacklisted("pwd"));
+        assert!(!is_denied(&tool, "ls"));
+        assert!(!is_denied(&tool, "cat"));
+        assert!(!is_denied(&tool, "echo"));
+        assert!(!is_denied(&tool, "git"));
+        assert!(!is_denied(&tool, "cargo"));
+        assert!(!is_denied(&tool, "pwd"));
     }
 
     #[tokio::test]
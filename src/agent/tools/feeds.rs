@@ -0,0 +1,1146 @@
+//! Feeds tool for the agent
+//!
+//! Subscribes to RSS and Atom feeds and returns the items published since
+//! the last check. Subscriptions and their cursors (the IDs of items already
+//! returned) persist in `feeds/subscriptions.json` in the workspace, so the
+//! agent, the `miniclaw feeds check` command and scheduled digests share one
+//! state. Feeds are fetched under the web tool's domain policy and private
+//! network protection, with conditional requests so unchanged feeds are not
+//! downloaded again.
+//!
+//! A digest is an interval job of the [`CronScheduler`] running
+//! `miniclaw feeds check`, whose output is sent to the chat that asked for it.
+
+pub mod parser;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agent::tools::policy::PolicyScope;
+use crate::agent::tools::security;
+use crate::agent::tools::ssrf::{self, AddressGuard};
+use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
+use crate::agent::tools::web::{
+    DomainPolicy, WebConfigError, WebToolConfig, guarded_client_builder,
+};
+use crate::cron::CronScheduler;
+use crate::cron::types::{JobNotification, MIN_INTERVAL_MINUTES};
+use parser::{Feed, FeedError, FeedItem};
+
+/// File holding the subscriptions, in `feeds/` under the workspace
+const SUBSCRIPTIONS_FILE: &str = "subscriptions.json";
+
+/// Timeout for fetching one feed in seconds
+const FEED_TIMEOUT_SECS: u64 = 15;
+
+/// Largest feed document read, in bytes
+const MAX_FEED_SIZE: usize = 5 * 1024 * 1024;
+
+/// Item IDs remembered per feed; older ones are forgotten
+const MAX_SEEN_IDS: usize = 1000;
+
+/// Items returned per feed and check unless asked otherwise
+pub const DEFAULT_CHECK_LIMIT: usize = 20;
+
+/// Upper bound on the items returned per feed and check
+const MAX_CHECK_LIMIT: usize = 100;
+
+/// Summaries longer than this many characters are shortened
+const MAX_SUMMARY_CHARS: usize = 300;
+
+/// Items shown when subscribing, as a preview
+const PREVIEW_ITEMS: usize = 3;
+
+const ACTIONS: &[&str] = &[
+    "subscribe",
+    "unsubscribe",
+    "list",
+    "check",
+    "schedule_digest",
+];
+
+/// A feed the workspace is subscribed to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub url: String,
+    /// Title announced by the feed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub subscribed_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked: Option<DateTime<Utc>>,
+    /// Validators of the last response, for conditional requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// IDs of items already returned, newest first
+    #[serde(default)]
+    pub seen: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Subscriptions {
+    feeds: BTreeMap<String, Subscription>,
+}
+
+/// An item returned by a check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewItem {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+}
+
+impl From<&FeedItem> for NewItem {
+    fn from(item: &FeedItem) -> Self {
+        let mut summary = item.summary.clone();
+        if summary.chars().count() > MAX_SUMMARY_CHARS {
+            summary = summary.chars().take(MAX_SUMMARY_CHARS).collect();
+            summary.push('…');
+        }
+        Self {
+            title: item.title.clone(),
+            link: item.link.clone(),
+            published: item.published.map(|date| date.to_rfc3339()),
+            summary,
+        }
+    }
+}
+
+/// Result of checking one feed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FeedUpdate {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// New items, newest first
+    pub items: Vec<NewItem>,
+    /// New items left out because of the limit
+    #[serde(skip_serializing_if = "is_zero")]
+    pub omitted: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Errors raised by feed operations
+#[derive(Debug, thiserror::Error)]
+pub enum FeedsError {
+    #[error("No feed named '{0}'")]
+    NotFound(String),
+    #[error("Already subscribed to this feed as '{0}'")]
+    AlreadySubscribed(String),
+    #[error("A feed named '{0}' already exists")]
+    NameTaken(String),
+    #[error("Invalid feed name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+    #[error("Invalid feed URL '{0}': expected an http:// or https:// URL")]
+    InvalidUrl(String),
+    #[error("{0}")]
+    Blocked(String),
+    #[error("Failed to fetch feed: {0}")]
+    Fetch(String),
+    #[error(transparent)]
+    Parse(#[from] FeedError),
+    #[error("Failed to access feed subscriptions: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Corrupt feed subscriptions: {0}")]
+    State(#[from] serde_json::Error),
+}
+
+/// A fetched feed with the validators of its response
+struct Fetched {
+    feed: Feed,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Subscriptions of a workspace and the client fetching them
+#[derive(Debug)]
+pub struct FeedStore {
+    dir: PathBuf,
+    client: reqwest::Client,
+    domains: Arc<DomainPolicy>,
+    addresses: Arc<AddressGuard>,
+    /// Serializes changes to the subscriptions file within this process
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl FeedStore {
+    /// Opens the subscriptions of `workspace`, reaching only the hosts the
+    /// web tool config allows
+    pub fn new(workspace: &Path, web: &WebToolConfig) -> Result<Self, WebConfigError> {
+        let domains = Arc::new(DomainPolicy::new(
+            &web.allowed_domains,
+            &web.denied_domains,
+        )?);
+        let addresses = Arc::new(AddressGuard::new(
+            &web.local_services,
+            web.allow_private_networks,
+        )?);
+
+        let client = guarded_client_builder(&domains, &addresses, None)
+            .timeout(Duration::from_secs(FEED_TIMEOUT_SECS))
+            .user_agent("miniclaw/0.1.0 (feed reader)")
+            .build()
+            .map_err(|e| WebConfigError::Client(e.to_string()))?;
+
+        Ok(Self {
+            dir: workspace.join("feeds"),
+            client,
+            domains,
+            addresses,
+            lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// Subscribes to the feed at `url`
+    ///
+    /// Items already in the feed count as seen, so the first check returns
+    /// only what is published afterwards. Without a name, one is derived from
+    /// the feed title.
+    pub async fn subscribe(
+        &self,
+        name: Option<&str>,
+        url: &str,
+    ) -> Result<(String, Subscription, Feed), FeedsError> {
+        let parsed = reqwest::Url::parse(url)
+            .ok()
+            .filter(|parsed| matches!(parsed.scheme(), "http" | "https"))
+            .ok_or_else(|| FeedsError::InvalidUrl(url.to_string()))?;
+        if let Some(name) = name {
+            validate_name(name)?;
+        }
+
+        let _guard = self.lock.lock().await;
+        let mut state = self.load().await?;
+        if let Some((existing, _)) = state
+            .feeds
+            .iter()
+            .find(|(_, sub)| sub.url == parsed.as_str())
+        {
+            return Err(FeedsError::AlreadySubscribed(existing.clone()));
+        }
+
+        let fetched = self
+            .fetch(parsed.as_str(), None, None)
+            .await?
+            .ok_or_else(|| FeedsError::Fetch("unexpected 304 Not Modified".to_string()))?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => derive_name(fetched.feed.title.as_deref(), &parsed),
+        };
+        if state.feeds.contains_key(&name) {
+            return Err(FeedsError::NameTaken(name));
+        }
+
+        let subscription = Subscription {
+            url: parsed.to_string(),
+            title: fetched.feed.title.clone(),
+            subscribed_at: Utc::now(),
+            last_checked: Some(Utc::now()),
+            etag: fetched.etag,
+            last_modified: fetched.last_modified,
+            seen: fetched
+                .feed
+                .items
+                .iter()
+                .map(|item| item.id.clone())
+                .take(MAX_SEEN_IDS)
+                .collect(),
+        };
+        state.feeds.insert(name.clone(), subscription.clone());
+        self.save(&state).await?;
+        Ok((name, subscription, fetched.feed))
+    }
+
+    /// Removes a subscription
+    pub async fn unsubscribe(&self, name: &str) -> Result<Subscription, FeedsError> {
+        let _guard = self.lock.lock().await;
+        let mut state = self.load().await?;
+        let subscription = state
+            .feeds
+            .remove(name)
+            .ok_or_else(|| FeedsError::NotFound(name.to_string()))?;
+        self.save(&state).await?;
+        Ok(subscription)
+    }
+
+    /// Lists the subscriptions by name
+    pub async fn list(&self) -> Result<BTreeMap<String, Subscription>, FeedsError> {
+        Ok(self.load().await?.feeds)
+    }
+
+    /// Returns the items published since the last check, for one feed or all
+    ///
+    /// At most `limit` items are returned per feed, newest first. With
+    /// `advance`, all new items (including omitted ones) are marked as seen;
+    /// otherwise the check is a peek that leaves the cursors alone. A feed
+    /// that cannot be fetched is reported in its update's `error`.
+    pub async fn check(
+        &self,
+        name: Option<&str>,
+        limit: usize,
+        advance: bool,
+    ) -> Result<Vec<FeedUpdate>, FeedsError> {
+        let _guard = self.lock.lock().await;
+        let mut state = self.load().await?;
+        let names: Vec<String> = match name {
+            Some(name) if state.feeds.contains_key(name) => vec![name.to_string()],
+            Some(name) => return Err(FeedsError::NotFound(name.to_string())),
+            None => state.feeds.keys().cloned().collect(),
+        };
+
+        let fetches = names.iter().map(|name| {
+            let subscription = &state.feeds[name];
+            self.fetch(
+                &subscription.url,
+                subscription.etag.as_deref(),
+                subscription.last_modified.as_deref(),
+            )
+        });
+        let results = futures::future::join_all(fetches).await;
+
+        let now = Utc::now();
+        let mut updates = Vec::with_capacity(names.len());
+        for (name, result) in names.into_iter().zip(results) {
+            let Some(subscription) = state.feeds.get_mut(&name) else {
+                continue;
+            };
+            let mut update = FeedUpdate {
+                name,
+                title: subscription.title.clone(),
+                items: Vec::new(),
+                omitted: 0,
+                error: None,
+            };
+            match result {
+                Err(e) => update.error = Some(e.to_string()),
+                // Not modified since the last check
+                Ok(None) => {
+                    if advance {
+                        subscription.last_checked = Some(now);
+                    }
+                }
+                Ok(Some(fetched)) => {
+                    let seen: HashSet<&str> =
+                        subscription.seen.iter().map(String::as_str).collect();
+                    let mut new_items: Vec<&FeedItem> = fetched
+                        .feed
+                        .items
+                        .iter()
+                        .filter(|item| !seen.contains(item.id.as_str()))
+                        .collect();
+                    // Newest first; undated items keep their document order at the end
+                    new_items.sort_by_key(|item| std::cmp::Reverse(item.published));
+                    let mut unique = HashSet::new();
+                    new_items.retain(|item| unique.insert(item.id.as_str()));
+
+                    update.omitted = new_items.len().saturating_sub(limit);
+                    update.items = new_items
+                        .iter()
+                        .take(limit)
+                        .map(|item| (*item).into())
+                        .collect();
+                    if fetched.feed.title.is_some() {
+                        update.title = fetched.feed.title.clone();
+                    }
+
+                    if advance {
+                        let mut seen_ids: Vec<String> =
+                            new_items.iter().map(|item| item.id.clone()).collect();
+                        seen_ids.append(&mut subscription.seen);
+                        seen_ids.truncate(MAX_SEEN_IDS);
+                        subscription.seen = seen_ids;
+                        subscription.title = update.title.clone();
+                        subscription.etag = fetched.etag;
+                        subscription.last_modified = fetched.last_modified;
+                        subscription.last_checked = Some(now);
+                    }
+                }
+            }
+            updates.push(update);
+        }
+
+        if advance {
+            self.save(&state).await?;
+        }
+        Ok(updates)
+    }
+
+    /// Fetches and parses a feed; `None` when the server answers 304
+    async fn fetch(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<Option<Fetched>, FeedsError> {
+        let parsed =
+            reqwest::Url::parse(url).map_err(|_| FeedsError::InvalidUrl(url.to_string()))?;
+        self.domains
+            .check(parsed.host_str().unwrap_or_default())
+            .map_err(FeedsError::Blocked)?;
+        self.addresses
+            .check_url(&parsed)
+            .map_err(|blocked| FeedsError::Blocked(blocked.to_string()))?;
+
+        let mut request = self.client.get(parsed).header(
+            header::ACCEPT,
+            "application/rss+xml, application/atom+xml, application/xml;q=0.9, text/xml;q=0.9, */*;q=0.5",
+        );
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let mut response = request.send().await.map_err(|e| {
+            if let Some(blocked) = ssrf::find_blocked(&e) {
+                FeedsError::Blocked(blocked.to_string())
+            } else if e.is_redirect() {
+                let reason = std::error::Error::source(&e)
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| e.to_string());
+                FeedsError::Blocked(format!("Redirect not followed: {}", reason))
+            } else {
+                FeedsError::Fetch(e.to_string())
+            }
+        })?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(FeedsError::Fetch(format!("HTTP {}", status.as_u16())));
+        }
+        let header_text = |name: header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header_text(header::ETAG);
+        let last_modified = header_text(header::LAST_MODIFIED);
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| FeedsError::Fetch(e.to_string()))?
+        {
+            if body.len() + chunk.len() > MAX_FEED_SIZE {
+                return Err(FeedsError::Fetch(format!(
+                    "feed is larger than {} MB",
+                    MAX_FEED_SIZE / (1024 * 1024)
+                )));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let feed = parser::parse(&String::from_utf8_lossy(&body))?;
+        Ok(Some(Fetched {
+            feed,
+            etag,
+            last_modified,
+        }))
+    }
+
+    async fn load(&self) -> Result<Subscriptions, FeedsError> {
+        match tokio::fs::read(self.dir.join(SUBSCRIPTIONS_FILE)).await {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Subscriptions::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the subscriptions through a temporary file
+    async fn save(&self, state: &Subscriptions) -> Result<(), FeedsError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.dir.join(SUBSCRIPTIONS_FILE);
+        let temp = self.dir.join(format!("{}.tmp", SUBSCRIPTIONS_FILE));
+        tokio::fs::write(&temp, serde_json::to_vec_pretty(state)?).await?;
+        tokio::fs::rename(&temp, &path).await?;
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), FeedsError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(FeedsError::InvalidName(name.to_string()))
+    }
+}
+
+/// Turns a feed title (or the host when there is none) into a feed name
+fn derive_name(title: Option<&str>, url: &reqwest::Url) -> String {
+    let source = title
+        .filter(|title| title.chars().any(|c| c.is_ascii_alphanumeric()))
+        .or_else(|| url.host_str())
+        .unwrap_or("feed");
+    let mut name = String::new();
+    for c in source.chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_lowercase());
+        } else if !name.is_empty() && !name.ends_with('-') {
+            name.push('-');
+        }
+        if name.len() >= 40 {
+            break;
+        }
+    }
+    let name = name.trim_end_matches('-');
+    if name.is_empty() {
+        "feed".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Renders the updates of a check as a message; empty when there is nothing
+/// new and nothing failed
+pub fn format_digest(updates: &[FeedUpdate]) -> String {
+    let mut sections = Vec::new();
+    for update in updates {
+        let title = update.title.as_deref().unwrap_or(&update.name);
+        if let Some(error) = &update.error {
+            sections.push(format!("⚠️ {}: {}", title, error));
+            continue;
+        }
+        if update.items.is_empty() {
+            continue;
+        }
+        let mut section = format!("📰 {} ({} new)", title, update.items.len() + update.omitted);
+        for item in &update.items {
+            section.push_str(&format!("\n• {}", item.title));
+            if let Some(link) = &item.link {
+                section.push_str(&format!("\n  {}", link));
+            }
+        }
+        if update.omitted > 0 {
+            section.push_str(&format!("\n…and {} more", update.omitted));
+        }
+        sections.push(section);
+    }
+    sections.join("\n\n")
+}
+
+/// Tool for following RSS and Atom feeds
+///
+/// Wraps a [`FeedStore`] and, when given a scheduler, sets up periodic
+/// digests as cron jobs running `miniclaw feeds check`.
+#[derive(Debug)]
+pub struct FeedsTool {
+    store: FeedStore,
+    workspace: PathBuf,
+    scheduler: Option<CronScheduler>,
+}
+
+impl FeedsTool {
+    /// Creates a feeds tool for `workspace`, fetching under the web tool's
+    /// domain policy and private network protection
+    pub fn new(workspace: PathBuf, web: &WebToolConfig) -> Result<Self, WebConfigError> {
+        Ok(Self {
+            store: FeedStore::new(&workspace, web)?,
+            workspace,
+            scheduler: None,
+        })
+    }
+
+    /// Enables the `schedule_digest` action, scheduling jobs on `scheduler`
+    pub fn with_scheduler(mut self, scheduler: CronScheduler) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    fn invalid_argument(&self, message: impl Into<String>) -> ToolError {
+        ToolError::InvalidArguments {
+            tool: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn map_error(&self, error: FeedsError) -> ToolError {
+        let tool = self.name().to_string();
+        match error {
+            FeedsError::NotFound(name) => ToolError::NotFound(format!("No feed named '{}'", name)),
+            FeedsError::Blocked(message) => ToolError::PermissionDenied { tool, message },
+            error @ (FeedsError::AlreadySubscribed(_)
+            | FeedsError::NameTaken(_)
+            | FeedsError::InvalidName(_)
+            | FeedsError::InvalidUrl(_)) => ToolError::InvalidArguments {
+                tool,
+                message: error.to_string(),
+            },
+            error => ToolError::ExecutionFailed {
+                tool,
+                message: error.to_string(),
+            },
+        }
+    }
+
+    fn string_arg<'a>(&self, args: &'a HashMap<String, Value>, name: &str) -> Option<&'a str> {
+        args.get(name)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    async fn subscribe(&self, args: &HashMap<String, Value>) -> ToolResult<String> {
+        let url = self.string_arg(args, "url").ok_or_else(|| {
+            self.invalid_argument("Missing required parameter 'url' for subscribe")
+        })?;
+        let (name, subscription, feed) = self
+            .store
+            .subscribe(self.string_arg(args, "feed"), url)
+            .await
+            .map_err(|e| self.map_error(e))?;
+        let latest: Vec<NewItem> = feed
+            .items
+            .iter()
+            .take(PREVIEW_ITEMS)
+            .map(NewItem::from)
+            .collect();
+        Ok(serde_json::json!({
+            "subscribed": name,
+            "url": subscription.url,
+            "title": subscription.title,
+            "items_in_feed": feed.items.len(),
+            "latest": latest,
+            "note": "Existing items are marked as seen; 'check' returns items published from now on.",
+        })
+        .to_string())
+    }
+
+    async fn check(&self, args: &HashMap<String, Value>) -> ToolResult<String> {
+        let limit = match args.get("limit") {
+            None | Some(Value::Null) => DEFAULT_CHECK_LIMIT,
+            Some(value) => value
+                .as_u64()
+                .filter(|n| (1..=MAX_CHECK_LIMIT as u64).contains(n))
+                .ok_or_else(|| {
+                    self.invalid_argument(format!(
+                        "'limit' must be an integer between 1 and {}",
+                        MAX_CHECK_LIMIT
+                    ))
+                })? as usize,
+        };
+        let peek = args.get("peek").and_then(Value::as_bool).unwrap_or(false);
+        let updates = self
+            .store
+            .check(self.string_arg(args, "feed"), limit, !peek)
+            .await
+            .map_err(|e| self.map_error(e))?;
+        let total: usize = updates
+            .iter()
+            .map(|update| update.items.len() + update.omitted)
+            .sum();
+        Ok(serde_json::json!({
+            "new_items": total,
+            "feeds": updates,
+            "marked_seen": !peek,
+        })
+        .to_string())
+    }
+
+    async fn schedule_digest(
+        &self,
+        args: &HashMap<String, Value>,
+        ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let scheduler = self
+            .scheduler
+            .as_ref()
+            .filter(|scheduler| scheduler.can_notify())
+            .ok_or_else(|| ToolError::ExecutionFailed {
+                tool: self.name().to_string(),
+                message: "Scheduled digests are not available".to_string(),
+            })?;
+        let (Some(channel), Some(chat_id)) = (&ctx.channel, &ctx.chat_id) else {
+            return Err(self.invalid_argument("'schedule_digest' needs a chat to send digests to"));
+        };
+        let minutes = args
+            .get("minutes")
+            .and_then(Value::as_u64)
+            .and_then(|minutes| u32::try_from(minutes).ok())
+            .filter(|minutes| *minutes >= MIN_INTERVAL_MINUTES)
+            .ok_or_else(|| {
+                self.invalid_argument(format!(
+                    "'minutes' must be an integer of at least {}",
+                    MIN_INTERVAL_MINUTES
+                ))
+            })?;
+        let feed = self.string_arg(args, "feed");
+        if let Some(feed) = feed {
+            let feeds = self.store.list().await.map_err(|e| self.map_error(e))?;
+            if !feeds.contains_key(feed) {
+                return Err(self.map_error(FeedsError::NotFound(feed.to_string())));
+            }
+        }
+
+        let command = std::env::current_exe()
+            .map_err(|e| ToolError::ExecutionFailed {
+                tool: self.name().to_string(),
+                message: format!("Failed to locate the miniclaw executable: {}", e),
+            })?
+            .to_string_lossy()
+            .into_owned();
+        let mut command_args = vec![
+            "feeds".to_string(),
+            "check".to_string(),
+            "--workspace".to_string(),
+            self.workspace.to_string_lossy().into_owned(),
+        ];
+        if let Some(feed) = feed {
+            command_args.extend(["--feed".to_string(), feed.to_string()]);
+        }
+
+        let scope = PolicyScope::from(ctx);
+        security::check_command(
+            self.name(),
+            scheduler.policy(),
+            &command,
+            &command_args,
+            &scope,
+        )?;
+        let notify = JobNotification {
+            channel: channel.clone(),
+            chat_id: chat_id.clone(),
+        };
+        let scheduled = scheduler
+            .schedule_interval(command, minutes, Some(command_args), scope, Some(notify))
+            .await
+            .map_err(|message| self.invalid_argument(message))?;
+
+        Ok(serde_json::json!({
+            "job_id": scheduled.job_id,
+            "every_minutes": minutes,
+            "feed": feed,
+            "next_execution": scheduled.next_execution.to_rfc3339(),
+            "note": "New items are sent to this chat; runs without new items stay silent. Cancel with the cron tool.",
+        })
+        .to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for FeedsTool {
+    fn name(&self) -> &str {
+        "feeds"
+    }
+
+    fn description(&self) -> &str {
+        "Follow RSS and Atom feeds. 'subscribe' to a feed URL (optionally naming it with 'feed'), \
+         'list' subscriptions, 'check' for items published since the last check (all feeds, or one with 'feed'; \
+         'peek' to leave them unread), 'unsubscribe', or 'schedule_digest' to have new items sent to this chat \
+         every 'minutes'. Prefer this over scraping news pages with the web tool; open an item's link with 'web'."
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ACTIONS,
+                    "description": "Operation to perform"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "Feed URL (subscribe)"
+                },
+                "feed": {
+                    "type": "string",
+                    "description": "Feed name (subscribe: optional name for the new feed; check, schedule_digest: one feed instead of all; unsubscribe)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_CHECK_LIMIT,
+                    "description": format!("Maximum new items returned per feed (check, default {})", DEFAULT_CHECK_LIMIT)
+                },
+                "peek": {
+                    "type": "boolean",
+                    "description": "Return new items without marking them as seen (check)"
+                },
+                "minutes": {
+                    "type": "integer",
+                    "minimum": MIN_INTERVAL_MINUTES,
+                    "description": "Interval between digests in minutes (schedule_digest)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, Value>,
+        ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let action = self
+            .string_arg(&args, "action")
+            .ok_or_else(|| self.invalid_argument("Missing required parameter 'action'"))?;
+
+        match action {
+            "subscribe" => self.subscribe(&args).await,
+            "unsubscribe" => {
+                let name = self.string_arg(&args, "feed").ok_or_else(|| {
+                    self.invalid_argument("Missing required parameter 'feed' for unsubscribe")
+                })?;
+                let subscription = self
+                    .store
+                    .unsubscribe(name)
+                    .await
+                    .map_err(|e| self.map_error(e))?;
+                Ok(serde_json::json!({
+                    "unsubscribed": name,
+                    "url": subscription.url,
+                })
+                .to_string())
+            }
+            "list" => {
+                let feeds = self.store.list().await.map_err(|e| self.map_error(e))?;
+                let feeds: Vec<Value> = feeds
+                    .into_iter()
+                    .map(|(name, subscription)| {
+                        serde_json::json!({
+                            "feed": name,
+                            "url": subscription.url,
+                            "title": subscription.title,
+                            "last_checked": subscription.last_checked.map(|date| date.to_rfc3339()),
+                        })
+                    })
+                    .collect();
+                Ok(serde_json::json!({ "feeds": feeds }).to_string())
+            }
+            "check" => self.check(&args).await,
+            "schedule_digest" => self.schedule_digest(&args, ctx).await,
+            _ => Err(self.invalid_argument(format!(
+                "Invalid action '{}'. Expected one of: {}",
+                action,
+                ACTIONS.join(", ")
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    fn args(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn rss(items: &[(&str, &str)]) -> String {
+        let items: String = items
+            .iter()
+            .map(|(id, date)| {
+                format!(
+                    "<item><title>Item {id}</title><link>https://news.test/{id}</link><guid>{id}</guid><pubDate>{date}</pubDate></item>"
+                )
+            })
+            .collect();
+        format!("<rss version=\"2.0\"><channel><title>Test News</title>{items}</channel></rss>")
+    }
+
+    /// Serves `responses` in turn, one per connection: a document with its
+    /// ETag, or `None` for 304 Not Modified. Returns the feed URL and the
+    /// requests received.
+    async fn serve_feed(
+        responses: Vec<Option<(&'static str, String)>>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                seen.lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase());
+                let response = match response {
+                    None => "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string(),
+                    Some((etag, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/rss+xml\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        etag,
+                        body.len(),
+                        body
+                    ),
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{}/feed.xml", addr), requests)
+    }
+
+    async fn run(tool: &FeedsTool, pairs: &[(&str, Value)]) -> Value {
+        let result = tool
+            .execute(args(pairs), &ToolExecutionContext::default())
+            .await
+            .unwrap();
+        serde_json::from_str(&result).unwrap()
+    }
+    fn local_config() -> WebToolConfig {
+        WebToolConfig {
+            local_services: vec!["127.0.0.1".to_string()],
+            ..WebToolConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_derive_name() {
+        let url = reqwest::Url::parse("https://blog.example.org/feed").unwrap();
+        assert_eq!(
+            derive_name(Some("Hacker News: Front Page"), &url),
+            "hacker-news-front-page"
+        );
+        assert_eq!(derive_name(Some("日本"), &url), "blog-example-org");
+        assert_eq!(derive_name(None, &url), "blog-example-org");
+        assert!(validate_name("hn_front-1").is_ok());
+        assert!(validate_name("no spaces").is_err());
+    }
+
+    #[test]
+    fn test_format_digest() {
+        let update = |items: usize, omitted, error: Option<&str>| FeedUpdate {
+            name: "news".to_string(),
+            title: Some("Test News".to_string()),
+            items: (0..items)
+                .map(|i| NewItem {
+                    title: format!("Item {}", i),
+                    link: Some(format!("https://news.test/{}", i)),
+                    published: None,
+                    summary: String::new(),
+                })
+                .collect(),
+            omitted,
+            error: error.map(str::to_string),
+        };
+        assert_eq!(format_digest(&[update(0, 0, None)]), "");
+        let digest = format_digest(&[update(2, 3, None), update(0, 0, Some("HTTP 500"))]);
+        assert!(digest.starts_with("📰 Test News (5 new)\n• Item 0\n  https://news.test/0"));
+        assert!(digest.contains("…and 3 more"));
+        assert!(digest.ends_with("⚠️ Test News: HTTP 500"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_check() {
+        let first = rss(&[("1", "Mon, 02 Jun 2025 10:00:00 GMT")]);
+        let second = rss(&[
+            ("3", "Wed, 04 Jun 2025 10:00:00 GMT"),
+            ("2", "Tue, 03 Jun 2025 10:00:00 GMT"),
+            ("1", "Mon, 02 Jun 2025 10:00:00 GMT"),
+        ]);
+        let (url, requests) = serve_feed(vec![
+            Some(("\"v1\"", first)),
+            Some(("\"v2\"", second.clone())),
+            Some(("\"v2\"", second)),
+            None,
+        ])
+        .await;
+        let workspace = TempDir::new().unwrap();
+        let tool = FeedsTool::new(workspace.path().to_path_buf(), &local_config()).unwrap();
+
+        let subscribed = run(
+            &tool,
+            &[
+                ("action", serde_json::json!("subscribe")),
+                ("url", serde_json::json!(url)),
+            ],
+        )
+        .await;
+        assert_eq!(subscribed["subscribed"], "test-news");
+        assert_eq!(subscribed["latest"][0]["title"], "Item 1");
+
+        // Existing items are already seen
+        let peeked = run(
+            &tool,
+            &[
+                ("action", serde_json::json!("check")),
+                ("peek", serde_json::json!(true)),
+            ],
+        )
+        .await;
+        assert_eq!(peeked["new_items"], 2);
+        assert_eq!(peeked["feeds"][0]["items"][0]["title"], "Item 3");
+        assert_eq!(peeked["marked_seen"], false);
+        // The peek sent the stored ETag but did not replace it
+        assert!(requests.lock().unwrap()[1].contains("if-none-match: \"v1\""));
+
+        let checked = run(
+            &tool,
+            &[
+                ("action", serde_json::json!("check")),
+                ("feed", serde_json::json!("test-news")),
+                ("limit", serde_json::json!(1)),
+            ],
+        )
+        .await;
+        assert_eq!(checked["new_items"], 2);
+        assert_eq!(checked["feeds"][0]["items"].as_array().unwrap().len(), 1);
+        assert_eq!(checked["feeds"][0]["omitted"], 1);
+
+        // Cursors persisted: nothing new, and the feed answers 304
+        let store = FeedStore::new(workspace.path(), &local_config()).unwrap();
+        let updates = store.check(None, 10, true).await.unwrap();
+        assert!(updates[0].items.is_empty());
+        assert!(requests.lock().unwrap()[3].contains("if-none-match: \"v2\""));
+        let subscription = &store.list().await.unwrap()["test-news"];
+        assert_eq!(subscription.seen[..3], ["3", "2", "1"]);
+        assert_eq!(subscription.etag.as_deref(), Some("\"v2\""));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_errors() {
+        let workspace = TempDir::new().unwrap();
+        let tool =
+            FeedsTool::new(workspace.path().to_path_buf(), &WebToolConfig::default()).unwrap();
+        let ctx = ToolExecutionContext::default();
+
+        let result = tool
+            .execute(
+                args(&[
+                    ("action", serde_json::json!("subscribe")),
+                    ("url", serde_json::json!("http://169.254.169.254/feed")),
+                ]),
+                &ctx,
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::PermissionDenied { .. })));
+
+        for invalid in [
+            args(&[("action", serde_json::json!("subscribe"))]),
+            args(&[
+                ("action", serde_json::json!("subscribe")),
+                ("url", serde_json::json!("file:///etc/passwd")),
+            ]),
+            args(&[
+                ("action", serde_json::json!("subscribe")),
+                ("url", serde_json::json!("https://news.test/feed")),
+                ("feed", serde_json::json!("bad name")),
+            ]),
+            args(&[
+                ("action", serde_json::json!("check")),
+                ("limit", serde_json::json!(0)),
+            ]),
+            args(&[("action", serde_json::json!("refresh"))]),
+        ] {
+            assert!(matches!(
+                tool.execute(invalid, &ctx).await,
+                Err(ToolError::InvalidArguments { .. })
+            ));
+        }
+
+        let result = tool
+            .execute(
+                args(&[
+                    ("action", serde_json::json!("unsubscribe")),
+                    ("feed", serde_json::json!("missing")),
+                ]),
+                &ctx,
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::NotFound(_))));
+
+        let listed = tool
+            .execute(args(&[("action", serde_json::json!("list"))]), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(listed, r#"{"feeds":[]}"#);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_digest() {
+        let workspace = TempDir::new().unwrap();
+        let hub = Arc::new(crate::chat::ChatHub::new());
+        let scheduler = CronScheduler::new().with_chat_hub(hub);
+        let tool = FeedsTool::new(workspace.path().to_path_buf(), &WebToolConfig::default())
+            .unwrap()
+            .with_scheduler(scheduler.clone());
+        let ctx = ToolExecutionContext {
+            channel: Some("telegram".to_string()),
+            chat_id: Some("42".to_string()),
+            user_id: None,
+        };
+
+        let result = tool
+            .execute(
+                args(&[
+                    ("action", serde_json::json!("schedule_digest")),
+                    ("minutes", serde_json::json!(60)),
+                ]),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        let output: Value = serde_json::from_str(&result).unwrap();
+        let job = scheduler
+            .get_job(output["job_id"].as_str().unwrap())
+            .await
+            .unwrap();
+        let job_args = job.args.unwrap();
+        assert_eq!(job_args[..3], ["feeds", "check", "--workspace"]);
+        assert_eq!(job_args[3], workspace.path().to_string_lossy());
+        assert_eq!(
+            job.notify,
+            Some(JobNotification {
+                channel: "telegram".to_string(),
+                chat_id: "42".to_string(),
+            })
+        );
+
+        // Needs a chat, a valid interval and an existing feed
+        for (pairs, context) in [
+            (
+                vec![("minutes", serde_json::json!(60))],
+                ToolExecutionContext::default(),
+            ),
+            (vec![("minutes", serde_json::json!(1))], ctx.clone()),
+            (
+                vec![
+                    ("minutes", serde_json::json!(60)),
+                    ("feed", serde_json::json!("missing")),
+                ],
+                ctx.clone(),
+            ),
+        ] {
+            let mut call = args(&pairs);
+            call.insert("action".to_string(), serde_json::json!("schedule_digest"));
+            assert!(tool.execute(call, &context).await.is_err());
+        }
+    }
+}
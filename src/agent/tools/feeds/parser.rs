@@ -0,0 +1,526 @@
+//! RSS and Atom parsing
+//!
+//! A small, lenient XML reader builds an element tree that is then read as
+//! RSS 2.0 (`<rss><channel><item>`), RSS 1.0 (`<rdf:RDF>` with `<item>`
+//! siblings of the channel) or Atom (`<feed><entry>`). Namespace prefixes are
+//! kept in element names, so `content:encoded` and `dc:date` can be told
+//! apart from `description` and `pubDate`. Malformed markup is tolerated:
+//! unclosed elements are closed at the end and stray end tags ignored.
+
+use chrono::{DateTime, Utc};
+
+use crate::agent::tools::html;
+
+/// A parsed feed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Feed {
+    pub title: Option<String>,
+    /// Website of the feed
+    pub link: Option<String>,
+    /// Items in document order, usually newest first
+    pub items: Vec<FeedItem>,
+}
+
+/// One entry of a feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedItem {
+    /// Stable identifier: guid, Atom id, link or title, in that order
+    pub id: String,
+    pub title: String,
+    pub link: Option<String>,
+    pub published: Option<DateTime<Utc>>,
+    /// Summary or content with markup removed
+    pub summary: String,
+}
+
+/// Errors raised for documents that are not feeds
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FeedError {
+    #[error("Document is not XML")]
+    NotXml,
+    #[error("Unsupported feed format: root element <{0}> (expected RSS or Atom)")]
+    UnsupportedFormat(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    /// Local part of the name, without namespace prefix
+    fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// First child element with exactly this (prefixed) name
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Concatenated text of the element and its descendants
+    fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text.trim().to_string()
+    }
+
+    fn collect_text(&self, out: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(element) => element.collect_text(out),
+            }
+        }
+    }
+
+    /// Text of the first child with one of `names` that is not blank
+    fn child_text(&self, names: &[&str]) -> Option<String> {
+        names
+            .iter()
+            .filter_map(|name| self.child(name))
+            .map(Element::text)
+            .find(|text| !text.is_empty())
+    }
+}
+
+/// Parses an RSS or Atom document
+pub fn parse(document: &str) -> Result<Feed, FeedError> {
+    let root = parse_xml(document).ok_or(FeedError::NotXml)?;
+    match root.local_name() {
+        "rss" | "RDF" => Ok(parse_rss(&root)),
+        "feed" => Ok(parse_atom(&root)),
+        _ => Err(FeedError::UnsupportedFormat(root.name.clone())),
+    }
+}
+
+/// Reads RSS 2.0 items from the channel, or RSS 1.0 items next to it
+fn parse_rss(root: &Element) -> Feed {
+    let channel = root.child("channel");
+    let items = channel
+        .into_iter()
+        .flat_map(Element::elements)
+        .chain(root.elements())
+        .filter(|element| element.local_name() == "item");
+    let channel = channel.unwrap_or(root);
+    Feed {
+        title: channel.child_text(&["title"]),
+        link: channel.child_text(&["link"]),
+        items: items.map(parse_rss_item).collect(),
+    }
+}
+
+fn parse_rss_item(item: &Element) -> FeedItem {
+    let link = item.child_text(&["link"]).or_else(|| {
+        // A guid is a permalink unless it says otherwise
+        item.child("guid")
+            .filter(|guid| guid.attribute("isPermaLink") != Some("false"))
+            .map(Element::text)
+            .filter(|guid| guid.starts_with("http://") || guid.starts_with("https://"))
+    });
+    let title = item.child_text(&["title"]).unwrap_or_default();
+    let published = item
+        .child_text(&["pubDate", "dc:date"])
+        .and_then(|date| parse_date(&date));
+    let summary = item
+        .child_text(&["description", "content:encoded"])
+        .unwrap_or_default();
+    let id = item
+        .child_text(&["guid"])
+        .or_else(|| item.attribute("rdf:about").map(str::to_string))
+        .or_else(|| link.clone())
+        .unwrap_or_else(|| title.clone());
+    FeedItem {
+        id,
+        title: plain_text(&title),
+        link,
+        published,
+        summary: plain_text(&summary),
+    }
+}
+
+fn parse_atom(feed: &Element) -> Feed {
+    Feed {
+        title: feed.child_text(&["title"]).map(|title| plain_text(&title)),
+        link: atom_link(feed),
+        items: feed
+            .elements()
+            .filter(|element| element.local_name() == "entry")
+            .map(parse_atom_entry)
+            .collect(),
+    }
+}
+
+fn parse_atom_entry(entry: &Element) -> FeedItem {
+    let link = atom_link(entry);
+    let title = entry.child_text(&["title"]).unwrap_or_default();
+    let published = entry
+        .child_text(&["published", "updated"])
+        .and_then(|date| parse_date(&date));
+    let summary = entry
+        .child_text(&["summary", "content"])
+        .unwrap_or_default();
+    let id = entry
+        .child_text(&["id"])
+        .or_else(|| link.clone())
+        .unwrap_or_else(|| title.clone());
+    FeedItem {
+        id,
+        title: plain_text(&title),
+        link,
+        published,
+        summary: plain_text(&summary),
+    }
+}
+
+/// The `alternate` link of an Atom feed or entry; a link without `rel` is one
+fn atom_link(element: &Element) -> Option<String> {
+    element
+        .elements()
+        .filter(|child| child.local_name() == "link")
+        .find(|link| link.attribute("rel").is_none_or(|rel| rel == "alternate"))
+        .and_then(|link| link.attribute("href"))
+        .map(str::to_string)
+}
+
+/// Parses RFC 822 dates (RSS) and RFC 3339 dates (Atom, Dublin Core)
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    DateTime::parse_from_rfc2822(text)
+        .or_else(|_| DateTime::parse_from_rfc3339(text))
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Removes markup from HTML carried in feed text and collapses whitespace
+fn plain_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    html::decode_entities(&out)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Builds the element tree of an XML document, returning its root element
+fn parse_xml(document: &str) -> Option<Element> {
+    // Open elements; the bottom one collects the top-level elements
+    let mut stack = vec![Element::default()];
+    let mut rest = document.trim_start_matches('\u{feff}');
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(&mut stack, rest);
+            break;
+        };
+        push_text(&mut stack, &rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").unwrap_or(after.len());
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(Node::Text(after[..end].to_string()));
+            }
+            rest = after.get(end + 3..).unwrap_or("");
+        } else if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+        } else if rest.starts_with("<?") {
+            rest = rest.find("?>").map_or("", |end| &rest[end + 2..]);
+        } else if rest.starts_with("<!") {
+            rest = skip_declaration(rest);
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').unwrap_or(after.len());
+            let name = after[..end].trim();
+            // Close up to the matching element; ignore stray end tags
+            if let Some(depth) = stack.iter().rposition(|element| element.name == name) {
+                if depth > 0 {
+                    while stack.len() > depth {
+                        close_element(&mut stack);
+                    }
+                }
+            }
+            rest = after.get(end + 1..).unwrap_or("");
+        } else {
+            let Some((element, self_closing, consumed)) = parse_start_tag(rest) else {
+                // A lone '<' in text
+                push_text(&mut stack, "<");
+                rest = &rest[1..];
+                continue;
+            };
+            rest = &rest[consumed..];
+            stack.push(element);
+            if self_closing {
+                close_element(&mut stack);
+            }
+        }
+    }
+    while stack.len() > 1 {
+        close_element(&mut stack);
+    }
+
+    stack
+        .pop()?
+        .children
+        .into_iter()
+        .find_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+}
+
+/// Skips `<!DOCTYPE ...>`, including an internal subset in brackets
+fn skip_declaration(rest: &str) -> &str {
+    let mut depth = 0usize;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            '>' if depth == 0 => return &rest[i + 1..],
+            _ => {}
+        }
+    }
+    ""
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(parent) = stack.last_mut() {
+        parent
+            .children
+            .push(Node::Text(html::decode_entities(text)));
+    }
+}
+
+fn close_element(stack: &mut Vec<Element>) {
+    if let Some(element) = stack.pop() {
+        if let Some(parent) = stack.last_mut() {
+            parent.children.push(Node::Element(element));
+        }
+    }
+}
+
+/// Parses `<name attr="value" ...>` at the start of `input`
+///
+/// Returns the element, whether it closes itself and the bytes consumed.
+fn parse_start_tag(input: &str) -> Option<(Element, bool, usize)> {
+    let bytes = input.as_bytes();
+    let name_end = input[1..]
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .map(|i| i + 1)?;
+    let name = &input[1..name_end];
+    if name.is_empty() || !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        return None;
+    }
+
+    let mut element = Element {
+        name: name.to_string(),
+        ..Element::default()
+    };
+    let mut i = name_end;
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        match bytes.get(i)? {
+            b'>' => return Some((element, false, i + 1)),
+            b'/' => {
+                let close = input[i..].find('>')? + i;
+                return Some((element, true, close + 1));
+            }
+            _ => {}
+        }
+
+        let key_end = input[i..]
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .map(|end| end + i)?;
+        let key = input[i..key_end].to_string();
+        i = key_end;
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if bytes.get(i) != Some(&b'=') {
+            element.attributes.push((key, String::new()));
+            continue;
+        }
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let value = match bytes.get(i)? {
+            quote @ (b'"' | b'\'') => {
+                let end = input[i + 1..].find(*quote as char)? + i + 1;
+                let value = &input[i + 1..end];
+                i = end + 1;
+                value
+            }
+            _ => {
+                let end = input[i..]
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .map_or(input.len(), |end| end + i);
+                let value = &input[i..end];
+                i = end;
+                value
+            }
+        };
+        element.attributes.push((key, html::decode_entities(value)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Example News</title>
+    <link>https://news.example.com/</link>
+    <item>
+      <title>Second &amp; latest</title>
+      <link>https://news.example.com/2</link>
+      <guid isPermaLink="false">post-2</guid>
+      <pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+      <description><![CDATA[<p>Some <b>bold</b> news</p>]]></description>
+    </item>
+    <item>
+      <title>First</title>
+      <guid>https://news.example.com/1</guid>
+      <dc:date>2025-06-09T08:30:00+02:00</dc:date>
+      <content:encoded>&lt;p&gt;Escaped &amp;amp; encoded&lt;/p&gt;</content:encoded>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="html">Dev &lt;Blog&gt;</title>
+  <link rel="self" href="https://blog.example.org/feed.xml"/>
+  <link href="https://blog.example.org/"/>
+  <entry>
+    <title>Hello Atom</title>
+    <link rel="alternate" type="text/html" href="https://blog.example.org/hello"/>
+    <id>tag:blog.example.org,2025:hello</id>
+    <updated>2025-06-01T12:00:00Z</updated>
+    <summary type="html">&lt;em&gt;Short&lt;/em&gt; summary</summary>
+  </entry>
+  <entry>
+    <title>No id</title>
+    <link href="https://blog.example.org/no-id"/>
+    <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>Inline <b>XHTML</b></p></div></content>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse(RSS).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Example News"));
+        assert_eq!(feed.link.as_deref(), Some("https://news.example.com/"));
+        assert_eq!(feed.items.len(), 2);
+
+        let latest = &feed.items[0];
+        assert_eq!(latest.id, "post-2");
+        assert_eq!(latest.title, "Second & latest");
+        assert_eq!(latest.link.as_deref(), Some("https://news.example.com/2"));
+        assert_eq!(latest.summary, "Some bold news");
+        assert_eq!(
+            latest.published,
+            Some("2025-06-10T04:00:00Z".parse().unwrap())
+        );
+
+        let first = &feed.items[1];
+        assert_eq!(first.id, "https://news.example.com/1");
+        assert_eq!(first.link.as_deref(), Some("https://news.example.com/1"));
+        assert_eq!(first.summary, "Escaped & encoded");
+        assert_eq!(
+            first.published,
+            Some("2025-06-09T06:30:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let feed = parse(ATOM).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Dev"));
+        assert_eq!(feed.link.as_deref(), Some("https://blog.example.org/"));
+
+        let hello = &feed.items[0];
+        assert_eq!(hello.id, "tag:blog.example.org,2025:hello");
+        assert_eq!(
+            hello.link.as_deref(),
+            Some("https://blog.example.org/hello")
+        );
+        assert_eq!(hello.summary, "Short summary");
+        assert!(hello.published.is_some());
+
+        let no_id = &feed.items[1];
+        assert_eq!(no_id.id, "https://blog.example.org/no-id");
+        assert_eq!(no_id.summary, "Inline XHTML");
+        assert_eq!(no_id.published, None);
+    }
+
+    #[test]
+    fn test_parse_rss_1() {
+        let feed = parse(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/">
+                 <channel rdf:about="https://example.net/"><title>RDF feed</title></channel>
+                 <item rdf:about="https://example.net/a"><title>A</title><link>https://example.net/a</link></item>
+               </rdf:RDF>"#,
+        )
+        .unwrap();
+        assert_eq!(feed.title.as_deref(), Some("RDF feed"));
+        assert_eq!(feed.items.len(), 1);
+        assert_eq!(feed.items[0].id, "https://example.net/a");
+    }
+
+    #[test]
+    fn test_lenient_and_invalid_documents() {
+        // Unclosed elements, a doctype and a stray end tag
+        let feed = parse(
+            "<!DOCTYPE rss [<!ENTITY x \"y\">]><rss><channel><title>Loose</title></p><item><title>Open item",
+        )
+        .unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Loose"));
+        assert_eq!(feed.items[0].title, "Open item");
+
+        assert_eq!(parse("just text"), Err(FeedError::NotXml));
+        assert_eq!(
+            parse("<html><body>Not a feed</body></html>"),
+            Err(FeedError::UnsupportedFormat("html".to_string()))
+        );
+    }
+}
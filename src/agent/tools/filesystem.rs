@@ -1,21 +1,66 @@
 //! Filesystem tool for the agent
 //!
-//! This tool provides filesystem operations (read, write, list) with security constraints.
+//! This tool provides filesystem operations (read, write, list, edit, append,
+//! insert_at_line, delete, move, mkdir, glob, grep) with security constraints.
 //! All paths are validated and canonicalized to prevent path traversal attacks.
+//!
+//! Reads are bounded: text output is capped at a configurable size, binary files
+//! are summarized (size, mime type guess, sha256) instead of returned, and small
+//! binaries can be read as base64 when explicitly requested.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 
+use base64::Engine;
+use regex::Regex;
 use serde_json::Value;
 use tokio::fs;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 
 use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
 use crate::utils::paths::{PathValidationError, validate_path};
 
+/// All supported operations, in the order they are documented
+const OPERATIONS: &[&str] = &[
+    "read",
+    "write",
+    "list",
+    "edit",
+    "append",
+    "insert_at_line",
+    "delete",
+    "move",
+    "mkdir",
+    "glob",
+    "grep",
+];
+
+/// Default maximum number of bytes of text returned by a read
+pub const DEFAULT_MAX_READ_BYTES: u64 = 256 * 1024;
+
+/// Default maximum size of a file that can be read as base64
+pub const DEFAULT_MAX_BASE64_BYTES: u64 = 64 * 1024;
+
+/// Number of leading bytes inspected to decide whether a file is binary
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Chunk size used when hashing files
+const HASH_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Maximum number of paths returned by a glob
+const MAX_GLOB_RESULTS: usize = 200;
+
+/// Maximum number of matching lines returned by a grep
+const MAX_GREP_MATCHES: usize = 100;
+
+/// Maximum number of characters of a matching line returned by a grep
+const MAX_GREP_LINE_CHARS: usize = 300;
+
 /// Tool for filesystem operations
 ///
-/// Provides read, write, and list operations on files within a restricted
-/// base directory. All paths are validated to prevent path traversal attacks.
+/// Provides reading, writing, editing and searching of files within a
+/// restricted base directory. Every path argument (including the destination
+/// of a move) is validated to prevent path traversal attacks.
 ///
 /// # Security
 /// This tool implements NFR-S3: All paths are canonicalized and validated
@@ -24,6 +69,24 @@ pub struct FilesystemTool {
     /// The canonicalized base directory that all operations are restricted to
     /// This is stored in canonical form for performance (no repeated canonicalization)
     base_dir: PathBuf,
+    /// Maximum number of bytes of text returned by a read, and the size limit
+    /// for files that are searched or edited in place
+    max_read_bytes: u64,
+    /// Maximum size of a file that can be read as base64
+    max_base64_bytes: u64,
+}
+
+/// Which lines of a text file a read returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineSelection {
+    /// The whole file
+    All,
+    /// An inclusive, 1-based range; `end` defaults to the last line
+    Range { start: usize, end: Option<usize> },
+    /// The first N lines
+    Head(usize),
+    /// The last N lines
+    Tail(usize),
 }
 
 impl FilesystemTool {
@@ -52,9 +115,22 @@ impl FilesystemTool {
 
         Self {
             base_dir: canonical_base,
+            max_read_bytes: DEFAULT_MAX_READ_BYTES,
+            max_base64_bytes: DEFAULT_MAX_BASE64_BYTES,
         }
     }
 
+    /// Sets the read limits
+    ///
+    /// # Arguments
+    /// * `max_read_bytes` - Maximum number of bytes of text returned by a read
+    /// * `max_base64_bytes` - Maximum size of a file that can be read as base64
+    pub fn with_read_limits(mut self, max_read_bytes: u64, max_base64_bytes: u64) -> Self {
+        self.max_read_bytes = max_read_bytes;
+        self.max_base64_bytes = max_base64_bytes;
+        self
+    }
+
     /// Validates a user-provided path using the centralized validation utilities
     ///
     /// # Arguments
@@ -88,16 +164,39 @@ impl FilesystemTool {
             })
     }
 
-    /// Reads the contents of a file
+    /// Reads the whole contents of a file to modify it
     ///
     /// # Arguments
     /// * `path` - The validated, canonicalized path to read
     ///
     /// # Returns
     /// * `Ok(String)` - The file contents as UTF-8 text
-    /// * `Err(ToolError)` - If file cannot be read
+    /// * `Err(ToolError)` - If the file cannot be read or is larger than `max_read_bytes`
     async fn read_file(&self, path: &Path) -> ToolResult<String> {
-        // Verify it's a file, not a directory
+        let size = self.file_metadata(path).await?.len();
+        if size > self.max_read_bytes {
+            return Err(self.exec_error(format!(
+                "'{}' is {} bytes, more than the {} bytes that can be edited in place",
+                self.relative_display(path),
+                size,
+                self.max_read_bytes
+            )));
+        }
+
+        // Read file content
+        fs::read_to_string(path)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed {
+                tool: self.name().to_string(),
+                message: format!("Failed to read file '{}': {}", path.display(), e),
+            })
+    }
+
+    /// Returns the metadata of a regular file
+    ///
+    /// Fails with `NotFound` if the path does not exist and with
+    /// `ExecutionFailed` if it is not a file.
+    async fn file_metadata(&self, path: &Path) -> ToolResult<std::fs::Metadata> {
         let metadata = match fs::metadata(path).await {
             Ok(meta) => meta,
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
@@ -121,13 +220,7 @@ impl FilesystemTool {
             });
         }
 
-        // Read file content
-        fs::read_to_string(path)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                tool: self.name().to_string(),
-                message: format!("Failed to read file '{}': {}", path.display(), e),
-            })
+        Ok(metadata)
     }
 
     /// Writes content to a file
@@ -237,6 +330,716 @@ impl FilesystemTool {
             message: format!("Failed to serialize directory listing: {}", e),
         })
     }
+
+    /// Builds an execution error for this tool
+    fn exec_error(&self, message: String) -> ToolError {
+        ToolError::ExecutionFailed {
+            tool: self.name().to_string(),
+            message,
+        }
+    }
+
+    /// Extracts a required string argument
+    fn required_str<'a>(
+        &self,
+        args: &'a HashMap<String, Value>,
+        name: &str,
+        operation: &str,
+    ) -> ToolResult<&'a str> {
+        args.get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: format!(
+                    "Missing required parameter '{}' for {} operation",
+                    name, operation
+                ),
+            })
+    }
+
+    /// Extracts an optional positive line number argument (1-based)
+    fn line_arg(&self, args: &HashMap<String, Value>, name: &str) -> ToolResult<Option<usize>> {
+        match args.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value
+                .as_u64()
+                .filter(|n| *n >= 1)
+                .map(|n| Some(n as usize))
+                .ok_or_else(|| ToolError::InvalidArguments {
+                    tool: self.name().to_string(),
+                    message: format!("Parameter '{}' must be a positive integer", name),
+                }),
+        }
+    }
+
+    /// Returns `path` relative to the base directory, for display
+    fn relative_display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.base_dir)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    /// Reads a file for the agent, within the configured limits
+    ///
+    /// Binary files are summarized unless `base64` is set, in which case files up
+    /// to `max_base64_bytes` are returned encoded. Text output is capped at
+    /// `max_read_bytes` with a notice explaining how to read further.
+    async fn read_path(
+        &self,
+        path: &Path,
+        selection: LineSelection,
+        base64: bool,
+    ) -> ToolResult<String> {
+        let size = self.file_metadata(path).await?.len();
+
+        if base64 {
+            return self.read_base64(path, size).await;
+        }
+        if self.is_binary(path).await? {
+            return self.binary_summary(path, size).await;
+        }
+
+        match selection {
+            LineSelection::All => self.read_text(path, size).await,
+            selection => self.read_lines(path, selection).await,
+        }
+    }
+
+    /// Returns true if the start of the file contains NUL bytes or invalid UTF-8
+    async fn is_binary(&self, path: &Path) -> ToolResult<bool> {
+        let mut file = self.open(path).await?;
+        let mut sample = Vec::with_capacity(BINARY_SNIFF_BYTES);
+        (&mut file)
+            .take(BINARY_SNIFF_BYTES as u64)
+            .read_to_end(&mut sample)
+            .await
+            .map_err(|e| self.read_error(path, e))?;
+
+        if sample.contains(&0) {
+            return Ok(true);
+        }
+        // A multi-byte character cut at the end of the sample is not an error
+        Ok(matches!(std::str::from_utf8(&sample), Err(e) if e.error_len().is_some()))
+    }
+
+    /// Reads a whole text file, truncated at the last line that fits the limit
+    async fn read_text(&self, path: &Path, size: u64) -> ToolResult<String> {
+        let mut bytes = Vec::new();
+        self.open(path)
+            .await?
+            .take(self.max_read_bytes)
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|e| self.read_error(path, e))?;
+
+        if size <= self.max_read_bytes {
+            return Ok(String::from_utf8_lossy(&bytes).into_owned());
+        }
+
+        if let Some(last_newline) = bytes.iter().rposition(|b| *b == b'\n') {
+            bytes.truncate(last_newline + 1);
+        }
+        Ok(format!(
+            "{}\n[Truncated: showing the first {} of {} bytes. Use start_line/end_line, head or tail to read other parts.]",
+            String::from_utf8_lossy(&bytes),
+            bytes.len(),
+            size
+        ))
+    }
+
+    /// Reads selected lines of a text file without loading the whole file
+    async fn read_lines(&self, path: &Path, selection: LineSelection) -> ToolResult<String> {
+        let mut reader = BufReader::new(self.open(path).await?);
+        let mut selected = VecDeque::new();
+        let mut buffer = Vec::new();
+        let mut line_count = 0;
+
+        loop {
+            buffer.clear();
+            let read = reader
+                .read_until(b'\n', &mut buffer)
+                .await
+                .map_err(|e| self.read_error(path, e))?;
+            if read == 0 {
+                break;
+            }
+            line_count += 1;
+
+            let keep = match selection {
+                LineSelection::All | LineSelection::Tail(_) => true,
+                LineSelection::Head(n) => line_count <= n,
+                LineSelection::Range { start, end } => {
+                    line_count >= start && end.is_none_or(|end| line_count <= end)
+                }
+            };
+            if keep {
+                let line = String::from_utf8_lossy(&buffer);
+                selected.push_back(line.trim_end_matches(['\n', '\r']).to_string());
+            }
+            if let LineSelection::Tail(n) = selection {
+                if selected.len() > n {
+                    selected.pop_front();
+                }
+            }
+
+            let done = match selection {
+                LineSelection::Head(n) => line_count >= n,
+                LineSelection::Range { end: Some(end), .. } => line_count >= end,
+                _ => false,
+            };
+            if done {
+                break;
+            }
+        }
+
+        if let LineSelection::Range { start, end } = selection {
+            if start > line_count || end.is_some_and(|end| start > end) {
+                return Err(ToolError::InvalidArguments {
+                    tool: self.name().to_string(),
+                    message: format!(
+                        "Invalid line range {}-{}: '{}' has {} line(s)",
+                        start,
+                        end.map_or_else(|| "end".to_string(), |end| end.to_string()),
+                        self.relative_display(path),
+                        line_count
+                    ),
+                });
+            }
+        }
+
+        let mut output = String::new();
+        let total = selected.len();
+        for (index, line) in selected.into_iter().enumerate() {
+            let separator = usize::from(index > 0);
+            if (output.len() + separator + line.len()) as u64 > self.max_read_bytes {
+                output.push_str(&format!(
+                    "\n[Truncated: {} of {} selected lines shown (limit {} bytes).]",
+                    index, total, self.max_read_bytes
+                ));
+                break;
+            }
+            if index > 0 {
+                output.push('\n');
+            }
+            output.push_str(&line);
+        }
+        Ok(output)
+    }
+
+    /// Returns a small file as base64 along with its metadata
+    async fn read_base64(&self, path: &Path, size: u64) -> ToolResult<String> {
+        if size > self.max_base64_bytes {
+            return Err(ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: format!(
+                    "'{}' is {} bytes; base64 reads are limited to {} bytes",
+                    self.relative_display(path),
+                    size,
+                    self.max_base64_bytes
+                ),
+            });
+        }
+
+        let bytes = fs::read(path).await.map_err(|e| self.read_error(path, e))?;
+        let digest = ring::digest::digest(&ring::digest::SHA256, &bytes);
+
+        Ok(serde_json::json!({
+            "path": self.relative_display(path),
+            "size": size,
+            "mime_type": guess_mime_type(path),
+            "sha256": hex_encode(digest.as_ref()),
+            "encoding": "base64",
+            "content": base64::engine::general_purpose::STANDARD.encode(&bytes),
+        })
+        .to_string())
+    }
+
+    /// Describes a binary file instead of returning its bytes
+    async fn binary_summary(&self, path: &Path, size: u64) -> ToolResult<String> {
+        let mut file = self.open(path).await?;
+        let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+        let mut chunk = vec![0u8; HASH_CHUNK_BYTES];
+        loop {
+            let read = file
+                .read(&mut chunk)
+                .await
+                .map_err(|e| self.read_error(path, e))?;
+            if read == 0 {
+                break;
+            }
+            context.update(&chunk[..read]);
+        }
+
+        let note = if size <= self.max_base64_bytes {
+            "Binary file. Set encoding to \"base64\" to read its content.".to_string()
+        } else {
+            format!(
+                "Binary file, too large to read as base64 (limit {} bytes).",
+                self.max_base64_bytes
+            )
+        };
+
+        Ok(serde_json::json!({
+            "path": self.relative_display(path),
+            "size": size,
+            "mime_type": guess_mime_type(path),
+            "sha256": hex_encode(context.finish().as_ref()),
+            "binary": true,
+            "note": note,
+        })
+        .to_string())
+    }
+
+    /// Opens a file for reading
+    async fn open(&self, path: &Path) -> ToolResult<fs::File> {
+        fs::File::open(path)
+            .await
+            .map_err(|e| self.read_error(path, e))
+    }
+
+    /// Builds the error for a failed read
+    fn read_error(&self, path: &Path, error: std::io::Error) -> ToolError {
+        self.exec_error(format!(
+            "Failed to read file '{}': {}",
+            path.display(),
+            error
+        ))
+    }
+
+    /// Parses the line selection options of a read
+    ///
+    /// `head`, `tail` and `start_line`/`end_line` are mutually exclusive.
+    fn line_selection(&self, args: &HashMap<String, Value>) -> ToolResult<LineSelection> {
+        let start = self.line_arg(args, "start_line")?;
+        let end = self.line_arg(args, "end_line")?;
+        let head = self.line_arg(args, "head")?;
+        let tail = self.line_arg(args, "tail")?;
+
+        let range = (start.is_some() || end.is_some()).then(|| LineSelection::Range {
+            start: start.unwrap_or(1),
+            end,
+        });
+        let options: Vec<LineSelection> = [
+            range,
+            head.map(LineSelection::Head),
+            tail.map(LineSelection::Tail),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        match options.as_slice() {
+            [] => Ok(LineSelection::All),
+            [selection] => Ok(*selection),
+            _ => Err(ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: "Use only one of start_line/end_line, head or tail".to_string(),
+            }),
+        }
+    }
+
+    /// Replaces exact text in a file
+    ///
+    /// Unless `replace_all` is set, `old_text` must occur exactly once so that an
+    /// ambiguous edit never changes the wrong place.
+    async fn edit_file(
+        &self,
+        path: &Path,
+        old_text: &str,
+        new_text: &str,
+        replace_all: bool,
+    ) -> ToolResult<String> {
+        if old_text.is_empty() {
+            return Err(ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: "Parameter 'old_text' must not be empty".to_string(),
+            });
+        }
+
+        let content = self.read_file(path).await?;
+        let occurrences = content.matches(old_text).count();
+        match occurrences {
+            0 => {
+                return Err(self.exec_error(format!(
+                    "Text to replace was not found in '{}'",
+                    self.relative_display(path)
+                )));
+            }
+            1 => {}
+            n if !replace_all => {
+                return Err(self.exec_error(format!(
+                    "Text to replace occurs {} times in '{}'. Include more surrounding context to make it unique, or set replace_all.",
+                    n,
+                    self.relative_display(path)
+                )));
+            }
+            _ => {}
+        }
+
+        let updated = content.replace(old_text, new_text);
+        fs::write(path, updated).await.map_err(|e| {
+            self.exec_error(format!("Failed to write file '{}': {}", path.display(), e))
+        })?;
+
+        Ok(format!(
+            "Replaced {} occurrence(s) in {}",
+            occurrences,
+            path.display()
+        ))
+    }
+
+    /// Appends content to a file, creating it (and its parents) if needed
+    async fn append_file(&self, path: &Path, content: &str) -> ToolResult<String> {
+        use tokio::io::AsyncWriteExt;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await.map_err(|e| {
+                self.exec_error(format!(
+                    "Failed to create directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(|e| {
+                self.exec_error(format!("Failed to open file '{}': {}", path.display(), e))
+            })?;
+        file.write_all(content.as_bytes()).await.map_err(|e| {
+            self.exec_error(format!("Failed to append to '{}': {}", path.display(), e))
+        })?;
+
+        Ok(format!(
+            "Appended {} bytes to {}",
+            content.len(),
+            path.display()
+        ))
+    }
+
+    /// Inserts content before the given 1-based line
+    ///
+    /// `line` may be one past the last line to insert at the end of the file.
+    async fn insert_at_line(&self, path: &Path, line: usize, content: &str) -> ToolResult<String> {
+        let existing = self.read_file(path).await?;
+        let mut lines: Vec<&str> = existing.split_inclusive('\n').collect();
+
+        if line > lines.len() + 1 {
+            return Err(ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: format!(
+                    "Line {} is out of range: '{}' has {} line(s)",
+                    line,
+                    self.relative_display(path),
+                    lines.len()
+                ),
+            });
+        }
+
+        // Keep the inserted block on its own lines
+        let mut inserted = content.to_string();
+        if !inserted.ends_with('\n') {
+            inserted.push('\n');
+        }
+        let mut prefix = String::new();
+        if line == lines.len() + 1 && lines.last().is_some_and(|l| !l.ends_with('\n')) {
+            prefix.push('\n');
+        }
+        let inserted = format!("{}{}", prefix, inserted);
+        lines.insert(line - 1, &inserted);
+
+        fs::write(path, lines.concat()).await.map_err(|e| {
+            self.exec_error(format!("Failed to write file '{}': {}", path.display(), e))
+        })?;
+
+        Ok(format!(
+            "Inserted content at line {} of {}",
+            line,
+            path.display()
+        ))
+    }
+
+    /// Deletes a file or directory
+    ///
+    /// Non-empty directories are only removed when `recursive` is set, and the
+    /// base directory itself can never be deleted.
+    async fn delete_path(&self, path: &Path, recursive: bool) -> ToolResult<String> {
+        if path == self.base_dir {
+            return Err(ToolError::PermissionDenied {
+                tool: self.name().to_string(),
+                message: "Refusing to delete the base directory".to_string(),
+            });
+        }
+
+        let metadata = fs::symlink_metadata(path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                ToolError::NotFound(format!("Path not found: {}", path.display()))
+            } else {
+                self.exec_error(format!("Cannot access '{}': {}", path.display(), e))
+            }
+        })?;
+
+        let result = if !metadata.is_dir() {
+            fs::remove_file(path).await
+        } else if recursive {
+            fs::remove_dir_all(path).await
+        } else {
+            fs::remove_dir(path).await
+        };
+        result.map_err(|e| {
+            let hint = if metadata.is_dir() && !recursive {
+                " (set recursive to delete a non-empty directory)"
+            } else {
+                ""
+            };
+            self.exec_error(format!(
+                "Failed to delete '{}': {}{}",
+                path.display(),
+                e,
+                hint
+            ))
+        })?;
+
+        Ok(format!("Deleted {}", path.display()))
+    }
+
+    /// Moves or renames a file or directory; the destination must not exist
+    async fn move_path(&self, from: &Path, to: &Path) -> ToolResult<String> {
+        if from == self.base_dir {
+            return Err(ToolError::PermissionDenied {
+                tool: self.name().to_string(),
+                message: "Refusing to move the base directory".to_string(),
+            });
+        }
+        if !fs::try_exists(from).await.unwrap_or(false) {
+            return Err(ToolError::NotFound(format!(
+                "Path not found: {}",
+                from.display()
+            )));
+        }
+        if fs::try_exists(to).await.unwrap_or(false) {
+            return Err(self.exec_error(format!(
+                "Destination '{}' already exists",
+                self.relative_display(to)
+            )));
+        }
+
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).await.map_err(|e| {
+                self.exec_error(format!(
+                    "Failed to create directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+        fs::rename(from, to).await.map_err(|e| {
+            self.exec_error(format!(
+                "Failed to move '{}' to '{}': {}",
+                from.display(),
+                to.display(),
+                e
+            ))
+        })?;
+
+        Ok(format!("Moved {} to {}", from.display(), to.display()))
+    }
+
+    /// Creates a directory and any missing parents
+    async fn make_dir(&self, path: &Path) -> ToolResult<String> {
+        fs::create_dir_all(path).await.map_err(|e| {
+            self.exec_error(format!(
+                "Failed to create directory '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        Ok(format!("Created directory {}", path.display()))
+    }
+
+    /// Recursively collects the files under `root`, sorted by path
+    ///
+    /// Symlinks are not followed, so the walk cannot leave the validated root.
+    async fn walk_files(&self, root: &Path) -> ToolResult<Vec<PathBuf>> {
+        let metadata = fs::metadata(root).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                ToolError::NotFound(format!("Path not found: {}", root.display()))
+            } else {
+                self.exec_error(format!("Cannot access '{}': {}", root.display(), e))
+            }
+        })?;
+        if metadata.is_file() {
+            return Ok(vec![root.to_path_buf()]);
+        }
+
+        let mut files = Vec::new();
+        let mut pending = vec![root.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let mut entries = match fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    tracing::debug!(path = %dir.display(), error = %e, "Skipping unreadable directory");
+                    continue;
+                }
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                match entry.file_type().await {
+                    Ok(ft) if ft.is_dir() => pending.push(entry.path()),
+                    Ok(ft) if ft.is_file() => files.push(entry.path()),
+                    _ => {}
+                }
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// Finds files under `root` whose relative path matches a glob pattern
+    ///
+    /// Returns a JSON array of paths relative to the base directory.
+    async fn glob_files(&self, root: &Path, pattern: &str) -> ToolResult<String> {
+        let matcher = glob_to_regex(pattern).map_err(|e| ToolError::InvalidArguments {
+            tool: self.name().to_string(),
+            message: format!("Invalid glob pattern '{}': {}", pattern, e),
+        })?;
+
+        let matches: Vec<String> = self
+            .walk_files(root)
+            .await?
+            .iter()
+            .filter(|path| {
+                let relative = path.strip_prefix(root).unwrap_or(path);
+                matcher.is_match(&relative.to_string_lossy().replace('\\', "/"))
+            })
+            .take(MAX_GLOB_RESULTS)
+            .map(|path| self.relative_display(path))
+            .collect();
+
+        serde_json::to_string(&matches)
+            .map_err(|e| self.exec_error(format!("Failed to serialize glob results: {}", e)))
+    }
+
+    /// Searches files under `root` for lines matching a regular expression
+    ///
+    /// Returns `path:line: text` lines; binary and non-UTF-8 files are skipped,
+    /// and so are files larger than `max_read_bytes`, with a note saying how many.
+    async fn grep_files(
+        &self,
+        root: &Path,
+        pattern: &str,
+        case_insensitive: bool,
+    ) -> ToolResult<String> {
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: format!("Invalid regular expression '{}': {}", pattern, e),
+            })?;
+
+        let mut results = Vec::new();
+        let mut too_large = 0;
+        'files: for path in self.walk_files(root).await? {
+            let Ok(metadata) = fs::metadata(&path).await else {
+                continue;
+            };
+            if metadata.len() > self.max_read_bytes {
+                too_large += 1;
+                continue;
+            }
+            if !matches!(self.is_binary(&path).await, Ok(false)) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path).await else {
+                continue;
+            };
+            for (index, line) in content.lines().enumerate() {
+                if !regex.is_match(line) {
+                    continue;
+                }
+                if results.len() == MAX_GREP_MATCHES {
+                    results.push(format!("... (stopped after {} matches)", MAX_GREP_MATCHES));
+                    break 'files;
+                }
+                let text: String = line.chars().take(MAX_GREP_LINE_CHARS).collect();
+                results.push(format!(
+                    "{}:{}: {}",
+                    self.relative_display(&path),
+                    index + 1,
+                    text
+                ));
+            }
+        }
+
+        if results.is_empty() {
+            results.push("No matches found".to_string());
+        }
+        if too_large > 0 {
+            results.push(format!(
+                "(skipped {} file(s) larger than {} bytes)",
+                too_large, self.max_read_bytes
+            ));
+        }
+        Ok(results.join("\n"))
+    }
+}
+
+/// Converts a glob pattern to an anchored regular expression
+///
+/// Supports `*` (any characters except `/`), `?` (one character except `/`),
+/// `**` (any number of directories) and `{a,b}` alternatives.
+fn glob_to_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    let mut in_alternatives = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '{' if !in_alternatives => {
+                in_alternatives = true;
+                regex.push_str("(?:");
+            }
+            '}' if in_alternatives => {
+                in_alternatives = false;
+                regex.push(')');
+            }
+            ',' if in_alternatives => regex.push('|'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+    Regex::new(&regex)
+}
+
+/// Guesses a mime type from the file extension
+fn guess_mime_type(path: &Path) -> String {
+    mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string()
+}
+
+/// Formats bytes as lowercase hexadecimal
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
 #[async_trait::async_trait]
@@ -246,7 +1049,7 @@ impl Tool for FilesystemTool {
     }
 
     fn description(&self) -> &str {
-        "Read, write, and list files in the base directory. Supports three operations: \"read\" to read file contents, \"write\" to create or overwrite files, and \"list\" to list directory contents. All paths are restricted to the base directory for security."
+        "Read, write, and list files in the base directory, plus targeted edits and search. Operations: \"read\" (large files are truncated; use start_line/end_line, head or tail for parts, and encoding \"base64\" for small binaries), \"write\" to create or overwrite a file, \"list\" a directory, \"edit\" to replace exact old_text with new_text (must match once unless replace_all), \"append\" content, \"insert_at_line\" to insert content before a line, \"delete\" a file or directory (recursive for non-empty directories), \"move\" to a destination, \"mkdir\" to create a directory, \"glob\" to find files matching a pattern such as \"**/*.md\", and \"grep\" to search file contents with a regular expression. All paths are restricted to the base directory for security."
     }
 
     fn parameters(&self) -> Value {
@@ -255,16 +1058,69 @@ impl Tool for FilesystemTool {
             "properties": {
                 "operation": {
                     "type": "string",
-                    "enum": ["read", "write", "list"],
-                    "description": "Filesystem operation to perform: read file, write file, or list directory"
+                    "enum": OPERATIONS,
+                    "description": "Filesystem operation to perform"
                 },
                 "path": {
                     "type": "string",
-                    "description": "File or directory path relative to the base directory"
+                    "description": "File or directory path relative to the base directory (the directory to search for glob and grep)"
                 },
                 "content": {
                     "type": "string",
-                    "description": "Content to write to the file (required for write operation)"
+                    "description": "Content to write, append or insert (required for write, append and insert_at_line)"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to read, 1-based (read only)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to read, inclusive (read only)"
+                },
+                "head": {
+                    "type": "integer",
+                    "description": "Read only the first N lines (read only)"
+                },
+                "tail": {
+                    "type": "integer",
+                    "description": "Read only the last N lines (read only)"
+                },
+                "encoding": {
+                    "type": "string",
+                    "enum": ["text", "base64"],
+                    "description": "Use base64 to read a small binary file; binary files are otherwise summarized (read only)"
+                },
+                "old_text": {
+                    "type": "string",
+                    "description": "Exact text to replace (required for edit)"
+                },
+                "new_text": {
+                    "type": "string",
+                    "description": "Replacement text (required for edit)"
+                },
+                "replace_all": {
+                    "type": "boolean",
+                    "description": "Replace every occurrence instead of requiring a unique match (edit only)"
+                },
+                "line": {
+                    "type": "integer",
+                    "description": "1-based line to insert before; one past the last line appends (required for insert_at_line)"
+                },
+                "recursive": {
+                    "type": "boolean",
+                    "description": "Delete a non-empty directory and its contents (delete only)"
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "Target path relative to the base directory (required for move)"
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Glob pattern relative to path for glob, or regular expression for grep"
+                },
+                "case_insensitive": {
+                    "type": "boolean",
+                    "description": "Match case-insensitively (grep only)"
                 }
             },
             "required": ["operation", "path"]
@@ -282,9 +1138,10 @@ impl Tool for FilesystemTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| ToolError::InvalidArguments {
                 tool: self.name().to_string(),
-                message:
-                    "Missing required parameter 'operation'. Must be 'read', 'write', or 'list'."
-                        .to_string(),
+                message: format!(
+                    "Missing required parameter 'operation'. Must be one of: {}.",
+                    OPERATIONS.join(", ")
+                ),
             })?;
 
         // Extract path
@@ -295,20 +1152,29 @@ impl Tool for FilesystemTool {
             }
         })?;
 
+        let flag = |name: &str| args.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+
         match operation {
             "read" => {
+                let selection = self.line_selection(&args)?;
+                let base64 = match args.get("encoding").and_then(|v| v.as_str()) {
+                    None | Some("text") => false,
+                    Some("base64") => true,
+                    Some(other) => {
+                        return Err(ToolError::InvalidArguments {
+                            tool: self.name().to_string(),
+                            message: format!(
+                                "Invalid encoding '{}'. Must be 'text' or 'base64'.",
+                                other
+                            ),
+                        });
+                    }
+                };
                 let path = self.validate_path_internal(path_str).await?;
-                self.read_file(&path).await
+                self.read_path(&path, selection, base64).await
             }
             "write" => {
-                let content = args
-                    .get("content")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| ToolError::InvalidArguments {
-                        tool: self.name().to_string(),
-                        message: "Missing required parameter 'content' for write operation"
-                            .to_string(),
-                    })?;
+                let content = self.required_str(&args, "content", operation)?;
                 let path = self.validate_path_internal(path_str).await?;
                 self.write_file(&path, content).await
             }
@@ -316,11 +1182,62 @@ impl Tool for FilesystemTool {
                 let path = self.validate_path_internal(path_str).await?;
                 self.list_dir(&path).await
             }
+            "edit" => {
+                let old_text = self.required_str(&args, "old_text", operation)?;
+                let new_text = self.required_str(&args, "new_text", operation)?;
+                let path = self.validate_path_internal(path_str).await?;
+                self.edit_file(&path, old_text, new_text, flag("replace_all"))
+                    .await
+            }
+            "append" => {
+                let content = self.required_str(&args, "content", operation)?;
+                let path = self.validate_path_internal(path_str).await?;
+                self.append_file(&path, content).await
+            }
+            "insert_at_line" => {
+                let content = self.required_str(&args, "content", operation)?;
+                let line =
+                    self.line_arg(&args, "line")?
+                        .ok_or_else(|| ToolError::InvalidArguments {
+                            tool: self.name().to_string(),
+                            message:
+                                "Missing required parameter 'line' for insert_at_line operation"
+                                    .to_string(),
+                        })?;
+                let path = self.validate_path_internal(path_str).await?;
+                self.insert_at_line(&path, line, content).await
+            }
+            "delete" => {
+                let path = self.validate_path_internal(path_str).await?;
+                self.delete_path(&path, flag("recursive")).await
+            }
+            "move" => {
+                let destination = self.required_str(&args, "destination", operation)?;
+                let from = self.validate_path_internal(path_str).await?;
+                let to = self.validate_path_internal(destination).await?;
+                self.move_path(&from, &to).await
+            }
+            "mkdir" => {
+                let path = self.validate_path_internal(path_str).await?;
+                self.make_dir(&path).await
+            }
+            "glob" => {
+                let pattern = self.required_str(&args, "pattern", operation)?;
+                let path = self.validate_path_internal(path_str).await?;
+                self.glob_files(&path, pattern).await
+            }
+            "grep" => {
+                let pattern = self.required_str(&args, "pattern", operation)?;
+                let path = self.validate_path_internal(path_str).await?;
+                self.grep_files(&path, pattern, flag("case_insensitive"))
+                    .await
+            }
             _ => Err(ToolError::InvalidArguments {
                 tool: self.name().to_string(),
                 message: format!(
-                    "Invalid operation '{}'. Must be 'read', 'write', or 'list'.",
-                    operation
+                    "Invalid operation '{}'. Must be one of: {}.",
+                    operation,
+                    OPERATIONS.join(", ")
                 ),
             }),
         }
@@ -713,7 +1630,7 @@ mod tests {
         let (tool, _temp) = create_test_tool();
 
         let mut args = HashMap::new();
-        args.insert("operation".to_string(), serde_json::json!("delete")); // Invalid operation
+        args.insert("operation".to_string(), serde_json::json!("explode")); // Invalid operation
         args.insert("path".to_string(), serde_json::json!("file.txt"));
 
         let ctx = ToolExecutionContext::default();
@@ -722,10 +1639,409 @@ mod tests {
         assert!(result.is_err());
         match result.unwrap_err() {
             ToolError::InvalidArguments { message, .. } => {
-                assert!(message.contains("delete"));
+                assert!(message.contains("explode"));
                 assert!(message.contains("Invalid operation"));
             }
             _ => panic!("Expected InvalidArguments error"),
         }
     }
+
+    /// Runs an operation with the given extra arguments
+    async fn run(
+        tool: &FilesystemTool,
+        operation: &str,
+        path: &str,
+        extra: serde_json::Value,
+    ) -> ToolResult<String> {
+        let mut args: HashMap<String, Value> = extra
+            .as_object()
+            .map(|o| o.clone().into_iter().collect())
+            .unwrap_or_default();
+        args.insert("operation".to_string(), serde_json::json!(operation));
+        args.insert("path".to_string(), serde_json::json!(path));
+        tool.execute(args, &ToolExecutionContext::default()).await
+    }
+
+    #[tokio::test]
+    async fn test_read_line_range() {
+        let (tool, temp) = create_test_tool();
+        std::fs::write(temp.path().join("f.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+
+        let result = run(
+            &tool,
+            "read",
+            "f.txt",
+            serde_json::json!({"start_line": 2, "end_line": 3}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result, "two\nthree");
+
+        let result = run(&tool, "read", "f.txt", serde_json::json!({"start_line": 4}))
+            .await
+            .unwrap();
+        assert_eq!(result, "four");
+
+        let result = run(&tool, "read", "f.txt", serde_json::json!({"start_line": 9})).await;
+        assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_edit_requires_unique_match() {
+        let (tool, temp) = create_test_tool();
+        let file = temp.path().join("f.txt");
+        std::fs::write(&file, "a = 1\nb = 1\n").unwrap();
+
+        let result = run(
+            &tool,
+            "edit",
+            "f.txt",
+            serde_json::json!({"old_text": "= 1", "new_text": "= 2"}),
+        )
+        .await;
+        match result.unwrap_err() {
+            ToolError::ExecutionFailed { message, .. } => assert!(message.contains("2 times")),
+            other => panic!("Expected ExecutionFailed, got {:?}", other),
+        }
+
+        run(
+            &tool,
+            "edit",
+            "f.txt",
+            serde_json::json!({"old_text": "a = 1", "new_text": "a = 3"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "a = 3\nb = 1\n");
+
+        run(
+            &tool,
+            "edit",
+            "f.txt",
+            serde_json::json!({"old_text": " = ", "new_text": "=", "replace_all": true}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "a=3\nb=1\n");
+
+        let result = run(
+            &tool,
+            "edit",
+            "f.txt",
+            serde_json::json!({"old_text": "missing", "new_text": "x"}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_append_and_insert_at_line() {
+        let (tool, temp) = create_test_tool();
+        let file = temp.path().join("notes/log.txt");
+
+        run(
+            &tool,
+            "append",
+            "notes/log.txt",
+            serde_json::json!({"content": "first\n"}),
+        )
+        .await
+        .unwrap();
+        run(
+            &tool,
+            "append",
+            "notes/log.txt",
+            serde_json::json!({"content": "third"}),
+        )
+        .await
+        .unwrap();
+        run(
+            &tool,
+            "insert_at_line",
+            "notes/log.txt",
+            serde_json::json!({"line": 2, "content": "second"}),
+        )
+        .await
+        .unwrap();
+        run(
+            &tool,
+            "insert_at_line",
+            "notes/log.txt",
+            serde_json::json!({"line": 4, "content": "fourth"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "first\nsecond\nthird\nfourth\n"
+        );
+
+        let result = run(
+            &tool,
+            "insert_at_line",
+            "notes/log.txt",
+            serde_json::json!({"line": 9, "content": "x"}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_mkdir_move_and_delete() {
+        let (tool, temp) = create_test_tool();
+
+        run(&tool, "mkdir", "a/b", serde_json::json!({}))
+            .await
+            .unwrap();
+        std::fs::write(temp.path().join("a/b/f.txt"), "x").unwrap();
+
+        run(
+            &tool,
+            "move",
+            "a/b/f.txt",
+            serde_json::json!({"destination": "c/g.txt"}),
+        )
+        .await
+        .unwrap();
+        assert!(temp.path().join("c/g.txt").exists());
+        assert!(!temp.path().join("a/b/f.txt").exists());
+
+        std::fs::write(temp.path().join("a/b/h.txt"), "y").unwrap();
+        let result = run(
+            &tool,
+            "move",
+            "a/b/h.txt",
+            serde_json::json!({"destination": "c/g.txt"}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed { .. })));
+
+        let result = run(&tool, "delete", "a", serde_json::json!({})).await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed { .. })));
+        run(&tool, "delete", "a", serde_json::json!({"recursive": true}))
+            .await
+            .unwrap();
+        assert!(!temp.path().join("a").exists());
+
+        run(&tool, "delete", "c/g.txt", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(!temp.path().join("c/g.txt").exists());
+
+        let result = run(&tool, "delete", ".", serde_json::json!({"recursive": true})).await;
+        assert!(matches!(result, Err(ToolError::PermissionDenied { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_move_destination_is_validated() {
+        let (tool, temp) = create_test_tool();
+        std::fs::write(temp.path().join("f.txt"), "x").unwrap();
+
+        let result = run(
+            &tool,
+            "move",
+            "f.txt",
+            serde_json::json!({"destination": "../escaped.txt"}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::PermissionDenied { .. })));
+        assert!(temp.path().join("f.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_glob() {
+        let (tool, temp) = create_test_tool();
+        std::fs::create_dir_all(temp.path().join("docs/deep")).unwrap();
+        std::fs::write(temp.path().join("README.md"), "").unwrap();
+        std::fs::write(temp.path().join("docs/guide.md"), "").unwrap();
+        std::fs::write(temp.path().join("docs/deep/notes.md"), "").unwrap();
+        std::fs::write(temp.path().join("docs/image.png"), "").unwrap();
+
+        let result = run(
+            &tool,
+            "glob",
+            ".",
+            serde_json::json!({"pattern": "**/*.md"}),
+        )
+        .await
+        .unwrap();
+        let paths: Vec<String> = serde_json::from_str(&result).unwrap();
+        assert_eq!(
+            paths,
+            vec!["README.md", "docs/deep/notes.md", "docs/guide.md"]
+        );
+
+        let result = run(
+            &tool,
+            "glob",
+            "docs",
+            serde_json::json!({"pattern": "*.{md,png}"}),
+        )
+        .await
+        .unwrap();
+        let paths: Vec<String> = serde_json::from_str(&result).unwrap();
+        assert_eq!(paths, vec!["docs/guide.md", "docs/image.png"]);
+    }
+
+    #[tokio::test]
+    async fn test_grep() {
+        let (tool, temp) = create_test_tool();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(temp.path().join("src/a.rs"), "fn main() {}\n// TODO: fix\n").unwrap();
+        std::fs::write(temp.path().join("src/b.rs"), "// todo later\n").unwrap();
+        std::fs::write(temp.path().join("src/blob.bin"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let result = run(&tool, "grep", "src", serde_json::json!({"pattern": "TODO"}))
+            .await
+            .unwrap();
+        assert_eq!(result, "src/a.rs:2: // TODO: fix");
+
+        let result = run(
+            &tool,
+            "grep",
+            "src",
+            serde_json::json!({"pattern": "todo", "case_insensitive": true}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.lines().count(), 2);
+
+        let result = run(&tool, "grep", "src", serde_json::json!({"pattern": "("})).await;
+        assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_large_files_are_not_searched_or_edited() {
+        let temp = TempDir::new().unwrap();
+        let tool = FilesystemTool::new(temp.path().to_path_buf()).with_read_limits(20, 16);
+        std::fs::write(temp.path().join("small.txt"), "needle\n").unwrap();
+        std::fs::write(temp.path().join("big.txt"), "needle\n".repeat(10)).unwrap();
+        std::fs::write(temp.path().join("nul.txt"), "needle\0\n").unwrap();
+
+        let result = run(&tool, "grep", ".", serde_json::json!({"pattern": "needle"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            "small.txt:1: needle\n(skipped 1 file(s) larger than 20 bytes)"
+        );
+
+        let result = run(
+            &tool,
+            "edit",
+            "big.txt",
+            serde_json::json!({"old_text": "needle", "new_text": "pin", "replace_all": true}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed { .. })));
+
+        let result = run(
+            &tool,
+            "insert_at_line",
+            "big.txt",
+            serde_json::json!({"line": 1, "content": "top"}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed { .. })));
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("big.txt")).unwrap(),
+            "needle\n".repeat(10)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_head_and_tail() {
+        let (tool, temp) = create_test_tool();
+        let content: String = (1..=10).map(|i| format!("line {}\r\n", i)).collect();
+        std::fs::write(temp.path().join("f.txt"), content).unwrap();
+
+        let result = run(&tool, "read", "f.txt", serde_json::json!({"head": 2}))
+            .await
+            .unwrap();
+        assert_eq!(result, "line 1\nline 2");
+
+        let result = run(&tool, "read", "f.txt", serde_json::json!({"tail": 3}))
+            .await
+            .unwrap();
+        assert_eq!(result, "line 8\nline 9\nline 10");
+
+        let result = run(
+            &tool,
+            "read",
+            "f.txt",
+            serde_json::json!({"head": 2, "tail": 2}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_read_truncates_large_text() {
+        let temp = TempDir::new().unwrap();
+        let tool = FilesystemTool::new(temp.path().to_path_buf()).with_read_limits(20, 16);
+        std::fs::write(temp.path().join("big.log"), "0123456789\n".repeat(10)).unwrap();
+
+        let result = run(&tool, "read", "big.log", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(result.starts_with("0123456789\n\n[Truncated"));
+        assert!(result.contains("of 110 bytes"));
+
+        let result = run(&tool, "read", "big.log", serde_json::json!({"tail": 5}))
+            .await
+            .unwrap();
+        assert!(result.starts_with("0123456789\n[Truncated: 1 of 5 selected lines"));
+    }
+
+    #[tokio::test]
+    async fn test_read_binary_returns_metadata() {
+        let (tool, temp) = create_test_tool();
+        std::fs::write(
+            temp.path().join("image.png"),
+            [0x89, b'P', b'N', b'G', 0x00, 0x01],
+        )
+        .unwrap();
+
+        let result = run(&tool, "read", "image.png", serde_json::json!({}))
+            .await
+            .unwrap();
+        let summary: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(summary["binary"], true);
+        assert_eq!(summary["size"], 6);
+        assert_eq!(summary["mime_type"], "image/png");
+        assert_eq!(summary["sha256"].as_str().unwrap().len(), 64);
+        assert!(summary.get("content").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_base64() {
+        let temp = TempDir::new().unwrap();
+        let tool = FilesystemTool::new(temp.path().to_path_buf()).with_read_limits(1024, 4);
+        std::fs::write(temp.path().join("small.bin"), [0x00, 0xff]).unwrap();
+        std::fs::write(temp.path().join("large.bin"), [0x00; 8]).unwrap();
+
+        let result = run(
+            &tool,
+            "read",
+            "small.bin",
+            serde_json::json!({"encoding": "base64"}),
+        )
+        .await
+        .unwrap();
+        let value: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(value["content"], "AP8=");
+        assert_eq!(
+            value["sha256"],
+            "06eb7d6a69ee19e5fbdf749018d3d2abfa04bcbd1365db312eb86dc7169389b8"
+        );
+
+        let result = run(
+            &tool,
+            "read",
+            "large.bin",
+            serde_json::json!({"encoding": "base64"}),
+        )
+        .await;
+        assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
+    }
 }
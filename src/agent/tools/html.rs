@@ -0,0 +1,1458 @@
+//! Readable content extraction from HTML
+//!
+//! Parses HTML into a lightweight tree, picks the main content in the spirit of
+//! Mozilla's Readability (paragraph scoring, class/id hints and link density)
+//! and renders it as Markdown-ish text: headings, lists, quotes, code blocks
+//! and tables. Links are collected into a separate numbered list and referenced
+//! from the text as `[text][n]`.
+
+mod entities;
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use entities::{ENTITIES, LEGACY_ENTITIES};
+
+/// Elements that never have children
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is text up to the matching end tag
+const RAW_TEXT_TAGS: &[&str] = &[
+    "iframe", "noembed", "noframes", "noscript", "script", "style", "textarea", "title", "xmp",
+];
+
+/// Elements whose start tag closes an open `<p>`
+const PARAGRAPH_CLOSERS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Elements that are never part of the readable content
+const NON_CONTENT_TAGS: &[&str] = &[
+    "aside", "button", "canvas", "dialog", "embed", "footer", "head", "iframe", "input", "nav",
+    "noscript", "object", "script", "select", "style", "svg", "template", "textarea",
+];
+
+/// ARIA roles of page chrome
+const NON_CONTENT_ROLES: &[&str] = &[
+    "alertdialog",
+    "banner",
+    "complementary",
+    "contentinfo",
+    "dialog",
+    "menu",
+    "menubar",
+    "navigation",
+    "search",
+];
+
+/// Children that keep a `<div>` from being scored as a paragraph
+const DIV_BLOCK_CHILDREN: &[&str] = &[
+    "blockquote",
+    "div",
+    "dl",
+    "img",
+    "ol",
+    "p",
+    "pre",
+    "table",
+    "ul",
+];
+
+/// Minimum paragraph length (in characters) that counts towards a score
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+
+/// Class/id hints of page chrome (comments, sidebars, share buttons, ...)
+static UNLIKELY_CANDIDATES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)-ad-|ai2html|banner|breadcrumbs|combx|comment|community|cookie|cover-wrap|disqus|extra|footer|gdpr|header|legends|menu|newsletter|related|remark|replies|rss|share|shoutbox|sidebar|skyscraper|social|sponsor|subscribe|supplemental|ad-break|agegate|pagination|pager|popup|yom-remote",
+    )
+    .expect("valid regex")
+});
+
+/// Class/id hints that override [`UNLIKELY_CANDIDATES`]
+static MAYBE_CANDIDATES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)and|article|body|column|content|main|mathjax|shadow").expect("valid regex")
+});
+
+/// Class/id hints of main content
+static POSITIVE_HINTS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)article|body|content|entry|hentry|h-entry|main|page|pagination|post|text|blog|story",
+    )
+    .expect("valid regex")
+});
+
+/// Class/id hints of secondary content
+static NEGATIVE_HINTS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)-ad-|hidden|^hid$| hid$| hid |^hid |banner|combx|comment|com-|contact|footer|gdpr|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|widget",
+    )
+    .expect("valid regex")
+});
+
+/// A link found in the extracted content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Link {
+    /// Number used to reference the link from the text (`[text][n]`)
+    pub index: usize,
+    /// Link text
+    pub text: String,
+    /// Absolute URL when a base URL is known, otherwise the raw `href`
+    pub url: String,
+}
+
+/// Readable content of an HTML document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Article {
+    /// Document title, from `<title>`, `og:title` or the first `<h1>`
+    pub title: Option<String>,
+    /// Main content rendered as Markdown-ish text
+    pub content: String,
+    /// Links referenced from the content, in order of first appearance
+    pub links: Vec<Link>,
+}
+
+/// Extracts the readable main content of an HTML document
+///
+/// Relative links are resolved against `base_url` when given.
+pub fn extract(html: &str, base_url: Option<&reqwest::Url>) -> Article {
+    let dom = parse(html);
+    let title = find_title(&dom);
+    let extractor = Extractor::new(&dom);
+    let selection = extractor.select_content();
+
+    let mut renderer = Renderer::new(&dom, &extractor.removed, base_url);
+    for id in selection {
+        renderer.render(id);
+    }
+    let (content, links) = renderer.finish();
+
+    Article {
+        title,
+        content,
+        links,
+    }
+}
+
+/// Decodes named and numeric character references in text content
+///
+/// Supports the complete WHATWG named reference table, decimal and hex
+/// references (with Windows-1252 remapping of C1 controls) and the legacy
+/// names that browsers accept without a trailing semicolon.
+pub fn decode_entities(text: &str) -> String {
+    decode(text, false)
+}
+
+fn decode(text: &str, in_attribute: bool) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp + 1..];
+        match decode_reference(rest, in_attribute) {
+            Some((decoded, consumed)) => {
+                out.push_str(decoded.as_ref());
+                rest = &rest[consumed..];
+            }
+            None => out.push('&'),
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decodes the reference following a `&`, returning the text and bytes consumed
+fn decode_reference(
+    s: &str,
+    in_attribute: bool,
+) -> Option<(std::borrow::Cow<'static, str>, usize)> {
+    if let Some(number) = s.strip_prefix('#') {
+        let (prefix_len, radix) = if number.starts_with(['x', 'X']) {
+            (1, 16)
+        } else {
+            (0, 10)
+        };
+        let digits_len = number[prefix_len..]
+            .bytes()
+            .take_while(|b| (*b as char).is_digit(radix))
+            .count();
+        if digits_len == 0 {
+            return None;
+        }
+        let digits = &number[prefix_len..prefix_len + digits_len];
+        let mut consumed = 1 + prefix_len + digits_len;
+        if s[consumed..].starts_with(';') {
+            consumed += 1;
+        }
+        let code = u32::from_str_radix(digits, radix).unwrap_or(u32::MAX);
+        return Some((numeric_char(code).to_string().into(), consumed));
+    }
+
+    let name_len = s.bytes().take_while(u8::is_ascii_alphanumeric).count();
+    if name_len == 0 {
+        return None;
+    }
+    let name = &s[..name_len];
+    if s[name_len..].starts_with(';') {
+        if let Some(value) = lookup_entity(name) {
+            return Some((value.into(), name_len + 1));
+        }
+    }
+
+    // Browsers accept a few legacy names without the semicolon in text, but
+    // not in attribute values where they would break URLs like `?a=1&copy=2`
+    if in_attribute {
+        return None;
+    }
+    (1..=name_len).rev().find_map(|len| {
+        let prefix = &name[..len];
+        LEGACY_ENTITIES.binary_search(&prefix).ok()?;
+        lookup_entity(prefix).map(|value| (value.into(), len))
+    })
+}
+
+fn lookup_entity(name: &str) -> Option<&'static str> {
+    ENTITIES
+        .binary_search_by(|(entity, _)| (*entity).cmp(name))
+        .ok()
+        .map(|index| ENTITIES[index].1)
+}
+
+/// Maps a numeric reference to a character like browsers do
+fn numeric_char(code: u32) -> char {
+    /// Windows-1252 characters for the C1 control range 0x80..=0x9F
+    const WINDOWS_1252: [u32; 32] = [
+        0x20AC, 0x81, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160,
+        0x2039, 0x0152, 0x8D, 0x017D, 0x8F, 0x90, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013,
+        0x2014, 0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x9D, 0x017E, 0x0178,
+    ];
+
+    let code = match code {
+        0 => return char::REPLACEMENT_CHARACTER,
+        0x80..=0x9F => WINDOWS_1252[(code - 0x80) as usize],
+        _ => code,
+    };
+    char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// Index of the document root in [`Dom::nodes`]
+const ROOT: usize = 0;
+
+#[derive(Debug)]
+enum NodeKind {
+    Element {
+        tag: String,
+        attrs: Vec<(String, String)>,
+    },
+    Text(String),
+}
+
+#[derive(Debug)]
+struct Node {
+    kind: NodeKind,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// Parsed document; parents always precede their children in `nodes`
+#[derive(Debug)]
+struct Dom {
+    nodes: Vec<Node>,
+}
+
+impl Dom {
+    fn tag(&self, id: usize) -> Option<&str> {
+        match &self.nodes[id].kind {
+            NodeKind::Element { tag, .. } => Some(tag),
+            NodeKind::Text(_) => None,
+        }
+    }
+
+    fn attr(&self, id: usize, name: &str) -> Option<&str> {
+        match &self.nodes[id].kind {
+            NodeKind::Element { attrs, .. } => attrs
+                .iter()
+                .find(|(attr, _)| attr == name)
+                .map(|(_, value)| value.as_str()),
+            NodeKind::Text(_) => None,
+        }
+    }
+
+    fn has_ancestor(&self, id: usize, tags: &[&str]) -> bool {
+        let mut current = self.nodes[id].parent;
+        while let Some(ancestor) = current {
+            if self.tag(ancestor).is_some_and(|tag| tags.contains(&tag)) {
+                return true;
+            }
+            current = self.nodes[ancestor].parent;
+        }
+        false
+    }
+
+    fn find_element(&self, tag: &str) -> Option<usize> {
+        (0..self.nodes.len()).find(|&id| self.tag(id) == Some(tag))
+    }
+
+    /// Text content with whitespace preserved
+    fn raw_text(&self, id: usize) -> String {
+        let mut out = String::new();
+        self.collect_text(id, &mut out);
+        out
+    }
+
+    /// Text content with whitespace collapsed
+    fn text(&self, id: usize) -> String {
+        collapse_whitespace(&self.raw_text(id))
+    }
+
+    fn collect_text(&self, id: usize, out: &mut String) {
+        match &self.nodes[id].kind {
+            NodeKind::Text(text) => out.push_str(text),
+            NodeKind::Element { tag, .. } => {
+                if tag == "br" {
+                    out.push('\n');
+                }
+                for &child in &self.nodes[id].children {
+                    self.collect_text(child, out);
+                }
+            }
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A parsed start tag
+struct StartTag {
+    name: String,
+    attrs: Vec<(String, String)>,
+    self_closing: bool,
+}
+
+/// Parses HTML leniently, recovering from unclosed and misnested tags
+fn parse(html: &str) -> Dom {
+    // ASCII lowercasing keeps byte offsets, so both strings share indices
+    let lower = html.to_ascii_lowercase();
+    let bytes = html.as_bytes();
+    let mut builder = TreeBuilder::new();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let Some(lt) = html[pos..].find('<').map(|i| pos + i) else {
+            builder.text(&html[pos..]);
+            break;
+        };
+        if lt > pos {
+            builder.text(&html[pos..lt]);
+        }
+        pos = lt;
+
+        let rest = &lower[pos..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            pos = comment
+                .find("-->")
+                .map(|i| pos + 4 + i + 3)
+                .unwrap_or(html.len());
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos = skip_past(html, pos, '>');
+        } else if rest.starts_with("</") {
+            let (name, end) = read_tag_name(&lower, pos + 2);
+            pos = skip_past(html, end, '>');
+            if !name.is_empty() {
+                builder.end_tag(name);
+            }
+        } else if bytes.get(pos + 1).is_some_and(u8::is_ascii_alphabetic) {
+            let (tag, end) = parse_start_tag(html, &lower, pos + 1);
+            pos = end;
+            let name = tag.name.clone();
+            let raw = RAW_TEXT_TAGS.contains(&name.as_str()) && !tag.self_closing;
+            builder.start_tag(tag);
+            if raw {
+                let close = format!("</{}", name);
+                let content_end = lower[pos..]
+                    .find(&close)
+                    .map(|i| pos + i)
+                    .unwrap_or(html.len());
+                let content = &html[pos..content_end];
+                if name == "title" || name == "textarea" {
+                    builder.text(content);
+                } else {
+                    builder.raw_text(content);
+                }
+                builder.end_tag(&name);
+                pos = skip_past(html, content_end, '>');
+            }
+        } else {
+            builder.text("<");
+            pos += 1;
+        }
+    }
+
+    builder.finish()
+}
+
+/// Returns the position just past the next `delimiter` at or after `from`
+fn skip_past(html: &str, from: usize, delimiter: char) -> usize {
+    html[from..]
+        .find(delimiter)
+        .map(|i| from + i + delimiter.len_utf8())
+        .unwrap_or(html.len())
+}
+
+fn read_tag_name(lower: &str, start: usize) -> (&str, usize) {
+    let len = lower[start..]
+        .bytes()
+        .take_while(|b| !b.is_ascii_whitespace() && *b != b'/' && *b != b'>')
+        .count();
+    (&lower[start..start + len], start + len)
+}
+
+/// Parses a start tag whose name begins at `start`, returning the tag and the
+/// position after it
+fn parse_start_tag(html: &str, lower: &str, start: usize) -> (StartTag, usize) {
+    let bytes = html.as_bytes();
+    let len = html.len();
+    let (name, mut i) = read_tag_name(lower, start);
+    let mut tag = StartTag {
+        name: name.to_string(),
+        attrs: Vec::new(),
+        self_closing: false,
+    };
+
+    loop {
+        while i < len && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= len {
+            break;
+        }
+        match bytes[i] {
+            b'>' => {
+                i += 1;
+                break;
+            }
+            b'/' => {
+                i += 1;
+                if bytes.get(i) == Some(&b'>') {
+                    tag.self_closing = true;
+                    i += 1;
+                    break;
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let name_start = i;
+        while i < len && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'=' | b'>' | b'/')
+        {
+            i += 1;
+        }
+        if i == name_start {
+            // Stray '=' without a name
+            i += 1;
+            continue;
+        }
+        let attr_name = lower[name_start..i].to_string();
+
+        while i < len && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < len && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let value_start = i + 1;
+                    let value_end = html[value_start..]
+                        .find(quote as char)
+                        .map(|e| value_start + e)
+                        .unwrap_or(len);
+                    value = decode(&html[value_start..value_end], true);
+                    i = (value_end + 1).min(len);
+                }
+                _ => {
+                    let value_start = i;
+                    while i < len && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    value = decode(&html[value_start..i], true);
+                }
+            }
+        }
+
+        if !tag.attrs.iter().any(|(existing, _)| *existing == attr_name) {
+            tag.attrs.push((attr_name, value));
+        }
+    }
+
+    (tag, i)
+}
+
+/// Builds the node tree from tokens, applying HTML's implied end tags
+struct TreeBuilder {
+    nodes: Vec<Node>,
+    /// Open elements; the root is never popped
+    stack: Vec<usize>,
+}
+
+impl TreeBuilder {
+    fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                kind: NodeKind::Element {
+                    tag: "#root".to_string(),
+                    attrs: Vec::new(),
+                },
+                parent: None,
+                children: Vec::new(),
+            }],
+            stack: vec![ROOT],
+        }
+    }
+
+    fn current(&self) -> usize {
+        *self.stack.last().unwrap_or(&ROOT)
+    }
+
+    fn tag(&self, id: usize) -> Option<&str> {
+        match &self.nodes[id].kind {
+            NodeKind::Element { tag, .. } => Some(tag),
+            NodeKind::Text(_) => None,
+        }
+    }
+
+    fn append(&mut self, kind: NodeKind) -> usize {
+        let parent = self.current();
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            kind,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(id);
+        id
+    }
+
+    fn text(&mut self, raw: &str) {
+        self.raw_text(&decode(raw, false));
+    }
+
+    fn raw_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let parent = self.current();
+        if let Some(&last) = self.nodes[parent].children.last() {
+            if let NodeKind::Text(existing) = &mut self.nodes[last].kind {
+                existing.push_str(text);
+                return;
+            }
+        }
+        self.append(NodeKind::Text(text.to_string()));
+    }
+
+    fn start_tag(&mut self, tag: StartTag) {
+        self.close_implied(&tag.name);
+        let void = VOID_TAGS.contains(&tag.name.as_str());
+        let id = self.append(NodeKind::Element {
+            tag: tag.name,
+            attrs: tag.attrs,
+        });
+        if !void && !tag.self_closing {
+            self.stack.push(id);
+        }
+    }
+
+    fn end_tag(&mut self, name: &str) {
+        if name == "br" {
+            // Browsers treat </br> as <br>
+            self.append(NodeKind::Element {
+                tag: "br".to_string(),
+                attrs: Vec::new(),
+            });
+            return;
+        }
+        if let Some(index) = self
+            .stack
+            .iter()
+            .rposition(|&id| self.tag(id) == Some(name))
+        {
+            if index > 0 {
+                self.stack.truncate(index);
+            }
+        }
+    }
+
+    /// Closes elements that the start tag `name` implicitly ends, e.g. an
+    /// open `<li>` when the next `<li>` starts
+    fn close_implied(&mut self, name: &str) {
+        let (targets, scope): (&[&str], &[&str]) = match name {
+            "li" => (&["li"], &["ul", "ol", "menu"]),
+            "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+            "tr" => (&["tr", "td", "th"], &["table", "thead", "tbody", "tfoot"]),
+            "td" | "th" => (&["td", "th"], &["tr", "table"]),
+            "thead" | "tbody" | "tfoot" => {
+                (&["thead", "tbody", "tfoot", "tr", "td", "th"], &["table"])
+            }
+            "option" => (&["option"], &["select", "datalist", "optgroup"]),
+            _ if PARAGRAPH_CLOSERS.contains(&name) => (&["p"], PARAGRAPH_CLOSERS),
+            _ => return,
+        };
+
+        let mut close_from = None;
+        for index in (1..self.stack.len()).rev() {
+            let tag = self.tag(self.stack[index]).unwrap_or_default();
+            if targets.contains(&tag) {
+                close_from = Some(index);
+            } else if scope.contains(&tag) || ["td", "th", "li", "table"].contains(&tag) {
+                break;
+            }
+        }
+        if let Some(index) = close_from {
+            self.stack.truncate(index);
+        }
+    }
+
+    fn finish(self) -> Dom {
+        Dom { nodes: self.nodes }
+    }
+}
+
+/// Finds the document title
+fn find_title(dom: &Dom) -> Option<String> {
+    let title = dom.find_element("title").map(|id| dom.text(id));
+    let og_title = || {
+        (0..dom.nodes.len())
+            .find(|&id| dom.tag(id) == Some("meta") && dom.attr(id, "property") == Some("og:title"))
+            .and_then(|id| dom.attr(id, "content"))
+            .map(collapse_whitespace)
+    };
+    let heading = || dom.find_element("h1").map(|id| dom.text(id));
+
+    [title, og_title(), heading()]
+        .into_iter()
+        .flatten()
+        .find(|title| !title.is_empty())
+}
+
+/// Picks the main content of a document
+struct Extractor<'a> {
+    dom: &'a Dom,
+    /// Nodes excluded from the output (page chrome, scripts, hidden elements)
+    removed: Vec<bool>,
+    /// Characters of visible text per node
+    text_len: Vec<usize>,
+    /// Characters of visible link text per node
+    link_len: Vec<usize>,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(dom: &'a Dom) -> Self {
+        let count = dom.nodes.len();
+        let mut removed = vec![false; count];
+        for id in 1..count {
+            let parent_removed = dom.nodes[id].parent.is_some_and(|parent| removed[parent]);
+            removed[id] = parent_removed || is_non_content(dom, id);
+        }
+
+        // Children follow their parents, so a reverse pass sees them first
+        let mut text_len = vec![0; count];
+        let mut link_len = vec![0; count];
+        for id in (0..count).rev() {
+            if removed[id] {
+                continue;
+            }
+            match &dom.nodes[id].kind {
+                NodeKind::Text(text) => {
+                    text_len[id] = collapse_whitespace(text).chars().count();
+                }
+                NodeKind::Element { tag, .. } => {
+                    let children = &dom.nodes[id].children;
+                    text_len[id] = children.iter().map(|&child| text_len[child]).sum();
+                    link_len[id] = if tag == "a" {
+                        text_len[id]
+                    } else {
+                        children.iter().map(|&child| link_len[child]).sum()
+                    };
+                }
+            }
+        }
+
+        Self {
+            dom,
+            removed,
+            text_len,
+            link_len,
+        }
+    }
+
+    fn link_density(&self, id: usize) -> f64 {
+        match self.text_len[id] {
+            0 => 0.0,
+            len => self.link_len[id] as f64 / len as f64,
+        }
+    }
+
+    fn class_weight(&self, id: usize) -> f64 {
+        let mut weight = 0.0;
+        for attr in ["class", "id"] {
+            if let Some(value) = self.dom.attr(id, attr) {
+                if NEGATIVE_HINTS.is_match(value) {
+                    weight -= 25.0;
+                }
+                if POSITIVE_HINTS.is_match(value) {
+                    weight += 25.0;
+                }
+            }
+        }
+        weight
+    }
+
+    fn initial_score(&self, id: usize) -> f64 {
+        let tag_score = match self.dom.tag(id).unwrap_or_default() {
+            "article" => 10.0,
+            "div" | "main" => 5.0,
+            "pre" | "td" | "blockquote" => 3.0,
+            "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+            _ => 0.0,
+        };
+        tag_score + self.class_weight(id)
+    }
+
+    /// Whether the node scores like a paragraph
+    fn is_scorable(&self, id: usize) -> bool {
+        match self.dom.tag(id) {
+            Some("p" | "pre" | "td" | "blockquote") => true,
+            Some("div") => !self.dom.nodes[id].children.iter().any(|&child| {
+                self.dom
+                    .tag(child)
+                    .is_some_and(|tag| DIV_BLOCK_CHILDREN.contains(&tag))
+            }),
+            _ => false,
+        }
+    }
+
+    /// Returns the nodes to render, in document order
+    fn select_content(&self) -> Vec<usize> {
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for id in 1..self.dom.nodes.len() {
+            if self.removed[id] || !self.is_scorable(id) {
+                continue;
+            }
+            let len = self.text_len[id];
+            if len < MIN_PARAGRAPH_LENGTH {
+                continue;
+            }
+            let commas = self
+                .dom
+                .text(id)
+                .chars()
+                .filter(|c| matches!(c, ',' | '，'))
+                .count();
+            let score = 1.0 + commas as f64 + (len as f64 / 100.0).min(3.0);
+
+            // Paragraphs lend their score to their container and, diminishing,
+            // to the containers above it
+            let mut ancestor = self.dom.nodes[id].parent;
+            for level in 0..3 {
+                let Some(candidate) = ancestor.filter(|&a| a != ROOT) else {
+                    break;
+                };
+                let divider = match level {
+                    0 => 1.0,
+                    1 => 2.0,
+                    _ => level as f64 * 3.0,
+                };
+                *scores
+                    .entry(candidate)
+                    .or_insert_with(|| self.initial_score(candidate)) += score / divider;
+                ancestor = self.dom.nodes[candidate].parent;
+            }
+        }
+
+        let top = scores
+            .iter()
+            .map(|(&id, &score)| (id, score * (1.0 - self.link_density(id))))
+            .fold(None::<(usize, f64)>, |best, (id, score)| match best {
+                Some((best_id, best_score))
+                    if best_score > score || (best_score == score && best_id < id) =>
+                {
+                    best
+                }
+                _ => Some((id, score)),
+            });
+
+        let Some((top, top_score)) = top else {
+            return vec![self.dom.find_element("body").unwrap_or(ROOT)];
+        };
+        let Some(parent) = self.dom.nodes[top].parent else {
+            return vec![top];
+        };
+
+        // Siblings that look like a continuation of the content are kept too
+        let threshold = (top_score * 0.2).max(10.0);
+        let top_class = self
+            .dom
+            .attr(top, "class")
+            .filter(|class| !class.is_empty());
+        self.dom.nodes[parent]
+            .children
+            .iter()
+            .copied()
+            .filter(|&sibling| {
+                if sibling == top {
+                    return true;
+                }
+                if self.removed[sibling] || self.dom.tag(sibling).is_none() {
+                    return false;
+                }
+                let bonus = if top_class.is_some() && self.dom.attr(sibling, "class") == top_class {
+                    top_score * 0.2
+                } else {
+                    0.0
+                };
+                if scores
+                    .get(&sibling)
+                    .is_some_and(|score| score + bonus >= threshold)
+                {
+                    return true;
+                }
+                if self.dom.tag(sibling) != Some("p") {
+                    return false;
+                }
+                let len = self.text_len[sibling];
+                let density = self.link_density(sibling);
+                if len > 80 {
+                    density < 0.25
+                } else {
+                    let text = self.dom.text(sibling);
+                    len > 0 && density == 0.0 && (text.contains(". ") || text.ends_with('.'))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether an element is page chrome or hidden
+fn is_non_content(dom: &Dom, id: usize) -> bool {
+    let Some(tag) = dom.tag(id) else {
+        return false;
+    };
+    if NON_CONTENT_TAGS.contains(&tag) {
+        return true;
+    }
+    if tag == "header" && !dom.has_ancestor(id, &["article", "main"]) {
+        return true;
+    }
+    if dom.attr(id, "hidden").is_some() || dom.attr(id, "aria-hidden") == Some("true") {
+        return true;
+    }
+    if let Some(style) = dom.attr(id, "style") {
+        let style: String = style
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if style.contains("display:none") || style.contains("visibility:hidden") {
+            return true;
+        }
+    }
+    if dom
+        .attr(id, "role")
+        .is_some_and(|role| NON_CONTENT_ROLES.contains(&role))
+    {
+        return true;
+    }
+
+    if matches!(
+        tag,
+        "html" | "body" | "article" | "main" | "a" | "table" | "tbody" | "tr" | "td" | "th"
+    ) {
+        return false;
+    }
+    let hints = format!(
+        "{} {}",
+        dom.attr(id, "class").unwrap_or_default(),
+        dom.attr(id, "id").unwrap_or_default()
+    );
+    UNLIKELY_CANDIDATES.is_match(&hints) && !MAYBE_CANDIDATES.is_match(&hints)
+}
+
+/// List being rendered
+struct ListState {
+    ordered: bool,
+    next: usize,
+}
+
+/// Renders nodes as Markdown-ish text
+struct Renderer<'a> {
+    dom: &'a Dom,
+    removed: &'a [bool],
+    base_url: Option<&'a reqwest::Url>,
+    out: String,
+    /// Prepended to each line (quote markers, list indentation)
+    prefix: String,
+    /// Newlines to emit before the next content
+    pending_break: usize,
+    /// Prefix in effect when the pending break was requested
+    break_prefix: String,
+    /// Whether a space separates the next content from the previous
+    pending_space: bool,
+    /// Set right after a list marker so the item's first block stays on its line
+    after_marker: bool,
+    lists: Vec<ListState>,
+    /// Cells written in the current table row
+    row_cells: usize,
+    /// Nesting depth of table cells, where blocks become spaces
+    cell_depth: usize,
+    links: Vec<Link>,
+    link_indices: HashMap<String, usize>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(dom: &'a Dom, removed: &'a [bool], base_url: Option<&'a reqwest::Url>) -> Self {
+        Self {
+            dom,
+            removed,
+            base_url,
+            out: String::new(),
+            prefix: String::new(),
+            pending_break: 1,
+            break_prefix: String::new(),
+            pending_space: false,
+            after_marker: false,
+            lists: Vec::new(),
+            row_cells: 0,
+            cell_depth: 0,
+            links: Vec::new(),
+            link_indices: HashMap::new(),
+        }
+    }
+
+    fn finish(mut self) -> (String, Vec<Link>) {
+        let trimmed = self.out.trim_end().len();
+        self.out.truncate(trimmed);
+        (self.out, self.links)
+    }
+
+    /// Emits pending line breaks or spacing before new content
+    fn flush(&mut self) {
+        if self.pending_break > 0 {
+            if !self.out.is_empty() {
+                let trimmed = self.out.trim_end_matches(' ').len();
+                self.out.truncate(trimmed);
+                // Blank lines only carry the prefix shared by both sides, so a
+                // quote starts after an empty line rather than a bare '>'
+                let shared = self
+                    .prefix
+                    .bytes()
+                    .zip(self.break_prefix.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                let blank_prefix = self.prefix[..shared].trim_end();
+                for line in 0..self.pending_break {
+                    self.out.push('\n');
+                    if line + 1 < self.pending_break {
+                        self.out.push_str(blank_prefix);
+                    }
+                }
+            }
+            self.out.push_str(&self.prefix);
+            self.pending_break = 0;
+        } else if self.pending_space && !self.out.is_empty() && !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+        self.pending_space = false;
+        self.after_marker = false;
+    }
+
+    /// Requests a line break (1) or blank line (2) before the next content
+    fn block(&mut self, lines: usize) {
+        if self.cell_depth > 0 {
+            self.pending_space = true;
+        } else if !self.after_marker {
+            self.line_break(lines);
+        }
+    }
+
+    fn line_break(&mut self, lines: usize) {
+        if self.pending_break == 0 {
+            self.break_prefix.clone_from(&self.prefix);
+        }
+        self.pending_break = self.pending_break.max(lines);
+    }
+
+    fn text(&mut self, text: &str) {
+        if text.starts_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+        let mut has_words = false;
+        for (index, word) in text.split_whitespace().enumerate() {
+            if index > 0 {
+                self.pending_space = true;
+            }
+            self.flush();
+            self.out.push_str(word);
+            has_words = true;
+        }
+        if has_words {
+            self.pending_space = text.ends_with(char::is_whitespace);
+        }
+    }
+
+    fn raw(&mut self, text: &str) {
+        self.flush();
+        self.out.push_str(text);
+    }
+
+    /// Appends a closing marker directly after the preceding content
+    fn close(&mut self, marker: &str) {
+        let space = self.pending_space;
+        self.out.push_str(marker);
+        self.pending_space = space;
+    }
+
+    fn children(&mut self, id: usize) {
+        let dom = self.dom;
+        for &child in &dom.nodes[id].children {
+            self.render(child);
+        }
+    }
+
+    fn render(&mut self, id: usize) {
+        if self.removed[id] {
+            return;
+        }
+        let dom = self.dom;
+        match &dom.nodes[id].kind {
+            NodeKind::Text(text) => self.text(text),
+            NodeKind::Element { tag, .. } => self.element(id, tag),
+        }
+    }
+
+    fn element(&mut self, id: usize, tag: &str) {
+        match tag {
+            "br" => {
+                if self.cell_depth > 0 {
+                    self.pending_space = true;
+                } else {
+                    self.line_break(1);
+                }
+            }
+            "hr" => {
+                self.block(2);
+                self.raw("---");
+                self.block(2);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(tag.as_bytes()[1] - b'0');
+                self.block(2);
+                self.flush();
+                let start = self.out.len();
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+                let content_start = self.out.len();
+                self.children(id);
+                if self.out.len() == content_start {
+                    self.out.truncate(start);
+                }
+                self.block(2);
+            }
+            "p" | "figure" | "dl" => {
+                self.block(2);
+                self.children(id);
+                self.block(2);
+            }
+            "ul" | "ol" | "menu" => {
+                let gap = if self.lists.is_empty() { 2 } else { 1 };
+                let start = self
+                    .dom
+                    .attr(id, "start")
+                    .and_then(|start| start.trim().parse().ok())
+                    .unwrap_or(1);
+                self.block(gap);
+                self.lists.push(ListState {
+                    ordered: tag == "ol",
+                    next: start,
+                });
+                self.children(id);
+                self.lists.pop();
+                self.block(gap);
+            }
+            "li" => {
+                let marker = match self.lists.last_mut() {
+                    Some(list) if list.ordered => {
+                        list.next += 1;
+                        format!("{}. ", list.next - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.block(1);
+                self.raw(&marker);
+                self.after_marker = true;
+                let indent = self.prefix.len();
+                self.prefix.push_str(&" ".repeat(marker.len()));
+                self.children(id);
+                self.prefix.truncate(indent);
+                self.after_marker = false;
+                self.block(1);
+            }
+            "dd" => {
+                self.block(1);
+                let indent = self.prefix.len();
+                self.prefix.push_str("  ");
+                self.children(id);
+                self.prefix.truncate(indent);
+                self.block(1);
+            }
+            "blockquote" => {
+                self.block(2);
+                let indent = self.prefix.len();
+                self.prefix.push_str("> ");
+                self.children(id);
+                self.prefix.truncate(indent);
+                self.block(2);
+            }
+            "pre" => self.code_block(id),
+            "code" | "kbd" | "samp" | "tt" => {
+                let code = self.dom.text(id);
+                if !code.is_empty() {
+                    self.raw(&format!("`{}`", code));
+                }
+            }
+            "strong" | "b" => self.emphasis(id, "**"),
+            "em" | "i" => self.emphasis(id, "*"),
+            "del" | "s" | "strike" => self.emphasis(id, "~~"),
+            "a" => self.link(id),
+            "img" => {
+                if let Some(alt) = self.dom.attr(id, "alt").map(collapse_whitespace) {
+                    if !alt.is_empty() {
+                        self.raw(&format!("[Image: {}]", alt));
+                    }
+                }
+            }
+            "table" => {
+                self.block(2);
+                let row_cells = self.row_cells;
+                self.children(id);
+                self.row_cells = row_cells;
+                self.block(2);
+            }
+            "tr" => {
+                self.block(1);
+                self.row_cells = 0;
+                self.children(id);
+                self.block(1);
+            }
+            "td" | "th" => {
+                if self.row_cells > 0 {
+                    self.pending_space = false;
+                    self.out.push_str(" | ");
+                }
+                self.row_cells += 1;
+                self.cell_depth += 1;
+                self.children(id);
+                self.cell_depth -= 1;
+            }
+            "div" | "section" | "article" | "main" | "header" | "footer" | "form" | "fieldset"
+            | "address" | "details" | "summary" | "figcaption" | "caption" | "center" | "dt"
+            | "legend" | "body" | "html" => {
+                self.block(1);
+                self.children(id);
+                self.block(1);
+            }
+            _ => self.children(id),
+        }
+    }
+
+    fn code_block(&mut self, id: usize) {
+        let code = self.dom.raw_text(id);
+        let code = code.trim_matches('\n').trim_end();
+        if code.is_empty() {
+            return;
+        }
+        let language = std::iter::once(id)
+            .chain(self.dom.nodes[id].children.iter().copied())
+            .filter_map(|node| self.dom.attr(node, "class"))
+            .flat_map(str::split_whitespace)
+            .find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or_else(|| class.strip_prefix("lang-"))
+            })
+            .unwrap_or_default()
+            .to_string();
+
+        self.block(2);
+        self.raw(&format!("```{}", language));
+        for line in code.lines() {
+            self.out.push('\n');
+            self.out.push_str(&self.prefix);
+            self.out.push_str(line.trim_end());
+        }
+        self.out.push('\n');
+        self.out.push_str(&self.prefix);
+        self.out.push_str("```");
+        self.block(2);
+    }
+
+    fn emphasis(&mut self, id: usize, marker: &str) {
+        self.raw(marker);
+        let content_start = self.out.len();
+        self.children(id);
+        if self.out.len() == content_start {
+            self.out.truncate(content_start - marker.len());
+        } else {
+            self.close(marker);
+        }
+    }
+
+    fn link(&mut self, id: usize) {
+        let Some(url) = self
+            .dom
+            .attr(id, "href")
+            .and_then(|href| self.resolve(href))
+        else {
+            self.children(id);
+            return;
+        };
+
+        self.raw("[");
+        let content_start = self.out.len();
+        self.children(id);
+        if self.out.len() == content_start {
+            self.out.truncate(content_start - 1);
+            return;
+        }
+
+        let index = match self.link_indices.get(&url) {
+            Some(&index) => index,
+            None => {
+                let index = self.links.len() + 1;
+                self.links.push(Link {
+                    index,
+                    text: self.dom.text(id),
+                    url: url.clone(),
+                });
+                self.link_indices.insert(url, index);
+                index
+            }
+        };
+        self.close(&format!("][{}]", index));
+    }
+
+    /// Resolves an `href`, skipping in-page anchors and script links
+    fn resolve(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        let lower = href.to_ascii_lowercase();
+        if href.is_empty()
+            || href.starts_with('#')
+            || lower.starts_with("javascript:")
+            || lower.starts_with("data:")
+        {
+            return None;
+        }
+        match self.base_url {
+            Some(base) => base.join(href).ok().map(String::from),
+            None => Some(href.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract_text(html: &str) -> String {
+        extract(html, None).content
+    }
+
+    #[test]
+    fn test_decode_named_and_numeric_entities() {
+        assert_eq!(
+            decode_entities("a &lt; b &amp;&amp; c &gt; d"),
+            "a < b && c > d"
+        );
+        assert_eq!(
+            decode_entities("&hellip;&mdash;&euro;&NotEqualTilde;"),
+            "…—€\u{2242}\u{338}"
+        );
+        assert_eq!(decode_entities("&#65;&#x42;&#X43;&#8217;"), "ABC\u{2019}");
+        assert_eq!(decode_entities("&#150;&#0;"), "\u{2013}\u{FFFD}");
+    }
+
+    #[test]
+    fn test_decode_legacy_and_unknown_entities() {
+        assert_eq!(decode_entities("&copy 2024 &amp Co"), "© 2024 & Co");
+        assert_eq!(decode_entities("&notit;"), "¬it;");
+        assert_eq!(decode_entities("&bogus; & &#;"), "&bogus; & &#;");
+        // Legacy names are not expanded inside attribute values
+        assert_eq!(decode("?a=1&copy=2", true), "?a=1&copy=2");
+        assert_eq!(decode("?a=1&amp;b=2", true), "?a=1&b=2");
+    }
+
+    #[test]
+    fn test_scripts_styles_and_chrome_removed() {
+        let html = r#"<html><head><title>T</title><style>body { color: red }</style></head>
+            <body><nav><a href="/">Home</a></nav>
+            <script>var x = "<p>not text</p>";</script>
+            <p>Visible</p>
+            <div style="display: none">Hidden</div>
+            <footer>Copyright</footer></body></html>"#;
+        let text = extract_text(html);
+        assert_eq!(text, "Visible");
+    }
+
+    #[test]
+    fn test_main_content_selected() {
+        let paragraph =
+            "This sentence is part of the article body, and it is long enough to count.";
+        let html = format!(
+            r#"<body>
+            <div class="sidebar"><p>{0}</p></div>
+            <div id="content">
+              <h1>Headline</h1>
+              <p>{0}</p><p>{0}</p><p>{0}</p>
+            </div>
+            <div class="comments"><p>{0}</p></div>
+            <div class="links"><p><a href="/a">Some link text that is long enough</a></p></div>
+            </body>"#,
+            paragraph
+        );
+        let text = extract_text(&html);
+        assert!(text.starts_with("# Headline\n\n"));
+        assert_eq!(text.matches(paragraph).count(), 3);
+        assert!(!text.contains("Some link text"));
+    }
+
+    #[test]
+    fn test_markdown_rendering() {
+        let html = r#"<body>
+            <h2>Section</h2>
+            <p>Some <strong>bold</strong> and <em>emphasised</em> text with <code>code()</code>.</p>
+            <ul><li>One<li>Two<ul><li>Nested</li></ul></li></ul>
+            <ol start="3"><li>Three</li><li>Four</li></ol>
+            <blockquote><p>Quoted</p><p>Twice</p></blockquote>
+            <pre class="language-rust">fn main() {
+    println!("hi");
+}</pre>
+            <table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>
+            </body>"#;
+        let expected = "## Section\n\n\
+            Some **bold** and *emphasised* text with `code()`.\n\n\
+            - One\n\
+            - Two\n  \
+              - Nested\n\n\
+            3. Three\n\
+            4. Four\n\n\
+            > Quoted\n\
+            >\n\
+            > Twice\n\n\
+            ```rust\n\
+            fn main() {\n    println!(\"hi\");\n}\n\
+            ```\n\n\
+            A | B\n\
+            1 | 2";
+        assert_eq!(extract_text(html), expected);
+    }
+
+    #[test]
+    fn test_links_collected_and_resolved() {
+        let base = reqwest::Url::parse("https://example.com/docs/page.html").unwrap();
+        let html = r##"<p>See <a href="intro.html">the intro</a>, <a href="https://other.org/">another site</a>
+            and <a href="/docs/intro.html">the intro again</a>.
+            <a href="#top">Top</a> <a href="javascript:void(0)">Click</a> <a href="x"></a></p>"##;
+        let article = extract(html, Some(&base));
+        assert_eq!(
+            article.content,
+            "See [the intro][1], [another site][2] and [the intro again][1]. Top Click"
+        );
+        assert_eq!(
+            article.links,
+            vec![
+                Link {
+                    index: 1,
+                    text: "the intro".to_string(),
+                    url: "https://example.com/docs/intro.html".to_string(),
+                },
+                Link {
+                    index: 2,
+                    text: "another site".to_string(),
+                    url: "https://other.org/".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_title_sources() {
+        let html = "<html><head><title> Page &amp; Title </title></head><body><h1>Heading</h1></body></html>";
+        assert_eq!(extract(html, None).title.as_deref(), Some("Page & Title"));
+
+        let html = r#"<meta property="og:title" content="OG title"><h1>Heading</h1>"#;
+        assert_eq!(extract(html, None).title.as_deref(), Some("OG title"));
+
+        assert_eq!(
+            extract("<h1>Heading</h1>", None).title.as_deref(),
+            Some("Heading")
+        );
+        assert_eq!(extract("<p>No title</p>", None).title, None);
+    }
+
+    #[test]
+    fn test_malformed_html() {
+        let html =
+            "<p>First<p>Second <b>bold <i>both</b> after</i><div>Block</div>a < b<!-- note -->";
+        assert_eq!(
+            extract_text(html),
+            "First\n\nSecond **bold *both*** after\n\nBlock\na < b"
+        );
+        assert_eq!(extract_text("<p unclosed=\"attr>text"), "");
+        assert_eq!(extract_text("<script>never closed"), "");
+    }
+}
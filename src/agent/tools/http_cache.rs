@@ -0,0 +1,581 @@
+//! On-disk HTTP cache for the web tool
+//!
+//! Keeps successful GET responses in the workspace so pages fetched again in
+//! later turns or by cron jobs are not downloaded twice. Freshness follows
+//! `Cache-Control` (`max-age`, `no-cache`, `no-store`) and `Expires`, falling
+//! back to a tenth of the time since `Last-Modified`. Stale entries with an
+//! `ETag` or `Last-Modified` are revalidated with a conditional request.
+//! Once the bodies exceed the size cap, the least recently used entries are
+//! evicted.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+/// Name of the file listing the entries, next to the bodies
+const INDEX_FILE: &str = "index.json";
+
+/// Longest freshness lifetime derived from `Last-Modified`, in seconds
+const MAX_HEURISTIC_LIFETIME_SECS: u64 = 24 * 60 * 60;
+
+/// A response as stored in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedPage {
+    pub status: u16,
+    pub content: String,
+    pub content_type: String,
+    /// URL after redirects
+    pub url: String,
+}
+
+/// Result of looking a request up in the cache
+#[derive(Debug)]
+pub enum Lookup {
+    /// Nothing usable is stored
+    Miss,
+    /// The stored response is fresh and can be used as-is
+    Fresh(CachedPage),
+    /// The stored response must be revalidated by sending `validators`
+    Stale {
+        page: CachedPage,
+        validators: HeaderMap,
+    },
+}
+
+/// How long a response may be served without revalidation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freshness {
+    /// `no-store`: never keep the response
+    NoStore,
+    /// Fresh for this many seconds after it was received
+    Lifetime(u64),
+}
+
+/// Bookkeeping for one stored response; the body lives in its own file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    /// Full request key, to tell apart keys whose hashes collide
+    key: String,
+    status: u16,
+    content_type: String,
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Unix time the response was received or last revalidated
+    stored_at: i64,
+    /// Seconds the response stays fresh after `stored_at`
+    lifetime: u64,
+    /// Size of the body file in bytes
+    size: u64,
+    /// Unix time the entry was last stored or served, for LRU eviction
+    last_used: i64,
+}
+
+/// Cache of GET responses in a directory, bounded to `max_bytes` of bodies
+#[derive(Debug)]
+pub struct HttpCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Entries by file stem; loaded from disk on first use
+    index: tokio::sync::Mutex<Option<HashMap<String, Entry>>>,
+}
+
+impl HttpCache {
+    /// Creates a cache in `dir`; the directory is created when first written
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            index: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Builds the key of a request from its URL, its headers and the host of
+    /// its credential
+    ///
+    /// Sensitive header values (secrets of credentials) are left out; the
+    /// credential host keeps authenticated and anonymous responses apart.
+    pub fn key(url: &reqwest::Url, headers: &HeaderMap, credential_host: Option<&str>) -> String {
+        let mut parts: Vec<String> = headers
+            .iter()
+            .filter(|(_, value)| !value.is_sensitive())
+            .map(|(name, value)| format!("{}:{}", name, String::from_utf8_lossy(value.as_bytes())))
+            .collect();
+        parts.sort();
+        let mut key = format!("GET {}", url);
+        for part in parts {
+            key.push('\n');
+            key.push_str(&part);
+        }
+        if let Some(host) = credential_host {
+            key.push_str("\ncredential:");
+            key.push_str(host);
+        }
+        key
+    }
+
+    /// Looks up a request
+    ///
+    /// With `max_age`, a stored response is fresh if it is younger than that many
+    /// seconds old, whatever the server said; `Some(0)` always revalidates.
+    pub async fn lookup(&self, key: &str, max_age: Option<u64>) -> Lookup {
+        let mut guard = self.index.lock().await;
+        let index = self.load(&mut guard).await;
+        let stem = file_stem(key);
+        let Some(entry) = index.get(&stem).filter(|entry| entry.key == key).cloned() else {
+            return Lookup::Miss;
+        };
+        let content = match tokio::fs::read_to_string(self.body_path(&stem)).await {
+            Ok(content) => content,
+            Err(_) => {
+                index.remove(&stem);
+                self.save(index).await;
+                return Lookup::Miss;
+            }
+        };
+        let page = CachedPage {
+            status: entry.status,
+            content,
+            content_type: entry.content_type.clone(),
+            url: entry.url.clone(),
+        };
+
+        let now = now();
+        let age = u64::try_from(now - entry.stored_at).unwrap_or(0);
+        let fresh = match max_age {
+            Some(max_age) => age < max_age,
+            None => age < entry.lifetime,
+        };
+        if fresh {
+            if let Some(entry) = index.get_mut(&stem) {
+                entry.last_used = now;
+            }
+            self.save(index).await;
+            return Lookup::Fresh(page);
+        }
+
+        let mut validators = HeaderMap::new();
+        if let Some(value) = entry.etag.as_deref().and_then(header_value) {
+            validators.insert(header::IF_NONE_MATCH, value);
+        }
+        if let Some(value) = entry.last_modified.as_deref().and_then(header_value) {
+            validators.insert(header::IF_MODIFIED_SINCE, value);
+        }
+        if validators.is_empty() {
+            Lookup::Miss
+        } else {
+            Lookup::Stale { page, validators }
+        }
+    }
+
+    /// Stores a response unless its headers forbid it or it exceeds the cap
+    pub async fn store(&self, key: &str, page: &CachedPage, headers: &HeaderMap) {
+        let Freshness::Lifetime(lifetime) = freshness(headers, now()) else {
+            return;
+        };
+        let size = page.content.len() as u64;
+        if size > self.max_bytes {
+            return;
+        }
+
+        let mut guard = self.index.lock().await;
+        let index = self.load(&mut guard).await;
+        let stem = file_stem(key);
+        if let Err(e) = tokio::fs::create_dir_all(&self.dir).await {
+            tracing::warn!(error = %e, "Failed to create web cache directory");
+            return;
+        }
+        if let Err(e) = tokio::fs::write(self.body_path(&stem), &page.content).await {
+            tracing::warn!(error = %e, "Failed to write web cache entry");
+            return;
+        }
+        let now = now();
+        index.insert(
+            stem.clone(),
+            Entry {
+                key: key.to_string(),
+                status: page.status,
+                content_type: page.content_type.clone(),
+                url: page.url.clone(),
+                etag: header_text(headers, header::ETAG),
+                last_modified: header_text(headers, header::LAST_MODIFIED),
+                stored_at: now,
+                lifetime,
+                size,
+                last_used: now,
+            },
+        );
+        self.evict(index, &stem).await;
+        self.save(index).await;
+    }
+
+    /// Records a `304 Not Modified` answer to a revalidation
+    ///
+    /// The entry's validators and lifetime are taken from the new headers, as
+    /// servers send them again on 304. A `no-store` answer drops the entry.
+    pub async fn refresh(&self, key: &str, headers: &HeaderMap) {
+        let mut guard = self.index.lock().await;
+        let index = self.load(&mut guard).await;
+        let stem = file_stem(key);
+        let now = now();
+        match freshness(headers, now) {
+            Freshness::NoStore => {
+                index.remove(&stem);
+                let _ = tokio::fs::remove_file(self.body_path(&stem)).await;
+            }
+            Freshness::Lifetime(lifetime) => {
+                let Some(entry) = index.get_mut(&stem).filter(|entry| entry.key == key) else {
+                    return;
+                };
+                entry.stored_at = now;
+                entry.last_used = now;
+                entry.lifetime = lifetime;
+                if let Some(etag) = header_text(headers, header::ETAG) {
+                    entry.etag = Some(etag);
+                }
+                if let Some(last_modified) = header_text(headers, header::LAST_MODIFIED) {
+                    entry.last_modified = Some(last_modified);
+                }
+            }
+        }
+        self.save(index).await;
+    }
+
+    /// Removes least recently used entries, except `keep`, until the bodies
+    /// fit in the cap
+    async fn evict(&self, index: &mut HashMap<String, Entry>, keep: &str) {
+        let mut total: u64 = index.values().map(|entry| entry.size).sum();
+        while total > self.max_bytes {
+            let Some(oldest) = index
+                .iter()
+                .filter(|(stem, _)| stem.as_str() != keep)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(stem, _)| stem.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = index.remove(&oldest) {
+                total -= entry.size;
+            }
+            let _ = tokio::fs::remove_file(self.body_path(&oldest)).await;
+        }
+    }
+
+    /// Reads the index from disk the first time it is needed
+    async fn load<'a>(
+        &self,
+        guard: &'a mut Option<HashMap<String, Entry>>,
+    ) -> &'a mut HashMap<String, Entry> {
+        if guard.is_none() {
+            let index = match tokio::fs::read(self.dir.join(INDEX_FILE)).await {
+                Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "Ignoring corrupt web cache index");
+                    HashMap::new()
+                }),
+                Err(_) => HashMap::new(),
+            };
+            *guard = Some(index);
+        }
+        guard.get_or_insert_with(HashMap::new)
+    }
+
+    /// Writes the index through a temporary file so readers never see half of it
+    async fn save(&self, index: &HashMap<String, Entry>) {
+        let data = match serde_json::to_vec(index) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to serialize web cache index");
+                return;
+            }
+        };
+        let _ = tokio::fs::create_dir_all(&self.dir).await;
+        let temp = self.dir.join(format!("{}.tmp", INDEX_FILE));
+        let result = match tokio::fs::write(&temp, data).await {
+            Ok(()) => tokio::fs::rename(&temp, self.dir.join(INDEX_FILE)).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to write web cache index");
+        }
+    }
+
+    fn body_path(&self, stem: &str) -> PathBuf {
+        self.dir.join(format!("{}.body", stem))
+    }
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// File name of an entry: FNV-1a hash of its key, stable across builds
+fn file_stem(key: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn header_text(headers: &HeaderMap, name: header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn header_value(text: &str) -> Option<HeaderValue> {
+    HeaderValue::from_str(text).ok()
+}
+
+fn parse_http_date(text: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc2822(text.trim())
+        .ok()
+        .map(|date| date.timestamp())
+}
+
+/// Works out how long a response stays fresh from its headers
+fn freshness(headers: &HeaderMap, now: i64) -> Freshness {
+    let mut max_age = None;
+    let mut no_cache = false;
+    for value in headers.get_all(header::CACHE_CONTROL) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for directive in value.split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            match directive.split_once('=') {
+                Some(("max-age", seconds)) => {
+                    max_age = seconds.trim_matches('"').parse::<u64>().ok().or(max_age);
+                }
+                _ if directive == "no-store" => return Freshness::NoStore,
+                _ if directive == "no-cache" => no_cache = true,
+                _ => {}
+            }
+        }
+    }
+    if no_cache {
+        return Freshness::Lifetime(0);
+    }
+    // Time the response already spent in caches upstream
+    let age = header_text(headers, header::AGE)
+        .and_then(|age| age.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    let date = header_text(headers, header::DATE)
+        .and_then(|date| parse_http_date(&date))
+        .unwrap_or(now);
+
+    let lifetime = if let Some(max_age) = max_age {
+        max_age
+    } else if let Some(expires) = header_text(headers, header::EXPIRES) {
+        // An invalid date such as "0" means already expired
+        parse_http_date(&expires)
+            .map(|expires| u64::try_from(expires - date).unwrap_or(0))
+            .unwrap_or(0)
+    } else if let Some(modified) =
+        header_text(headers, header::LAST_MODIFIED).and_then(|text| parse_http_date(&text))
+    {
+        (u64::try_from(date - modified).unwrap_or(0) / 10).min(MAX_HEURISTIC_LIFETIME_SECS)
+    } else {
+        0
+    };
+    Freshness::Lifetime(lifetime.saturating_sub(age))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn page(content: &str) -> CachedPage {
+        CachedPage {
+            status: 200,
+            content: content.to_string(),
+            content_type: "text/html".to_string(),
+            url: "https://example.com/".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_freshness() {
+        let now = parse_http_date("Wed, 21 Oct 2026 07:28:00 GMT").unwrap();
+        let cases = [
+            (
+                vec![("cache-control", "public, max-age=600")],
+                Freshness::Lifetime(600),
+            ),
+            (
+                vec![("cache-control", "max-age=600"), ("age", "100")],
+                Freshness::Lifetime(500),
+            ),
+            (
+                vec![("cache-control", "no-cache, max-age=600")],
+                Freshness::Lifetime(0),
+            ),
+            (
+                vec![("cache-control", "private, no-store")],
+                Freshness::NoStore,
+            ),
+            (
+                vec![
+                    ("date", "Wed, 21 Oct 2026 07:28:00 GMT"),
+                    ("expires", "Wed, 21 Oct 2026 08:28:00 GMT"),
+                ],
+                Freshness::Lifetime(3600),
+            ),
+            (vec![("expires", "0")], Freshness::Lifetime(0)),
+            (
+                vec![("last-modified", "Wed, 21 Oct 2026 04:28:00 GMT")],
+                Freshness::Lifetime(1080),
+            ),
+            (
+                vec![("last-modified", "Mon, 01 Jan 2024 00:00:00 GMT")],
+                Freshness::Lifetime(MAX_HEURISTIC_LIFETIME_SECS),
+            ),
+            (vec![], Freshness::Lifetime(0)),
+        ];
+        for (pairs, expected) in cases {
+            assert_eq!(freshness(&headers(&pairs), now), expected, "{:?}", pairs);
+        }
+    }
+
+    #[test]
+    fn test_key_ignores_secrets() {
+        let url = reqwest::Url::parse("https://example.com/a").unwrap();
+        let mut first = headers(&[("accept", "text/html")]);
+        let mut secret = HeaderValue::from_static("Bearer one");
+        secret.set_sensitive(true);
+        first.insert("authorization", secret);
+        let key = HttpCache::key(&url, &first, Some("example.com"));
+        assert!(!key.contains("Bearer"));
+        assert!(key.contains("accept:text/html"));
+        assert_ne!(key, HttpCache::key(&url, &first, None));
+        assert_ne!(
+            key,
+            HttpCache::key(&url, &HeaderMap::new(), Some("example.com"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_store_and_lookup() {
+        let dir = TempDir::new().unwrap();
+        let cache = HttpCache::new(dir.path().to_path_buf(), 1024);
+
+        assert!(matches!(cache.lookup("a", None).await, Lookup::Miss));
+        cache
+            .store(
+                "a",
+                &page("fresh"),
+                &headers(&[("cache-control", "max-age=60")]),
+            )
+            .await;
+        match cache.lookup("a", None).await {
+            Lookup::Fresh(cached) => assert_eq!(cached, page("fresh")),
+            other => panic!("expected a fresh entry, got {:?}", other),
+        }
+        // max_age 0 forces revalidation, but there is nothing to revalidate with
+        assert!(matches!(cache.lookup("a", Some(0)).await, Lookup::Miss));
+
+        cache
+            .store(
+                "b",
+                &page("nope"),
+                &headers(&[("cache-control", "no-store")]),
+            )
+            .await;
+        assert!(matches!(cache.lookup("b", None).await, Lookup::Miss));
+
+        // Entries survive a restart
+        let reopened = HttpCache::new(dir.path().to_path_buf(), 1024);
+        assert!(matches!(reopened.lookup("a", None).await, Lookup::Fresh(_)));
+    }
+
+    #[tokio::test]
+    async fn test_revalidation() {
+        let dir = TempDir::new().unwrap();
+        let cache = HttpCache::new(dir.path().to_path_buf(), 1024);
+        cache
+            .store(
+                "a",
+                &page("body"),
+                &headers(&[
+                    ("cache-control", "no-cache"),
+                    ("etag", "\"v1\""),
+                    ("last-modified", "Wed, 21 Oct 2026 04:28:00 GMT"),
+                ]),
+            )
+            .await;
+
+        match cache.lookup("a", None).await {
+            Lookup::Stale {
+                page: cached,
+                validators,
+            } => {
+                assert_eq!(cached.content, "body");
+                assert_eq!(validators[header::IF_NONE_MATCH], "\"v1\"");
+                assert_eq!(
+                    validators[header::IF_MODIFIED_SINCE],
+                    "Wed, 21 Oct 2026 04:28:00 GMT"
+                );
+            }
+            other => panic!("expected a stale entry, got {:?}", other),
+        }
+        // An explicit max_age accepts the stored copy without asking the server
+        assert!(matches!(
+            cache.lookup("a", Some(3600)).await,
+            Lookup::Fresh(_)
+        ));
+
+        cache
+            .refresh(
+                "a",
+                &headers(&[("cache-control", "max-age=60"), ("etag", "\"v2\"")]),
+            )
+            .await;
+        assert!(matches!(cache.lookup("a", None).await, Lookup::Fresh(_)));
+        match cache.lookup("a", Some(0)).await {
+            Lookup::Stale { validators, .. } => {
+                assert_eq!(validators[header::IF_NONE_MATCH], "\"v2\"")
+            }
+            other => panic!("expected a stale entry, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction() {
+        let dir = TempDir::new().unwrap();
+        let cache = HttpCache::new(dir.path().to_path_buf(), 10);
+        let fresh = headers(&[("cache-control", "max-age=60")]);
+
+        cache.store("a", &page("aaaa"), &fresh).await;
+        cache.store("b", &page("bbbb"), &fresh).await;
+        // Make "b" the least recently used entry
+        {
+            let mut guard = cache.index.lock().await;
+            let index = cache.load(&mut guard).await;
+            index.get_mut(&file_stem("b")).unwrap().last_used -= 10;
+        }
+        assert!(matches!(cache.lookup("a", None).await, Lookup::Fresh(_)));
+        cache.store("c", &page("cccc"), &fresh).await;
+
+        assert!(matches!(cache.lookup("a", None).await, Lookup::Fresh(_)));
+        assert!(matches!(cache.lookup("b", None).await, Lookup::Miss));
+        assert!(matches!(cache.lookup("c", None).await, Lookup::Fresh(_)));
+        assert!(!dir.path().join(format!("{}.body", file_stem("b"))).exists());
+
+        // A body larger than the whole cache is not stored
+        cache.store("d", &page("ddddddddddddd"), &fresh).await;
+        assert!(matches!(cache.lookup("d", None).await, Lookup::Miss));
+    }
+}
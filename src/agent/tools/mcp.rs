@@ -0,0 +1,894 @@
+//! MCP (Model Context Protocol) client
+//!
+//! Servers declared under `tools.mcp.servers` are started as child processes
+//! speaking MCP over stdio, or reached at a streamable HTTP endpoint. After
+//! the `initialize` handshake the client lists each server's tools and wraps
+//! every one of them in a proxy [`Tool`] named `<server>__<tool>`, so
+//! servers cannot shadow built-in tools or each other. Calls are forwarded
+//! with `tools/call` and the returned content is flattened to text for the
+//! agent.
+//!
+//! The gateway connects once at startup with [`connect_servers`] and shares
+//! the proxies between the registries of all agent profiles with
+//! [`register_tools`]; the servers stop when the last proxy is dropped.
+
+pub mod transport;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::agent::tools::ToolRegistry;
+use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
+use transport::{HttpTransport, StdioTransport, Transport};
+
+/// Protocol revision requested during the handshake
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// Default timeout for a single MCP request in seconds
+const DEFAULT_MCP_TIMEOUT_SECS: u64 = 30;
+
+/// Tool names longer than this are rejected by the LLM providers
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Separator between the server and tool parts of a proxy tool name
+const NAME_SEPARATOR: &str = "__";
+
+/// MCP servers whose tools are offered to the agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpConfig {
+    /// Servers connected at startup, in order
+    pub servers: Vec<McpServerConfig>,
+}
+
+/// One MCP server, started with `command` or reached at `url`.
+///
+/// Missing fields fall back to the built-in defaults.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpServerConfig {
+    /// Prefix of the server's tool names (letters, digits, `-` and `_`)
+    pub name: String,
+    /// Program speaking MCP on stdin/stdout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Arguments passed to `command`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Variables set for `command`, which inherits only `PATH` and `HOME` otherwise
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Streamable HTTP endpoint of a remote server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Headers sent with every HTTP request, e.g. `Authorization`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Seconds to wait for the server to answer a request
+    pub timeout_secs: u64,
+}
+
+impl Default for McpServerConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            command: None,
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            url: None,
+            headers: BTreeMap::new(),
+            timeout_secs: DEFAULT_MCP_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl std::fmt::Debug for McpServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Environment and header values commonly hold tokens
+        let redacted = |map: &BTreeMap<String, String>| -> Vec<String> {
+            map.keys().map(|k| format!("{}=<redacted>", k)).collect()
+        };
+        f.debug_struct("McpServerConfig")
+            .field("name", &self.name)
+            .field("command", &self.command)
+            .field("args", &self.args)
+            .field("env", &redacted(&self.env))
+            .field("url", &self.url)
+            .field("headers", &redacted(&self.headers))
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
+}
+
+/// Errors raised while validating the MCP server list
+#[derive(Debug, thiserror::Error)]
+pub enum McpConfigError {
+    #[error("Invalid server name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+    #[error("Server '{0}' is declared more than once")]
+    DuplicateName(String),
+    #[error("Server '{0}' needs exactly one of 'command' and 'url'")]
+    MissingEndpoint(String),
+    #[error("Invalid URL '{0}': expected an http:// or https:// URL")]
+    InvalidUrl(String),
+    #[error("Server '{0}' has an invalid header '{1}'")]
+    InvalidHeader(String, String),
+    #[error("Server '{0}' needs a timeout_secs of at least 1")]
+    InvalidTimeout(String),
+}
+
+impl McpConfig {
+    /// Validate the server list.
+    ///
+    /// Checks:
+    /// - Names are non-empty, unique and made of letters, digits, `-` and `_`
+    /// - Each server has exactly one of `command` and `url`
+    /// - URLs are http(s) and header names and values are well-formed
+    /// - timeout_secs is at least 1
+    pub fn validate(&self) -> Result<(), McpConfigError> {
+        let mut seen = HashSet::new();
+        for server in &self.servers {
+            let name = &server.name;
+            if name.is_empty()
+                || name.contains(NAME_SEPARATOR)
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(McpConfigError::InvalidName(name.clone()));
+            }
+            if !seen.insert(name.as_str()) {
+                return Err(McpConfigError::DuplicateName(name.clone()));
+            }
+            match (&server.command, &server.url) {
+                (Some(command), None) if !command.trim().is_empty() => {}
+                (None, Some(url)) => {
+                    let parsed = reqwest::Url::parse(url)
+                        .map_err(|_| McpConfigError::InvalidUrl(url.clone()))?;
+                    if !matches!(parsed.scheme(), "http" | "https") {
+                        return Err(McpConfigError::InvalidUrl(url.clone()));
+                    }
+                }
+                _ => return Err(McpConfigError::MissingEndpoint(name.clone())),
+            }
+            for (header, value) in &server.headers {
+                if reqwest::header::HeaderName::from_bytes(header.as_bytes()).is_err()
+                    || reqwest::header::HeaderValue::from_str(value).is_err()
+                {
+                    return Err(McpConfigError::InvalidHeader(name.clone(), header.clone()));
+                }
+            }
+            if server.timeout_secs == 0 {
+                return Err(McpConfigError::InvalidTimeout(name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors raised while talking to an MCP server
+#[derive(Debug, thiserror::Error)]
+pub enum McpError {
+    #[error("{0}")]
+    Transport(String),
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+    #[error("Server error {code}: {message}")]
+    Server { code: i64, message: String },
+    #[error("No answer within {0} seconds")]
+    Timeout(u64),
+}
+
+/// A tool as listed by `tools/list`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct McpToolInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, rename = "inputSchema")]
+    pub input_schema: Option<Value>,
+}
+
+/// A connected, initialized MCP server
+#[derive(Debug)]
+pub struct McpClient {
+    server: String,
+    transport: Transport,
+    next_id: AtomicU64,
+    timeout: Duration,
+}
+
+impl McpClient {
+    /// Starts or reaches the server and performs the `initialize` handshake
+    pub async fn connect(config: &McpServerConfig) -> Result<Self, McpError> {
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let transport = match (&config.command, &config.url) {
+            (Some(command), _) => Transport::Stdio(StdioTransport::spawn(
+                &config.name,
+                command,
+                &config.args,
+                &config.env,
+            )?),
+            (None, Some(url)) => {
+                Transport::Http(HttpTransport::new(url, &config.headers, timeout)?)
+            }
+            (None, None) => {
+                return Err(McpError::Transport(
+                    "neither 'command' nor 'url' is set".to_string(),
+                ));
+            }
+        };
+        let client = Self {
+            server: config.name.clone(),
+            transport,
+            next_id: AtomicU64::new(1),
+            timeout,
+        };
+
+        let init = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {"name": "miniclaw", "version": env!("CARGO_PKG_VERSION")}
+                }),
+            )
+            .await?;
+        tracing::debug!(
+            server = %client.server,
+            protocol = init.get("protocolVersion").and_then(|v| v.as_str()).unwrap_or("unknown"),
+            "MCP server initialized"
+        );
+        client
+            .transport
+            .notify(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await?;
+
+        Ok(client)
+    }
+
+    /// Name of the server in the config
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Lists every tool the server offers, following pagination cursors
+    pub async fn list_tools(&self) -> Result<Vec<McpToolInfo>, McpError> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({"cursor": cursor}),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params).await?;
+            let page: Vec<McpToolInfo> =
+                serde_json::from_value(result.get("tools").cloned().unwrap_or(json!([])))
+                    .map_err(|e| McpError::Protocol(format!("invalid tool list: {}", e)))?;
+            tools.extend(page);
+
+            match result.get("nextCursor").and_then(|v| v.as_str()) {
+                Some(next) if cursor.as_deref() != Some(next) => cursor = Some(next.to_string()),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Calls a tool and returns the raw `CallToolResult`
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Map<String, Value>,
+    ) -> Result<Value, McpError> {
+        self.request("tools/call", json!({"name": name, "arguments": arguments}))
+            .await
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, McpError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let message = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+
+        let response = tokio::time::timeout(self.timeout, self.transport.request(&message))
+            .await
+            .map_err(|_| McpError::Timeout(self.timeout.as_secs()))??;
+
+        if let Some(error) = response.get("error") {
+            return Err(McpError::Server {
+                code: error.get("code").and_then(|v| v.as_i64()).unwrap_or(0),
+                message: error
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown error")
+                    .to_string(),
+            });
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| McpError::Protocol(format!("response to '{}' has no result", method)))
+    }
+}
+
+/// Name under which a server's tool is registered
+///
+/// Characters providers do not accept in tool names become `_`, and the
+/// result is cut to [`MAX_TOOL_NAME_LEN`] characters.
+pub fn proxy_tool_name(server: &str, tool: &str) -> String {
+    format!("{}{}{}", server, NAME_SEPARATOR, tool)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_TOOL_NAME_LEN)
+        .collect()
+}
+
+/// Flattens the content of a `CallToolResult` into text
+///
+/// Text blocks are kept as they are and embedded text resources are
+/// inlined; images, audio and links are replaced by a short placeholder.
+/// When there is no content, the structured result is returned as JSON.
+pub fn format_call_result(result: &Value) -> String {
+    let blocks: Vec<String> = result
+        .get("content")
+        .and_then(|v| v.as_array())
+        .map(|content| content.iter().map(format_content_block).collect())
+        .unwrap_or_default();
+
+    if blocks.is_empty() {
+        if let Some(structured) = result.get("structuredContent") {
+            return structured.to_string();
+        }
+    }
+    blocks.join("\n")
+}
+
+fn format_content_block(block: &Value) -> String {
+    let field = |value: &Value, key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string()
+    };
+    match block.get("type").and_then(|v| v.as_str()) {
+        Some("text") => field(block, "text"),
+        Some("image") => format!("[image: {}]", field(block, "mimeType")),
+        Some("audio") => format!("[audio: {}]", field(block, "mimeType")),
+        Some("resource") => {
+            let resource = block.get("resource").unwrap_or(&Value::Null);
+            match resource.get("text").and_then(|v| v.as_str()) {
+                Some(text) => text.to_string(),
+                None => format!("[resource: {}]", field(resource, "uri")),
+            }
+        }
+        Some("resource_link") => format!("[resource: {}]", field(block, "uri")),
+        _ => block.to_string(),
+    }
+}
+
+/// Proxy for one tool of an MCP server
+///
+/// Clones share the connection to the server.
+#[derive(Clone)]
+pub struct McpTool {
+    /// Namespaced name seen by the agent
+    name: String,
+    /// Name of the tool on the server
+    remote_name: String,
+    description: String,
+    schema: Value,
+    client: Arc<McpClient>,
+}
+
+impl McpTool {
+    /// Wraps a listed tool of a connected server
+    pub fn new(client: Arc<McpClient>, info: McpToolInfo) -> Self {
+        let description = match info.description.filter(|d| !d.trim().is_empty()) {
+            Some(description) => format!("[MCP: {}] {}", client.server(), description),
+            None => format!(
+                "Tool '{}' of the MCP server '{}'",
+                info.name,
+                client.server()
+            ),
+        };
+
+        // Tool parameters must be an object schema
+        let mut schema = match info.input_schema {
+            Some(Value::Object(schema)) => schema,
+            _ => Map::new(),
+        };
+        schema.insert("type".to_string(), json!("object"));
+        if !schema.get("properties").is_some_and(|p| p.is_object()) {
+            schema.insert("properties".to_string(), json!({}));
+        }
+        if schema.get("required").is_some_and(|r| !r.is_array()) {
+            schema.remove("required");
+        }
+
+        Self {
+            name: proxy_tool_name(client.server(), &info.name),
+            remote_name: info.name,
+            description,
+            schema: Value::Object(schema),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters(&self) -> Value {
+        self.schema.clone()
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, Value>,
+        _ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let arguments: Map<String, Value> = args.into_iter().collect();
+        let result = self
+            .client
+            .call_tool(&self.remote_name, arguments)
+            .await
+            .map_err(|e| match e {
+                McpError::Timeout(secs) => ToolError::Timeout {
+                    tool: self.name.clone(),
+                    duration: secs,
+                },
+                e => ToolError::ExecutionFailed {
+                    tool: self.name.clone(),
+                    message: format!("MCP server '{}': {}", self.client.server(), e),
+                },
+            })?;
+
+        let text = format_call_result(&result);
+        if result.get("isError").and_then(|v| v.as_bool()) == Some(true) {
+            return Err(ToolError::ExecutionFailed {
+                tool: self.name.clone(),
+                message: text,
+            });
+        }
+        Ok(text)
+    }
+}
+
+/// Connects to every configured server and returns proxies for its tools
+///
+/// Servers are connected concurrently. A server that cannot be reached is
+/// logged and skipped so the agent still starts.
+pub async fn connect_servers(config: &McpConfig) -> Vec<McpTool> {
+    let connections = config.servers.iter().map(|server| async move {
+        let client = Arc::new(McpClient::connect(server).await?);
+        let tools = client.list_tools().await?;
+        Ok::<_, McpError>((client, tools))
+    });
+    let results = futures::future::join_all(connections).await;
+
+    let mut proxies = Vec::new();
+    for (server, result) in config.servers.iter().zip(results) {
+        let (client, tools) = match result {
+            Ok(connected) => connected,
+            Err(e) => {
+                tracing::warn!(server = %server.name, error = %e, "Failed to connect to MCP server, continuing without it");
+                continue;
+            }
+        };
+        tracing::info!(server = %server.name, tool_count = tools.len(), "MCP server connected");
+        proxies.extend(
+            tools
+                .into_iter()
+                .map(|info| McpTool::new(Arc::clone(&client), info)),
+        );
+    }
+    proxies
+}
+
+/// Registers copies of the proxy tools in `registry`
+///
+/// A tool whose name is already taken is logged and skipped. Returns the
+/// number of tools registered.
+pub async fn register_tools(registry: &ToolRegistry, tools: &[McpTool]) -> usize {
+    let mut registered = 0;
+    for tool in tools {
+        match registry.register(Box::new(tool.clone())).await {
+            Ok(()) => registered += 1,
+            Err(e) => {
+                tracing::warn!(tool = %tool.name, error = %e, "Failed to register MCP tool, continuing without it");
+            }
+        }
+    }
+    registered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn stdio_server(command: &str, args: &[&str]) -> McpServerConfig {
+        McpServerConfig {
+            name: "fixture".to_string(),
+            command: Some(command.to_string()),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            timeout_secs: 5,
+            ..McpServerConfig::default()
+        }
+    }
+
+    fn http_server(url: String) -> McpServerConfig {
+        McpServerConfig {
+            name: "remote".to_string(),
+            url: Some(url),
+            headers: BTreeMap::from([("Authorization".to_string(), "Bearer t0ken".to_string())]),
+            timeout_secs: 5,
+            ..McpServerConfig::default()
+        }
+    }
+
+    /// Reads one HTTP request and returns its headers (lowercased) and body
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> (String, String) {
+        let mut data = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            data.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&data).to_string();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let head = text[..end].to_ascii_lowercase();
+                let length = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length:"))
+                    .map(|l| l.trim().parse::<usize>().unwrap())
+                    .unwrap_or(0);
+                if data.len() >= end + 4 + length {
+                    return (head, text[end + 4..end + 4 + length].to_string());
+                }
+            }
+            if n == 0 {
+                panic!("connection closed mid-request");
+            }
+        }
+    }
+
+    /// A streamable HTTP MCP server offering `echo` and `fail`
+    ///
+    /// The handshake is answered with JSON and a session id, the tool list
+    /// as an event stream preceded by a log notification. Returns the
+    /// endpoint and the headers of every request.
+    async fn serve_mcp() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (head, body) = read_request(&mut stream).await;
+                seen.lock().unwrap().push(head.clone());
+                let message: Value = serde_json::from_str(&body).unwrap();
+                let id = message["id"].clone();
+                let reply = |result: Value| json!({"jsonrpc": "2.0", "id": id, "result": result});
+
+                let (content_type, extra, body) = match message["method"].as_str().unwrap() {
+                    "initialize" => (
+                        "application/json",
+                        "Mcp-Session-Id: s-42\r\n",
+                        reply(json!({
+                            "protocolVersion": PROTOCOL_VERSION,
+                            "capabilities": {"tools": {}},
+                            "serverInfo": {"name": "fixture", "version": "1.0"}
+                        }))
+                        .to_string(),
+                    ),
+                    "notifications/initialized" => ("application/json", "", String::new()),
+                    "tools/list" => (
+                        "text/event-stream",
+                        "",
+                        format!(
+                            "data: {}\n\nevent: message\ndata: {}\n\n",
+                            json!({"jsonrpc": "2.0", "method": "notifications/message", "params": {"level": "info", "data": "listing"}}),
+                            reply(json!({"tools": [
+                                {
+                                    "name": "echo",
+                                    "description": "Echo the text back",
+                                    "inputSchema": {
+                                        "type": "object",
+                                        "properties": {"text": {"type": "string"}},
+                                        "required": ["text"]
+                                    }
+                                },
+                                {"name": "fail"}
+                            ]}))
+                        ),
+                    ),
+                    "tools/call" => {
+                        let params = &message["params"];
+                        let result = if params["name"] == "echo" {
+                            json!({"content": [{"type": "text", "text": params["arguments"]["text"]}]})
+                        } else {
+                            json!({"content": [{"type": "text", "text": "boom"}], "isError": true})
+                        };
+                        ("application/json", "", reply(result).to_string())
+                    }
+                    other => panic!("unexpected method {}", other),
+                };
+                let status = if body.is_empty() {
+                    "202 Accepted"
+                } else {
+                    "200 OK"
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    content_type,
+                    extra,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{}/mcp", addr), requests)
+    }
+
+    #[test]
+    fn test_proxy_tool_name() {
+        assert_eq!(
+            proxy_tool_name("github", "create_issue"),
+            "github__create_issue"
+        );
+        assert_eq!(
+            proxy_tool_name("docs", "search.v2 beta"),
+            "docs__search_v2_beta"
+        );
+        assert_eq!(
+            proxy_tool_name("s", &"x".repeat(100)).len(),
+            MAX_TOOL_NAME_LEN
+        );
+    }
+
+    #[test]
+    fn test_config_validate() {
+        let config: McpConfig = serde_json::from_str(
+            r#"{"servers": [
+                {"name": "files", "command": "mcp-files", "args": ["--root", "/tmp"]},
+                {"name": "docs", "url": "https://docs.example.com/mcp"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.servers[1].timeout_secs, DEFAULT_MCP_TIMEOUT_SECS);
+
+        let invalid = |servers: &str| {
+            let config: McpConfig =
+                serde_json::from_str(&format!(r#"{{"servers": {}}}"#, servers)).unwrap();
+            config.validate().unwrap_err().to_string()
+        };
+        assert!(invalid(r#"[{"name": "a b", "command": "x"}]"#).contains("Invalid server name"));
+        assert!(invalid(r#"[{"name": "a__b", "command": "x"}]"#).contains("Invalid server name"));
+        assert!(
+            invalid(r#"[{"name": "a", "command": "x"}, {"name": "a", "command": "y"}]"#)
+                .contains("more than once")
+        );
+        assert!(invalid(r#"[{"name": "a"}]"#).contains("exactly one"));
+        assert!(
+            invalid(r#"[{"name": "a", "command": "x", "url": "http://h"}]"#)
+                .contains("exactly one")
+        );
+        assert!(invalid(r#"[{"name": "a", "url": "ftp://h"}]"#).contains("Invalid URL"));
+        assert!(
+            invalid(r#"[{"name": "a", "url": "http://h", "headers": {"bad header": "v"}}]"#)
+                .contains("invalid header")
+        );
+        assert!(
+            invalid(r#"[{"name": "a", "command": "x", "timeout_secs": 0}]"#).contains("timeout")
+        );
+    }
+
+    #[test]
+    fn test_config_debug_redacts_secrets() {
+        let server = McpServerConfig {
+            name: "gh".to_string(),
+            command: Some("mcp-github".to_string()),
+            env: BTreeMap::from([("GITHUB_TOKEN".to_string(), "ghp_secret".to_string())]),
+            ..McpServerConfig::default()
+        };
+        let debug = format!("{:?}", server);
+        assert!(debug.contains("GITHUB_TOKEN"));
+        assert!(!debug.contains("ghp_secret"));
+    }
+
+    #[test]
+    fn test_format_call_result() {
+        let result = json!({"content": [
+            {"type": "text", "text": "first"},
+            {"type": "image", "data": "aGk=", "mimeType": "image/png"},
+            {"type": "resource", "resource": {"uri": "file:///a.txt", "text": "inline"}},
+            {"type": "resource", "resource": {"uri": "file:///b.bin", "blob": "AA=="}},
+            {"type": "resource_link", "uri": "file:///c.txt", "name": "c"}
+        ]});
+        assert_eq!(
+            format_call_result(&result),
+            "first\n[image: image/png]\ninline\n[resource: file:///b.bin]\n[resource: file:///c.txt]"
+        );
+
+        let structured = json!({"content": [], "structuredContent": {"temp": 21}});
+        assert_eq!(format_call_result(&structured), r#"{"temp":21}"#);
+    }
+
+    #[test]
+    fn test_mcp_tool_schema_is_object() {
+        let client = Arc::new(McpClient {
+            server: "s".to_string(),
+            transport: Transport::Http(
+                HttpTransport::new(
+                    "http://127.0.0.1:1/mcp",
+                    &BTreeMap::new(),
+                    Duration::from_secs(1),
+                )
+                .unwrap(),
+            ),
+            next_id: AtomicU64::new(1),
+            timeout: Duration::from_secs(1),
+        });
+        let tool = McpTool::new(
+            client,
+            McpToolInfo {
+                name: "bare".to_string(),
+                description: None,
+                input_schema: None,
+            },
+        );
+        assert_eq!(tool.name(), "s__bare");
+        assert_eq!(
+            tool.parameters(),
+            json!({"type": "object", "properties": {}})
+        );
+        assert!(tool.description().contains("'s'"));
+    }
+
+    #[tokio::test]
+    async fn test_http_server_tools_are_proxied() {
+        let (url, requests) = serve_mcp().await;
+        let registry = ToolRegistry::new();
+        let config = McpConfig {
+            servers: vec![http_server(url)],
+        };
+
+        let tools = connect_servers(&config).await;
+        assert_eq!(register_tools(&registry, &tools).await, 2);
+        assert!(registry.contains("remote__echo").await);
+        assert!(registry.contains("remote__fail").await);
+
+        let ctx = ToolExecutionContext::default();
+        let args = HashMap::from([("text".to_string(), json!("hello mcp"))]);
+        let output = registry
+            .execute_tool("remote__echo", args, &ctx)
+            .await
+            .unwrap();
+        assert_eq!(output, "hello mcp");
+
+        // The schema from the server is enforced before forwarding
+        let missing = registry
+            .execute_tool("remote__echo", HashMap::new(), &ctx)
+            .await;
+        assert!(matches!(missing, Err(ToolError::InvalidArguments { .. })));
+
+        let failed = registry
+            .execute_tool("remote__fail", HashMap::new(), &ctx)
+            .await
+            .unwrap_err();
+        assert!(failed.to_string().contains("boom"));
+
+        // Another registry shares the connection instead of starting a new one
+        let other = ToolRegistry::new();
+        assert_eq!(register_tools(&other, &tools).await, 2);
+        assert_eq!(register_tools(&other, &tools).await, 0);
+        let output = other
+            .execute_tool(
+                "remote__echo",
+                HashMap::from([("text".to_string(), json!("again"))]),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output, "again");
+
+        // One handshake (initialize, initialized, tools/list) and three calls
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 6);
+        assert!(
+            requests
+                .iter()
+                .all(|r| r.contains("authorization: bearer t0ken"))
+        );
+        assert!(!requests[0].contains("mcp-session-id"));
+        assert!(
+            requests[1..]
+                .iter()
+                .all(|r| r.contains("mcp-session-id: s-42"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stdio_server_tools_are_proxied() {
+        // Canned answers to initialize (id 1), tools/list (id 2) and
+        // tools/call (id 3), with a log notification in between
+        let script = r#"
+read line
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26","capabilities":{"tools":{}},"serverInfo":{"name":"fixture","version":"1"}}}'
+read line
+read line
+echo '{"jsonrpc":"2.0","method":"notifications/message","params":{"level":"info","data":"hi"}}'
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"greet","description":"Says hi","inputSchema":{"type":"object","properties":{"name":{"type":"string"}}}}]}}'
+read line
+echo '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"hi there"}]}}'
+read line
+"#;
+        let registry = ToolRegistry::new();
+        let config = McpConfig {
+            servers: vec![stdio_server("sh", &["-c", script])],
+        };
+
+        let tools = connect_servers(&config).await;
+        assert_eq!(register_tools(&registry, &tools).await, 1);
+        let definitions = registry.get_definitions().await;
+        assert!(definitions[0].function.description.contains("Says hi"));
+
+        let output = registry
+            .execute_tool(
+                "fixture__greet",
+                HashMap::from([("name".to_string(), json!("Ada"))]),
+                &ToolExecutionContext::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output, "hi there");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_skipped() {
+        let config = McpConfig {
+            servers: vec![
+                stdio_server("/nonexistent/mcp-server", &[]),
+                stdio_server("sh", &["-c", "exit 0"]),
+            ],
+        };
+        assert!(connect_servers(&config).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_reported() {
+        let script = r#"
+read line
+echo '{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"Unsupported protocol version"}}'
+"#;
+        let err = McpClient::connect(&stdio_server("sh", &["-c", script]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::Server { code: -32602, .. }));
+        assert!(err.to_string().contains("Unsupported protocol version"));
+    }
+}
@@ -0,0 +1,455 @@
+//! MCP transports
+//!
+//! JSON-RPC messages reach a server either over the stdin/stdout of a child
+//! process, one message per line, or over streamable HTTP, where every
+//! message is POSTed to a single endpoint and the answer comes back as a JSON
+//! body or as a server-sent event stream. Both transports handle one request
+//! at a time; messages the server sends in between (log notifications,
+//! progress, pings) are skipped or answered so they never reach the caller.
+
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::StreamExt;
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use super::McpError;
+
+/// Header carrying the session assigned by a streamable HTTP server
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Variables a server process inherits from miniclaw, so it can find programs
+/// and its own configuration; everything else must be configured explicitly
+const INHERITED_ENV: &[&str] = &["PATH", "HOME"];
+
+/// A connection to one MCP server
+#[derive(Debug)]
+pub enum Transport {
+    Stdio(StdioTransport),
+    Http(HttpTransport),
+}
+
+impl Transport {
+    /// Sends a request and waits for the response carrying the same id
+    pub async fn request(&self, message: &Value) -> Result<Value, McpError> {
+        let id = message.get("id").cloned().unwrap_or(Value::Null);
+        match self {
+            Self::Stdio(stdio) => stdio.request(message, &id).await,
+            Self::Http(http) => http.request(message, &id).await,
+        }
+    }
+
+    /// Sends a notification, which gets no response
+    pub async fn notify(&self, message: &Value) -> Result<(), McpError> {
+        match self {
+            Self::Stdio(stdio) => stdio.send(message).await,
+            Self::Http(http) => http.notify(message).await,
+        }
+    }
+}
+
+/// A server running as a child process
+///
+/// The process gets only the configured variables plus `PATH` and `HOME`,
+/// so secrets in miniclaw's environment do not leak to it, and is killed when
+/// the transport is dropped. Its stderr is
+/// forwarded to the debug log.
+#[derive(Debug)]
+pub struct StdioTransport {
+    server: String,
+    pipes: tokio::sync::Mutex<Pipes>,
+    /// Kept so the process lives as long as the transport
+    _child: Mutex<Child>,
+}
+
+#[derive(Debug)]
+struct Pipes {
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl StdioTransport {
+    /// Starts the server process
+    pub fn spawn(
+        server: &str,
+        command: &str,
+        args: &[String],
+        env: &BTreeMap<String, String>,
+    ) -> Result<Self, McpError> {
+        let inherited = INHERITED_ENV
+            .iter()
+            .filter_map(|name| std::env::var_os(name).map(|value| (*name, value)));
+        let mut child = Command::new(command)
+            .args(args)
+            .env_clear()
+            .envs(inherited)
+            .envs(env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| McpError::Transport(format!("failed to start '{}': {}", command, e)))?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(McpError::Transport(
+                "failed to open the process pipes".to_string(),
+            ));
+        };
+        if let Some(stderr) = child.stderr.take() {
+            let server = server.to_string();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::debug!(server = %server, "MCP server stderr: {}", line);
+                }
+            });
+        }
+
+        Ok(Self {
+            server: server.to_string(),
+            pipes: tokio::sync::Mutex::new(Pipes {
+                stdin,
+                stdout: BufReader::new(stdout),
+            }),
+            _child: Mutex::new(child),
+        })
+    }
+
+    async fn send(&self, message: &Value) -> Result<(), McpError> {
+        let mut pipes = self.pipes.lock().await;
+        write_line(&mut pipes.stdin, message).await
+    }
+
+    async fn request(&self, message: &Value, id: &Value) -> Result<Value, McpError> {
+        let mut pipes = self.pipes.lock().await;
+        write_line(&mut pipes.stdin, message).await?;
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read =
+                pipes.stdout.read_line(&mut line).await.map_err(|e| {
+                    McpError::Transport(format!("failed to read from server: {}", e))
+                })?;
+            if read == 0 {
+                return Err(McpError::Transport("server closed its output".to_string()));
+            }
+            let Ok(incoming) = serde_json::from_str::<Value>(line.trim()) else {
+                tracing::debug!(server = %self.server, "Skipping non-JSON output: {}", line.trim());
+                continue;
+            };
+            if let Some(reply) = reply_to_server_request(&incoming) {
+                write_line(&mut pipes.stdin, &reply).await?;
+                continue;
+            }
+            if is_response_to(&incoming, id) {
+                return Ok(incoming);
+            }
+        }
+    }
+}
+
+async fn write_line(stdin: &mut ChildStdin, message: &Value) -> Result<(), McpError> {
+    let mut line = message.to_string();
+    line.push('\n');
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| McpError::Transport(format!("failed to write to server: {}", e)))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| McpError::Transport(format!("failed to write to server: {}", e)))
+}
+
+/// A server reached over streamable HTTP
+#[derive(Debug)]
+pub struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    /// Configured headers, e.g. `Authorization`
+    headers: HeaderMap,
+    /// Session id handed out by the server during the handshake
+    session: Mutex<Option<String>>,
+}
+
+impl HttpTransport {
+    /// Builds the transport; no request is sent yet
+    pub fn new(
+        url: &str,
+        headers: &BTreeMap<String, String>,
+        timeout: Duration,
+    ) -> Result<Self, McpError> {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| McpError::Transport(format!("invalid header name '{}'", name)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| McpError::Transport(format!("invalid value for header '{}'", name)))?;
+            header_map.insert(name, value);
+        }
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| McpError::Transport(format!("failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            url: url.to_string(),
+            headers: header_map,
+            session: Mutex::new(None),
+        })
+    }
+
+    async fn post(&self, message: &Value) -> Result<reqwest::Response, McpError> {
+        let session = self.session.lock().unwrap().clone();
+        let mut request = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(message);
+        if let Some(session) = session {
+            request = request.header(SESSION_HEADER, session);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| McpError::Transport(format!("request to {} failed: {}", self.url, e)))?;
+        if !response.status().is_success() {
+            return Err(McpError::Transport(format!(
+                "server answered with HTTP {}",
+                response.status()
+            )));
+        }
+        if let Some(session) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            *self.session.lock().unwrap() = Some(session.to_string());
+        }
+        Ok(response)
+    }
+
+    async fn notify(&self, message: &Value) -> Result<(), McpError> {
+        self.post(message).await.map(|_| ())
+    }
+
+    async fn request(&self, message: &Value, id: &Value) -> Result<Value, McpError> {
+        let response = self.post(message).await?;
+        let is_stream = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("text/event-stream"));
+
+        if !is_stream {
+            let incoming: Value = response
+                .json()
+                .await
+                .map_err(|e| McpError::Protocol(format!("invalid JSON response: {}", e)))?;
+            return find_response(incoming, id)
+                .ok_or_else(|| McpError::Protocol("response does not answer the request".into()));
+        }
+
+        // Read events until the one carrying our response; the server may
+        // keep the stream open afterwards
+        let mut events = SseBuffer::default();
+        let mut body = response.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            let chunk =
+                chunk.map_err(|e| McpError::Transport(format!("event stream failed: {}", e)))?;
+            for data in events.push(&chunk) {
+                let Ok(incoming) = serde_json::from_str::<Value>(&data) else {
+                    continue;
+                };
+                if let Some(reply) = reply_to_server_request(&incoming) {
+                    self.notify(&reply).await?;
+                    continue;
+                }
+                if let Some(found) = find_response(incoming, id) {
+                    return Ok(found);
+                }
+            }
+        }
+        Err(McpError::Protocol(
+            "event stream ended without a response".to_string(),
+        ))
+    }
+}
+
+/// Splits a server-sent event stream into the data of complete events
+///
+/// Bytes are kept until an event is complete and only then decoded, since a
+/// chunk may end in the middle of a multibyte character.
+#[derive(Debug, Default)]
+pub(crate) struct SseBuffer {
+    pending: Vec<u8>,
+}
+
+impl SseBuffer {
+    /// Adds received bytes and returns the data of every event they complete
+    pub(crate) fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some((end, separator)) = event_end(&self.pending) {
+            let event: Vec<u8> = self.pending.drain(..end + separator).collect();
+            let event = String::from_utf8_lossy(&event[..end]);
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
+
+/// Position and length of the blank line that ends the first event in `bytes`
+fn event_end(bytes: &[u8]) -> Option<(usize, usize)> {
+    (0..bytes.len()).find_map(|i| {
+        [&b"\n\n"[..], b"\r\n\r\n"]
+            .into_iter()
+            .find(|separator| bytes[i..].starts_with(separator))
+            .map(|separator| (i, separator.len()))
+    })
+}
+
+/// Whether `message` is the response to the request with `id`
+fn is_response_to(message: &Value, id: &Value) -> bool {
+    message.get("method").is_none() && message.get("id") == Some(id)
+}
+
+/// Picks the response to `id` out of a single message or a batch
+fn find_response(message: Value, id: &Value) -> Option<Value> {
+    match message {
+        Value::Array(batch) => batch.into_iter().find(|m| is_response_to(m, id)),
+        message if is_response_to(&message, id) => Some(message),
+        _ => None,
+    }
+}
+
+/// Builds the answer to a request the server sent to us
+///
+/// Pings get an empty result; anything else is declined, since the client
+/// offers no capabilities. Returns `None` for notifications and responses.
+fn reply_to_server_request(message: &Value) -> Option<Value> {
+    let method = message.get("method")?.as_str()?;
+    let id = message.get("id")?;
+    Some(if method == "ping" {
+        json!({"jsonrpc": "2.0", "id": id, "result": {}})
+    } else {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": -32601, "message": format!("Method not supported: {}", method)}
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_buffer_splits_events() {
+        let mut buffer = SseBuffer::default();
+        assert!(buffer.push(b"event: message\r\ndata: {\"a\":").is_empty());
+        let events = buffer.push(b"1}\r\n\r\n: comment\n\ndata: one\ndata: two\n\n");
+        assert_eq!(
+            events,
+            vec!["{\"a\":1}".to_string(), "one\ntwo".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_sse_buffer_keeps_characters_split_across_chunks() {
+        let event = "data: {\"text\":\"héllo ✓\"}\n\n".as_bytes();
+        let split = event.iter().position(|&b| b == 0xc3).unwrap() + 1;
+
+        let mut buffer = SseBuffer::default();
+        assert!(buffer.push(&event[..split]).is_empty());
+        assert_eq!(
+            buffer.push(&event[split..]),
+            vec!["{\"text\":\"héllo ✓\"}".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stdio_server_gets_only_configured_environment() {
+        // Any variable of ours the server should not see
+        let Some(parent_var) = std::env::vars().map(|(name, _)| name).find(|name| {
+            !INHERITED_ENV.contains(&name.as_str())
+                && !["PWD", "OLDPWD", "SHLVL", "_"].contains(&name.as_str())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }) else {
+            return;
+        };
+        let script = format!(
+            r#"read line; printf '{{"jsonrpc":"2.0","id":1,"result":{{"configured":"%s","parent":"%s","path":"%s"}}}}\n' "$FIXTURE_TOKEN" "${{{}-unset}}" "${{PATH:+set}}""#,
+            parent_var
+        );
+        let transport = Transport::Stdio(
+            StdioTransport::spawn(
+                "fixture",
+                "sh",
+                &["-c".to_string(), script],
+                &BTreeMap::from([("FIXTURE_TOKEN".to_string(), "secret".to_string())]),
+            )
+            .unwrap(),
+        );
+
+        let response = transport
+            .request(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            response["result"],
+            json!({"configured": "secret", "parent": "unset", "path": "set"})
+        );
+    }
+
+    #[test]
+    fn test_find_response() {
+        let id = json!(2);
+        let notification = json!({"jsonrpc": "2.0", "method": "notifications/progress"});
+        assert_eq!(find_response(notification.clone(), &id), None);
+
+        let response = json!({"jsonrpc": "2.0", "id": 2, "result": {}});
+        let batch = json!([notification, response.clone()]);
+        assert_eq!(find_response(batch, &id), Some(response));
+        assert_eq!(
+            find_response(json!({"jsonrpc": "2.0", "id": 3, "result": {}}), &id),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reply_to_server_request() {
+        let ping = json!({"jsonrpc": "2.0", "id": "p1", "method": "ping"});
+        assert_eq!(
+            reply_to_server_request(&ping),
+            Some(json!({"jsonrpc": "2.0", "id": "p1", "result": {}}))
+        );
+
+        let sampling = json!({"jsonrpc": "2.0", "id": 7, "method": "sampling/createMessage"});
+        assert_eq!(
+            reply_to_server_request(&sampling).unwrap()["error"]["code"],
+            -32601
+        );
+
+        let log = json!({"jsonrpc": "2.0", "method": "notifications/message"});
+        assert_eq!(reply_to_server_request(&log), None);
+    }
+}
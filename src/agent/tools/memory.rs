@@ -1,7 +1,9 @@
 //! Memory tool for the agent
 //!
-//! This tool provides memory management capabilities allowing the agent to
-//! persist information to long-term memory or create daily notes.
+//! This module provides memory management capabilities allowing the agent to
+//! persist information to long-term memory or create daily notes
+//! (`write_memory`), and to search, read back, correct or forget memories
+//! (`manage_memory`).
 
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -9,8 +11,13 @@ use std::path::PathBuf;
 use serde_json::{Value, json};
 
 use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
-use crate::memory::MemoryStore;
-use crate::memory::types::MemoryType;
+use crate::memory::types::{MemoryError, MemoryType};
+use crate::memory::{
+    DAILY_NOTE_RETENTION_DAYS, DEFAULT_RECENT_DAYS, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_RESULTS,
+    MemoryStore,
+};
+
+const MANAGE_ACTIONS: &[&str] = &["search", "recent", "sections", "update", "delete"];
 
 /// Tool for memory management
 ///
@@ -131,32 +138,39 @@ impl Tool for MemoryTool {
 
         // Execute based on memory type
         let result = match memory_type {
-            MemoryType::LongTerm => {
-                self.memory_store
-                    .append_to_memory(content.to_string())
-                    .await
-            }
-            MemoryType::Daily => {
-                self.memory_store
-                    .create_daily_note(content.to_string())
-                    .await
-            }
+            MemoryType::LongTerm => self
+                .memory_store
+                .append_to_memory(content.to_string())
+                .await
+                .map(|entry| {
+                    let file_path = self.memory_store.memory_file_path();
+                    (file_path.to_string_lossy().to_string(), Some(entry.id))
+                }),
+            MemoryType::Daily => self
+                .memory_store
+                .create_daily_note(content.to_string())
+                .await
+                .map(|file_path| (file_path, None)),
         };
 
         // Handle result with proper error mapping
         match result {
-            Ok(file_path) => {
+            Ok((file_path, id)) => {
                 let message = match memory_type {
                     MemoryType::LongTerm => "Memory updated",
                     MemoryType::Daily => "Daily note created",
                 };
 
-                let response = json!({
+                let mut response = json!({
                     "success": true,
                     "message": message,
                     "file_path": file_path,
                     "timestamp": chrono::Utc::now().to_rfc3339(),
                 });
+                // Long-term entries can later be updated or deleted by id
+                if let Some(id) = id {
+                    response["id"] = json!(id);
+                }
 
                 tracing::info!("Memory operation succeeded: {}", message);
                 Ok(serde_json::to_string(&response).unwrap())
@@ -184,6 +198,9 @@ impl Tool for MemoryTool {
                             message: msg,
                         })
                     }
+                    crate::memory::types::MemoryError::EntryNotFound(id) => Err(
+                        ToolError::NotFound(format!("Memory entry not found: {}", id)),
+                    ),
                     crate::memory::types::MemoryError::FileNotFound(path) => {
                         // Return execution error
                         Err(ToolError::ExecutionFailed {
@@ -214,6 +231,304 @@ impl Tool for MemoryTool {
     }
 }
 
+/// Tool for reading and maintaining memory
+///
+/// Provides capabilities for:
+/// - Searching long-term memory and daily notes
+/// - Reading recent daily notes and the sections of MEMORY.md
+/// - Updating or deleting long-term entries by id
+#[derive(Debug, Clone)]
+pub struct ManageMemoryTool {
+    memory_store: MemoryStore,
+}
+
+impl ManageMemoryTool {
+    /// Creates a new ManageMemoryTool with the provided workspace path
+    ///
+    /// # Arguments
+    /// * `workspace_path` - The workspace directory for memory files
+    ///
+    /// # Returns
+    /// * `Ok(ManageMemoryTool)` - Successfully created tool
+    /// * `Err(String)` - If workspace path cannot be canonicalized
+    pub fn new(workspace_path: PathBuf) -> Result<Self, String> {
+        let canonical_workspace = std::fs::canonicalize(&workspace_path).map_err(|e| {
+            format!(
+                "Failed to canonicalize workspace path {:?}: {}",
+                workspace_path, e
+            )
+        })?;
+
+        Ok(Self {
+            memory_store: MemoryStore::new(canonical_workspace),
+        })
+    }
+
+    fn invalid_argument(&self, message: impl Into<String>) -> ToolError {
+        ToolError::InvalidArguments {
+            tool: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn map_error(&self, error: MemoryError) -> ToolError {
+        match error {
+            MemoryError::EntryNotFound(id) => {
+                ToolError::NotFound(format!("Memory entry not found: {}", id))
+            }
+            MemoryError::InvalidContent(message) => self.invalid_argument(message),
+            MemoryError::PathValidationFailed(message) => ToolError::PermissionDenied {
+                tool: self.name().to_string(),
+                message,
+            },
+            error => ToolError::ExecutionFailed {
+                tool: self.name().to_string(),
+                message: error.to_string(),
+            },
+        }
+    }
+
+    fn required_str<'a>(
+        &self,
+        args: &'a HashMap<String, Value>,
+        name: &str,
+        action: &str,
+    ) -> ToolResult<&'a str> {
+        args.get(name)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                self.invalid_argument(format!(
+                    "Missing required parameter '{}' for {}",
+                    name, action
+                ))
+            })
+    }
+
+    /// Reads an optional integer parameter in `1..=max`
+    fn bounded_usize(
+        &self,
+        args: &HashMap<String, Value>,
+        name: &str,
+        default: usize,
+        max: usize,
+    ) -> ToolResult<usize> {
+        match args.get(name) {
+            None | Some(Value::Null) => Ok(default),
+            Some(value) => value
+                .as_u64()
+                .filter(|n| (1..=max as u64).contains(n))
+                .map(|n| n as usize)
+                .ok_or_else(|| {
+                    self.invalid_argument(format!(
+                        "'{}' must be an integer between 1 and {}",
+                        name, max
+                    ))
+                }),
+        }
+    }
+
+    async fn search(&self, args: &HashMap<String, Value>) -> ToolResult<Value> {
+        let query = self.required_str(args, "query", "search")?;
+        let limit = self.bounded_usize(args, "limit", DEFAULT_SEARCH_LIMIT, MAX_SEARCH_RESULTS)?;
+        let results = self
+            .memory_store
+            .search_memories(query, limit)
+            .await
+            .map_err(|e| self.map_error(e))?;
+
+        let results: Vec<Value> = results
+            .into_iter()
+            .map(|memory| {
+                json!({
+                    "id": memory.id,
+                    "source": memory.source.to_string(),
+                    "date": memory.date.map(|date| date.to_rfc3339()),
+                    "score": memory.score,
+                    "content": memory.content,
+                })
+            })
+            .collect();
+        Ok(json!({ "query": query, "results": results }))
+    }
+
+    async fn recent(&self, args: &HashMap<String, Value>) -> ToolResult<Value> {
+        let days = self.bounded_usize(
+            args,
+            "days",
+            DEFAULT_RECENT_DAYS,
+            DAILY_NOTE_RETENTION_DAYS as usize,
+        )?;
+        let sections = self
+            .memory_store
+            .read_recent_daily_notes(days)
+            .await
+            .map_err(|e| self.map_error(e))?;
+
+        let notes: Vec<Value> = sections
+            .into_iter()
+            .map(|section| {
+                let entries: Vec<Value> = section
+                    .entries
+                    .into_iter()
+                    .map(|entry| {
+                        json!({
+                            "time": entry.timestamp.format("%H:%M:%S").to_string(),
+                            "content": entry.content,
+                        })
+                    })
+                    .collect();
+                json!({ "date": section.date.to_string(), "entries": entries })
+            })
+            .collect();
+        Ok(json!({ "days": days, "daily_notes": notes }))
+    }
+
+    /// Lists the dated sections of MEMORY.md, or the entries of one of them
+    async fn sections(&self, args: &HashMap<String, Value>) -> ToolResult<Value> {
+        let sections = self
+            .memory_store
+            .long_term()
+            .read_all()
+            .await
+            .map_err(|e| self.map_error(e))?;
+
+        let Some(date) = args.get("date").and_then(Value::as_str) else {
+            let sections: Vec<Value> = sections
+                .iter()
+                .map(|section| {
+                    json!({ "date": section.date.to_string(), "entries": section.entries.len() })
+                })
+                .collect();
+            return Ok(json!({ "sections": sections }));
+        };
+
+        let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| {
+            self.invalid_argument(format!("Invalid date '{}': expected YYYY-MM-DD", date))
+        })?;
+        let entries: Vec<Value> = sections
+            .into_iter()
+            .filter(|section| section.date == date)
+            .flat_map(|section| section.entries)
+            .map(|entry| {
+                json!({
+                    "id": entry.id,
+                    "content": entry.content,
+                    "added_at": entry.timestamp.to_rfc3339(),
+                })
+            })
+            .collect();
+        Ok(json!({ "date": date.to_string(), "entries": entries }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for ManageMemoryTool {
+    fn name(&self) -> &str {
+        "manage_memory"
+    }
+
+    fn description(&self) -> &str {
+        "Read and maintain memory. 'search' long-term memory and daily notes with a 'query', \
+         read 'recent' daily notes, list the dated 'sections' of long-term memory (or the entries \
+         of one with 'date'), 'update' an entry's content by 'id', or 'delete' an entry by 'id'. \
+         Long-term entry ids come from search, sections and write_memory. Use write_memory to add entries."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": MANAGE_ACTIONS,
+                    "description": "Operation to perform"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Keywords to search for (search)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_SEARCH_RESULTS,
+                    "description": format!("Maximum number of results (search, default {})", DEFAULT_SEARCH_LIMIT)
+                },
+                "days": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": DAILY_NOTE_RETENTION_DAYS,
+                    "description": format!("Number of days of daily notes to read (recent, default {})", DEFAULT_RECENT_DAYS)
+                },
+                "date": {
+                    "type": "string",
+                    "description": "Section date YYYY-MM-DD whose entries to list (sections)"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Id of a long-term memory entry (update, delete)"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "New content of the entry (update)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, Value>,
+        _ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let action = self.required_str(&args, "action", "manage_memory")?;
+
+        tracing::info!(action = %action, "Manage memory tool executing");
+
+        let response = match action {
+            "search" => self.search(&args).await?,
+            "recent" => self.recent(&args).await?,
+            "sections" => self.sections(&args).await?,
+            "update" => {
+                let id = self.required_str(&args, "id", "update")?;
+                let content = self.required_str(&args, "content", "update")?;
+                let entry = self
+                    .memory_store
+                    .long_term()
+                    .update_entry(id, content)
+                    .await
+                    .map_err(|e| self.map_error(e))?;
+                json!({
+                    "updated": entry.id,
+                    "content": entry.content,
+                    "added_at": entry.timestamp.to_rfc3339(),
+                })
+            }
+            "delete" => {
+                let id = self.required_str(&args, "id", "delete")?;
+                let entry = self
+                    .memory_store
+                    .long_term()
+                    .delete_entry(id)
+                    .await
+                    .map_err(|e| self.map_error(e))?;
+                json!({ "deleted": entry.id, "content": entry.content })
+            }
+            _ => {
+                return Err(self.invalid_argument(format!(
+                    "Invalid action '{}'. Expected one of: {}",
+                    action,
+                    MANAGE_ACTIONS.join(", ")
+                )));
+            }
+        };
+
+        Ok(response.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +574,101 @@ mod tests {
         assert!(enum_values.contains(&json!("long_term")));
         assert!(enum_values.contains(&json!("daily")));
     }
+
+    #[tokio::test]
+    async fn test_manage_memory_lifecycle() {
+        let temp_dir = tempdir().unwrap();
+        let workspace_path = temp_dir.path().to_path_buf();
+        let writer = MemoryTool::new(workspace_path.clone()).unwrap();
+        let tool = ManageMemoryTool::new(workspace_path).unwrap();
+        let ctx = ToolExecutionContext::default();
+        let call = |pairs: &[(&str, Value)]| -> HashMap<String, Value> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect()
+        };
+
+        let written: Value = serde_json::from_str(
+            &writer
+                .execute(call(&[("content", json!("Locker code is 4512"))]), &ctx)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        let id = written["id"].as_str().unwrap().to_string();
+
+        let found: Value = serde_json::from_str(
+            &tool
+                .execute(
+                    call(&[("action", json!("search")), ("query", json!("locker"))]),
+                    &ctx,
+                )
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(found["results"][0]["id"], json!(id));
+
+        tool.execute(
+            call(&[
+                ("action", json!("update")),
+                ("id", json!(id)),
+                ("content", json!("Locker code is 7789")),
+            ]),
+            &ctx,
+        )
+        .await
+        .unwrap();
+
+        let sections: Value = serde_json::from_str(
+            &tool
+                .execute(call(&[("action", json!("sections"))]), &ctx)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        let date = sections["sections"][0]["date"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(sections["sections"][0]["entries"], 1);
+        let section: Value = serde_json::from_str(
+            &tool
+                .execute(
+                    call(&[("action", json!("sections")), ("date", json!(date))]),
+                    &ctx,
+                )
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(section["entries"][0]["content"], "Locker code is 7789");
+
+        tool.execute(
+            call(&[("action", json!("delete")), ("id", json!(id))]),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let result = tool
+            .execute(
+                call(&[("action", json!("delete")), ("id", json!(id))]),
+                &ctx,
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::NotFound(_))));
+
+        for invalid in [
+            call(&[("action", json!("search"))]),
+            call(&[("action", json!("recent")), ("days", json!(0))]),
+            call(&[("action", json!("sections")), ("date", json!("yesterday"))]),
+            call(&[("action", json!("forget"))]),
+        ] {
+            assert!(matches!(
+                tool.execute(invalid, &ctx).await,
+                Err(ToolError::InvalidArguments { .. })
+            ));
+        }
+    }
 }
@@ -400,6 +400,7 @@ mod tests {
         let ctx = ToolExecutionContext {
             channel: Some("telegram".to_string()),
             chat_id: Some("ctx-chat".to_string()),
+            user_id: None,
         };
 
         let mut args = HashMap::new();
@@ -5,14 +5,25 @@
 
 pub mod cron;
 pub mod exec;
+pub mod feeds;
 pub mod filesystem;
+pub mod html;
+pub mod http_cache;
+pub mod mcp;
 pub mod memory;
 pub mod message;
+pub mod policy;
+pub mod processes;
+pub mod sandbox;
 pub mod security;
+#[cfg(unix)]
+pub mod shell;
 pub mod skill;
 pub mod spawn;
+pub mod ssrf;
 pub mod types;
 pub mod web;
+pub mod web_search;
 
 // Re-export types from types module for backward compatibility
 pub use types::{
@@ -28,6 +39,15 @@ use tokio::sync::RwLock;
 
 use crate::config::Config;
 
+/// Default timeout for a single tool execution, in seconds
+pub const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 30;
+
+/// Default maximum number of shell sessions per conversation
+pub const DEFAULT_MAX_SHELL_SESSIONS: usize = 4;
+
+/// Default time after which an unused shell session is closed, in seconds
+pub const DEFAULT_SHELL_IDLE_TIMEOUT_SECS: u64 = 30 * 60;
+
 /// Registry for managing available tools
 ///
 /// The ToolRegistry stores and manages all tools available to the agent.
@@ -85,130 +105,221 @@ impl ToolRegistry {
     /// # Returns
     /// A ToolRegistry with all default tools registered. If a tool fails to register,
     /// a warning is logged and the registry continues without that tool (graceful degradation).
+    /// An invalid command policy or sandbox leaves out every tool that runs commands.
     pub async fn with_all_default_tools(
         workspace_path: PathBuf,
         chat_hub: Arc<crate::chat::ChatHub>,
         config: &Config,
         default_channel: impl Into<String>,
+    ) -> Self {
+        Self::with_allowed_default_tools(workspace_path, chat_hub, config, default_channel, None)
+            .await
+    }
+
+    /// Creates a new registry with the default tools named in `allowlist`
+    ///
+    /// Like [`ToolRegistry::with_all_default_tools`], which passes `None`, but
+    /// tools left out of the allowlist are never built, so an agent profile
+    /// without cron or feeds starts no cron scheduler, and one without shell
+    /// or spawn no session reaper or process registry.
+    pub async fn with_allowed_default_tools(
+        workspace_path: PathBuf,
+        chat_hub: Arc<crate::chat::ChatHub>,
+        config: &Config,
+        default_channel: impl Into<String>,
+        allowlist: Option<&[String]>,
     ) -> Self {
         let registry = Self::new();
         let default_channel = default_channel.into();
+        let allowed = |name: &str| is_allowed(allowlist, name);
 
         // Register filesystem tool
-        if let Err(e) = registry
-            .register(Box::new(
-                crate::agent::tools::filesystem::FilesystemTool::new(workspace_path.clone()),
-            ))
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to register filesystem tool, continuing without it");
+        if allowed("filesystem") {
+            if let Err(e) = registry
+                .register(Box::new(
+                    crate::agent::tools::filesystem::FilesystemTool::new(workspace_path.clone())
+                        .with_read_limits(
+                            config.tools.filesystem.max_read_bytes,
+                            config.tools.filesystem.max_base64_bytes,
+                        ),
+                ))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to register filesystem tool, continuing without it");
+            }
         }
 
-        // Register exec tool
-        match crate::agent::tools::exec::ExecTool::new(workspace_path.clone()) {
-            Ok(exec_tool) => {
-                if let Err(e) = registry.register(Box::new(exec_tool)).await {
-                    tracing::warn!(error = %e, "Failed to register exec tool, continuing without it");
+        // Register web tool; a broken domain policy must not fall back to allowing everything
+        if allowed("web") {
+            match config.tools.web_tool() {
+                Ok(web_tool) => {
+                    let cache = &config.tools.web.cache;
+                    let web_tool = if cache.enabled {
+                        web_tool.with_cache(crate::agent::tools::http_cache::HttpCache::new(
+                            workspace_path.join("cache").join("web"),
+                            cache.max_bytes(),
+                        ))
+                    } else {
+                        web_tool
+                    };
+                    if let Err(e) = registry.register(Box::new(web_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register web tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Invalid web tool config, continuing without it");
                 }
-            }
-            Err(e) => {
-                tracing::warn!(error = %e, "Failed to create exec tool, continuing without it");
             }
         }
 
-        // Register web tool
-        if let Err(e) = registry
-            .register(Box::new(crate::agent::tools::web::WebTool::new()))
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to register web tool, continuing without it");
+        // Register web search tool
+        if allowed("web_search") {
+            match config.tools.web_search_tool() {
+                Ok(web_search_tool) => {
+                    if let Err(e) = registry.register(Box::new(web_search_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register web search tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Invalid web search config, continuing without it");
+                }
+            }
         }
 
-        // Register spawn tool
-        match crate::agent::tools::spawn::SpawnTool::new(
-            workspace_path.clone(),
-            config.spawn_log_output,
-        ) {
-            Ok(spawn_tool) => {
-                if let Err(e) = registry.register(Box::new(spawn_tool)).await {
-                    tracing::warn!(error = %e, "Failed to register spawn tool, continuing without it");
-                }
+        // The command tools share one command policy and one sandbox; a broken
+        // policy or sandbox must not fall back to allowing everything or running
+        // unconfined, so they are left out instead
+        let command_security = config
+            .tools
+            .command_policy()
+            .and_then(|policy| Ok((policy, config.tools.sandbox()?)));
+        let cron_scheduler = match command_security {
+            Ok((policy, sandbox)) => {
+                registry
+                    .register_command_tools(
+                        &workspace_path,
+                        &chat_hub,
+                        config,
+                        Arc::new(policy),
+                        Arc::new(sandbox),
+                        allowlist,
+                    )
+                    .await
             }
             Err(e) => {
-                tracing::warn!(error = %e, "Failed to create spawn tool, continuing without it");
+                tracing::warn!(
+                    error = %e,
+                    "Invalid command policy or sandbox, continuing without exec, shell, spawn, cron and run_skill"
+                );
+                None
             }
-        }
-
-        // Register cron tool and start scheduler
-        let cron_scheduler = crate::cron::CronScheduler::new();
-        let cron_tool = crate::agent::tools::cron::CronTool::new(cron_scheduler.clone());
+        };
 
-        if let Err(e) = registry.register(Box::new(cron_tool)).await {
-            tracing::warn!(error = %e, "Failed to register cron tool, continuing without it");
-        } else {
-            // Start cron scheduler background task
-            let _scheduler_handle = cron_scheduler.start_scheduler();
-            tracing::info!("Cron scheduler started");
+        // Register feeds tool, scheduling digests on the cron scheduler
+        if allowed("feeds") {
+            match crate::agent::tools::feeds::FeedsTool::new(
+                workspace_path.clone(),
+                &config.tools.web,
+            ) {
+                Ok(feeds_tool) => {
+                    let feeds_tool = match &cron_scheduler {
+                        Some(scheduler) => feeds_tool.with_scheduler(scheduler.clone()),
+                        None => feeds_tool,
+                    };
+                    if let Err(e) = registry.register(Box::new(feeds_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register feeds tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Invalid web config, continuing without feeds tool");
+                }
+            }
         }
 
         // Register memory tool
-        match crate::agent::tools::memory::MemoryTool::new(workspace_path.clone()) {
-            Ok(memory_tool) => {
-                if let Err(e) = registry.register(Box::new(memory_tool)).await {
-                    tracing::warn!(error = %e, "Failed to register memory tool, continuing without it");
+        if allowed("write_memory") {
+            match crate::agent::tools::memory::MemoryTool::new(workspace_path.clone()) {
+                Ok(memory_tool) => {
+                    if let Err(e) = registry.register(Box::new(memory_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register memory tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to create memory tool, continuing without it");
                 }
             }
-            Err(e) => {
-                tracing::warn!(error = %e, "Failed to create memory tool, continuing without it");
+        }
+
+        // Register memory management tool
+        if allowed("manage_memory") {
+            match crate::agent::tools::memory::ManageMemoryTool::new(workspace_path.clone()) {
+                Ok(manage_memory_tool) => {
+                    if let Err(e) = registry.register(Box::new(manage_memory_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register manage_memory tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to create manage_memory tool, continuing without it");
+                }
             }
         }
 
         // Register skill management tools
-        if let Err(e) = registry
-            .register(Box::new(crate::agent::tools::skill::CreateSkillTool::new(
-                workspace_path.clone(),
-            )))
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to register create_skill tool, continuing without it");
+        if allowed("create_skill") {
+            if let Err(e) = registry
+                .register(Box::new(crate::agent::tools::skill::CreateSkillTool::new(
+                    workspace_path.clone(),
+                )))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to register create_skill tool, continuing without it");
+            }
         }
 
-        if let Err(e) = registry
-            .register(Box::new(crate::agent::tools::skill::ListSkillsTool::new(
-                workspace_path.clone(),
-            )))
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to register list_skills tool, continuing without it");
+        if allowed("list_skills") {
+            if let Err(e) = registry
+                .register(Box::new(crate::agent::tools::skill::ListSkillsTool::new(
+                    workspace_path.clone(),
+                )))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to register list_skills tool, continuing without it");
+            }
         }
 
-        if let Err(e) = registry
-            .register(Box::new(crate::agent::tools::skill::ReadSkillTool::new(
-                workspace_path.clone(),
-            )))
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to register read_skill tool, continuing without it");
+        if allowed("read_skill") {
+            if let Err(e) = registry
+                .register(Box::new(crate::agent::tools::skill::ReadSkillTool::new(
+                    workspace_path.clone(),
+                )))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to register read_skill tool, continuing without it");
+            }
         }
 
-        if let Err(e) = registry
-            .register(Box::new(crate::agent::tools::skill::DeleteSkillTool::new(
-                workspace_path.clone(),
-            )))
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to register delete_skill tool, continuing without it");
+        if allowed("delete_skill") {
+            if let Err(e) = registry
+                .register(Box::new(crate::agent::tools::skill::DeleteSkillTool::new(
+                    workspace_path.clone(),
+                )))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to register delete_skill tool, continuing without it");
+            }
         }
 
         // Register message tool
-        if let Err(e) = registry
-            .register(Box::new(crate::agent::tools::message::MessageTool::new(
-                chat_hub,
-                default_channel,
-            )))
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to register message tool, continuing without it");
+        if allowed("message") {
+            if let Err(e) = registry
+                .register(Box::new(crate::agent::tools::message::MessageTool::new(
+                    chat_hub,
+                    default_channel,
+                )))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to register message tool, continuing without it");
+            }
         }
 
         let tool_count = registry.len().await;
@@ -220,6 +331,150 @@ impl ToolRegistry {
         registry
     }
 
+    /// Names of the tools [`ToolRegistry::with_all_default_tools`] registers
+    /// for a valid config
+    ///
+    /// For callers that only need the names, such as `miniclaw context`;
+    /// unlike building the registry, this starts no cron scheduler.
+    pub fn default_tool_names() -> Vec<String> {
+        let mut names = vec!["filesystem", "exec"];
+        if cfg!(unix) {
+            names.push("shell");
+        }
+        names.extend([
+            "spawn",
+            "cron",
+            "run_skill",
+            "web",
+            "web_search",
+            "feeds",
+            "write_memory",
+            "manage_memory",
+            "create_skill",
+            "list_skills",
+            "read_skill",
+            "delete_skill",
+            "message",
+        ]);
+        names.into_iter().map(String::from).collect()
+    }
+
+    /// Registers exec, shell, spawn, cron and run_skill, which all run
+    /// commands under `policy` inside `sandbox`
+    ///
+    /// # Returns
+    /// The cron scheduler, started, if the cron tool was registered
+    async fn register_command_tools(
+        &self,
+        workspace_path: &std::path::Path,
+        chat_hub: &Arc<crate::chat::ChatHub>,
+        config: &Config,
+        policy: Arc<crate::agent::tools::policy::CommandPolicy>,
+        sandbox: Arc<crate::agent::tools::sandbox::Sandbox>,
+        allowlist: Option<&[String]>,
+    ) -> Option<crate::cron::CronScheduler> {
+        let allowed = |name: &str| is_allowed(allowlist, name);
+
+        // Register exec tool
+        if allowed("exec") {
+            match crate::agent::tools::exec::ExecTool::new(workspace_path.to_path_buf()) {
+                Ok(exec_tool) => {
+                    let exec_tool = exec_tool
+                        .with_policy(Arc::clone(&policy))
+                        .with_sandbox(Arc::clone(&sandbox));
+                    if let Err(e) = self.register(Box::new(exec_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register exec tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to create exec tool, continuing without it");
+                }
+            }
+        }
+
+        // Register shell session tool
+        #[cfg(unix)]
+        if allowed("shell") {
+            match crate::agent::tools::shell::ShellTool::new(workspace_path.to_path_buf()) {
+                Ok(shell_tool) => {
+                    let shell_tool = shell_tool
+                        .with_policy(Arc::clone(&policy))
+                        .with_sandbox(Arc::clone(&sandbox))
+                        .with_limits(
+                            config.tools.shell.max_sessions,
+                            Duration::from_secs(config.tools.shell.idle_timeout_secs),
+                        )
+                        .with_tool_timeout(config.agent.tool_timeout());
+                    // Close idle sessions in the background
+                    let _reaper_handle = shell_tool.start_reaper();
+                    if let Err(e) = self.register(Box::new(shell_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register shell tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to create shell tool, continuing without it");
+                }
+            }
+        }
+
+        // Register spawn tool
+        if allowed("spawn") {
+            match crate::agent::tools::spawn::SpawnTool::new(
+                workspace_path.to_path_buf(),
+                config.spawn_log_output,
+            ) {
+                Ok(spawn_tool) => {
+                    let spawn_tool = spawn_tool
+                        .with_policy(Arc::clone(&policy))
+                        .with_sandbox(Arc::clone(&sandbox))
+                        .with_chat_hub(Arc::clone(chat_hub));
+                    if let Err(e) = self.register(Box::new(spawn_tool)).await {
+                        tracing::warn!(error = %e, "Failed to register spawn tool, continuing without it");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to create spawn tool, continuing without it");
+                }
+            }
+        }
+
+        // Register cron tool and start the scheduler, which feeds digests run on too
+        let cron_scheduler = if allowed("cron") || allowed("feeds") {
+            let cron_scheduler = crate::cron::CronScheduler::new()
+                .with_policy(Arc::clone(&policy))
+                .with_sandbox(Arc::clone(&sandbox))
+                .with_chat_hub(Arc::clone(chat_hub));
+            if allowed("cron") {
+                let cron_tool = crate::agent::tools::cron::CronTool::new(cron_scheduler.clone());
+                if let Err(e) = self.register(Box::new(cron_tool)).await {
+                    tracing::warn!(error = %e, "Failed to register cron tool, continuing without it");
+                }
+            }
+            // Start cron scheduler background task
+            let _scheduler_handle = cron_scheduler.clone().start_scheduler();
+            tracing::info!("Cron scheduler started");
+            Some(cron_scheduler)
+        } else {
+            None
+        };
+
+        // Register run_skill tool
+        if allowed("run_skill") {
+            if let Err(e) = self
+                .register(Box::new(
+                    crate::agent::tools::skill::RunSkillTool::new(workspace_path.to_path_buf())
+                        .with_policy(policy)
+                        .with_sandbox(sandbox),
+                ))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to register run_skill tool, continuing without it");
+            }
+        }
+
+        cron_scheduler
+    }
+
     /// Registers a tool in the registry
     ///
     /// # Arguments
@@ -271,6 +526,42 @@ impl ToolRegistry {
         removed
     }
 
+    /// Removes every tool whose name is not in `allowlist`
+    ///
+    /// Used to restrict an agent profile to a subset of the default tools.
+    ///
+    /// # Returns
+    /// The names of the tools that were removed, sorted
+    pub async fn retain_tools(&self, allowlist: &[String]) -> Vec<String> {
+        let mut tools = self.tools.write().await;
+        let mut removed: Vec<String> = tools
+            .keys()
+            .filter(|name| !allowlist.contains(name))
+            .cloned()
+            .collect();
+        removed.sort();
+
+        for name in &removed {
+            tools.remove(name);
+        }
+
+        if !removed.is_empty() {
+            // Invalidate cache
+            let mut cache = self.definitions_cache.write().await;
+            *cache = None;
+        }
+
+        removed
+    }
+
+    /// Tells every tool that the agent session `session_id` expired
+    pub async fn session_expired(&self, session_id: &str) {
+        let tools = self.tools.read().await;
+        for tool in tools.values() {
+            tool.session_expired(session_id).await;
+        }
+    }
+
     /// Checks if a tool is registered
     ///
     /// # Arguments
@@ -378,7 +669,7 @@ impl ToolRegistry {
     /// This method validates arguments against the tool's schema before execution
     ///
     /// # Timeout
-    /// Tool execution has a 30-second timeout by default
+    /// Tool execution has a [`DEFAULT_TOOL_TIMEOUT_SECS`] timeout
     #[allow(clippy::await_holding_lock)]
     pub async fn execute_tool(
         &self,
@@ -386,8 +677,13 @@ impl ToolRegistry {
         args: HashMap<String, Value>,
         ctx: &ToolExecutionContext,
     ) -> types::ToolResult<String> {
-        self.execute_tool_with_timeout(name, args, ctx, Duration::from_secs(30))
-            .await
+        self.execute_tool_with_timeout(
+            name,
+            args,
+            ctx,
+            Duration::from_secs(DEFAULT_TOOL_TIMEOUT_SECS),
+        )
+        .await
     }
 
     /// Executes a tool with a custom timeout
@@ -443,6 +739,11 @@ impl Default for ToolRegistry {
     }
 }
 
+/// Whether `name` is in `allowlist`; no allowlist allows every tool
+fn is_allowed(allowlist: Option<&[String]>, name: &str) -> bool {
+    allowlist.is_none_or(|names| names.iter().any(|n| n == name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -553,6 +854,22 @@ mod tests {
         assert!(!not_found);
     }
 
+    #[tokio::test]
+    async fn test_retain_tools() {
+        let registry = ToolRegistry::new();
+        registry.register(Box::new(TestTool)).await.unwrap();
+        assert_eq!(registry.get_tool_definitions().await.len(), 1);
+
+        let removed = registry.retain_tools(&["test_tool".to_string()]).await;
+        assert!(removed.is_empty());
+        assert!(registry.contains("test_tool").await);
+
+        let removed = registry.retain_tools(&["exec".to_string()]).await;
+        assert_eq!(removed, vec!["test_tool".to_string()]);
+        assert!(registry.is_empty().await);
+        assert!(registry.get_tool_definitions().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_list_tools() {
         let registry = ToolRegistry::new();
@@ -732,13 +1049,114 @@ mod tests {
         assert!(registry.contains("filesystem").await);
         assert!(registry.contains("exec").await);
         assert!(registry.contains("web").await);
+        assert!(registry.contains("web_search").await);
+        assert!(registry.contains("feeds").await);
         assert!(registry.contains("spawn").await);
         assert!(registry.contains("cron").await);
         assert!(registry.contains("write_memory").await);
+        assert!(registry.contains("manage_memory").await);
         assert!(registry.contains("create_skill").await);
         assert!(registry.contains("list_skills").await);
         assert!(registry.contains("read_skill").await);
         assert!(registry.contains("delete_skill").await);
+        assert!(registry.contains("run_skill").await);
         assert!(registry.contains("message").await);
     }
+
+    #[tokio::test]
+    async fn test_invalid_command_policy_leaves_out_command_tools() {
+        use crate::agent::tools::policy::ArgumentRule;
+        use crate::chat::ChatHub;
+        use crate::config::Config;
+
+        let mut config = Config::default();
+        config.tools.commands.rules = vec![ArgumentRule::deny("find", "(", "broken")];
+
+        let registry = ToolRegistry::with_all_default_tools(
+            std::env::temp_dir(),
+            Arc::new(ChatHub::new()),
+            &config,
+            "test_channel",
+        )
+        .await;
+
+        for name in ["exec", "shell", "spawn", "cron", "run_skill"] {
+            assert!(!registry.contains(name).await, "{} was registered", name);
+        }
+        assert!(registry.contains("filesystem").await);
+        assert!(registry.contains("feeds").await);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_sandbox_leaves_out_command_tools() {
+        use crate::chat::ChatHub;
+        use crate::config::Config;
+
+        let mut config = Config::default();
+        config.tools.sandbox.enabled = true;
+        config.tools.sandbox.max_open_files = Some(0);
+
+        let registry = ToolRegistry::with_all_default_tools(
+            std::env::temp_dir(),
+            Arc::new(ChatHub::new()),
+            &config,
+            "test_channel",
+        )
+        .await;
+
+        for name in ["exec", "shell", "spawn", "cron", "run_skill"] {
+            assert!(!registry.contains(name).await, "{} was registered", name);
+        }
+        assert!(registry.contains("filesystem").await);
+    }
+
+    #[tokio::test]
+    async fn test_allowed_default_tools() {
+        use crate::chat::ChatHub;
+        use crate::config::Config;
+
+        let allowlist = vec!["filesystem".to_string(), "feeds".to_string()];
+        let registry = ToolRegistry::with_allowed_default_tools(
+            std::env::temp_dir(),
+            Arc::new(ChatHub::new()),
+            &Config::default(),
+            "test_channel",
+            Some(&allowlist),
+        )
+        .await;
+
+        let mut names: Vec<String> = registry
+            .list_tools()
+            .await
+            .into_iter()
+            .map(|(name, _, _)| name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["feeds", "filesystem"]);
+    }
+
+    #[tokio::test]
+    async fn test_default_tool_names_match_registry() {
+        use crate::chat::ChatHub;
+        use crate::config::Config;
+
+        let registry = ToolRegistry::with_all_default_tools(
+            std::env::temp_dir(),
+            Arc::new(ChatHub::new()),
+            &Config::default(),
+            "test_channel",
+        )
+        .await;
+        let mut registered: Vec<String> = registry
+            .list_tools()
+            .await
+            .into_iter()
+            .map(|(name, _, _)| name)
+            .collect();
+        registered.sort();
+
+        let mut names = ToolRegistry::default_tool_names();
+        names.sort();
+        assert_eq!(names, registered);
+    }
 }
@@ -0,0 +1,998 @@
+//! Command policy engine shared by the exec, spawn and cron executors
+//!
+//! Every command the agent runs (directly, in the background or on a schedule)
+//! is evaluated against a single [`CommandPolicy`] built from the `tools.commands`
+//! section of the config:
+//!
+//! ```json
+//! "tools": {
+//!   "commands": {
+//!     "mode": "denylist",
+//!     "deny": ["rm", "sudo", "mkfs*"],
+//!     "rules": [
+//!       { "command": "find", "args": "^-(delete|exec)$", "reason": "find can delete files" }
+//!     ],
+//!     "profiles": [
+//!       { "name": "admin", "user_ids": ["123456789"], "allow": ["rm"] }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! Evaluation is layered: the profiles matching the caller's channel, chat and
+//! user are consulted first (in config order), then the global settings.
+//!
+//! 1. Argument rules: the first rule whose command matches and whose pattern
+//!    matches any argument decides.
+//! 2. Command lists: the first layer that lists the command in `deny` or `allow`
+//!    decides (deny wins within a layer).
+//! 3. Mode: in `denylist` mode anything else is allowed, in `allowlist` mode
+//!    anything else is denied.
+//!
+//! Wrapper commands such as `env`, `nice` or `xargs` are unwrapped and the command
+//! they run is evaluated too, so `env rm -rf x` is denied like `rm -rf x`. Their
+//! options are parsed so that option values (`env -u FOO`) are not mistaken for
+//! the command; a wrapper whose command cannot be told apart is denied.
+//! [`CommandPolicy::evaluate`] records each step, which makes it usable as a
+//! dry-run explanation (`miniclaw policy explain`).
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::tools::types::ToolExecutionContext;
+
+/// Maximum number of nested wrapper commands that are unwrapped
+const MAX_WRAPPER_DEPTH: usize = 4;
+
+/// What happens to commands that no list or rule mentions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyMode {
+    /// Allow everything that is not denied
+    #[default]
+    Denylist,
+    /// Deny everything that is not allowed
+    Allowlist,
+}
+
+/// Whether a matching argument rule allows or denies the command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    #[default]
+    Deny,
+    Allow,
+}
+
+/// A rule matching a command by its arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArgumentRule {
+    /// Command name; a leading or trailing `*` is a wildcard (e.g. `python*`)
+    pub command: String,
+    /// Regular expression matched against each argument
+    pub args: String,
+    /// Action taken when the rule matches
+    #[serde(default)]
+    pub action: RuleAction,
+    /// Explanation reported when the rule matches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl ArgumentRule {
+    /// Creates a deny rule with a reason
+    pub fn deny(command: &str, args: &str, reason: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            args: args.to_string(),
+            action: RuleAction::Deny,
+            reason: Some(reason.to_string()),
+        }
+    }
+}
+
+/// Policy overrides for specific channels, chats or users
+///
+/// Every non-empty criterion must match the caller; profiles are consulted
+/// before the global settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicyProfile {
+    /// Profile name, used in explanations
+    pub name: String,
+    /// Channels this profile applies to (e.g. "telegram")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+    /// Chat ids this profile applies to
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chat_ids: Vec<String>,
+    /// User ids this profile applies to
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub user_ids: Vec<String>,
+    /// Mode override for matching callers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<PolicyMode>,
+    /// Commands allowed for matching callers
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Commands denied for matching callers
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    /// Argument rules for matching callers
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ArgumentRule>,
+}
+
+/// Command policy settings (`tools.commands` in the config)
+///
+/// Missing fields fall back to the built-in defaults; a field that is set
+/// replaces its default entirely.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandPolicyConfig {
+    /// Whether unlisted commands are allowed or denied
+    pub mode: PolicyMode,
+    /// Commands allowed (required in allowlist mode)
+    pub allow: Vec<String>,
+    /// Commands denied in every mode
+    pub deny: Vec<String>,
+    /// Argument rules, evaluated before the command lists
+    pub rules: Vec<ArgumentRule>,
+    /// Commands that run another command given as an argument
+    pub wrappers: Vec<String>,
+    /// Channel, chat and user specific overrides
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<PolicyProfile>,
+}
+
+impl Default for CommandPolicyConfig {
+    fn default() -> Self {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect();
+        Self {
+            mode: PolicyMode::Denylist,
+            allow: Vec::new(),
+            deny: names(&[
+                "rm", "shred", "sudo", "su", "doas", "pkexec", "dd", "mkfs*", "fdisk", "sfdisk",
+                "parted", "wipefs", "mount", "umount", "chroot", "shutdown", "reboot", "halt",
+                "poweroff", "passwd", "chpasswd", "useradd", "userdel", "usermod", "visudo",
+                "crontab",
+            ]),
+            rules: vec![
+                ArgumentRule::deny(
+                    "find",
+                    r"^-(delete|exec|execdir|ok|okdir)$",
+                    "find can delete files or run arbitrary commands",
+                ),
+                ArgumentRule::deny(
+                    "python*",
+                    r"^-[A-Za-z]*c",
+                    "inline Python code is not allowed",
+                ),
+                ArgumentRule::deny(
+                    "perl",
+                    r"^-[A-Za-z]*[eE]",
+                    "inline Perl code is not allowed",
+                ),
+                ArgumentRule::deny("ruby", r"^-[A-Za-z]*e", "inline Ruby code is not allowed"),
+                ArgumentRule::deny(
+                    "node",
+                    r"^(-e|--eval|-p|--print)(=|$)",
+                    "inline JavaScript is not allowed",
+                ),
+                ArgumentRule::deny("php", r"^-r$", "inline PHP code is not allowed"),
+                ArgumentRule::deny("*awk", r"system\s*\(", "awk programs cannot run commands"),
+                ArgumentRule::deny(
+                    "tar",
+                    r"^--(to-command|checkpoint-action|use-compress-program|rsh-command)",
+                    "tar options that run commands are not allowed",
+                ),
+            ]
+            .into_iter()
+            .chain(["sh", "bash", "zsh", "dash", "ksh", "fish"].map(|shell| {
+                ArgumentRule::deny(
+                    shell,
+                    r"^-[A-Za-z]*c",
+                    "inline shell commands are not allowed",
+                )
+            }))
+            .collect(),
+            wrappers: names(&[
+                "env", "nice", "nohup", "timeout", "stdbuf", "ionice", "setsid", "time", "xargs",
+                "busybox", "watch",
+            ]),
+            profiles: Vec::new(),
+        }
+    }
+}
+
+/// Who is asking to run a command
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicyScope {
+    /// Channel of the conversation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Chat identifier of the conversation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chat_id: Option<String>,
+    /// User who sent the message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+impl PolicyScope {
+    /// Returns true if no caller information is known
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_none() && self.chat_id.is_none() && self.user_id.is_none()
+    }
+}
+
+impl From<&ToolExecutionContext> for PolicyScope {
+    fn from(ctx: &ToolExecutionContext) -> Self {
+        Self {
+            channel: ctx.channel.clone(),
+            chat_id: ctx.chat_id.clone(),
+            user_id: ctx.user_id.clone(),
+        }
+    }
+}
+
+/// Errors raised while building a policy from its config
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("Invalid pattern '{pattern}' in command rule for '{command}': {source}")]
+    InvalidPattern {
+        command: String,
+        pattern: String,
+        source: regex::Error,
+    },
+    #[error("Invalid command policy: {0}")]
+    Invalid(String),
+}
+
+/// The outcome of evaluating a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyDecision {
+    /// Whether the command may run
+    pub allowed: bool,
+    /// Why it was allowed or denied
+    pub reason: String,
+    /// Profiles that applied to the caller
+    pub profiles: Vec<String>,
+    /// Each evaluation step, for explanations
+    pub steps: Vec<String>,
+}
+
+/// An argument rule with its compiled pattern
+#[derive(Debug)]
+struct CompiledRule {
+    command: String,
+    pattern: Regex,
+    action: RuleAction,
+    reason: Option<String>,
+}
+
+/// Allow/deny lists and rules from either a profile or the global settings
+#[derive(Debug)]
+struct Layer {
+    /// Profile name; "global" for the top-level settings
+    name: String,
+    mode: Option<PolicyMode>,
+    allow: Vec<String>,
+    deny: Vec<String>,
+    rules: Vec<CompiledRule>,
+}
+
+/// A profile layer with the criteria that select it
+#[derive(Debug)]
+struct ProfileLayer {
+    channels: Vec<String>,
+    chat_ids: Vec<String>,
+    user_ids: Vec<String>,
+    layer: Layer,
+}
+
+impl ProfileLayer {
+    fn matches(&self, scope: &PolicyScope) -> bool {
+        let matches = |values: &[String], value: &Option<String>| {
+            values.is_empty() || value.as_ref().is_some_and(|v| values.contains(v))
+        };
+        matches(&self.channels, &scope.channel)
+            && matches(&self.chat_ids, &scope.chat_id)
+            && matches(&self.user_ids, &scope.user_id)
+    }
+}
+
+/// Compiled command policy
+#[derive(Debug)]
+pub struct CommandPolicy {
+    global: Layer,
+    profiles: Vec<ProfileLayer>,
+    wrappers: Vec<String>,
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self::from_config(&CommandPolicyConfig::default())
+            .expect("default command policy must be valid")
+    }
+}
+
+impl CommandPolicy {
+    /// Builds a policy from its config, compiling every argument pattern
+    ///
+    /// Profiles must have a unique, non-empty name and at least one criterion.
+    pub fn from_config(config: &CommandPolicyConfig) -> Result<Self, PolicyError> {
+        let global = Layer {
+            name: "global".to_string(),
+            mode: Some(config.mode),
+            allow: lowercase(&config.allow),
+            deny: lowercase(&config.deny),
+            rules: compile_rules(&config.rules)?,
+        };
+
+        let mut names = HashSet::new();
+        let mut profiles = Vec::with_capacity(config.profiles.len());
+        for profile in &config.profiles {
+            if profile.name.trim().is_empty() {
+                return Err(PolicyError::Invalid(
+                    "command policy profiles need a name".to_string(),
+                ));
+            }
+            if !names.insert(profile.name.as_str()) {
+                return Err(PolicyError::Invalid(format!(
+                    "duplicate command policy profile '{}'",
+                    profile.name
+                )));
+            }
+            if profile.channels.is_empty()
+                && profile.chat_ids.is_empty()
+                && profile.user_ids.is_empty()
+            {
+                return Err(PolicyError::Invalid(format!(
+                    "command policy profile '{}' must set channels, chat_ids or user_ids",
+                    profile.name
+                )));
+            }
+
+            profiles.push(ProfileLayer {
+                channels: profile.channels.clone(),
+                chat_ids: profile.chat_ids.clone(),
+                user_ids: profile.user_ids.clone(),
+                layer: Layer {
+                    name: profile.name.clone(),
+                    mode: profile.mode,
+                    allow: lowercase(&profile.allow),
+                    deny: lowercase(&profile.deny),
+                    rules: compile_rules(&profile.rules)?,
+                },
+            });
+        }
+
+        Ok(Self {
+            global,
+            profiles,
+            wrappers: lowercase(&config.wrappers),
+        })
+    }
+
+    /// Evaluates a command without running it
+    ///
+    /// The returned decision lists every step taken, so it doubles as an
+    /// explanation of why a command is allowed or denied.
+    pub fn evaluate(&self, command: &str, args: &[String], scope: &PolicyScope) -> PolicyDecision {
+        let profiles: Vec<&ProfileLayer> =
+            self.profiles.iter().filter(|p| p.matches(scope)).collect();
+        let mut layers: Vec<&Layer> = profiles.iter().map(|p| &p.layer).collect();
+        layers.push(&self.global);
+
+        let mode = layers
+            .iter()
+            .find_map(|layer| layer.mode)
+            .unwrap_or_default();
+
+        let mut steps = Vec::new();
+        if !profiles.is_empty() {
+            steps.push(format!(
+                "Profiles applied: {}",
+                layers[..profiles.len()]
+                    .iter()
+                    .map(|l| l.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        steps.push(format!("Mode: {:?}", mode).to_lowercase());
+
+        let (allowed, reason) = self.evaluate_command(command, args, &layers, mode, 0, &mut steps);
+        PolicyDecision {
+            allowed,
+            reason,
+            profiles: profiles.iter().map(|p| p.layer.name.clone()).collect(),
+            steps,
+        }
+    }
+
+    /// Returns true if the command may run for the given caller
+    pub fn is_allowed(&self, command: &str, args: &[String], scope: &PolicyScope) -> bool {
+        self.evaluate(command, args, scope).allowed
+    }
+
+    fn evaluate_command(
+        &self,
+        command: &str,
+        args: &[String],
+        layers: &[&Layer],
+        mode: PolicyMode,
+        depth: usize,
+        steps: &mut Vec<String>,
+    ) -> (bool, String) {
+        let name = command_name(command);
+        if name.is_empty() {
+            steps.push("Empty command".to_string());
+            return (false, "empty command".to_string());
+        }
+
+        let (allowed, reason) = Self::decide(&name, args, layers, mode, steps);
+        if !allowed {
+            return (false, reason);
+        }
+
+        if self.wrappers.iter().any(|w| name_matches(w, &name)) {
+            let index = match wrapped_command_index(&name, args) {
+                Ok(Some(index)) => index,
+                Ok(None) => return (true, reason),
+                Err(uncertain) => {
+                    steps.push(format!("'{}' is a wrapper; {}", name, uncertain));
+                    return (false, uncertain);
+                }
+            };
+            if depth >= MAX_WRAPPER_DEPTH {
+                steps.push(format!("'{}' nests too many wrapper commands", name));
+                return (false, "too many nested wrapper commands".to_string());
+            }
+            steps.push(format!(
+                "'{}' is a wrapper; evaluating wrapped command '{}'",
+                name, args[index]
+            ));
+            return self.evaluate_command(
+                &args[index],
+                &args[index + 1..],
+                layers,
+                mode,
+                depth + 1,
+                steps,
+            );
+        }
+
+        (true, reason)
+    }
+
+    /// Applies rules, command lists and the mode to a single command name
+    fn decide(
+        name: &str,
+        args: &[String],
+        layers: &[&Layer],
+        mode: PolicyMode,
+        steps: &mut Vec<String>,
+    ) -> (bool, String) {
+        for layer in layers {
+            for rule in &layer.rules {
+                if !name_matches(&rule.command, name) {
+                    continue;
+                }
+                let Some(arg) = args.iter().find(|arg| rule.pattern.is_match(arg)) else {
+                    continue;
+                };
+                let allowed = rule.action == RuleAction::Allow;
+                let reason = rule.reason.clone().unwrap_or_else(|| {
+                    format!(
+                        "argument '{}' matches rule '{}' /{}/",
+                        arg, rule.command, rule.pattern
+                    )
+                });
+                steps.push(format!(
+                    "[{}] rule '{}' /{}/ matched argument '{}': {}",
+                    layer.name,
+                    rule.command,
+                    rule.pattern,
+                    arg,
+                    if allowed { "allow" } else { "deny" }
+                ));
+                return (allowed, reason);
+            }
+        }
+
+        for layer in layers {
+            if let Some(entry) = layer.deny.iter().find(|entry| name_matches(entry, name)) {
+                steps.push(format!(
+                    "[{}] '{}' matches deny entry '{}'",
+                    layer.name, name, entry
+                ));
+                return (false, format!("'{}' is denied by the command policy", name));
+            }
+            if let Some(entry) = layer.allow.iter().find(|entry| name_matches(entry, name)) {
+                steps.push(format!(
+                    "[{}] '{}' matches allow entry '{}'",
+                    layer.name, name, entry
+                ));
+                return (true, format!("'{}' is allowed by the command policy", name));
+            }
+        }
+
+        match mode {
+            PolicyMode::Denylist => {
+                steps.push(format!("'{}' is not denied", name));
+                (true, format!("'{}' is not denied", name))
+            }
+            PolicyMode::Allowlist => {
+                steps.push(format!("'{}' is not in any allowlist", name));
+                (false, format!("'{}' is not in the command allowlist", name))
+            }
+        }
+    }
+}
+
+/// Compiles the argument patterns of a list of rules
+fn compile_rules(rules: &[ArgumentRule]) -> Result<Vec<CompiledRule>, PolicyError> {
+    rules
+        .iter()
+        .map(|rule| {
+            if rule.command.trim().is_empty() {
+                return Err(PolicyError::Invalid(
+                    "command rules need a command name".to_string(),
+                ));
+            }
+            let pattern = Regex::new(&rule.args).map_err(|source| PolicyError::InvalidPattern {
+                command: rule.command.clone(),
+                pattern: rule.args.clone(),
+                source,
+            })?;
+            Ok(CompiledRule {
+                command: rule.command.to_lowercase(),
+                pattern,
+                action: rule.action,
+                reason: rule.reason.clone(),
+            })
+        })
+        .collect()
+}
+
+fn lowercase(names: &[String]) -> Vec<String> {
+    names.iter().map(|n| n.trim().to_lowercase()).collect()
+}
+
+/// Returns the lowercase base name of a command (`/usr/bin/RM` -> `rm`)
+fn command_name(command: &str) -> String {
+    Path::new(command.trim())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(command)
+        .to_lowercase()
+}
+
+/// Matches a command name against a list entry
+///
+/// `*` matches everything, a leading or trailing `*` matches any prefix or
+/// suffix, anything else must match exactly.
+fn name_matches(pattern: &str, name: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    if let Some(prefix) = pattern.strip_suffix('*') {
+        return name.starts_with(prefix);
+    }
+    if let Some(suffix) = pattern.strip_prefix('*') {
+        return name.ends_with(suffix);
+    }
+    pattern == name
+}
+
+/// How the arguments of a known wrapper lead up to the command it runs
+struct WrapperSyntax {
+    name: &'static str,
+    /// Options that take the next argument as their value
+    value_options: &'static [&'static str],
+    /// Options whose value is a whole command line, which cannot be checked
+    command_options: &'static [&'static str],
+    /// Operands before the command, such as the duration of `timeout`
+    leading_operands: usize,
+    /// Whether `NAME=value` assignments may precede the command
+    assignments: bool,
+}
+
+impl WrapperSyntax {
+    const fn new(name: &'static str, value_options: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            value_options,
+            command_options: &[],
+            leading_operands: 0,
+            assignments: false,
+        }
+    }
+}
+
+/// Argument syntax of the default wrappers (GNU coreutils, findutils,
+/// util-linux and procps)
+const WRAPPER_SYNTAX: &[WrapperSyntax] = &[
+    WrapperSyntax {
+        command_options: &["-S", "--split-string"],
+        assignments: true,
+        ..WrapperSyntax::new("env", &["-u", "--unset", "-C", "--chdir", "-a", "--argv0"])
+    },
+    WrapperSyntax::new("nice", &["-n", "--adjustment"]),
+    WrapperSyntax::new("nohup", &[]),
+    WrapperSyntax {
+        leading_operands: 1,
+        ..WrapperSyntax::new("timeout", &["-s", "--signal", "-k", "--kill-after"])
+    },
+    WrapperSyntax::new(
+        "stdbuf",
+        &["-i", "-o", "-e", "--input", "--output", "--error"],
+    ),
+    WrapperSyntax::new(
+        "ionice",
+        &[
+            "-c",
+            "--class",
+            "-n",
+            "--classdata",
+            "-p",
+            "--pid",
+            "-P",
+            "--pgid",
+            "-u",
+            "--uid",
+        ],
+    ),
+    WrapperSyntax::new("setsid", &[]),
+    WrapperSyntax::new("time", &["-f", "--format", "-o", "--output"]),
+    WrapperSyntax::new(
+        "xargs",
+        &[
+            "-a",
+            "--arg-file",
+            "-d",
+            "--delimiter",
+            "-E",
+            "-I",
+            "-L",
+            "-n",
+            "--max-args",
+            "-P",
+            "--max-procs",
+            "-s",
+            "--max-chars",
+            "--process-slot-var",
+        ],
+    ),
+    WrapperSyntax::new("busybox", &[]),
+    WrapperSyntax::new("watch", &["-n", "--interval", "-q", "--equexit"]),
+];
+
+/// Finds the command run by a wrapper
+///
+/// Known wrappers are parsed with their [`WRAPPER_SYNTAX`], skipping options
+/// and their values. For other wrappers the command is the first argument
+/// that is not an option, an environment assignment or a number (such as a
+/// timeout). Returns an error when the command cannot be determined, e.g.
+/// `env -S 'rm -rf x'` or an abbreviated option that may take a value.
+fn wrapped_command_index(wrapper: &str, args: &[String]) -> Result<Option<usize>, String> {
+    let Some(syntax) = WRAPPER_SYNTAX.iter().find(|s| s.name == wrapper) else {
+        return Ok(args.iter().position(|arg| {
+            !arg.is_empty()
+                && !arg.starts_with('-')
+                && !arg.contains('=')
+                && arg
+                    .trim_end_matches(['s', 'm', 'h', 'd'])
+                    .parse::<f64>()
+                    .is_err()
+        }));
+    };
+
+    let mut operands = syntax.leading_operands;
+    let mut options = true;
+    let mut index = 0;
+    while index < args.len() {
+        let arg = args[index].as_str();
+        index += 1;
+        if options && arg == "--" {
+            options = false;
+        } else if options && arg.starts_with('-') {
+            if option_takes_value(syntax, arg)? {
+                index += 1;
+            }
+        } else {
+            // Like the wrappers themselves, stop parsing options at the first operand
+            options = false;
+            if syntax.assignments && arg.contains('=') {
+                continue;
+            }
+            if operands > 0 {
+                operands -= 1;
+                continue;
+            }
+            return Ok(Some(index - 1));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether the option `arg` of a wrapper consumes the next argument
+fn option_takes_value(syntax: &WrapperSyntax, arg: &str) -> Result<bool, String> {
+    let uncertain = |option: &str| {
+        format!(
+            "cannot tell which command '{}' runs with option '{}'",
+            syntax.name, option
+        )
+    };
+
+    if let Some(long) = arg.strip_prefix("--") {
+        let (name, inline_value) = match long.split_once('=') {
+            Some((name, _)) => (name, true),
+            None => (long, false),
+        };
+        let option = format!("--{}", name);
+        if syntax.command_options.contains(&option.as_str()) {
+            return Err(uncertain(&option));
+        }
+        if syntax.value_options.contains(&option.as_str()) {
+            return Ok(!inline_value);
+        }
+        // Long options may be abbreviated to any unambiguous prefix
+        let abbreviates = |list: &[&str]| list.iter().any(|o| o.starts_with(&option));
+        if abbreviates(syntax.value_options) || abbreviates(syntax.command_options) {
+            return Err(uncertain(&option));
+        }
+        return Ok(false);
+    }
+
+    // A cluster of short options; one taking a value consumes the rest of
+    // the cluster or, at its end, the next argument
+    let cluster = &arg[1..];
+    for (position, flag) in cluster.char_indices() {
+        let option = format!("-{}", flag);
+        if syntax.command_options.contains(&option.as_str()) {
+            return Err(uncertain(&option));
+        }
+        if syntax.value_options.contains(&option.as_str()) {
+            return Ok(position + flag.len_utf8() == cluster.len());
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn allowed(policy: &CommandPolicy, command: &str, list: &[&str]) -> bool {
+        policy.is_allowed(command, &args(list), &PolicyScope::default())
+    }
+
+    #[test]
+    fn test_default_denies_dangerous_commands() {
+        let policy = CommandPolicy::default();
+        for command in [
+            "rm", "sudo", "dd", "mkfs", "shutdown", "reboot", "passwd", "visudo",
+        ] {
+            assert!(
+                !allowed(&policy, command, &[]),
+                "{} should be denied",
+                command
+            );
+        }
+        assert!(!allowed(&policy, "mkfs.ext4", &["/dev/sda1"]));
+    }
+
+    #[test]
+    fn test_default_denies_paths_and_case_variants() {
+        let policy = CommandPolicy::default();
+        assert!(!allowed(&policy, "/bin/rm", &[]));
+        assert!(!allowed(&policy, "/usr/bin/sudo", &[]));
+        assert!(!allowed(&policy, "./rm", &[]));
+        assert!(!allowed(&policy, "RM", &[]));
+        assert!(!allowed(&policy, "SuDo", &[]));
+    }
+
+    #[test]
+    fn test_default_allows_common_commands() {
+        let policy = CommandPolicy::default();
+        for command in [
+            "ls", "cat", "echo", "git", "cargo", "pwd", "sleep", "python",
+        ] {
+            assert!(
+                allowed(&policy, command, &[]),
+                "{} should be allowed",
+                command
+            );
+        }
+        assert!(allowed(&policy, "python3", &["script.py"]));
+        assert!(allowed(&policy, "find", &[".", "-name", "*.rs"]));
+    }
+
+    #[test]
+    fn test_default_argument_rules_block_bypasses() {
+        let policy = CommandPolicy::default();
+        assert!(!allowed(&policy, "find", &[".", "-delete"]));
+        assert!(!allowed(&policy, "find", &[".", "-exec", "rm", "{}", ";"]));
+        assert!(!allowed(&policy, "python3", &["-c", "import os"]));
+        assert!(!allowed(&policy, "python", &["-Sc", "import os"]));
+        assert!(!allowed(&policy, "bash", &["-c", "rm -rf /"]));
+        assert!(!allowed(&policy, "node", &["--eval=process.exit()"]));
+        assert!(!allowed(&policy, "awk", &["BEGIN { system(\"id\") }"]));
+    }
+
+    #[test]
+    fn test_wrappers_are_unwrapped() {
+        let policy = CommandPolicy::default();
+        assert!(!allowed(&policy, "env", &["FOO=1", "rm", "-rf", "x"]));
+        assert!(!allowed(&policy, "nice", &["-n", "10", "sudo", "id"]));
+        assert!(!allowed(&policy, "timeout", &["5s", "python3", "-c", "1"]));
+        assert!(!allowed(&policy, "xargs", &["rm"]));
+        assert!(allowed(&policy, "env", &["FOO=1", "ls"]));
+        assert!(allowed(&policy, "env", &[]));
+    }
+
+    #[test]
+    fn test_wrapper_option_values_are_skipped() {
+        let policy = CommandPolicy::default();
+        assert!(!allowed(&policy, "env", &["-u", "FOO", "rm", "-rf", "x"]));
+        assert!(!allowed(&policy, "env", &["--unset", "FOO", "rm", "x"]));
+        assert!(!allowed(&policy, "env", &["-iu", "FOO", "rm", "x"]));
+        assert!(!allowed(
+            &policy,
+            "timeout",
+            &["-s", "KILL", "5", "rm", "x"]
+        ));
+        assert!(!allowed(
+            &policy,
+            "timeout",
+            &["--signal=KILL", "5", "rm", "x"]
+        ));
+        assert!(!allowed(&policy, "xargs", &["-I", "{}", "rm", "{}"]));
+        assert!(!allowed(&policy, "xargs", &["-I{}", "rm", "{}"]));
+        assert!(!allowed(&policy, "nice", &["--", "sudo", "id"]));
+        assert!(allowed(&policy, "env", &["-u", "RM", "ls"]));
+        assert!(allowed(&policy, "timeout", &["-k", "1", "5", "ls"]));
+        assert!(allowed(&policy, "xargs", &["-I", "rm", "echo", "rm"]));
+    }
+
+    #[test]
+    fn test_uncertain_wrapped_command_is_denied() {
+        let policy = CommandPolicy::default();
+        assert!(!allowed(&policy, "env", &["-S", "ls -la"]));
+        assert!(!allowed(&policy, "env", &["--split-string=ls"]));
+        // Abbreviates --signal, which takes a value
+        assert!(!allowed(&policy, "timeout", &["--sig", "KILL", "5", "ls"]));
+
+        let decision = policy.evaluate("env", &args(&["-S", "rm -rf x"]), &PolicyScope::default());
+        assert!(!decision.allowed);
+        assert!(decision.reason.contains("'-S'"));
+    }
+
+    #[test]
+    fn test_allowlist_mode() {
+        let config = CommandPolicyConfig {
+            mode: PolicyMode::Allowlist,
+            allow: args(&["ls", "git", "env"]),
+            ..CommandPolicyConfig::default()
+        };
+        let policy = CommandPolicy::from_config(&config).unwrap();
+
+        assert!(allowed(&policy, "ls", &["-la"]));
+        assert!(!allowed(&policy, "cat", &["file"]));
+        assert!(!allowed(&policy, "env", &["cat", "file"]));
+        assert!(allowed(&policy, "env", &["git", "status"]));
+    }
+
+    #[test]
+    fn test_profiles_apply_to_matching_scope() {
+        let config = CommandPolicyConfig {
+            profiles: vec![
+                PolicyProfile {
+                    name: "admin".to_string(),
+                    user_ids: args(&["42"]),
+                    allow: args(&["rm"]),
+                    ..PolicyProfile::default()
+                },
+                PolicyProfile {
+                    name: "telegram".to_string(),
+                    channels: args(&["telegram"]),
+                    mode: Some(PolicyMode::Allowlist),
+                    allow: args(&["ls"]),
+                    ..PolicyProfile::default()
+                },
+            ],
+            ..CommandPolicyConfig::default()
+        };
+        let policy = CommandPolicy::from_config(&config).unwrap();
+
+        let admin = PolicyScope {
+            channel: Some("cli".to_string()),
+            user_id: Some("42".to_string()),
+            ..PolicyScope::default()
+        };
+        let decision = policy.evaluate("rm", &args(&["x"]), &admin);
+        assert!(decision.allowed);
+        assert_eq!(decision.profiles, vec!["admin"]);
+        // Argument rules still apply to the admin
+        assert!(!policy.is_allowed("find", &args(&[".", "-delete"]), &admin));
+
+        let telegram = PolicyScope {
+            channel: Some("telegram".to_string()),
+            ..PolicyScope::default()
+        };
+        assert!(policy.is_allowed("ls", &[], &telegram));
+        assert!(!policy.is_allowed("cat", &[], &telegram));
+        assert!(!policy.is_allowed("rm", &[], &PolicyScope::default()));
+        assert!(policy.is_allowed("cat", &[], &PolicyScope::default()));
+    }
+
+    #[test]
+    fn test_allow_rule_overrides_deny_list() {
+        let config = CommandPolicyConfig {
+            rules: vec![ArgumentRule {
+                command: "rm".to_string(),
+                args: r"^tmp/".to_string(),
+                action: RuleAction::Allow,
+                reason: None,
+            }],
+            ..CommandPolicyConfig::default()
+        };
+        let policy = CommandPolicy::from_config(&config).unwrap();
+
+        assert!(allowed(&policy, "rm", &["tmp/cache.txt"]));
+        assert!(!allowed(&policy, "rm", &["notes.md"]));
+    }
+
+    #[test]
+    fn test_evaluate_explains_decision() {
+        let policy = CommandPolicy::default();
+        let decision = policy.evaluate(
+            "env",
+            &args(&["find", ".", "-delete"]),
+            &PolicyScope::default(),
+        );
+
+        assert!(!decision.allowed);
+        assert_eq!(
+            decision.reason,
+            "find can delete files or run arbitrary commands"
+        );
+        assert!(decision.steps.iter().any(|s| s.contains("wrapper")));
+        assert!(decision.steps.iter().any(|s| s.contains("-delete")));
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let config = CommandPolicyConfig {
+            rules: vec![ArgumentRule::deny("find", "(", "broken")],
+            ..CommandPolicyConfig::default()
+        };
+        assert!(matches!(
+            CommandPolicy::from_config(&config),
+            Err(PolicyError::InvalidPattern { .. })
+        ));
+
+        let config = CommandPolicyConfig {
+            profiles: vec![PolicyProfile {
+                name: "everyone".to_string(),
+                ..PolicyProfile::default()
+            }],
+            ..CommandPolicyConfig::default()
+        };
+        assert!(matches!(
+            CommandPolicy::from_config(&config),
+            Err(PolicyError::Invalid(_))
+        ));
+    }
+}
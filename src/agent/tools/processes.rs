@@ -0,0 +1,641 @@
+//! Registry of background processes started by the spawn tool
+//!
+//! Every spawned process is tracked until it exits: its stdout and stderr are
+//! captured to a log file under `processes/` in the workspace, its exit code is
+//! recorded, and an optional message is sent to the conversation that started it
+//! once it finishes. This lets the agent follow up on long-running jobs with the
+//! `list`, `status`, `tail_output` and `kill` actions of the spawn tool.
+//!
+//! Log files are size-capped: when a log reaches the limit it is rotated to
+//! `<name>.log.1` (replacing any previous rotation), so a process never uses more
+//! than twice the limit on disk. Processes are only visible to the conversation
+//! that started them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Child;
+use tokio::sync::watch;
+
+use crate::chat::{ChatHub, OutboundMessage};
+
+/// Default size at which a process log is rotated
+pub const DEFAULT_MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Finished processes kept in the registry (their logs are deleted on eviction)
+const MAX_FINISHED_PROCESSES: usize = 50;
+
+/// Time a process gets to exit after SIGTERM before it is killed
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// Time given to the output copies to drain once the process has exited
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Lines of output included in completion notifications
+const NOTIFICATION_TAIL_LINES: usize = 10;
+
+/// Lifecycle state of a background process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum ProcessState {
+    /// Still running
+    Running,
+    /// Exited on its own or after a signal
+    Exited {
+        /// Exit code, absent when terminated by a signal
+        exit_code: Option<i32>,
+        /// Terminating signal, if any
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signal: Option<i32>,
+    },
+    /// Could not be waited for
+    Failed { error: String },
+}
+
+/// Information about a tracked process
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub command: String,
+    pub args: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub state: ProcessState,
+    pub log_path: PathBuf,
+    /// Conversation that started the process
+    #[serde(skip)]
+    pub owner: String,
+}
+
+impl ProcessInfo {
+    /// Returns true while the process is running
+    pub fn is_running(&self) -> bool {
+        self.state == ProcessState::Running
+    }
+}
+
+/// Where to send a message when a process finishes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExitNotification {
+    pub channel: String,
+    pub chat_id: String,
+}
+
+/// Errors returned by registry actions
+#[derive(Debug, thiserror::Error)]
+pub enum ProcessError {
+    #[error("No background process with pid {0}")]
+    NotFound(u32),
+    #[error("Process {0} is not running")]
+    NotRunning(u32),
+    #[error("Failed to signal process {pid}: {source}")]
+    Signal { pid: u32, source: std::io::Error },
+    #[error("Failed to read process log: {0}")]
+    Log(#[from] std::io::Error),
+}
+
+#[derive(Debug)]
+struct ProcessEntry {
+    info: ProcessInfo,
+    /// Flips to true once the process has exited and its output is flushed
+    done: watch::Receiver<bool>,
+}
+
+/// Tracks background processes, their logs and exit codes
+pub struct ProcessRegistry {
+    log_dir: PathBuf,
+    max_log_bytes: u64,
+    chat_hub: Option<Arc<ChatHub>>,
+    processes: Mutex<HashMap<u32, ProcessEntry>>,
+}
+
+impl std::fmt::Debug for ProcessRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessRegistry")
+            .field("log_dir", &self.log_dir)
+            .field("max_log_bytes", &self.max_log_bytes)
+            .field("notifications", &self.chat_hub.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProcessRegistry {
+    /// Creates a registry writing logs to `log_dir` (created on first use)
+    pub fn new(log_dir: PathBuf) -> Self {
+        Self {
+            log_dir,
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            chat_hub: None,
+            processes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Overrides the size at which logs are rotated
+    pub fn with_max_log_bytes(mut self, max_log_bytes: u64) -> Self {
+        self.max_log_bytes = max_log_bytes.max(1);
+        self
+    }
+
+    /// Enables completion notifications through the chat hub
+    pub fn with_chat_hub(mut self, chat_hub: Arc<ChatHub>) -> Self {
+        self.chat_hub = Some(chat_hub);
+        self
+    }
+
+    /// Returns the log file path for a process started now
+    pub async fn create_log(&self, pid: u32) -> std::io::Result<PathBuf> {
+        tokio::fs::create_dir_all(&self.log_dir).await?;
+        Ok(self.log_dir.join(format!(
+            "{}-{}.log",
+            Utc::now().format("%Y%m%d-%H%M%S"),
+            pid
+        )))
+    }
+
+    /// Starts tracking a spawned child whose stdout and stderr are piped
+    ///
+    /// Output is copied to `log_path`. When `notify` is set, a message with the
+    /// exit code and the last lines of output is sent once the process exits.
+    /// When `log_output` is set, the tail of the output is also logged.
+    pub fn track(
+        self: &Arc<Self>,
+        mut child: Child,
+        info: ProcessInfo,
+        notify: Option<ExitNotification>,
+        log_output: bool,
+    ) {
+        let pid = info.pid;
+        let (done_tx, done_rx) = watch::channel(false);
+        let log_path = info.log_path.clone();
+        self.insert(ProcessEntry {
+            info,
+            done: done_rx,
+        });
+
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            let log = Arc::new(tokio::sync::Mutex::new(
+                LogFile::create(log_path, registry.max_log_bytes).await,
+            ));
+            let copies = [
+                child
+                    .stdout
+                    .take()
+                    .map(|out| copy_to_log(out, Arc::clone(&log))),
+                child
+                    .stderr
+                    .take()
+                    .map(|err| copy_to_log(err, Arc::clone(&log))),
+            ];
+
+            let state = match child.wait().await {
+                Ok(status) => ProcessState::Exited {
+                    exit_code: status.code(),
+                    signal: exit_signal(&status),
+                },
+                Err(e) => ProcessState::Failed {
+                    error: e.to_string(),
+                },
+            };
+            // Let the copies drain what the process wrote before exiting; a
+            // daemonized child may keep the pipes open, so do not wait forever
+            for copy in copies.into_iter().flatten() {
+                let _ = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, copy).await;
+            }
+            log.lock().await.flush().await;
+
+            let Some(info) = registry.finish(pid, state) else {
+                return;
+            };
+            let _ = done_tx.send(true);
+            tracing::info!(pid = %pid, command = %info.command, state = ?info.state, "Background process finished");
+
+            if log_output || notify.is_some() {
+                let tail = tail_lines(&info.log_path, NOTIFICATION_TAIL_LINES)
+                    .await
+                    .unwrap_or_default();
+                if log_output && !tail.is_empty() {
+                    tracing::info!(pid = %pid, command = %info.command, output = %tail, "Background process output");
+                }
+                if let (Some(notify), Some(hub)) = (notify, &registry.chat_hub) {
+                    let message = OutboundMessage::new(
+                        &notify.channel,
+                        &notify.chat_id,
+                        completion_message(&info, &tail),
+                    );
+                    if let Err(e) = hub.send_outbound(message).await {
+                        tracing::warn!(pid = %pid, error = %e, "Failed to send process notification");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Returns a receiver that turns true once the process has finished
+    pub fn done_signal(&self, pid: u32) -> Option<watch::Receiver<bool>> {
+        self.lock().get(&pid).map(|entry| entry.done.clone())
+    }
+
+    /// Lists the processes of a conversation, newest first
+    pub fn list(&self, owner: &str) -> Vec<ProcessInfo> {
+        let mut processes: Vec<ProcessInfo> = self
+            .lock()
+            .values()
+            .filter(|entry| entry.info.owner == owner)
+            .map(|entry| entry.info.clone())
+            .collect();
+        processes.sort_by_key(|info| std::cmp::Reverse(info.started_at));
+        processes
+    }
+
+    /// Returns a process of a conversation
+    pub fn status(&self, owner: &str, pid: u32) -> Result<ProcessInfo, ProcessError> {
+        self.lock()
+            .get(&pid)
+            .filter(|entry| entry.info.owner == owner)
+            .map(|entry| entry.info.clone())
+            .ok_or(ProcessError::NotFound(pid))
+    }
+
+    /// Returns the last `lines` lines of a process's output
+    pub async fn tail_output(
+        &self,
+        owner: &str,
+        pid: u32,
+        lines: usize,
+    ) -> Result<(ProcessInfo, String), ProcessError> {
+        let info = self.status(owner, pid)?;
+        let output = tail_lines(&info.log_path, lines).await?;
+        Ok((info, output))
+    }
+
+    /// Terminates a running process and the processes it started
+    ///
+    /// Sends SIGTERM to the process group, then SIGKILL if it is still running
+    /// after a short grace period.
+    pub async fn kill(&self, owner: &str, pid: u32) -> Result<ProcessInfo, ProcessError> {
+        let mut done = {
+            let processes = self.lock();
+            let entry = processes
+                .get(&pid)
+                .filter(|entry| entry.info.owner == owner)
+                .ok_or(ProcessError::NotFound(pid))?;
+            if !entry.info.is_running() {
+                return Err(ProcessError::NotRunning(pid));
+            }
+            entry.done.clone()
+        };
+
+        signal_group(pid, Signal::Terminate)?;
+        let exited = tokio::time::timeout(KILL_GRACE, done.wait_for(|done| *done))
+            .await
+            .is_ok();
+        if !exited {
+            signal_group(pid, Signal::Kill)?;
+            let _ = tokio::time::timeout(KILL_GRACE, done.wait_for(|done| *done)).await;
+        }
+        self.status(owner, pid)
+    }
+
+    fn insert(&self, entry: ProcessEntry) {
+        let mut processes = self.lock();
+        processes.insert(entry.info.pid, entry);
+
+        // Evict the oldest finished processes beyond the retention limit
+        let mut finished: Vec<(DateTime<Utc>, u32)> = processes
+            .values()
+            .filter(|entry| !entry.info.is_running())
+            .map(|entry| (entry.info.started_at, entry.info.pid))
+            .collect();
+        if finished.len() > MAX_FINISHED_PROCESSES {
+            finished.sort();
+            for (_, pid) in &finished[..finished.len() - MAX_FINISHED_PROCESSES] {
+                if let Some(entry) = processes.remove(pid) {
+                    let _ = std::fs::remove_file(&entry.info.log_path);
+                    let _ = std::fs::remove_file(rotated_path(&entry.info.log_path));
+                }
+            }
+        }
+    }
+
+    fn finish(&self, pid: u32, state: ProcessState) -> Option<ProcessInfo> {
+        let mut processes = self.lock();
+        let entry = processes.get_mut(&pid)?;
+        entry.info.state = state;
+        entry.info.finished_at = Some(Utc::now());
+        Some(entry.info.clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u32, ProcessEntry>> {
+        // A poisoned lock only means another caller panicked; the map is still valid
+        self.processes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A log file rotated once it reaches its size limit
+struct LogFile {
+    path: PathBuf,
+    file: Option<tokio::fs::File>,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl LogFile {
+    async fn create(path: PathBuf, max_bytes: u64) -> Self {
+        let file = match tokio::fs::File::create(&path).await {
+            Ok(file) => Some(file),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to create process log");
+                None
+            }
+        };
+        Self {
+            path,
+            file,
+            written: 0,
+            max_bytes,
+        }
+    }
+
+    async fn write(&mut self, chunk: &[u8]) {
+        if self.written > 0 && self.written + chunk.len() as u64 > self.max_bytes {
+            self.rotate().await;
+        }
+        if let Some(file) = &mut self.file {
+            if file.write_all(chunk).await.is_ok() {
+                self.written += chunk.len() as u64;
+            }
+        }
+    }
+
+    async fn rotate(&mut self) {
+        self.flush().await;
+        self.file = None;
+        let _ = tokio::fs::rename(&self.path, rotated_path(&self.path)).await;
+        self.file = tokio::fs::File::create(&self.path).await.ok();
+        self.written = 0;
+    }
+
+    async fn flush(&mut self) {
+        if let Some(file) = &mut self.file {
+            let _ = file.flush().await;
+        }
+    }
+}
+
+/// Copies a process stream into its log until the stream closes
+fn copy_to_log<R>(
+    mut reader: R,
+    log: Arc<tokio::sync::Mutex<LogFile>>,
+) -> tokio::task::JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = [0u8; 8192];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => log.lock().await.write(&buf[..n]).await,
+            }
+        }
+    })
+}
+
+/// Path of the rotated copy of a log
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Reads the last `lines` lines of a log, including its rotated copy if needed
+async fn tail_lines(path: &Path, lines: usize) -> std::io::Result<String> {
+    let current = tokio::fs::read(path).await?;
+    let mut text = String::from_utf8_lossy(&current).into_owned();
+    if text.lines().count() < lines {
+        if let Ok(previous) = tokio::fs::read(rotated_path(path)).await {
+            text = String::from_utf8_lossy(&previous).into_owned() + &text;
+        }
+    }
+
+    let all: Vec<&str> = text.lines().collect();
+    Ok(all[all.len().saturating_sub(lines)..].join("\n"))
+}
+
+/// Text of the completion notification
+fn completion_message(info: &ProcessInfo, tail: &str) -> String {
+    let outcome = match &info.state {
+        ProcessState::Exited {
+            exit_code: Some(code),
+            ..
+        } => format!("exited with code {}", code),
+        ProcessState::Exited {
+            signal: Some(signal),
+            ..
+        } => format!("was terminated by signal {}", signal),
+        ProcessState::Exited { .. } => "exited".to_string(),
+        ProcessState::Failed { error } => format!("failed: {}", error),
+        ProcessState::Running => "is still running".to_string(),
+    };
+    let elapsed = info
+        .finished_at
+        .map(|finished| (finished - info.started_at).num_seconds())
+        .unwrap_or(0);
+
+    let mut message = format!(
+        "Background process {} ({}) {} after {}s.",
+        info.pid, info.command, outcome, elapsed
+    );
+    if !tail.is_empty() {
+        message.push_str(&format!("\nLast output:\n```\n{}\n```", tail));
+    }
+    message
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &std::process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &std::process::ExitStatus) -> Option<i32> {
+    None
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+/// Signals the process group led by `pid` (spawned processes lead their group)
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: Signal) -> Result<(), ProcessError> {
+    let signal_number = match signal {
+        Signal::Terminate => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    let pgid = i32::try_from(pid).map_err(|_| ProcessError::NotFound(pid))?;
+    // SAFETY: kill has no memory-safety requirements
+    if unsafe { libc::kill(-pgid, signal_number) } != 0 {
+        let error = std::io::Error::last_os_error();
+        // Already gone: nothing left to signal
+        if error.raw_os_error() != Some(libc::ESRCH) {
+            return Err(ProcessError::Signal { pid, source: error });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn signal_group(pid: u32, _signal: Signal) -> Result<(), ProcessError> {
+    Err(ProcessError::Signal {
+        pid,
+        source: std::io::Error::from(std::io::ErrorKind::Unsupported),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use tokio::process::Command;
+
+    async fn start(
+        registry: &Arc<ProcessRegistry>,
+        script: &str,
+        notify: Option<ExitNotification>,
+    ) -> u32 {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let child = cmd.spawn().unwrap();
+        let pid = child.id().unwrap();
+        let info = ProcessInfo {
+            pid,
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            started_at: Utc::now(),
+            finished_at: None,
+            state: ProcessState::Running,
+            log_path: registry.create_log(pid).await.unwrap(),
+            owner: "telegram:1".to_string(),
+        };
+        registry.track(child, info, notify, false);
+        pid
+    }
+
+    async fn wait_done(registry: &ProcessRegistry, pid: u32) {
+        let mut done = registry.lock().get(&pid).unwrap().done.clone();
+        tokio::time::timeout(Duration::from_secs(10), done.wait_for(|d| *d))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_output_and_exit_code_are_recorded() {
+        let dir = TempDir::new().unwrap();
+        let registry = Arc::new(ProcessRegistry::new(dir.path().join("processes")));
+
+        let pid = start(&registry, "echo one; echo two >&2; exit 3", None).await;
+        wait_done(&registry, pid).await;
+
+        let info = registry.status("telegram:1", pid).unwrap();
+        assert_eq!(
+            info.state,
+            ProcessState::Exited {
+                exit_code: Some(3),
+                signal: None
+            }
+        );
+        let (_, output) = registry.tail_output("telegram:1", pid, 10).await.unwrap();
+        assert!(output.contains("one") && output.contains("two"));
+
+        // Other conversations cannot see the process
+        assert!(matches!(
+            registry.status("telegram:2", pid),
+            Err(ProcessError::NotFound(_))
+        ));
+        assert!(registry.list("telegram:2").is_empty());
+        assert_eq!(registry.list("telegram:1").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_log_is_rotated() {
+        let dir = TempDir::new().unwrap();
+        let registry =
+            Arc::new(ProcessRegistry::new(dir.path().join("processes")).with_max_log_bytes(100));
+
+        let pid = start(
+            &registry,
+            "i=0; while [ $i -lt 50 ]; do echo line-$i; i=$((i+1)); sleep 0.001; done",
+            None,
+        )
+        .await;
+        wait_done(&registry, pid).await;
+
+        let info = registry.status("telegram:1", pid).unwrap();
+        assert!(std::fs::metadata(&info.log_path).unwrap().len() <= 100);
+        assert!(rotated_path(&info.log_path).exists());
+        let (_, output) = registry.tail_output("telegram:1", pid, 3).await.unwrap();
+        assert_eq!(output, "line-47\nline-48\nline-49");
+    }
+
+    #[tokio::test]
+    async fn test_kill_terminates_process_group() {
+        let dir = TempDir::new().unwrap();
+        let registry = Arc::new(ProcessRegistry::new(dir.path().join("processes")));
+
+        let pid = start(&registry, "sleep 30 & wait", None).await;
+        let info = registry.kill("telegram:1", pid).await.unwrap();
+        assert!(!info.is_running());
+        assert!(matches!(
+            registry.kill("telegram:1", pid).await,
+            Err(ProcessError::NotRunning(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_completion_notification() {
+        let dir = TempDir::new().unwrap();
+        let hub = Arc::new(ChatHub::new());
+        let registry = Arc::new(
+            ProcessRegistry::new(dir.path().join("processes")).with_chat_hub(Arc::clone(&hub)),
+        );
+
+        let notify = ExitNotification {
+            channel: "telegram".to_string(),
+            chat_id: "1".to_string(),
+        };
+        start(&registry, "echo finished", Some(notify)).await;
+
+        let message = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Some(message) = hub.test_try_recv_outbound().await {
+                    return message;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(message.chat_id, "1");
+        assert!(message.content.contains("exited with code 0"));
+        assert!(message.content.contains("finished"));
+    }
+}
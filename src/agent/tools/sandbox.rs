@@ -0,0 +1,878 @@
+//! Resource-limited execution sandbox for exec, spawn and cron commands
+//!
+//! Commands started by the agent are confined without requiring root:
+//!
+//! - **Resource limits**: CPU time, address space, open files and process count
+//!   are capped with `setrlimit` (limits are only ever lowered)
+//! - **Private temp directory**: each command gets its own `0700` directory,
+//!   exported as `TMPDIR`, `TMP` and `TEMP` and removed once the command is done
+//! - **Scrubbed environment**: only allowlisted variables are inherited, and
+//!   wildcard entries never let secrets such as `OPENAI_API_KEY` through
+//! - **Network isolation** (Linux, optional): the command runs in a new user and
+//!   network namespace with no interface but a down loopback
+//! - **Seccomp** (Linux x86_64/aarch64): a BPF filter rejects the configured
+//!   system calls (`ptrace`, `mount`, `bpf`, ...)
+//!
+//! The sandbox is configured in the `tools.sandbox` section of the config:
+//!
+//! ```json
+//! "tools": {
+//!   "sandbox": {
+//!     "cpu_time_secs": 300,
+//!     "memory_mb": 2048,
+//!     "network": false,
+//!     "env_passthrough": ["PATH", "HOME", "LANG", "LC_*"],
+//!     "seccomp": { "action": "errno", "deny": ["ptrace", "mount"] }
+//!   }
+//! }
+//! ```
+//!
+//! Setting a limit to `null` removes it. When a confinement step fails (for
+//! example user namespaces are disabled), the command fails to start rather
+//! than running unconfined.
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+/// Environment variable name fragments that mark a secret
+const SECRET_MARKERS: &[&str] = &[
+    "KEY",
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "AUTH",
+];
+
+/// Counter making private temp directory names unique within the process
+static TEMP_DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// What the kernel does when a denied system call is made
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeccompAction {
+    /// Fail the call with `EPERM`
+    #[default]
+    Errno,
+    /// Kill the process
+    Kill,
+    /// Allow the call but log it to the kernel audit log
+    Log,
+}
+
+/// Seccomp settings of the sandbox
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeccompConfig {
+    /// Whether the filter is installed (ignored on unsupported platforms)
+    pub enabled: bool,
+    /// Action taken on denied system calls
+    pub action: SeccompAction,
+    /// Names of the denied system calls
+    pub deny: Vec<String>,
+}
+
+impl Default for SeccompConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: SeccompAction::Errno,
+            deny: [
+                "ptrace",
+                "process_vm_readv",
+                "process_vm_writev",
+                "mount",
+                "umount2",
+                "pivot_root",
+                "chroot",
+                "swapon",
+                "swapoff",
+                "reboot",
+                "kexec_load",
+                "init_module",
+                "finit_module",
+                "delete_module",
+                "bpf",
+                "perf_event_open",
+                "setns",
+                "keyctl",
+                "add_key",
+                "request_key",
+                "acct",
+                "settimeofday",
+                "clock_settime",
+                "adjtimex",
+                "sethostname",
+                "setdomainname",
+                "quotactl",
+                "userfaultfd",
+                "open_by_handle_at",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+}
+
+/// Sandbox settings for commands run by the exec, spawn and cron executors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SandboxConfig {
+    /// Whether commands are sandboxed at all
+    pub enabled: bool,
+    /// CPU time limit in seconds
+    pub cpu_time_secs: Option<u64>,
+    /// Address space limit in megabytes
+    pub memory_mb: Option<u64>,
+    /// Maximum number of open file descriptors
+    pub max_open_files: Option<u64>,
+    /// Maximum number of processes of the user (counts all processes of the
+    /// gateway user, not only the command's)
+    pub max_processes: Option<u64>,
+    /// Whether each command gets a private temp directory
+    pub private_tmp: bool,
+    /// Whether commands may use the network; `false` runs them in an empty
+    /// network namespace
+    pub network: bool,
+    /// Environment variables inherited by commands; a trailing `*` matches a
+    /// prefix but never lets secret-looking variables through
+    pub env_passthrough: Vec<String>,
+    /// System call filter
+    pub seccomp: SeccompConfig,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cpu_time_secs: Some(300),
+            memory_mb: Some(2048),
+            max_open_files: Some(256),
+            max_processes: Some(256),
+            private_tmp: true,
+            network: true,
+            env_passthrough: [
+                "PATH", "HOME", "USER", "LOGNAME", "SHELL", "LANG", "LANGUAGE", "LC_*", "TERM",
+                "TZ",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            seccomp: SeccompConfig::default(),
+        }
+    }
+}
+
+/// Errors raised while building a sandbox from its config
+#[derive(Debug, thiserror::Error)]
+pub enum SandboxError {
+    #[error("Unknown system call '{0}' in seccomp deny list")]
+    UnknownSyscall(String),
+    #[error("Invalid sandbox config: {0}")]
+    Invalid(String),
+}
+
+/// A resource limited with `setrlimit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResourceLimit {
+    CpuTime,
+    AddressSpace,
+    OpenFiles,
+    Processes,
+}
+
+/// A BPF instruction, laid out like the kernel's `struct sock_filter`
+#[cfg_attr(
+    not(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )),
+    allow(dead_code)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+struct BpfInstruction {
+    code: u16,
+    jt: u8,
+    jf: u8,
+    k: u32,
+}
+
+/// Confinement applied to commands before they start
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    enabled: bool,
+    limits: Vec<(ResourceLimit, u64)>,
+    private_tmp: bool,
+    isolate_network: bool,
+    env_passthrough: Vec<String>,
+    seccomp_filter: Option<Vec<BpfInstruction>>,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::from_config(&SandboxConfig::default()).expect("default sandbox config is valid")
+    }
+}
+
+impl Sandbox {
+    /// Creates a sandbox that leaves commands untouched
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            limits: Vec::new(),
+            private_tmp: false,
+            isolate_network: false,
+            env_passthrough: Vec::new(),
+            seccomp_filter: None,
+        }
+    }
+
+    /// Builds a sandbox from its config
+    ///
+    /// Fails on zero limits and on system call names unknown to this platform.
+    pub fn from_config(config: &SandboxConfig) -> Result<Self, SandboxError> {
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+
+        let mut limits = Vec::new();
+        for (resource, value, name) in [
+            (
+                ResourceLimit::CpuTime,
+                config.cpu_time_secs,
+                "cpu_time_secs",
+            ),
+            (ResourceLimit::AddressSpace, config.memory_mb, "memory_mb"),
+            (
+                ResourceLimit::OpenFiles,
+                config.max_open_files,
+                "max_open_files",
+            ),
+            (
+                ResourceLimit::Processes,
+                config.max_processes,
+                "max_processes",
+            ),
+        ] {
+            match value {
+                Some(0) => {
+                    return Err(SandboxError::Invalid(format!(
+                        "{} must be greater than 0 (use null for no limit)",
+                        name
+                    )));
+                }
+                Some(value) if resource == ResourceLimit::AddressSpace => {
+                    limits.push((resource, value.saturating_mul(1024 * 1024)));
+                }
+                Some(value) => limits.push((resource, value)),
+                None => {}
+            }
+        }
+
+        if !config.network && !cfg!(target_os = "linux") {
+            return Err(SandboxError::Invalid(
+                "network isolation is only supported on Linux".to_string(),
+            ));
+        }
+
+        let seccomp_filter = if config.seccomp.enabled && !config.seccomp.deny.is_empty() {
+            seccomp::build_filter(&config.seccomp)?
+        } else {
+            None
+        };
+
+        Ok(Self {
+            enabled: true,
+            limits,
+            private_tmp: config.private_tmp,
+            isolate_network: !config.network,
+            env_passthrough: config.env_passthrough.clone(),
+            seccomp_filter,
+        })
+    }
+
+    /// Returns whether commands are confined at all
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Configures a command to run inside the sandbox
+    ///
+    /// The returned guard owns the private temp directory and removes it when
+    /// dropped, so it must be kept alive until the command has exited.
+    pub fn prepare(&self, cmd: &mut Command) -> io::Result<SandboxGuard> {
+        let mut guard = SandboxGuard::default();
+        if !self.enabled {
+            return Ok(guard);
+        }
+
+        cmd.env_clear();
+        cmd.envs(scrub_env(std::env::vars_os(), &self.env_passthrough));
+
+        if self.private_tmp {
+            let dir = create_private_temp_dir()?;
+            for var in ["TMPDIR", "TMP", "TEMP"] {
+                cmd.env(var, &dir);
+            }
+            guard.temp_dir = Some(dir);
+        }
+
+        #[cfg(unix)]
+        self.install_pre_exec(cmd);
+
+        Ok(guard)
+    }
+
+    /// Registers the limits, namespaces and seccomp filter applied in the child
+    #[cfg(unix)]
+    fn install_pre_exec(&self, cmd: &mut Command) {
+        let limits = self.limits.clone();
+        let network = self.isolate_network.then(namespace::IdMaps::current);
+        let filter = self.seccomp_filter.clone();
+
+        // SAFETY: the closure runs between fork and exec; it only performs raw
+        // system calls on data prepared before the fork and does not allocate.
+        unsafe {
+            cmd.pre_exec(move || {
+                for (resource, value) in &limits {
+                    rlimit::lower(*resource, *value)?;
+                }
+                if let Some(maps) = &network {
+                    namespace::isolate_network(maps)?;
+                }
+                if let Some(filter) = &filter {
+                    seccomp::install(filter)?;
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+/// Keeps the per-command sandbox resources alive
+#[derive(Debug, Default)]
+#[must_use = "the private temp directory is removed when the guard is dropped"]
+pub struct SandboxGuard {
+    temp_dir: Option<PathBuf>,
+}
+
+impl SandboxGuard {
+    /// Returns the private temp directory of the command, if any
+    pub fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+}
+
+impl Drop for SandboxGuard {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.temp_dir {
+            if let Err(e) = std::fs::remove_dir_all(dir) {
+                tracing::debug!(path = %dir.display(), error = %e, "Failed to remove sandbox temp dir");
+            }
+        }
+    }
+}
+
+/// Filters environment variables through the passthrough list
+///
+/// Exact entries are always honoured; prefix entries (`LC_*`) skip variables
+/// whose name looks like a secret.
+fn scrub_env<I>(vars: I, passthrough: &[String]) -> Vec<(OsString, OsString)>
+where
+    I: IntoIterator<Item = (OsString, OsString)>,
+{
+    vars.into_iter()
+        .filter(|(name, _)| {
+            let Some(name) = name.to_str() else {
+                return false;
+            };
+            passthrough
+                .iter()
+                .any(|entry| match entry.strip_suffix('*') {
+                    Some(prefix) => name.starts_with(prefix) && !looks_secret(name),
+                    None => entry == name,
+                })
+        })
+        .collect()
+}
+
+/// Returns true if an environment variable name looks like it holds a secret
+fn looks_secret(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+/// Creates a fresh `0700` directory under the system temp directory
+fn create_private_temp_dir() -> io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!(
+        "miniclaw-sandbox-{}-{}",
+        std::process::id(),
+        TEMP_DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir(&dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(dir)
+}
+
+#[cfg(unix)]
+mod rlimit {
+    use std::io;
+
+    use super::ResourceLimit;
+
+    /// Lowers a resource limit, never raising it above the current hard limit
+    pub(super) fn lower(resource: ResourceLimit, value: u64) -> io::Result<()> {
+        let resource = match resource {
+            ResourceLimit::CpuTime => libc::RLIMIT_CPU,
+            ResourceLimit::AddressSpace => libc::RLIMIT_AS,
+            ResourceLimit::OpenFiles => libc::RLIMIT_NOFILE,
+            ResourceLimit::Processes => libc::RLIMIT_NPROC,
+        };
+
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: `current` is a valid, writable rlimit
+        if unsafe { libc::getrlimit(resource, &mut current) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let value = value as libc::rlim_t;
+        let hard = current.rlim_max.min(value);
+        let limit = libc::rlimit {
+            rlim_cur: current.rlim_cur.min(hard),
+            rlim_max: hard,
+        };
+        // SAFETY: `limit` is a valid rlimit
+        if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+mod namespace {
+    use std::io;
+
+    /// uid/gid map contents, formatted before the fork
+    #[derive(Debug, Clone)]
+    pub(super) struct IdMaps {
+        #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+        uid_map: String,
+        #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+        gid_map: String,
+    }
+
+    impl IdMaps {
+        /// Maps the current user and group onto themselves
+        pub(super) fn current() -> Self {
+            // SAFETY: getuid and getgid cannot fail
+            let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+            Self {
+                uid_map: format!("{} {} 1", uid, uid),
+                gid_map: format!("{} {} 1", gid, gid),
+            }
+        }
+    }
+
+    /// Moves the calling process into new user and network namespaces
+    #[cfg(target_os = "linux")]
+    pub(super) fn isolate_network(maps: &IdMaps) -> io::Result<()> {
+        // SAFETY: unshare has no memory-safety requirements
+        if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        write_file(c"/proc/self/setgroups", b"deny")?;
+        write_file(c"/proc/self/uid_map", maps.uid_map.as_bytes())?;
+        write_file(c"/proc/self/gid_map", maps.gid_map.as_bytes())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(super) fn isolate_network(_maps: &IdMaps) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    /// Writes a buffer to a file with raw system calls (no allocation)
+    #[cfg(target_os = "linux")]
+    fn write_file(path: &std::ffi::CStr, data: &[u8]) -> io::Result<()> {
+        // SAFETY: `path` is NUL-terminated and `data` is a valid buffer
+        unsafe {
+            let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let written = libc::write(fd, data.as_ptr().cast(), data.len());
+            let result = if written < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            };
+            libc::close(fd);
+            result
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod seccomp {
+    use std::io;
+
+    use super::{BpfInstruction, SandboxError, SeccompAction, SeccompConfig};
+
+    /// `BPF_LD | BPF_W | BPF_ABS`
+    const BPF_LD_W_ABS: u16 = 0x20;
+    /// `BPF_JMP | BPF_JEQ | BPF_K`
+    const BPF_JMP_JEQ_K: u16 = 0x15;
+    /// `BPF_JMP | BPF_JGE | BPF_K`
+    const BPF_JMP_JGE_K: u16 = 0x35;
+    /// `BPF_RET | BPF_K`
+    const BPF_RET_K: u16 = 0x06;
+
+    /// Offsets in `struct seccomp_data`
+    const SECCOMP_DATA_NR: u32 = 0;
+    const SECCOMP_DATA_ARCH: u32 = 4;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xC000_003E;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xC000_00B7;
+
+    /// Builds the filter program for the configured deny list
+    pub(super) fn build_filter(
+        config: &SeccompConfig,
+    ) -> Result<Option<Vec<BpfInstruction>>, SandboxError> {
+        let numbers = config
+            .deny
+            .iter()
+            .map(|name| {
+                syscall_number(name).ok_or_else(|| SandboxError::UnknownSyscall(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let action = match config.action {
+            SeccompAction::Errno => libc::SECCOMP_RET_ERRNO | libc::EPERM as u32,
+            SeccompAction::Kill => libc::SECCOMP_RET_KILL_PROCESS,
+            SeccompAction::Log => libc::SECCOMP_RET_LOG,
+        };
+
+        let stmt = |code, k| BpfInstruction {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        };
+        let jump = |code, k, jt, jf| BpfInstruction { code, jt, jf, k };
+
+        let mut program = vec![
+            // Kill anything using another ABI: syscall numbers would not match
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH),
+            jump(BPF_JMP_JEQ_K, AUDIT_ARCH, 1, 0),
+            stmt(BPF_RET_K, libc::SECCOMP_RET_KILL_PROCESS),
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR),
+        ];
+        if cfg!(target_arch = "x86_64") {
+            // x32 system calls share the architecture but set this bit
+            program.push(jump(BPF_JMP_JGE_K, 0x4000_0000, 0, 1));
+            program.push(stmt(BPF_RET_K, action));
+        }
+        for number in numbers {
+            program.push(jump(BPF_JMP_JEQ_K, number, 0, 1));
+            program.push(stmt(BPF_RET_K, action));
+        }
+        program.push(stmt(BPF_RET_K, libc::SECCOMP_RET_ALLOW));
+
+        if program.len() > u16::MAX as usize {
+            return Err(SandboxError::Invalid(
+                "seccomp deny list is too long".to_string(),
+            ));
+        }
+        Ok(Some(program))
+    }
+
+    /// Installs the filter on the calling process
+    pub(super) fn install(filter: &[BpfInstruction]) -> io::Result<()> {
+        let program = libc::sock_fprog {
+            len: filter.len() as u16,
+            filter: filter.as_ptr() as *mut libc::sock_filter,
+        };
+        // SAFETY: `BpfInstruction` has the layout of `sock_filter` and `program`
+        // points to `filter`, which outlives both calls
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                &program as *const libc::sock_fprog,
+            ) != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves a system call name for the current architecture
+    fn syscall_number(name: &str) -> Option<u32> {
+        let number = match name {
+            "accept" => libc::SYS_accept,
+            "accept4" => libc::SYS_accept4,
+            "acct" => libc::SYS_acct,
+            "add_key" => libc::SYS_add_key,
+            "adjtimex" => libc::SYS_adjtimex,
+            "bind" => libc::SYS_bind,
+            "bpf" => libc::SYS_bpf,
+            "chroot" => libc::SYS_chroot,
+            "clock_adjtime" => libc::SYS_clock_adjtime,
+            "clock_settime" => libc::SYS_clock_settime,
+            "connect" => libc::SYS_connect,
+            "delete_module" => libc::SYS_delete_module,
+            "execve" => libc::SYS_execve,
+            "execveat" => libc::SYS_execveat,
+            "fanotify_init" => libc::SYS_fanotify_init,
+            "finit_module" => libc::SYS_finit_module,
+            "init_module" => libc::SYS_init_module,
+            "ioctl" => libc::SYS_ioctl,
+            "kcmp" => libc::SYS_kcmp,
+            "kexec_load" => libc::SYS_kexec_load,
+            "keyctl" => libc::SYS_keyctl,
+            "kill" => libc::SYS_kill,
+            "listen" => libc::SYS_listen,
+            "lookup_dcookie" => libc::SYS_lookup_dcookie,
+            "mkdirat" => libc::SYS_mkdirat,
+            "mknodat" => libc::SYS_mknodat,
+            "mount" => libc::SYS_mount,
+            "name_to_handle_at" => libc::SYS_name_to_handle_at,
+            "open_by_handle_at" => libc::SYS_open_by_handle_at,
+            "perf_event_open" => libc::SYS_perf_event_open,
+            "personality" => libc::SYS_personality,
+            "pivot_root" => libc::SYS_pivot_root,
+            "process_vm_readv" => libc::SYS_process_vm_readv,
+            "process_vm_writev" => libc::SYS_process_vm_writev,
+            "ptrace" => libc::SYS_ptrace,
+            "quotactl" => libc::SYS_quotactl,
+            "reboot" => libc::SYS_reboot,
+            "request_key" => libc::SYS_request_key,
+            "setdomainname" => libc::SYS_setdomainname,
+            "setgid" => libc::SYS_setgid,
+            "sethostname" => libc::SYS_sethostname,
+            "setns" => libc::SYS_setns,
+            "setresgid" => libc::SYS_setresgid,
+            "setresuid" => libc::SYS_setresuid,
+            "settimeofday" => libc::SYS_settimeofday,
+            "setuid" => libc::SYS_setuid,
+            "socket" => libc::SYS_socket,
+            "swapoff" => libc::SYS_swapoff,
+            "swapon" => libc::SYS_swapon,
+            "umount2" => libc::SYS_umount2,
+            "unshare" => libc::SYS_unshare,
+            "userfaultfd" => libc::SYS_userfaultfd,
+            "vhangup" => libc::SYS_vhangup,
+            #[cfg(target_arch = "x86_64")]
+            "ioperm" => libc::SYS_ioperm,
+            #[cfg(target_arch = "x86_64")]
+            "iopl" => libc::SYS_iopl,
+            #[cfg(target_arch = "x86_64")]
+            "mkdir" => libc::SYS_mkdir,
+            #[cfg(target_arch = "x86_64")]
+            "mknod" => libc::SYS_mknod,
+            _ => return None,
+        };
+        u32::try_from(number).ok()
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod seccomp {
+    use std::io;
+
+    use super::{BpfInstruction, SandboxError, SeccompConfig};
+
+    /// Seccomp is unavailable here: the filter is skipped with a warning
+    pub(super) fn build_filter(
+        _config: &SeccompConfig,
+    ) -> Result<Option<Vec<BpfInstruction>>, SandboxError> {
+        tracing::warn!("Seccomp is not supported on this platform, skipping the syscall filter");
+        Ok(None)
+    }
+
+    pub(super) fn install(_filter: &[BpfInstruction]) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_config() -> SandboxConfig {
+        SandboxConfig {
+            max_open_files: Some(64),
+            ..SandboxConfig::default()
+        }
+    }
+
+    async fn run_sh(sandbox: &Sandbox, script: &str) -> io::Result<(String, SandboxGuard)> {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]);
+        let guard = sandbox.prepare(&mut cmd)?;
+        let output = cmd.output().await?;
+        let text = String::from_utf8_lossy(&output.stdout).to_string()
+            + &String::from_utf8_lossy(&output.stderr);
+        Ok((text, guard))
+    }
+
+    #[test]
+    fn test_scrub_env() {
+        let vars = [
+            ("PATH", "/usr/bin"),
+            ("LC_ALL", "C"),
+            ("LC_API_KEY", "x"),
+            ("OPENAI_API_KEY", "sk-secret"),
+            ("GITHUB_TOKEN", "ghp"),
+            ("EDITOR", "vi"),
+        ]
+        .map(|(k, v)| (OsString::from(k), OsString::from(v)));
+        let passthrough = vec![
+            "PATH".to_string(),
+            "LC_*".to_string(),
+            "GITHUB_TOKEN".to_string(),
+        ];
+
+        let names: Vec<_> = scrub_env(vars, &passthrough)
+            .into_iter()
+            .map(|(name, _)| name.into_string().unwrap())
+            .collect();
+        assert_eq!(names, vec!["PATH", "LC_ALL", "GITHUB_TOKEN"]);
+    }
+
+    #[test]
+    fn test_from_config_validation() {
+        let config = SandboxConfig {
+            memory_mb: Some(0),
+            ..SandboxConfig::default()
+        };
+        assert!(matches!(
+            Sandbox::from_config(&config),
+            Err(SandboxError::Invalid(_))
+        ));
+
+        let config = SandboxConfig {
+            enabled: false,
+            memory_mb: Some(0),
+            ..SandboxConfig::default()
+        };
+        assert!(!Sandbox::from_config(&config).unwrap().is_enabled());
+
+        if cfg!(all(
+            target_os = "linux",
+            any(target_arch = "x86_64", target_arch = "aarch64")
+        )) {
+            let mut config = SandboxConfig::default();
+            config.seccomp.deny.push("not_a_syscall".to_string());
+            assert!(matches!(
+                Sandbox::from_config(&config),
+                Err(SandboxError::UnknownSyscall(name)) if name == "not_a_syscall"
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limits_and_private_tmp() {
+        let sandbox = Sandbox::from_config(&run_config()).unwrap();
+
+        let (output, guard) = run_sh(
+            &sandbox,
+            "ulimit -n; echo \"$TMPDIR\"; touch \"$TMPDIR/scratch\" && echo created",
+        )
+        .await
+        .unwrap();
+        let temp_dir = guard.temp_dir().unwrap().to_path_buf();
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "64");
+        assert_eq!(lines[1], temp_dir.to_str().unwrap());
+        assert_eq!(lines[2], "created");
+        assert!(temp_dir.join("scratch").exists());
+
+        drop(guard);
+        assert!(!temp_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_sandbox_inherits_everything() {
+        let (output, guard) = run_sh(&Sandbox::disabled(), "echo \"${TMPDIR:-unset}\"")
+            .await
+            .unwrap();
+        assert!(guard.temp_dir().is_none());
+        assert_eq!(
+            output.trim(),
+            std::env::var("TMPDIR").unwrap_or_else(|_| "unset".to_string())
+        );
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[tokio::test]
+    async fn test_seccomp_denies_syscalls() {
+        let mut config = run_config();
+        config.seccomp.deny = if cfg!(target_arch = "x86_64") {
+            vec!["mkdir".to_string(), "mkdirat".to_string()]
+        } else {
+            vec!["mkdirat".to_string()]
+        };
+        let sandbox = Sandbox::from_config(&config).unwrap();
+
+        let (output, _guard) = run_sh(&sandbox, "mkdir \"$TMPDIR/denied\" || echo refused")
+            .await
+            .unwrap();
+        assert!(output.contains("refused"), "output: {}", output);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_network_isolation() {
+        let config = SandboxConfig {
+            network: false,
+            ..run_config()
+        };
+        let sandbox = Sandbox::from_config(&config).unwrap();
+
+        match run_sh(&sandbox, "cat /proc/self/net/dev").await {
+            Ok((output, _guard)) => {
+                let interfaces: Vec<&str> = output
+                    .lines()
+                    .skip(2)
+                    .filter_map(|line| line.split(':').next())
+                    .map(str::trim)
+                    .collect();
+                assert_eq!(interfaces, vec!["lo"]);
+            }
+            // User namespaces may be disabled on the host
+            Err(e) => eprintln!("Skipping network isolation test: {}", e),
+        }
+    }
+}
@@ -1,41 +1,48 @@
 //! Security utilities shared between exec and spawn tools
 //!
-//! Provides a shared command blacklist and validation helpers to avoid duplication
-//! between the exec and spawn tool implementations.
+//! Provides command policy checks and working directory validation to avoid
+//! duplication between the exec and spawn tool implementations.
 
 use std::path::{Path, PathBuf};
 
+use crate::agent::tools::policy::{CommandPolicy, PolicyScope};
 use crate::agent::tools::types::{ToolError, ToolResult};
 use crate::utils::paths::{PathValidationError, validate_path};
 
-/// Commands that cannot be executed or spawned for security reasons.
-/// This list is shared between the exec and spawn tools.
-///
-/// # Security
-/// Implements NFR-S4: Commands are validated against this blacklist to prevent
-/// execution of dangerous system commands.
-pub const COMMAND_BLACKLIST: &[&str] = &[
-    "rm", "sudo", "dd", "mkfs", "shutdown", "reboot", "passwd", "visudo",
-];
-
-/// Checks if a command is in the shared blacklist.
-///
-/// Extracts the base command name (last path component) and checks
-/// case-insensitively against [`COMMAND_BLACKLIST`].
+/// Checks a command against the command policy.
 ///
 /// # Arguments
-/// * `command` - The command string to check (may include a path prefix)
+/// * `tool_name` - Name of the calling tool (used in error messages)
+/// * `policy` - The policy to evaluate
+/// * `command` - The command to run (may include a path prefix)
+/// * `args` - The command arguments
+/// * `scope` - The caller the policy is evaluated for
 ///
 /// # Returns
-/// `true` if the command is blacklisted, `false` otherwise
-pub fn is_blacklisted(command: &str) -> bool {
-    let base_cmd = std::path::Path::new(command)
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or(command)
-        .to_lowercase();
+/// * `Ok(())` - If the command may run
+/// * `Err(ToolError::PermissionDenied)` - With the policy's reason otherwise
+pub fn check_command(
+    tool_name: &str,
+    policy: &CommandPolicy,
+    command: &str,
+    args: &[String],
+    scope: &PolicyScope,
+) -> ToolResult<()> {
+    let decision = policy.evaluate(command, args, scope);
+    if decision.allowed {
+        return Ok(());
+    }
 
-    COMMAND_BLACKLIST.contains(&base_cmd.as_str())
+    tracing::warn!(
+        tool = tool_name,
+        command = command,
+        reason = %decision.reason,
+        "Command blocked by policy"
+    );
+    Err(ToolError::PermissionDenied {
+        tool: tool_name.to_string(),
+        message: format!("Command not allowed: {} ({})", command, decision.reason),
+    })
 }
 
 /// Validates a working directory path against a base directory.
@@ -79,41 +86,19 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_is_blacklisted_simple_commands() {
-        assert!(is_blacklisted("rm"));
-        assert!(is_blacklisted("sudo"));
-        assert!(is_blacklisted("dd"));
-        assert!(is_blacklisted("mkfs"));
-        assert!(is_blacklisted("shutdown"));
-        assert!(is_blacklisted("reboot"));
-        assert!(is_blacklisted("passwd"));
-        assert!(is_blacklisted("visudo"));
-    }
+    fn test_check_command_reports_reason() {
+        let policy = CommandPolicy::default();
+        let scope = PolicyScope::default();
 
-    #[test]
-    fn test_is_blacklisted_with_paths() {
-        assert!(is_blacklisted("/bin/rm"));
-        assert!(is_blacklisted("/usr/bin/sudo"));
-        assert!(is_blacklisted("./rm"));
-    }
-
-    #[test]
-    fn test_is_blacklisted_case_insensitive() {
-        assert!(is_blacklisted("RM"));
-        assert!(is_blacklisted("SUDO"));
-        assert!(is_blacklisted("Rm"));
-        assert!(is_blacklisted("SuDo"));
-    }
+        assert!(check_command("exec", &policy, "ls", &[], &scope).is_ok());
 
-    #[test]
-    fn test_is_not_blacklisted() {
-        assert!(!is_blacklisted("ls"));
-        assert!(!is_blacklisted("cat"));
-        assert!(!is_blacklisted("echo"));
-        assert!(!is_blacklisted("git"));
-        assert!(!is_blacklisted("cargo"));
-        assert!(!is_blacklisted("pwd"));
-        assert!(!is_blacklisted("sleep"));
-        assert!(!is_blacklisted("python"));
+        match check_command("exec", &policy, "/bin/rm", &["-rf".to_string()], &scope) {
+            Err(ToolError::PermissionDenied { tool, message }) => {
+                assert_eq!(tool, "exec");
+                assert!(message.contains("Command not allowed: /bin/rm"));
+                assert!(message.contains("denied by the command policy"));
+            }
+            other => panic!("Expected PermissionDenied, got {:?}", other),
+        }
     }
 }
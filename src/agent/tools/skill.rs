@@ -5,13 +5,22 @@
 //! - list_skills: List all available skills
 //! - read_skill: Read the full content of a skill
 //! - delete_skill: Delete a user-created skill
+//! - run_skill: Run the script bundled with a skill
 
+use crate::agent::tools::exec::CommandRunner;
+use crate::agent::tools::policy::{CommandPolicy, PolicyScope};
+use crate::agent::tools::sandbox::Sandbox;
 use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
-use crate::skills::{SkillManagerError, SkillParameter, SkillsManager};
+use crate::skills::{SkillManagerError, SkillParameter, SkillsManager, load_skill};
 use async_trait::async_trait;
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Timeout for a skill script in seconds
+const RUN_SKILL_TIMEOUT_SECS: u64 = 60;
 
 /// Tool for creating new skills
 pub struct CreateSkillTool {
@@ -466,6 +475,211 @@ impl Tool for DeleteSkillTool {
     }
 }
 
+/// Tool for running the script bundled with a skill
+///
+/// The skill's SKILL.md declares the script in a `## Script` section. Arguments
+/// are checked against the skill's parameters and passed to the script as
+/// `--name value` pairs. The script runs under the same command policy and
+/// sandbox as the exec tool, with the skill directory as working directory.
+pub struct RunSkillTool {
+    workspace_path: PathBuf,
+    /// Policy deciding whether the interpreter may run
+    policy: Arc<CommandPolicy>,
+    /// Confinement applied to the script
+    sandbox: Arc<Sandbox>,
+    /// The script is killed when this elapses
+    timeout: Duration,
+}
+
+impl RunSkillTool {
+    /// Create a new RunSkillTool
+    pub fn new(workspace_path: PathBuf) -> Self {
+        let canonical_workspace = std::fs::canonicalize(&workspace_path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to canonicalize workspace path {:?}: {}",
+                workspace_path, e
+            )
+        });
+
+        Self {
+            workspace_path: canonical_workspace,
+            policy: Arc::new(CommandPolicy::default()),
+            sandbox: Arc::new(Sandbox::default()),
+            timeout: Duration::from_secs(RUN_SKILL_TIMEOUT_SECS),
+        }
+    }
+
+    /// Replaces the default command policy
+    pub fn with_policy(mut self, policy: Arc<CommandPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replaces the default sandbox
+    pub fn with_sandbox(mut self, sandbox: Arc<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    fn invalid(&self, message: String) -> ToolError {
+        ToolError::InvalidArguments {
+            tool: self.name().to_string(),
+            message,
+        }
+    }
+
+    /// Checks the arguments against the skill's parameters and turns them
+    /// into `--name value` pairs in declaration order
+    fn script_args(
+        &self,
+        parameters: &[SkillParameter],
+        arguments: &Map<String, Value>,
+    ) -> ToolResult<Vec<String>> {
+        if let Some(unknown) = arguments
+            .keys()
+            .find(|key| !parameters.iter().any(|p| &p.name == *key))
+        {
+            return Err(self.invalid(format!("Unknown skill parameter '{}'", unknown)));
+        }
+
+        let mut args = Vec::new();
+        for param in parameters {
+            let value = match arguments.get(&param.name) {
+                Some(Value::Null) | None if param.required => {
+                    return Err(
+                        self.invalid(format!("Missing required skill parameter '{}'", param.name))
+                    );
+                }
+                Some(Value::Null) | None => continue,
+                Some(value) => value,
+            };
+            let text = parameter_value(&param.param_type, value).ok_or_else(|| {
+                self.invalid(format!(
+                    "Skill parameter '{}' must be of type {}",
+                    param.name, param.param_type
+                ))
+            })?;
+            args.push(format!("--{}", param.name));
+            args.push(text);
+        }
+        Ok(args)
+    }
+}
+
+/// Renders an argument as a command-line value if it matches the declared type
+fn parameter_value(param_type: &str, value: &Value) -> Option<String> {
+    match (param_type, value) {
+        ("string", Value::String(s)) => Some(s.clone()),
+        ("number", Value::Number(n)) => Some(n.to_string()),
+        ("integer", Value::Number(n)) if n.is_i64() || n.is_u64() => Some(n.to_string()),
+        ("boolean", Value::Bool(b)) => Some(b.to_string()),
+        ("string" | "number" | "integer" | "boolean", _) => None,
+        // Other declared types are passed through as text or JSON
+        (_, Value::String(s)) => Some(s.clone()),
+        (_, other) => Some(other.to_string()),
+    }
+}
+
+#[async_trait]
+impl Tool for RunSkillTool {
+    fn name(&self) -> &str {
+        "run_skill"
+    }
+
+    fn description(&self) -> &str {
+        "Run the script bundled with a skill. Arguments are validated against the skill's \
+         parameters and passed to the script as --name value pairs; the script runs sandboxed \
+         under the same command policy as exec, and its stdout, stderr and exit code are returned."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "skill": {
+                    "type": "string",
+                    "description": "Directory name of the skill to run"
+                },
+                "arguments": {
+                    "type": "object",
+                    "description": "Values for the skill's parameters, keyed by parameter name"
+                }
+            },
+            "required": ["skill"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, Value>,
+        ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let name = args
+            .get("skill")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.invalid("Missing required parameter 'skill'".to_string()))?;
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(self.invalid(format!("Invalid skill name '{}'", name)));
+        }
+
+        let empty = Map::new();
+        let arguments = match args.get("arguments") {
+            None | Some(Value::Null) => &empty,
+            Some(Value::Object(map)) => map,
+            Some(_) => return Err(self.invalid("'arguments' must be an object".to_string())),
+        };
+
+        let skills_manager = SkillsManager::new(self.workspace_path.clone());
+        let skill_dir = skills_manager.skills_dir().join(name);
+        if !skill_dir.is_dir() {
+            return Err(ToolError::NotFound(format!("Skill '{}' not found", name)));
+        }
+        let skill = load_skill(&skill_dir).map_err(|e| ToolError::ExecutionFailed {
+            tool: self.name().to_string(),
+            message: format!("Failed to load skill '{}': {}", name, e),
+        })?;
+        let script = skill.script.ok_or_else(|| ToolError::ExecutionFailed {
+            tool: self.name().to_string(),
+            message: format!("Skill '{}' does not declare a script", name),
+        })?;
+
+        // The script must be a file inside the skill directory
+        let canonical_dir =
+            std::fs::canonicalize(&skill_dir).map_err(|e| ToolError::ExecutionFailed {
+                tool: self.name().to_string(),
+                message: format!("Failed to resolve skill directory: {}", e),
+            })?;
+        let script_path = std::fs::canonicalize(canonical_dir.join(&script.path))
+            .ok()
+            .filter(|path| path.starts_with(&canonical_dir) && path.is_file())
+            .ok_or_else(|| ToolError::PermissionDenied {
+                tool: self.name().to_string(),
+                message: format!(
+                    "Script '{}' is not a file inside the skill directory",
+                    script.path
+                ),
+            })?;
+
+        let mut command_args = vec![script_path.to_string_lossy().to_string()];
+        command_args.extend(self.script_args(&skill.parameters, arguments)?);
+
+        let runner = CommandRunner {
+            tool: self.name(),
+            policy: &self.policy,
+            sandbox: &self.sandbox,
+            timeout: self.timeout,
+        };
+        runner
+            .run(
+                &script.interpreter,
+                &command_args,
+                Some(&canonical_dir),
+                &PolicyScope::from(ctx),
+            )
+            .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -475,6 +689,7 @@ mod tests {
         ToolExecutionContext {
             chat_id: Some("test".to_string()),
             channel: Some("test".to_string()),
+            user_id: None,
         }
     }
 
@@ -741,4 +956,113 @@ mod tests {
         let response: Value = serde_json::from_str(&result.unwrap()).unwrap();
         assert!(response["success"].as_bool().unwrap());
     }
+
+    /// Writes a skill with a `sh` script that echoes its arguments
+    fn write_script_skill(workspace_path: &std::path::Path) {
+        let skill_dir = workspace_path.join("skills").join("greet");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "# Skill: Greet\n\n## Description\nGreets someone.\n\n## Parameters\n\
+             - `name` (string, required): Who to greet\n\
+             - `times` (integer, optional): How often\n\n## Script\n\
+             - `greet.sh` (shell): Prints the greeting\n",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("greet.sh"), "echo \"args: $*\"\n").unwrap();
+    }
+
+    fn run_args(arguments: Value) -> HashMap<String, Value> {
+        let mut args = HashMap::new();
+        args.insert("skill".to_string(), json!("greet"));
+        args.insert("arguments".to_string(), arguments);
+        args
+    }
+
+    #[tokio::test]
+    async fn test_run_skill_tool_runs_script() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        write_script_skill(&workspace_path);
+
+        let tool = RunSkillTool::new(workspace_path);
+        let result = tool
+            .execute(
+                run_args(json!({"name": "Ada", "times": 2})),
+                &create_test_context(),
+            )
+            .await
+            .unwrap();
+
+        let response: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(response["exit_code"], 0);
+        assert_eq!(
+            response["stdout"].as_str().unwrap().trim(),
+            "args: --name Ada --times 2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_skill_tool_validates_arguments() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        write_script_skill(&workspace_path);
+
+        let tool = RunSkillTool::new(workspace_path);
+        let ctx = create_test_context();
+
+        let missing = tool.execute(run_args(json!({})), &ctx).await.unwrap_err();
+        assert!(missing.to_string().contains("Missing required"));
+
+        let unknown = tool
+            .execute(run_args(json!({"name": "Ada", "loud": true})), &ctx)
+            .await
+            .unwrap_err();
+        assert!(unknown.to_string().contains("Unknown skill parameter"));
+
+        let wrong_type = tool
+            .execute(run_args(json!({"name": "Ada", "times": "two"})), &ctx)
+            .await
+            .unwrap_err();
+        assert!(wrong_type.to_string().contains("must be of type integer"));
+    }
+
+    #[tokio::test]
+    async fn test_run_skill_tool_rejects_script_outside_skill() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        write_script_skill(&workspace_path);
+        let skill_md = workspace_path.join("skills/greet/SKILL.md");
+        let content = std::fs::read_to_string(&skill_md).unwrap();
+        std::fs::write(&skill_md, content.replace("greet.sh", "../../outside.sh")).unwrap();
+        std::fs::write(workspace_path.join("outside.sh"), "echo escaped\n").unwrap();
+
+        let tool = RunSkillTool::new(workspace_path);
+        let result = tool
+            .execute(run_args(json!({"name": "Ada"})), &create_test_context())
+            .await;
+        assert!(matches!(result, Err(ToolError::PermissionDenied { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_run_skill_tool_without_script() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        let skill_dir = workspace_path.join("skills").join("notes");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "# Skill: Notes\n\n## Description\nJust instructions.\n",
+        )
+        .unwrap();
+
+        let tool = RunSkillTool::new(workspace_path);
+        let mut args = HashMap::new();
+        args.insert("skill".to_string(), json!("notes"));
+        let err = tool
+            .execute(args, &create_test_context())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not declare a script"));
+    }
 }
@@ -1,41 +1,71 @@
 //! Spawn tool for the agent
 //!
 //! This tool provides background process spawning with security constraints.
-//! Commands are validated against a blacklist to prevent dangerous operations.
+//! Commands are validated against the shared command policy to prevent
+//! dangerous operations.
 //! Unlike exec tool, spawn returns immediately with a PID and doesn't wait
-//! for process completion (fire-and-forget pattern).
+//! for process completion. Spawned processes are tracked by a
+//! [`ProcessRegistry`]: their output is captured to log files in the workspace
+//! and the agent can list them, check their status, tail their output and kill
+//! them.
 
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use chrono::Utc;
 use serde_json::Value;
 use tokio::process::Command;
 
+use crate::agent::tools::policy::{CommandPolicy, PolicyScope};
+use crate::agent::tools::processes::{
+    ExitNotification, ProcessError, ProcessInfo, ProcessRegistry, ProcessState,
+};
+use crate::agent::tools::sandbox::Sandbox;
 use crate::agent::tools::security;
 use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
+use crate::chat::ChatHub;
 
-/// Tool for spawning background processes
+/// Supported actions
+const ACTIONS: &[&str] = &["spawn", "list", "status", "tail_output", "kill"];
+
+/// Default and maximum number of lines returned by `tail_output`
+const DEFAULT_TAIL_LINES: usize = 50;
+const MAX_TAIL_LINES: usize = 500;
+
+/// Directory of the process logs, relative to the base directory
+const PROCESS_LOG_DIR: &str = "processes";
+
+/// Tool for spawning and managing background processes
 ///
-/// Provides process spawning with security constraints including a blacklist
-/// of dangerous commands and path validation for working directories.
+/// Provides process spawning with security constraints including the command
+/// policy and path validation for working directories.
 ///
 /// # Security
-/// This tool implements NFR-S4: Commands are validated against a blacklist
-/// to prevent execution of dangerous system commands.
+/// This tool implements NFR-S4: Commands are validated against the
+/// [`CommandPolicy`] to prevent execution of dangerous system commands.
 #[derive(Debug)]
 pub struct SpawnTool {
     /// The canonicalized base directory that all cwd operations are restricted to
     base_dir: PathBuf,
-    /// Whether to log stdout/stderr from spawned processes
+    /// Whether to log the output of spawned processes when they finish
     log_output: bool,
+    /// Policy deciding which commands may run
+    policy: Arc<CommandPolicy>,
+    /// Confinement applied to started commands
+    sandbox: Arc<Sandbox>,
+    /// Spawned processes, their logs and exit codes
+    registry: Arc<ProcessRegistry>,
 }
 
 impl SpawnTool {
     /// Creates a new SpawnTool with the specified base directory
     ///
+    /// Process logs are written to `processes/` under the base directory.
+    ///
     /// # Arguments
     /// * `base_dir` - The root directory that all cwd operations are restricted to
-    /// * `log_output` - Whether to log stdout/stderr from spawned processes
+    /// * `log_output` - Whether to log the output of spawned processes when they finish
     ///
     /// # Returns
     /// * `Ok(Self)` - Successfully created SpawnTool
@@ -51,23 +81,37 @@ impl SpawnTool {
                 ),
             })?;
 
+        let registry = Arc::new(ProcessRegistry::new(canonical_base.join(PROCESS_LOG_DIR)));
         Ok(Self {
             base_dir: canonical_base,
             log_output,
+            policy: Arc::new(CommandPolicy::default()),
+            sandbox: Arc::new(Sandbox::default()),
+            registry,
         })
     }
 
-    /// Checks if a command is in the blacklist
-    ///
-    /// Delegates to the shared security module's [`security::is_blacklisted`].
-    ///
-    /// # Arguments
-    /// * `command` - The command to check
-    ///
-    /// # Returns
-    /// `true` if the command is blacklisted, `false` otherwise
-    fn is_blacklisted(&self, command: &str) -> bool {
-        security::is_blacklisted(command)
+    /// Replaces the default command policy
+    pub fn with_policy(mut self, policy: Arc<CommandPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replaces the default sandbox
+    pub fn with_sandbox(mut self, sandbox: Arc<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Enables completion notifications sent through the given hub
+    pub fn with_chat_hub(mut self, chat_hub: Arc<ChatHub>) -> Self {
+        self.registry = Arc::new(ProcessRegistry::new(self.log_dir()).with_chat_hub(chat_hub));
+        self
+    }
+
+    /// Returns the directory process logs are written to
+    pub fn log_dir(&self) -> PathBuf {
+        self.base_dir.join(PROCESS_LOG_DIR)
     }
 
     /// Validates a working directory path
@@ -88,43 +132,52 @@ impl SpawnTool {
     /// * `command` - The command to spawn
     /// * `args` - Command arguments as an array
     /// * `cwd` - Optional working directory
+    /// * `ctx` - Caller, for the command policy, ownership and notifications
+    /// * `notify` - Whether to message the caller when the process finishes
     ///
     /// # Returns
-    /// JSON string with success status, pid, and message
+    /// JSON string with success status, pid, log path and message
     async fn spawn_command(
         &self,
         command: &str,
         args: &[String],
         cwd: Option<&std::path::Path>,
+        ctx: &ToolExecutionContext,
+        notify: bool,
     ) -> ToolResult<String> {
-        // Check blacklist
-        if self.is_blacklisted(command) {
-            return Err(ToolError::PermissionDenied {
-                tool: self.name().to_string(),
-                message: format!("Command not allowed: {}", command),
-            });
-        }
-
-        // Build the command
+        security::check_command(
+            self.name(),
+            &self.policy,
+            command,
+            args,
+            &PolicyScope::from(ctx),
+        )?;
+
+        // Build the command; output always goes to the process log
         let mut cmd = Command::new(command);
         cmd.args(args);
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
 
-        // Configure stdout/stderr based on log_output setting
-        if self.log_output {
-            cmd.stdout(std::process::Stdio::piped());
-            cmd.stderr(std::process::Stdio::piped());
-        } else {
-            cmd.stdout(std::process::Stdio::null());
-            cmd.stderr(std::process::Stdio::null());
-        }
+        // Lead a new process group so kill reaches the processes it starts
+        #[cfg(unix)]
+        cmd.process_group(0);
 
         // Set working directory if provided
         if let Some(dir) = cwd {
             cmd.current_dir(dir);
         }
 
+        let sandbox_guard =
+            self.sandbox
+                .prepare(&mut cmd)
+                .map_err(|e| ToolError::ExecutionFailed {
+                    tool: self.name().to_string(),
+                    message: format!("Failed to prepare sandbox for '{}': {}", command, e),
+                })?;
+
         // Spawn the process
-        let mut child = cmd.spawn().map_err(|e| {
+        let child = cmd.spawn().map_err(|e| {
             // Classify error types for better debugging
             match e.kind() {
                 std::io::ErrorKind::NotFound => {
@@ -141,99 +194,42 @@ impl SpawnTool {
             }
         })?;
 
-        // Get the process ID
         let pid = child.id().unwrap_or(0);
-        let log_output = self.log_output;
-        let command_name = command.to_string();
+        let log_path =
+            self.registry
+                .create_log(pid)
+                .await
+                .map_err(|e| ToolError::ExecutionFailed {
+                    tool: self.name().to_string(),
+                    message: format!("Failed to create process log: {}", e),
+                })?;
+
+        let notification = match (notify, &ctx.channel, &ctx.chat_id) {
+            (true, Some(channel), Some(chat_id)) => Some(ExitNotification {
+                channel: channel.clone(),
+                chat_id: chat_id.clone(),
+            }),
+            _ => None,
+        };
+        let info = ProcessInfo {
+            pid,
+            command: command.to_string(),
+            args: args.to_vec(),
+            started_at: Utc::now(),
+            finished_at: None,
+            state: ProcessState::Running,
+            log_path: log_path.clone(),
+            owner: ctx.conversation_key(),
+        };
+        self.registry
+            .track(child, info, notification.clone(), self.log_output);
 
-        // Spawn a background task to track the process (fire-and-forget)
+        // The private temp dir lives as long as the process
+        let mut done = self.registry.done_signal(pid);
         tokio::spawn(async move {
-            if log_output {
-                // Capture output if configured
-                let stdout = child.stdout.take();
-                let stderr = child.stderr.take();
-
-                match child.wait().await {
-                    Ok(status) => {
-                        let exit_code = status.code().unwrap_or(-1);
-
-                        // Read and log stdout if available
-                        if let Some(mut stdout) = stdout {
-                            let mut buf = Vec::new();
-                            use tokio::io::AsyncReadExt;
-                            let _ = stdout.read_to_end(&mut buf).await;
-                            let stdout_str = String::from_utf8_lossy(&buf);
-                            if !stdout_str.is_empty() {
-                                tracing::info!(
-                                    pid = %pid,
-                                    command = %command_name,
-                                    exit_code = %exit_code,
-                                    stdout = %stdout_str,
-                                    "Spawned process completed"
-                                );
-                            } else {
-                                tracing::info!(
-                                    pid = %pid,
-                                    command = %command_name,
-                                    exit_code = %exit_code,
-                                    "Spawned process completed"
-                                );
-                            }
-                        } else {
-                            tracing::info!(
-                                pid = %pid,
-                                command = %command_name,
-                                exit_code = %exit_code,
-                                "Spawned process completed"
-                            );
-                        }
-
-                        // Log stderr if available
-                        if let Some(mut stderr) = stderr {
-                            let mut buf = Vec::new();
-                            use tokio::io::AsyncReadExt;
-                            let _ = stderr.read_to_end(&mut buf).await;
-                            let stderr_str = String::from_utf8_lossy(&buf);
-                            if !stderr_str.is_empty() {
-                                tracing::warn!(
-                                    pid = %pid,
-                                    command = %command_name,
-                                    stderr = %stderr_str,
-                                    "Spawned process stderr"
-                                );
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            pid = %pid,
-                            command = %command_name,
-                            error = %e,
-                            "Spawned process error"
-                        );
-                    }
-                }
-            } else {
-                // Just wait for completion without capturing output
-                match child.wait().await {
-                    Ok(status) => {
-                        let exit_code = status.code().unwrap_or(-1);
-                        tracing::info!(
-                            pid = %pid,
-                            command = %command_name,
-                            exit_code = %exit_code,
-                            "Spawned process completed"
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            pid = %pid,
-                            command = %command_name,
-                            error = %e,
-                            "Spawned process error"
-                        );
-                    }
-                }
+            let _sandbox_guard = sandbox_guard;
+            if let Some(done) = &mut done {
+                let _ = done.wait_for(|done| *done).await;
             }
         });
 
@@ -241,57 +237,52 @@ impl SpawnTool {
         let result = serde_json::json!({
             "success": true,
             "pid": pid,
+            "log_path": log_path.display().to_string(),
+            "notify": notification.is_some(),
             "message": "Process spawned successfully"
         });
 
-        serde_json::to_string(&result).map_err(|e| ToolError::ExecutionFailed {
-            tool: self.name().to_string(),
-            message: format!("Failed to serialize spawn result: {}", e),
-        })
+        self.to_json(&result)
     }
-}
 
-#[async_trait::async_trait]
-impl Tool for SpawnTool {
-    fn name(&self) -> &str {
-        "spawn"
+    /// Maps registry errors to tool errors
+    fn registry_error(&self, error: ProcessError) -> ToolError {
+        match error {
+            ProcessError::NotFound(_) => ToolError::NotFound(error.to_string()),
+            ProcessError::NotRunning(_) => ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: error.to_string(),
+            },
+            _ => ToolError::ExecutionFailed {
+                tool: self.name().to_string(),
+                message: error.to_string(),
+            },
+        }
     }
 
-    fn description(&self) -> &str {
-        "Spawn background processes without blocking. Commands are validated against a blacklist of dangerous operations. \
-         Arguments must be provided as an array to prevent shell injection. Optional 'cwd' parameter to set working directory. \
-         Returns immediately with process ID. Process runs in background and exit code is logged. \
-         Blacklist: rm, sudo, dd, mkfs, shutdown, reboot, passwd, visudo"
+    fn to_json(&self, value: &Value) -> ToolResult<String> {
+        serde_json::to_string(value).map_err(|e| ToolError::ExecutionFailed {
+            tool: self.name().to_string(),
+            message: format!("Failed to serialize spawn result: {}", e),
+        })
     }
 
-    fn parameters(&self) -> Value {
-        serde_json::json!({
-            "type": "object",
-            "properties": {
-                "command": {
-                    "type": "string",
-                    "description": "Command to spawn (e.g., 'sleep', 'python', 'npm')"
-                },
-                "args": {
-                    "type": "array",
-                    "items": {
-                        "type": "string"
-                    },
-                    "description": "Command arguments as array (prevents shell injection)"
-                },
-                "cwd": {
-                    "type": "string",
-                    "description": "Optional working directory path (must be within base directory)"
-                }
-            },
-            "required": ["command", "args"]
-        })
+    /// Extracts the required `pid` argument
+    fn pid_arg(&self, args: &HashMap<String, Value>, action: &str) -> ToolResult<u32> {
+        args.get("pid")
+            .and_then(|v| v.as_u64())
+            .and_then(|pid| u32::try_from(pid).ok())
+            .ok_or_else(|| ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: format!("Missing required parameter 'pid' for {} action", action),
+            })
     }
 
-    async fn execute(
+    /// Handles the `spawn` action
+    async fn spawn(
         &self,
-        args: HashMap<String, Value>,
-        _ctx: &ToolExecutionContext,
+        args: &HashMap<String, Value>,
+        ctx: &ToolExecutionContext,
     ) -> ToolResult<String> {
         // Extract command
         let command = args
@@ -333,17 +324,148 @@ impl Tool for SpawnTool {
             None
         };
 
+        let notify = args
+            .get("notify")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         // Spawn the command
-        self.spawn_command(command, &args_strings, cwd_path.as_deref())
+        self.spawn_command(command, &args_strings, cwd_path.as_deref(), ctx, notify)
             .await
     }
 }
 
+#[async_trait::async_trait]
+impl Tool for SpawnTool {
+    fn name(&self) -> &str {
+        "spawn"
+    }
+
+    fn description(&self) -> &str {
+        "Spawn and manage background processes without blocking. Commands and their arguments are checked against the command policy, \
+         which blocks dangerous operations such as rm, sudo, dd, mkfs, shutdown, inline interpreter code and find -delete. \
+         Arguments must be provided as an array to prevent shell injection. Optional 'cwd' parameter to set working directory. \
+         Commands run sandboxed: CPU, memory and file limits apply, the environment holds no secrets and $TMPDIR is a private temp directory. \
+         The 'spawn' action (default) returns immediately with the process ID; set 'notify' to get a message when it finishes. \
+         Output is captured to a log file. Use 'list' to see your processes, 'status' for the state and exit code of one, \
+         'tail_output' for its last output lines and 'kill' to stop it."
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ACTIONS,
+                    "description": "Action to perform (default: spawn)"
+                },
+                "command": {
+                    "type": "string",
+                    "description": "Command to spawn (e.g., 'sleep', 'python', 'npm')"
+                },
+                "args": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "description": "Command arguments as array (prevents shell injection)"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Optional working directory path (must be within base directory)"
+                },
+                "notify": {
+                    "type": "boolean",
+                    "description": "Send a message to this chat when the process finishes (spawn)"
+                },
+                "pid": {
+                    "type": "integer",
+                    "description": "Process ID (status, tail_output, kill)"
+                },
+                "lines": {
+                    "type": "integer",
+                    "description": "Number of output lines to return (tail_output, default: 50, max: 500)"
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, Value>,
+        ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("spawn");
+        let owner = ctx.conversation_key();
+
+        match action {
+            "spawn" => self.spawn(&args, ctx).await,
+            "list" => {
+                let processes = self.registry.list(&owner);
+                self.to_json(&serde_json::json!({ "processes": processes }))
+            }
+            "status" => {
+                let pid = self.pid_arg(&args, action)?;
+                let info = self
+                    .registry
+                    .status(&owner, pid)
+                    .map_err(|e| self.registry_error(e))?;
+                self.to_json(&serde_json::json!(info))
+            }
+            "tail_output" => {
+                let pid = self.pid_arg(&args, action)?;
+                let lines = args
+                    .get("lines")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| (n as usize).clamp(1, MAX_TAIL_LINES))
+                    .unwrap_or(DEFAULT_TAIL_LINES);
+                let (info, output) = self
+                    .registry
+                    .tail_output(&owner, pid, lines)
+                    .await
+                    .map_err(|e| self.registry_error(e))?;
+                let mut result = serde_json::json!(info);
+                result["output"] = Value::String(output);
+                self.to_json(&result)
+            }
+            "kill" => {
+                let pid = self.pid_arg(&args, action)?;
+                let info = self
+                    .registry
+                    .kill(&owner, pid)
+                    .await
+                    .map_err(|e| self.registry_error(e))?;
+                self.to_json(&serde_json::json!(info))
+            }
+            other => Err(ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: format!(
+                    "Unknown action '{}'. Must be one of: {}.",
+                    other,
+                    ACTIONS.join(", ")
+                ),
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    /// Returns true if the tool's policy denies the command without arguments
+    fn is_denied(tool: &SpawnTool, command: &str) -> bool {
+        !tool
+            .policy
+            .is_allowed(command, &[], &PolicyScope::default())
+    }
+
     /// Creates a SpawnTool with a temporary directory as base
     fn create_test_tool(log_output: bool) -> (SpawnTool, TempDir) {
         let temp_dir = TempDir::new().unwrap();
@@ -362,7 +484,7 @@ mod tests {
         let (tool, _temp) = create_test_tool(false);
         let desc = tool.description();
         assert!(desc.contains("background"));
-        assert!(desc.contains("blacklist"));
+        assert!(desc.contains("command policy"));
         assert!(desc.contains("rm"));
         assert!(desc.contains("sudo"));
     }
@@ -376,65 +498,60 @@ mod tests {
         assert!(params["properties"]["command"]["type"] == "string");
         assert!(params["properties"]["args"]["type"] == "array");
         assert!(params["properties"]["cwd"]["type"] == "string");
-        assert!(
-            params["required"]
-                .as_array()
-                .unwrap()
-                .contains(&serde_json::json!("command"))
-        );
-        assert!(
-            params["required"]
-                .as_array()
-                .unwrap()
-                .contains(&serde_json::json!("args"))
-        );
+        assert!(params["properties"]["pid"]["type"] == "integer");
+        let actions = params["properties"]["action"]["enum"].as_array().unwrap();
+        for action in ACTIONS {
+            assert!(actions.contains(&serde_json::json!(action)));
+        }
+        // Required parameters depend on the action and are checked in execute
+        assert!(params["required"].as_array().unwrap().is_empty());
     }
 
     #[test]
     fn test_blacklist_simple_commands() {
         let (tool, _temp) = create_test_tool(false);
 
-        assert!(tool.is_blacklisted("rm"));
-        assert!(tool.is_blacklisted("sudo"));
-        assert!(tool.is_blacklisted("dd"));
-        assert!(tool.is_blacklisted("mkfs"));
-        assert!(tool.is_blacklisted("shutdown"));
-        assert!(tool.is_blacklisted("reboot"));
-        assert!(tool.is_blacklisted("passwd"));
-        assert!(tool.is_blacklisted("visudo"));
+        assert!(is_denied(&tool, "rm"));
+        assert!(is_denied(&tool, "sudo"));
+        assert!(is_denied(&tool, "dd"));
+        assert!(is_denied(&tool, "mkfs"));
+        assert!(is_denied(&tool, "shutdown"));
+        assert!(is_denied(&tool, "reboot"));
+        assert!(is_denied(&tool, "passwd"));
+        assert!(is_denied(&tool, "visudo"));
     }
 
     #[test]
     fn test_blacklist_with_paths() {
         let (tool, _temp) = create_test_tool(false);
 
-        assert!(tool.is_blacklisted("/bin/rm"));
-        assert!(tool.is_blacklisted("/usr/bin/sudo"));
-        assert!(tool.is_blacklisted("./rm"));
+        assert!(is_denied(&tool, "/bin/rm"));
+        assert!(is_denied(&tool, "/usr/bin/sudo"));
+        assert!(is_denied(&tool, "./rm"));
     }
 
     #[test]
     fn test_blacklist_case_insensitive() {
         let (tool, _temp) = create_test_tool(false);
 
-        assert!(tool.is_blacklisted("RM"));
-        assert!(tool.is_blacklisted("SUDO"));
-        assert!(tool.is_blacklisted("Rm"));
-        assert!(tool.is_blacklisted("SuDo"));
+        assert!(is_denied(&tool, "RM"));
+        assert!(is_denied(&tool, "SUDO"));
+        assert!(is_denied(&tool, "Rm"));
+        assert!(is_denied(&tool, "SuDo"));
     }
 
     #[test]
     fn test_non_blacklisted_commands() {
         let (tool, _temp) = create_test_tool(false);
 
-        assert!(!tool.is_blacklisted("ls"));
-        assert!(!tool.is_blacklisted("cat"));
-        assert!(!tool.is_blacklisted("echo"));
-        assert!(!tool.is_blacklisted("git"));
-        assert!(!tool.is_blacklisted("cargo"));
-        assert!(!tool.is_blacklisted("pwd"));
-        assert!(!tool.is_blacklisted("sleep"));
-        assert!(!tool.is_blacklisted("python"));
+        assert!(!is_denied(&tool, "ls"));
+        assert!(!is_denied(&tool, "cat"));
+        assert!(!is_denied(&tool, "echo"));
+        assert!(!is_denied(&tool, "git"));
+        assert!(!is_denied(&tool, "cargo"));
+        assert!(!is_denied(&tool, "pwd"));
+        assert!(!is_denied(&tool, "sleep"));
+        assert!(!is_denied(&tool, "python"));
     }
 
     #[tokio::test]
@@ -686,4 +803,125 @@ mod tests {
         assert_eq!(output["success"], true);
         assert!(output["pid"].as_u64().unwrap() > 0);
     }
+
+    /// Spawns `command` through the tool and returns its pid
+    async fn spawn_process(
+        tool: &SpawnTool,
+        command: &str,
+        command_args: &[&str],
+        ctx: &ToolExecutionContext,
+    ) -> u64 {
+        let mut args = HashMap::new();
+        args.insert("command".to_string(), serde_json::json!(command));
+        args.insert("args".to_string(), serde_json::json!(command_args));
+        let output: serde_json::Value =
+            serde_json::from_str(&tool.execute(args, ctx).await.unwrap()).unwrap();
+        assert!(output["log_path"].as_str().unwrap().contains("processes"));
+        output["pid"].as_u64().unwrap()
+    }
+
+    fn chat_context(chat_id: &str) -> ToolExecutionContext {
+        ToolExecutionContext {
+            channel: Some("telegram".to_string()),
+            chat_id: Some(chat_id.to_string()),
+            user_id: None,
+        }
+    }
+
+    async fn run_action(
+        tool: &SpawnTool,
+        action: &str,
+        pid: u64,
+        ctx: &ToolExecutionContext,
+    ) -> ToolResult<serde_json::Value> {
+        let mut args = HashMap::new();
+        args.insert("action".to_string(), serde_json::json!(action));
+        args.insert("pid".to_string(), serde_json::json!(pid));
+        tool.execute(args, ctx)
+            .await
+            .map(|output| serde_json::from_str(&output).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_status_and_tail_output_after_exit() {
+        let (tool, _temp) = create_test_tool(false);
+        let ctx = ToolExecutionContext::default();
+        let pid = spawn_process(&tool, "printf", &["first\\nsecond\\n"], &ctx).await;
+
+        let mut status = run_action(&tool, "status", pid, &ctx).await.unwrap();
+        for _ in 0..100 {
+            if status["state"] != "running" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            status = run_action(&tool, "status", pid, &ctx).await.unwrap();
+        }
+        assert_eq!(status["state"], "exited");
+        assert_eq!(status["exit_code"], 0);
+
+        let tail = run_action(&tool, "tail_output", pid, &ctx).await.unwrap();
+        assert_eq!(tail["output"], "first\nsecond");
+    }
+
+    #[tokio::test]
+    async fn test_list_is_scoped_to_conversation() {
+        let (tool, _temp) = create_test_tool(false);
+        let ctx = chat_context("chat1");
+        let pid = spawn_process(&tool, "true", &[], &ctx).await;
+
+        let mut args = HashMap::new();
+        args.insert("action".to_string(), serde_json::json!("list"));
+        let output: serde_json::Value =
+            serde_json::from_str(&tool.execute(args.clone(), &ctx).await.unwrap()).unwrap();
+        let processes = output["processes"].as_array().unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0]["pid"], pid);
+
+        let other = chat_context("chat2");
+        let output: serde_json::Value =
+            serde_json::from_str(&tool.execute(args, &other).await.unwrap()).unwrap();
+        assert!(output["processes"].as_array().unwrap().is_empty());
+        assert!(matches!(
+            run_action(&tool, "status", pid, &other).await,
+            Err(ToolError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_kill_running_process() {
+        let (tool, _temp) = create_test_tool(false);
+        let ctx = ToolExecutionContext::default();
+        let pid = spawn_process(&tool, "sleep", &["30"], &ctx).await;
+
+        let killed = run_action(&tool, "kill", pid, &ctx).await.unwrap();
+        assert_eq!(killed["state"], "exited");
+        assert!(killed["signal"].as_i64().is_some());
+
+        assert!(matches!(
+            run_action(&tool, "kill", pid, &ctx).await,
+            Err(ToolError::InvalidArguments { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_action_validation() {
+        let (tool, _temp) = create_test_tool(false);
+        let ctx = ToolExecutionContext::default();
+
+        let mut args = HashMap::new();
+        args.insert("action".to_string(), serde_json::json!("status"));
+        match tool.execute(args, &ctx).await {
+            Err(ToolError::InvalidArguments { message, .. }) => assert!(message.contains("pid")),
+            other => panic!("expected InvalidArguments, got {:?}", other),
+        }
+
+        let mut args = HashMap::new();
+        args.insert("action".to_string(), serde_json::json!("restart"));
+        match tool.execute(args, &ctx).await {
+            Err(ToolError::InvalidArguments { message, .. }) => {
+                assert!(message.contains("Unknown action"))
+            }
+            other => panic!("expected InvalidArguments, got {:?}", other),
+        }
+    }
 }
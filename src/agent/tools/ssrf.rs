@@ -0,0 +1,379 @@
+//! Server-side request forgery protection for the web tool
+//!
+//! A page read by the agent can ask it to fetch `http://169.254.169.254/`, a
+//! local model server or a router admin panel. [`AddressGuard`] classifies IP
+//! addresses and refuses loopback, private, link-local, multicast and other
+//! non-public ranges unless the host or network is listed in
+//! `tools.web.local_services`. [`GuardedResolver`] applies the guard to every
+//! DNS answer, so the checked addresses are the ones connected to, including
+//! on redirect hops.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+use crate::agent::tools::web::{DomainPattern, WebConfigError, normalize_host};
+
+/// A request refused because its host resolves to a non-public address
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Host '{host}' resolves to {addr}, a {kind} address. Add it to tools.web.local_services to allow it."
+)]
+pub struct BlockedAddress {
+    pub host: String,
+    pub addr: IpAddr,
+    /// Kind of range the address belongs to, e.g. "loopback"
+    pub kind: &'static str,
+}
+
+/// An IP network in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    /// Parses `10.0.0.0/8`, `fd00::/8` or a single address
+    pub fn parse(text: &str) -> Option<Self> {
+        let (addr, prefix) = match text.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+            None => (text, None),
+        };
+        let addr: IpAddr = addr
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .ok()?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    /// Whether `addr` lies in this network
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.addr, canonical(addr)) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Unwraps IPv4 addresses embedded in IPv6 (`::ffff:a.b.c.d`, `64:ff9b::a.b.c.d`)
+fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return IpAddr::V4(v4);
+            }
+            let segments = v6.segments();
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [a, b, c, d] = (u128::from(v6) as u32).to_be_bytes();
+                return IpAddr::V4(Ipv4Addr::new(a, b, c, d));
+            }
+            IpAddr::V6(v6)
+        }
+        v4 => v4,
+    }
+}
+
+/// Names the non-public range an address belongs to, if any
+pub fn non_public_kind(addr: IpAddr) -> Option<&'static str> {
+    match canonical(addr) {
+        IpAddr::V4(v4) => ipv4_kind(v4),
+        IpAddr::V6(v6) => ipv6_kind(v6),
+    }
+}
+
+fn ipv4_kind(addr: Ipv4Addr) -> Option<&'static str> {
+    let [a, b, ..] = addr.octets();
+    if addr.is_unspecified() || a == 0 {
+        Some("unspecified")
+    } else if addr.is_loopback() {
+        Some("loopback")
+    } else if addr.is_private() {
+        Some("private")
+    } else if addr.is_link_local() {
+        Some("link-local")
+    } else if a == 100 && (64..128).contains(&b) {
+        Some("shared (carrier-grade NAT)")
+    } else if addr.is_multicast() {
+        Some("multicast")
+    } else if addr.is_broadcast() || a >= 240 {
+        Some("reserved")
+    } else if (a == 192 && b == 0 && addr.octets()[2] == 0) || (a == 198 && (b == 18 || b == 19)) {
+        Some("special-purpose")
+    } else {
+        None
+    }
+}
+
+fn ipv6_kind(addr: Ipv6Addr) -> Option<&'static str> {
+    let first = addr.segments()[0];
+    if addr.is_unspecified() {
+        Some("unspecified")
+    } else if addr.is_loopback() {
+        Some("loopback")
+    } else if first & 0xfe00 == 0xfc00 {
+        Some("private")
+    } else if first & 0xffc0 == 0xfe80 || first & 0xffc0 == 0xfec0 {
+        Some("link-local")
+    } else if addr.is_multicast() {
+        Some("multicast")
+    } else {
+        None
+    }
+}
+
+/// Decides which resolved addresses the web tool may connect to
+#[derive(Debug, Clone, Default)]
+pub struct AddressGuard {
+    /// Disables the checks entirely (`tools.web.allow_private_networks`)
+    allow_all: bool,
+    /// Hosts allowed whatever they resolve to
+    hosts: Vec<DomainPattern>,
+    /// Networks allowed even though they are not public
+    networks: Vec<IpNetwork>,
+}
+
+impl AddressGuard {
+    /// Builds a guard from `local_services` entries: host patterns, IP
+    /// addresses or CIDR ranges
+    pub fn new(local_services: &[String], allow_all: bool) -> Result<Self, WebConfigError> {
+        let mut guard = Self {
+            allow_all,
+            ..Self::default()
+        };
+        for entry in local_services {
+            let entry = entry.trim();
+            if let Some(network) = IpNetwork::parse(entry) {
+                guard.networks.push(network);
+            } else if entry.contains('/') {
+                return Err(WebConfigError::InvalidLocalService(entry.to_string()));
+            } else {
+                let pattern = DomainPattern::parse(entry)
+                    .map_err(|_| WebConfigError::InvalidLocalService(entry.to_string()))?;
+                guard.hosts.push(pattern);
+            }
+        }
+        Ok(guard)
+    }
+
+    /// Checks that `host` may be reached at `addr`
+    pub fn check(&self, host: &str, addr: IpAddr) -> Result<(), BlockedAddress> {
+        if self.allow_all {
+            return Ok(());
+        }
+        let Some(kind) = non_public_kind(addr) else {
+            return Ok(());
+        };
+        let host = normalize_host(host);
+        if self.hosts.iter().any(|pattern| pattern.matches(&host))
+            || self.networks.iter().any(|network| network.contains(addr))
+        {
+            return Ok(());
+        }
+        Err(BlockedAddress {
+            host,
+            addr: canonical(addr),
+            kind,
+        })
+    }
+
+    /// Checks a URL whose host is an IP literal; names are checked on resolution
+    pub fn check_url(&self, url: &reqwest::Url) -> Result<(), BlockedAddress> {
+        let host = url.host_str().unwrap_or_default();
+        match host.trim_start_matches('[').trim_end_matches(']').parse() {
+            Ok(addr) => self.check(host, addr),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// DNS resolver that refuses hosts resolving to blocked addresses
+///
+/// A host is refused if any of its addresses is blocked, so a name that mixes
+/// public and internal answers cannot be used to reach the internal one.
+#[derive(Debug)]
+pub struct GuardedResolver {
+    guard: Arc<AddressGuard>,
+}
+
+impl GuardedResolver {
+    pub fn new(guard: Arc<AddressGuard>) -> Self {
+        Self { guard }
+    }
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let guard = Arc::clone(&self.guard);
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            for addr in &addrs {
+                guard.check(&host, addr.ip())?;
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Finds a [`BlockedAddress`] in an error's source chain
+pub fn find_blocked<'a>(
+    error: &'a (dyn std::error::Error + 'static),
+) -> Option<&'a BlockedAddress> {
+    let mut current = Some(error);
+    while let Some(error) = current {
+        if let Some(blocked) = error.downcast_ref::<BlockedAddress>() {
+            return Some(blocked);
+        }
+        current = error.source();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(text: &str) -> IpAddr {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_non_public_ranges() {
+        for (addr, kind) in [
+            ("127.0.0.1", "loopback"),
+            ("10.1.2.3", "private"),
+            ("172.16.0.1", "private"),
+            ("192.168.1.1", "private"),
+            ("169.254.169.254", "link-local"),
+            ("100.64.0.1", "shared (carrier-grade NAT)"),
+            ("0.0.0.0", "unspecified"),
+            ("224.0.0.1", "multicast"),
+            ("255.255.255.255", "reserved"),
+            ("::1", "loopback"),
+            ("::", "unspecified"),
+            ("fd12::1", "private"),
+            ("fe80::1", "link-local"),
+            ("ff02::1", "multicast"),
+            ("::ffff:127.0.0.1", "loopback"),
+            ("::ffff:169.254.169.254", "link-local"),
+            ("64:ff9b::a00:1", "private"),
+        ] {
+            assert_eq!(non_public_kind(ip(addr)), Some(kind), "{}", addr);
+        }
+        for addr in ["8.8.8.8", "1.1.1.1", "172.32.0.1", "2606:4700::1111"] {
+            assert_eq!(non_public_kind(ip(addr)), None, "{}", addr);
+        }
+    }
+
+    #[test]
+    fn test_ip_network() {
+        let network = IpNetwork::parse("192.168.1.0/24").unwrap();
+        assert!(network.contains(ip("192.168.1.200")));
+        assert!(network.contains(ip("::ffff:192.168.1.5")));
+        assert!(!network.contains(ip("192.168.2.1")));
+        assert!(
+            IpNetwork::parse("10.0.0.5")
+                .unwrap()
+                .contains(ip("10.0.0.5"))
+        );
+        assert!(
+            !IpNetwork::parse("10.0.0.5")
+                .unwrap()
+                .contains(ip("10.0.0.6"))
+        );
+        assert!(
+            IpNetwork::parse("0.0.0.0/0")
+                .unwrap()
+                .contains(ip("127.0.0.1"))
+        );
+        assert!(
+            IpNetwork::parse("fd00::/8")
+                .unwrap()
+                .contains(ip("fd12::1"))
+        );
+        assert!(IpNetwork::parse("10.0.0.0/33").is_none());
+        assert!(IpNetwork::parse("example.com").is_none());
+    }
+
+    #[test]
+    fn test_guard_allowlist() {
+        let guard = AddressGuard::new(
+            &[
+                "homeassistant.local".to_string(),
+                "192.168.1.0/24".to_string(),
+                "::1".to_string(),
+            ],
+            false,
+        )
+        .unwrap();
+        assert!(guard.check("example.com", ip("93.184.215.14")).is_ok());
+        assert!(guard.check("HomeAssistant.local", ip("10.0.0.7")).is_ok());
+        assert!(guard.check("nas.lan", ip("192.168.1.20")).is_ok());
+        assert!(guard.check("localhost", ip("::1")).is_ok());
+
+        let blocked = guard.check("localhost", ip("127.0.0.1")).unwrap_err();
+        assert_eq!(blocked.kind, "loopback");
+        assert!(blocked.to_string().contains("tools.web.local_services"));
+        assert!(guard.check("metadata", ip("169.254.169.254")).is_err());
+
+        assert!(
+            AddressGuard::new(&[], true)
+                .unwrap()
+                .check("x", ip("127.0.0.1"))
+                .is_ok()
+        );
+        assert!(AddressGuard::new(&["10.0.0.0/99".to_string()], false).is_err());
+        assert!(AddressGuard::new(&["http://x".to_string()], false).is_err());
+    }
+
+    #[test]
+    fn test_check_url_literals() {
+        let guard = AddressGuard::default();
+        for url in [
+            "http://127.0.0.1:11434/api/delete",
+            "http://[::1]/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://0x7f000001/",
+            "http://2130706433/",
+        ] {
+            let url = reqwest::Url::parse(url).unwrap();
+            assert!(guard.check_url(&url).is_err(), "{}", url);
+        }
+        let url = reqwest::Url::parse("http://localhost/").unwrap();
+        assert!(guard.check_url(&url).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_resolver_blocks_localhost() {
+        let resolver = GuardedResolver::new(Arc::new(AddressGuard::default()));
+        let error = match resolver.resolve("localhost".parse().unwrap()).await {
+            Ok(_) => panic!("localhost should be blocked"),
+            Err(error) => error,
+        };
+        let blocked = find_blocked(error.as_ref()).unwrap();
+        assert_eq!(blocked.host, "localhost");
+        assert_eq!(blocked.kind, "loopback");
+
+        let allowed = GuardedResolver::new(Arc::new(
+            AddressGuard::new(&["localhost".to_string()], false).unwrap(),
+        ));
+        assert!(allowed.resolve("localhost".parse().unwrap()).await.is_ok());
+    }
+}
@@ -162,6 +162,9 @@ pub type ToolResult<T> = std::result::Result<T, ToolError>;
 /// * `chat_id` - The unique identifier for the current conversation or user.
 ///   Will be `None` if the tool is executed outside of a conversation context.
 ///   Tools should handle this gracefully when user identification is required.
+///
+/// * `user_id` - The sender of the message, when the channel reports it
+///   (e.g. the Telegram user id). Used by per-user command policies.
 #[derive(Debug, Clone, Default)]
 pub struct ToolExecutionContext {
     /// Channel for the current conversation (e.g., "telegram", "cli")
@@ -170,6 +173,47 @@ pub struct ToolExecutionContext {
     /// Chat/user identifier for the current conversation
     /// None when executing outside a conversation context
     pub chat_id: Option<String>,
+    /// Sender of the current message, if the channel provides it
+    pub user_id: Option<String>,
+}
+
+impl ToolExecutionContext {
+    /// Builds the context for tools called while handling `message`
+    pub fn for_message(message: &crate::chat::InboundMessage) -> Self {
+        let user_id = message.metadata.get("user_id").and_then(|v| match v {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        });
+        Self {
+            channel: Some(message.channel.clone()),
+            chat_id: Some(message.chat_id.clone()),
+            user_id,
+        }
+    }
+
+    /// Key identifying the conversation, used to scope per-conversation state
+    ///
+    /// Missing channel or chat id parts are reported as `default`.
+    pub fn conversation_key(&self) -> String {
+        format!(
+            "{}:{}",
+            self.channel.as_deref().unwrap_or("default"),
+            self.chat_id.as_deref().unwrap_or("default")
+        )
+    }
+
+    /// Id of the agent session holding this conversation's history
+    ///
+    /// Matches the ids the session manager assigns, so per-conversation state
+    /// can be released when [`Tool::session_expired`] names the session.
+    pub fn session_id(&self) -> String {
+        format!(
+            "{}_{}",
+            self.channel.as_deref().unwrap_or("default"),
+            self.chat_id.as_deref().unwrap_or("default")
+        )
+    }
 }
 
 /// Definition of a tool for LLM function calling
@@ -282,6 +326,12 @@ pub trait Tool: Send + Sync {
     fn to_tool_definition(&self) -> ToolDefinition {
         ToolDefinition::new(self.name(), self.description(), self.parameters())
     }
+
+    /// Called when the agent session `session_id` expires
+    ///
+    /// Tools that keep state per conversation release it here. The default
+    /// implementation does nothing.
+    async fn session_expired(&self, _session_id: &str) {}
 }
 
 #[cfg(test)]
@@ -386,11 +436,29 @@ mod tests {
         let ctx = ToolExecutionContext {
             channel: Some("telegram".to_string()),
             chat_id: Some("123456".to_string()),
+            user_id: None,
         };
         assert_eq!(ctx.channel, Some("telegram".to_string()));
         assert_eq!(ctx.chat_id, Some("123456".to_string()));
     }
 
+    #[test]
+    fn test_tool_execution_context_for_message() {
+        let message = crate::chat::InboundMessage::new("telegram", "42", "hi")
+            .with_metadata("user_id", serde_json::json!(1234));
+        let ctx = ToolExecutionContext::for_message(&message);
+
+        assert_eq!(ctx.channel.as_deref(), Some("telegram"));
+        assert_eq!(ctx.chat_id.as_deref(), Some("42"));
+        assert_eq!(ctx.user_id.as_deref(), Some("1234"));
+        assert_eq!(ctx.conversation_key(), "telegram:42");
+        assert_eq!(ctx.session_id(), "telegram_42");
+        assert_eq!(
+            ToolExecutionContext::default().conversation_key(),
+            "default:default"
+        );
+    }
+
     #[test]
     fn test_tool_result_type() {
         let ok_result: ToolResult<String> = Ok("success".to_string());
@@ -1,39 +1,493 @@
 //! Web tool for the agent
 //!
-//! This tool provides web content fetching capabilities with HTML tag stripping
-//! and proper error handling for network operations.
-
-use std::collections::HashMap;
+//! This tool provides web content fetching capabilities with readable content
+//! extraction for HTML, pagination of long documents and proper error handling
+//! for network operations. Beyond GET it can call REST APIs: POST, PUT, PATCH
+//! and DELETE with JSON or form bodies, custom headers and named credentials
+//! from the config, restricted by a domain allowlist and denylist.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use std::time::Duration;
 
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::agent::tools::html::{self, Article};
+use crate::agent::tools::http_cache::{CachedPage, HttpCache, Lookup};
+use crate::agent::tools::ssrf::{self, AddressGuard, GuardedResolver};
 use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
 
 /// Default timeout for HTTP requests in seconds
 const DEFAULT_WEB_TIMEOUT_SECS: u64 = 30;
+
+/// Default size cap of the HTTP cache in megabytes
+const DEFAULT_CACHE_SIZE_MB: u64 = 50;
 /// Maximum number of redirects to follow
 const MAX_REDIRECTS: usize = 5;
-/// Maximum response size in bytes (100KB)
-const MAX_RESPONSE_SIZE: usize = 100 * 1024;
+/// Maximum response size in bytes (5MB); longer bodies are cut
+const MAX_RESPONSE_SIZE: usize = 5 * 1024 * 1024;
+/// Number of characters returned per page of content
+const PAGE_SIZE: usize = 20_000;
 /// Maximum error body size to include in error messages
 const MAX_ERROR_BODY_SIZE: usize = 500;
+/// Supported HTTP methods
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+/// Headers managed by the HTTP client that callers may not set
+const RESTRICTED_HEADERS: &[&str] = &["host", "content-length", "transfer-encoding", "connection"];
+
+const DESCRIPTION: &str = "Fetches web content from a URL. HTML pages are reduced to their main readable content and returned as Markdown \
+     (headings, lists, quotes, code blocks) with links numbered like [text][1] and listed separately in 'links'. \
+     JSON and plain text are returned as-is. Long content is split into pages of 20000 characters: \
+     pass 'page' (starting at 1) or 'offset' (the 'next_offset' of the previous call) to read further. \
+     Also calls APIs: set 'method' (GET, POST, PUT, PATCH, DELETE), 'headers', and a 'json' or 'form' body. \
+     Secrets are never passed directly: name a configured 'credential' and it is added to the request. \
+     Local and private network addresses are refused unless configured as local services. \
+     GET responses are cached following the server's caching headers; pass 'max_age' (seconds) \
+     to accept an older cached copy, or 0 to force a check with the server. \
+     HTTP/HTTPS only. Follows up to 5 redirects. 30-second timeout. Max 5MB download. \
+     Assumes UTF-8 encoding for all content.";
+
+/// Domain rules and named credentials for the web tool.
+///
+/// Missing fields fall back to the built-in defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebToolConfig {
+    /// Hosts that may be reached; empty allows every host that is not denied.
+    /// `example.com` also matches its subdomains, `*.example.com` only them
+    pub allowed_domains: Vec<String>,
+    /// Hosts that may never be reached, even when allowed
+    pub denied_domains: Vec<String>,
+    /// Credentials the agent can reference by name
+    pub credentials: BTreeMap<String, WebCredentialConfig>,
+    /// Hosts, IP addresses or CIDR ranges that may be reached even though
+    /// they resolve to loopback, private or link-local addresses
+    pub local_services: Vec<String>,
+    /// Turns off the private network checks entirely
+    pub allow_private_networks: bool,
+    /// On-disk cache of fetched pages
+    pub cache: WebCacheConfig,
+}
+
+/// Settings of the web tool's HTTP cache.
+///
+/// Missing fields fall back to the built-in defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebCacheConfig {
+    /// Whether GET responses are cached in the workspace
+    pub enabled: bool,
+    /// Total size of the cached bodies, in megabytes
+    pub max_size_mb: u64,
+}
+
+impl Default for WebCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: DEFAULT_CACHE_SIZE_MB,
+        }
+    }
+}
+
+impl WebCacheConfig {
+    /// Size cap of the cache in bytes
+    pub fn max_bytes(&self) -> u64 {
+        self.max_size_mb.saturating_mul(1024 * 1024)
+    }
+}
 
-/// Tool for fetching web content
+/// A secret sent as a request header, referenced by name in tool calls
 ///
-/// Provides HTTP GET requests with redirect following, HTML tag stripping,
-/// and comprehensive error handling for network operations.
+/// Exactly one of `value` and `env` must be set.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebCredentialConfig {
+    /// Header carrying the secret
+    #[serde(default = "default_credential_header")]
+    pub header: String,
+    /// Text placed before the secret, e.g. "Bearer "
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub prefix: String,
+    /// The secret itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Environment variable holding the secret, read when the request is made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    /// Hosts the credential may be sent to
+    pub domains: Vec<String>,
+}
+
+fn default_credential_header() -> String {
+    "Authorization".to_string()
+}
+
+impl std::fmt::Debug for WebCredentialConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebCredentialConfig")
+            .field("header", &self.header)
+            .field("prefix", &self.prefix)
+            .field("value", &self.value.as_ref().map(|_| "<redacted>"))
+            .field("env", &self.env)
+            .field("domains", &self.domains)
+            .finish()
+    }
+}
+
+/// Errors raised while building the web tool from its config
+#[derive(Debug, thiserror::Error)]
+pub enum WebConfigError {
+    #[error("Invalid domain pattern '{0}'")]
+    InvalidDomain(String),
+    #[error("Invalid local service '{0}': expected a host, IP address or CIDR range")]
+    InvalidLocalService(String),
+    #[error("cache.max_size_mb must be greater than 0")]
+    InvalidCacheSize,
+    #[error("Invalid credential '{name}': {message}")]
+    Credential { name: String, message: String },
+    #[error("Failed to create HTTP client: {0}")]
+    Client(String),
+}
+
+/// A host pattern of the domain policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DomainPattern {
+    domain: String,
+    /// Set for `*.example.com`, which does not match `example.com` itself
+    subdomains_only: bool,
+}
+
+impl DomainPattern {
+    pub(crate) fn parse(pattern: &str) -> Result<Self, WebConfigError> {
+        let invalid = || WebConfigError::InvalidDomain(pattern.to_string());
+        let trimmed = pattern.trim();
+        let (domain, subdomains_only) = match trimmed.strip_prefix("*.") {
+            Some(domain) => (domain, true),
+            None => (trimmed, false),
+        };
+        if domain.is_empty() || domain.contains(['/', '@', '*']) {
+            return Err(invalid());
+        }
+
+        // Let the URL parser normalize the host (case, IDNA, IP literals)
+        let url = reqwest::Url::parse(&format!("http://{}/", domain)).map_err(|_| invalid())?;
+        if url.port().is_some() || url.path() != "/" || url.query().is_some() {
+            return Err(invalid());
+        }
+        let domain = url.host_str().map(normalize_host).ok_or_else(invalid)?;
+        Ok(Self {
+            domain,
+            subdomains_only,
+        })
+    }
+
+    pub(crate) fn matches(&self, host: &str) -> bool {
+        (!self.subdomains_only && host == self.domain)
+            || host
+                .strip_suffix(self.domain.as_str())
+                .is_some_and(|sub| sub.ends_with('.'))
+    }
+}
+
+impl std::fmt::Display for DomainPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.subdomains_only {
+            write!(f, "*.")?;
+        }
+        write!(f, "{}", self.domain)
+    }
+}
+
+/// Lowercases a host and strips IPv6 brackets and a trailing dot
+pub(crate) fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// Starts an HTTP client that only reaches hosts allowed by `domains` and
+/// `addresses`
+///
+/// Every resolved address, including on redirects, is checked by the
+/// [`AddressGuard`], and proxies are bypassed so the check applies to the
+/// real destination. At most [`MAX_REDIRECTS`] redirects are followed, each
+/// checked against the domain policy and, when `credential_host` is set,
+/// kept on that host so a credential never leaves it.
+pub(crate) fn guarded_client_builder(
+    domains: &Arc<DomainPolicy>,
+    addresses: &Arc<AddressGuard>,
+    credential_host: Option<String>,
+) -> reqwest::ClientBuilder {
+    let redirect_domains = Arc::clone(domains);
+    let redirect_addresses = Arc::clone(addresses);
+    let redirect_policy = reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() > MAX_REDIRECTS {
+            return attempt.error(format!("Too many redirects (max {})", MAX_REDIRECTS));
+        }
+        let host = attempt
+            .url()
+            .host_str()
+            .map(normalize_host)
+            .unwrap_or_default();
+        if let Err(reason) = redirect_domains.check(&host) {
+            return attempt.error(reason);
+        }
+        if let Err(blocked) = redirect_addresses.check_url(attempt.url()) {
+            return attempt.error(blocked);
+        }
+        if let Some(origin) = &credential_host {
+            if host != *origin {
+                return attempt.error(format!(
+                    "Redirect to '{}' refused: the request carries a credential for '{}'",
+                    host, origin
+                ));
+            }
+        }
+        attempt.follow()
+    });
+
+    reqwest::Client::builder()
+        .redirect(redirect_policy)
+        .dns_resolver(Arc::new(GuardedResolver::new(Arc::clone(addresses))))
+        .no_proxy()
+}
+
+/// Which hosts the web tool may reach
+#[derive(Debug, Clone, Default)]
+pub struct DomainPolicy {
+    allowed: Vec<DomainPattern>,
+    denied: Vec<DomainPattern>,
+}
+
+impl DomainPolicy {
+    /// Builds a policy from allowlist and denylist patterns
+    pub fn new(allowed: &[String], denied: &[String]) -> Result<Self, WebConfigError> {
+        let parse = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| DomainPattern::parse(pattern))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            allowed: parse(allowed)?,
+            denied: parse(denied)?,
+        })
+    }
+
+    /// Checks that `host` may be reached, explaining why not otherwise
+    pub fn check(&self, host: &str) -> Result<(), String> {
+        let host = normalize_host(host);
+        if let Some(pattern) = self.denied.iter().find(|pattern| pattern.matches(&host)) {
+            return Err(format!(
+                "Host '{}' is blocked by the web domain denylist ({})",
+                host, pattern
+            ));
+        }
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|pattern| pattern.matches(&host)) {
+            return Err(format!(
+                "Host '{}' is not in the web domain allowlist ({})",
+                host,
+                join_patterns(&self.allowed)
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn join_patterns(patterns: &[DomainPattern]) -> String {
+    patterns
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Where a credential's secret comes from
+#[derive(Clone)]
+enum Secret {
+    Value(String),
+    Env(String),
+}
+
+/// A named credential, resolved into a header when a request uses it
+#[derive(Clone)]
+struct Credential {
+    header: HeaderName,
+    prefix: String,
+    secret: Secret,
+    domains: Vec<DomainPattern>,
+}
+
+impl std::fmt::Debug for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match &self.secret {
+            Secret::Value(_) => "<redacted>".to_string(),
+            Secret::Env(var) => format!("${}", var),
+        };
+        f.debug_struct("Credential")
+            .field("header", &self.header)
+            .field("secret", &source)
+            .field("domains", &self.domains)
+            .finish()
+    }
+}
+
+impl Credential {
+    fn from_config(name: &str, config: &WebCredentialConfig) -> Result<Self, WebConfigError> {
+        let invalid = |message: &str| WebConfigError::Credential {
+            name: name.to_string(),
+            message: message.to_string(),
+        };
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(invalid(
+                "names may only contain letters, digits, '-' and '_'",
+            ));
+        }
+        let header = HeaderName::from_bytes(config.header.as_bytes())
+            .map_err(|_| invalid(&format!("'{}' is not a valid header name", config.header)))?;
+        let secret = match (&config.value, &config.env) {
+            (Some(value), None) => Secret::Value(value.clone()),
+            (None, Some(var)) if !var.is_empty() => Secret::Env(var.clone()),
+            _ => return Err(invalid("set exactly one of 'value' and 'env'")),
+        };
+        if config.domains.is_empty() {
+            return Err(invalid("'domains' must list the hosts it may be sent to"));
+        }
+        let domains = config
+            .domains
+            .iter()
+            .map(|pattern| DomainPattern::parse(pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            header,
+            prefix: config.prefix.clone(),
+            secret,
+            domains,
+        })
+    }
+
+    fn allows(&self, host: &str) -> bool {
+        let host = normalize_host(host);
+        self.domains.iter().any(|pattern| pattern.matches(&host))
+    }
+
+    /// Resolves the secret into a header value marked as sensitive
+    fn header_value(&self) -> Result<HeaderValue, String> {
+        let secret = match &self.secret {
+            Secret::Value(value) => value.clone(),
+            Secret::Env(var) => std::env::var(var)
+                .ok()
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| format!("environment variable {} is not set", var))?,
+        };
+        let mut value = HeaderValue::from_str(&format!("{}{}", self.prefix, secret))
+            .map_err(|_| "the secret contains characters not allowed in a header".to_string())?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
+}
+
+/// Body of an outgoing request
+#[derive(Debug, Clone, PartialEq)]
+enum RequestBody {
+    Empty,
+    Json(Value),
+    Form(Vec<(String, String)>),
+}
+
+/// A validated request, ready to send
+#[derive(Debug)]
+struct HttpRequest {
+    method: reqwest::Method,
+    url: reqwest::Url,
+    headers: HeaderMap,
+    body: RequestBody,
+    /// Host the request's credential is bound to, if one is attached
+    credential_host: Option<String>,
+}
+
+/// Tool for fetching web content and calling HTTP APIs
+///
+/// Provides HTTP requests with redirect following, readable content
+/// extraction, pagination and comprehensive error handling for network
+/// operations.
 ///
 /// # Security
 /// This tool implements NFR-S6: All HTTP requests use HTTPS/TLS 1.2+
-/// and URL validation prevents malicious URLs.
+/// and URL validation prevents malicious URLs. Every host, including
+/// redirect targets, is checked against the [`DomainPolicy`], and
+/// credentials are only ever sent to the hosts they are configured for.
+/// Hosts resolving to loopback, private or link-local addresses are refused
+/// unless listed as local services (see [`AddressGuard`]).
 #[derive(Debug)]
-pub struct WebTool;
+pub struct WebTool {
+    domains: Arc<DomainPolicy>,
+    addresses: Arc<AddressGuard>,
+    credentials: BTreeMap<String, Credential>,
+    cache: Option<Arc<HttpCache>>,
+    description: String,
+}
 
 impl WebTool {
-    /// Creates a new WebTool
+    /// Creates a new WebTool that may reach any host and has no credentials
     pub fn new() -> Self {
-        Self
+        Self {
+            domains: Arc::new(DomainPolicy::default()),
+            addresses: Arc::new(AddressGuard::default()),
+            credentials: BTreeMap::new(),
+            cache: None,
+            description: DESCRIPTION.to_string(),
+        }
+    }
+
+    /// Creates a WebTool with the domain policy and credentials of `config`
+    pub fn from_config(config: &WebToolConfig) -> Result<Self, WebConfigError> {
+        if config.cache.enabled && config.cache.max_size_mb == 0 {
+            return Err(WebConfigError::InvalidCacheSize);
+        }
+        let domains = DomainPolicy::new(&config.allowed_domains, &config.denied_domains)?;
+        let addresses = AddressGuard::new(&config.local_services, config.allow_private_networks)?;
+        let credentials = config
+            .credentials
+            .iter()
+            .map(|(name, credential)| {
+                Ok((name.clone(), Credential::from_config(name, credential)?))
+            })
+            .collect::<Result<BTreeMap<_, _>, WebConfigError>>()?;
+
+        let mut description = DESCRIPTION.to_string();
+        if !domains.allowed.is_empty() {
+            description.push_str(&format!(
+                " Only these hosts can be reached: {}.",
+                join_patterns(&domains.allowed)
+            ));
+        }
+        if !credentials.is_empty() {
+            let names: Vec<_> = credentials.keys().map(String::as_str).collect();
+            description.push_str(&format!(" Configured credentials: {}.", names.join(", ")));
+        }
+
+        Ok(Self {
+            domains: Arc::new(domains),
+            addresses: Arc::new(addresses),
+            credentials,
+            cache: None,
+            description,
+        })
+    }
+
+    /// Caches GET responses in `cache`
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
     }
 
     /// Validates a URL string
@@ -46,9 +500,9 @@ impl WebTool {
     /// * `url` - The URL string to validate
     ///
     /// # Returns
-    /// * `Ok(())` - URL is valid
+    /// * `Ok(Url)` - The parsed URL
     /// * `Err(ToolError)` - URL is invalid or uses unsupported protocol
-    fn validate_url(&self, url: &str) -> ToolResult<()> {
+    fn validate_url(&self, url: &str) -> ToolResult<reqwest::Url> {
         // Parse the URL
         let parsed = reqwest::Url::parse(url).map_err(|e| ToolError::InvalidArguments {
             tool: self.name().to_string(),
@@ -67,19 +521,182 @@ impl WebTool {
             });
         }
 
-        Ok(())
+        Ok(parsed)
+    }
+
+    fn invalid_argument(&self, message: impl Into<String>) -> ToolError {
+        ToolError::InvalidArguments {
+            tool: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Builds and validates a request from the tool arguments
+    ///
+    /// Checks the URL against the domain policy, parses the method, headers
+    /// and body, and attaches the named credential if its domains allow it.
+    fn build_request(&self, args: &HashMap<String, Value>) -> ToolResult<HttpRequest> {
+        let url = args
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.invalid_argument("Missing required parameter 'url'"))?;
+        let url = self.validate_url(url)?;
+        let host = url.host_str().map(normalize_host).unwrap_or_default();
+        self.domains
+            .check(&host)
+            .map_err(|message| ToolError::PermissionDenied {
+                tool: self.name().to_string(),
+                message,
+            })?;
+        // Names are checked when resolved; IP literals never reach the resolver
+        self.addresses
+            .check_url(&url)
+            .map_err(|blocked| ToolError::PermissionDenied {
+                tool: self.name().to_string(),
+                message: blocked.to_string(),
+            })?;
+
+        let method = match args.get("method") {
+            None | Some(Value::Null) => "GET".to_string(),
+            Some(value) => value
+                .as_str()
+                .map(str::to_ascii_uppercase)
+                .filter(|method| METHODS.contains(&method.as_str()))
+                .ok_or_else(|| {
+                    self.invalid_argument(format!(
+                        "Invalid method {}. Must be one of: {}.",
+                        value,
+                        METHODS.join(", ")
+                    ))
+                })?,
+        };
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|e| self.invalid_argument(format!("Invalid method: {}", e)))?;
+
+        let mut headers = self.parse_headers(args.get("headers"))?;
+        let body = self.parse_body(args)?;
+        if method == reqwest::Method::GET && body != RequestBody::Empty {
+            return Err(self.invalid_argument(
+                "GET requests cannot have a body; use POST, PUT, PATCH or DELETE",
+            ));
+        }
+
+        let mut credential_host = None;
+        if let Some(name) = args.get("credential").filter(|v| !v.is_null()) {
+            let name = name
+                .as_str()
+                .ok_or_else(|| self.invalid_argument("Parameter 'credential' must be a string"))?;
+            let credential = self.credentials.get(name).ok_or_else(|| {
+                let known: Vec<_> = self.credentials.keys().map(String::as_str).collect();
+                self.invalid_argument(if known.is_empty() {
+                    format!(
+                        "Unknown credential '{}'. No credentials are configured.",
+                        name
+                    )
+                } else {
+                    format!(
+                        "Unknown credential '{}'. Configured credentials: {}.",
+                        name,
+                        known.join(", ")
+                    )
+                })
+            })?;
+            if !credential.allows(&host) {
+                return Err(ToolError::PermissionDenied {
+                    tool: self.name().to_string(),
+                    message: format!(
+                        "Credential '{}' may only be sent to: {}",
+                        name,
+                        join_patterns(&credential.domains)
+                    ),
+                });
+            }
+            let value = credential
+                .header_value()
+                .map_err(|reason| ToolError::ExecutionFailed {
+                    tool: self.name().to_string(),
+                    message: format!("Credential '{}' is unavailable: {}", name, reason),
+                })?;
+            headers.insert(credential.header.clone(), value);
+            credential_host = Some(host);
+        }
+
+        Ok(HttpRequest {
+            method,
+            url,
+            headers,
+            body,
+            credential_host,
+        })
+    }
+
+    /// Parses the `headers` argument, an object of header names to values
+    fn parse_headers(&self, value: Option<&Value>) -> ToolResult<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        let entries = match value {
+            None | Some(Value::Null) => return Ok(headers),
+            Some(Value::Object(entries)) => entries,
+            Some(_) => return Err(self.invalid_argument("Parameter 'headers' must be an object")),
+        };
+
+        for (name, value) in entries {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| self.invalid_argument(format!("Invalid header name '{}'", name)))?;
+            if RESTRICTED_HEADERS.contains(&header.as_str()) {
+                return Err(self.invalid_argument(format!(
+                    "Header '{}' is set automatically and cannot be overridden",
+                    name
+                )));
+            }
+            let text = scalar_to_string(value).ok_or_else(|| {
+                self.invalid_argument(format!("Header '{}' must be a string", name))
+            })?;
+            let value = HeaderValue::from_str(&text).map_err(|_| {
+                self.invalid_argument(format!("Header '{}' has an invalid value", name))
+            })?;
+            headers.append(header, value);
+        }
+        Ok(headers)
+    }
+
+    /// Parses the `json` or `form` body arguments
+    fn parse_body(&self, args: &HashMap<String, Value>) -> ToolResult<RequestBody> {
+        let json = args.get("json").filter(|v| !v.is_null());
+        let form = args.get("form").filter(|v| !v.is_null());
+        match (json, form) {
+            (Some(_), Some(_)) => {
+                Err(self.invalid_argument("Specify either 'json' or 'form', not both"))
+            }
+            (Some(json), None) => Ok(RequestBody::Json(json.clone())),
+            (None, Some(Value::Object(fields))) => fields
+                .iter()
+                .map(|(name, value)| {
+                    scalar_to_string(value)
+                        .map(|value| (name.clone(), value))
+                        .ok_or_else(|| {
+                            self.invalid_argument(format!(
+                                "Form field '{}' must be a string, number or boolean",
+                                name
+                            ))
+                        })
+                })
+                .collect::<ToolResult<Vec<_>>>()
+                .map(RequestBody::Form),
+            (None, Some(_)) => Err(self.invalid_argument("Parameter 'form' must be an object")),
+            (None, None) => Ok(RequestBody::Empty),
+        }
     }
 
     /// Creates a configured HTTP client
     ///
     /// Configures the client with:
     /// - 30 second timeout
-    /// - Max 5 redirects
+    /// - The redirect and address checks of [`guarded_client_builder`], keeping
+    ///   redirects on the credential's host when a credential is attached
     /// - User-Agent header for proper identification
-    fn create_client(&self) -> ToolResult<reqwest::Client> {
-        reqwest::Client::builder()
+    fn create_client(&self, credential_host: Option<String>) -> ToolResult<reqwest::Client> {
+        guarded_client_builder(&self.domains, &self.addresses, credential_host)
             .timeout(Duration::from_secs(DEFAULT_WEB_TIMEOUT_SECS))
-            .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
             .user_agent("miniclaw/0.1.0 (autonomous-agent)")
             .build()
             .map_err(|e| ToolError::ExecutionFailed {
@@ -88,19 +705,66 @@ impl WebTool {
             })
     }
 
-    /// Fetches content from a URL
+    /// Sends a request and reads the response
+    ///
+    /// Bodies larger than `MAX_RESPONSE_SIZE` are cut at the limit. With a
+    /// cache, GET requests are answered from it while fresh, revalidated once
+    /// stale, and complete `200 OK` responses are stored.
     ///
     /// # Arguments
-    /// * `url` - The URL to fetch
+    /// * `request` - The validated request to send
+    /// * `max_age` - Age in seconds up to which a cached response is accepted,
+    ///   overriding the server's caching headers
     ///
     /// # Returns
-    /// * `Ok((status, content, content_type))` - Tuple of HTTP status, body content, and content type
+    /// * `Ok(FetchedPage)` - HTTP status, body content, content type and final URL
     /// * `Err(ToolError)` - If the request fails
-    async fn fetch_url(&self, url: &str) -> ToolResult<(u16, String, String)> {
-        let client = self.create_client()?;
+    async fn fetch(
+        &self,
+        mut request: HttpRequest,
+        max_age: Option<u64>,
+    ) -> ToolResult<FetchedPage> {
+        let cache = self.cache.as_ref().filter(|_| {
+            request.method == reqwest::Method::GET && request.body == RequestBody::Empty
+        });
+        let cache_key = cache.map(|_| {
+            HttpCache::key(
+                &request.url,
+                &request.headers,
+                request.credential_host.as_deref(),
+            )
+        });
+        let mut stale = None;
+        if let (Some(cache), Some(key)) = (cache, &cache_key) {
+            match cache.lookup(key, max_age).await {
+                Lookup::Fresh(page) => return Ok(FetchedPage::from_cache(page, &request.url)),
+                Lookup::Stale { page, validators } => {
+                    request.headers.extend(validators);
+                    stale = Some(page);
+                }
+                Lookup::Miss => {}
+            }
+        }
+        let request_url = request.url.clone();
+
+        let client = self.create_client(request.credential_host)?;
 
-        let response = client.get(url).send().await.map_err(|e| {
-            if e.is_timeout() {
+        let builder = client
+            .request(request.method, request.url)
+            .headers(request.headers);
+        let builder = match &request.body {
+            RequestBody::Empty => builder,
+            RequestBody::Json(json) => builder.json(json),
+            RequestBody::Form(fields) => builder.form(fields),
+        };
+
+        let mut response = builder.send().await.map_err(|e| {
+            if let Some(blocked) = ssrf::find_blocked(&e) {
+                ToolError::PermissionDenied {
+                    tool: self.name().to_string(),
+                    message: blocked.to_string(),
+                }
+            } else if e.is_timeout() {
                 ToolError::Timeout {
                     tool: self.name().to_string(),
                     duration: DEFAULT_WEB_TIMEOUT_SECS,
@@ -113,6 +777,15 @@ impl WebTool {
                         e
                     ),
                 }
+            } else if e.is_redirect() {
+                // The policy's reason is the source; reqwest's message only names the URL
+                let reason = std::error::Error::source(&e)
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| e.to_string());
+                ToolError::PermissionDenied {
+                    tool: self.name().to_string(),
+                    message: format!("Redirect not followed: {}", reason),
+                }
             } else {
                 ToolError::ExecutionFailed {
                     tool: self.name().to_string(),
@@ -122,6 +795,16 @@ impl WebTool {
         })?;
 
         let status = response.status().as_u16();
+        let final_url = response.url().clone();
+        let response_headers = response.headers().clone();
+
+        // Not modified since cached: reuse the stored body
+        if status == 304 {
+            if let (Some(cache), Some(key), Some(page)) = (cache, &cache_key, stale) {
+                cache.refresh(key, &response_headers).await;
+                return Ok(FetchedPage::from_cache(page, &request_url));
+            }
+        }
         let content_type = response
             .headers()
             .get(reqwest::header::CONTENT_TYPE)
@@ -129,92 +812,46 @@ impl WebTool {
             .unwrap_or("text/plain")
             .to_string();
 
-        // Get content with size limit
-        let content_bytes = response
-            .bytes()
+        // Read the body chunk by chunk, stopping at the size limit
+        let mut content_bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
             .await
             .map_err(|e| ToolError::ExecutionFailed {
                 tool: self.name().to_string(),
                 message: format!("Failed to read response body: {}", e),
-            })?;
-
-        // Truncate to MAX_RESPONSE_SIZE if needed (100KB limit per AC#4)
-        let content = if content_bytes.len() > MAX_RESPONSE_SIZE {
-            String::from_utf8_lossy(&content_bytes[..MAX_RESPONSE_SIZE]).to_string()
-        } else {
-            String::from_utf8_lossy(&content_bytes).to_string()
-        };
-
-        Ok((status, content, content_type))
-    }
-
-    /// Extracts text content from HTML
-    ///
-    /// Strips HTML tags while preserving text structure.
-    /// Uses a simple state machine approach to avoid regex dependency.
-    /// Decodes common HTML entities for readability.
-    ///
-    /// # Arguments
-    /// * `html` - The HTML content to process
-    ///
-    /// # Returns
-    /// Plain text with HTML tags removed
-    ///
-    /// # Note
-    /// Only common HTML entities are decoded (&lt;, &gt;, &amp;, &quot;, &apos;, &nbsp;).
-    /// Complex or numeric entities beyond the basic set may not be decoded.
-    /// This is sufficient for most web content but may not handle all HTML5 entities.
-    fn extract_text_from_html(&self, html: &str) -> String {
-        // First pass: replace block-level closing tags with newlines for structure
-        let with_structure = html
-            .replace("<br>", "\n")
-            .replace("<br/>", "\n")
-            .replace("<br />", "\n")
-            .replace("</p>", "\n\n")
-            .replace("</div>", "\n")
-            .replace("</h1>", "\n\n")
-            .replace("</h2>", "\n\n")
-            .replace("</h3>", "\n\n")
-            .replace("</h4>", "\n")
-            .replace("</h5>", "\n")
-            .replace("</h6>", "\n")
-            .replace("</li>", "\n");
-
-        // Second pass: strip all HTML tags using state machine
-        let mut result = String::with_capacity(with_structure.len());
-        let mut in_tag = false;
-
-        for ch in with_structure.chars() {
-            if ch == '<' {
-                in_tag = true;
-            } else if ch == '>' {
-                in_tag = false;
-            } else if !in_tag {
-                result.push(ch);
+            })?
+        {
+            let remaining = MAX_RESPONSE_SIZE - content_bytes.len();
+            if chunk.len() > remaining {
+                content_bytes.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
             }
+            content_bytes.extend_from_slice(&chunk);
         }
 
-        // Third pass: decode common HTML entities
-        let decoded = result
-            .replace("&lt;", "<")
-            .replace("&gt;", ">")
-            .replace("&amp;", "&")
-            .replace("&quot;", "\"")
-            .replace("&apos;", "'")
-            .replace("&#39;", "'")
-            .replace("&#x27;", "'")
-            .replace("&nbsp;", " ");
-
-        // Normalize whitespace
-        let mut normalized_lines: Vec<&str> = Vec::new();
-        for line in decoded.lines() {
-            let trimmed = line.trim();
-            if !trimmed.is_empty() {
-                normalized_lines.push(trimmed);
+        let fetched = FetchedPage {
+            status,
+            content: String::from_utf8_lossy(&content_bytes).to_string(),
+            content_type,
+            url: final_url,
+            truncated,
+            cache_hit: false,
+        };
+        if let (Some(cache), Some(key)) = (cache, &cache_key) {
+            if status == 200 && !truncated {
+                let page = CachedPage {
+                    status,
+                    content: fetched.content.clone(),
+                    content_type: fetched.content_type.clone(),
+                    url: fetched.url.to_string(),
+                };
+                cache.store(key, &page, &response_headers).await;
             }
         }
-
-        normalized_lines.join("\n")
+        Ok(fetched)
     }
 
     /// Determines if content is HTML based on content type
@@ -224,18 +861,127 @@ impl WebTool {
 
     /// Processes the response content based on content type
     ///
-    /// - HTML: Strips tags and extracts text
+    /// - HTML: Extracts the readable main content and its links
     /// - JSON: Returns raw JSON
     /// - Other: Returns as-is
-    fn process_content(&self, content: &str, content_type: &str) -> String {
+    fn process_content(
+        &self,
+        content: &str,
+        content_type: &str,
+        base_url: Option<&reqwest::Url>,
+    ) -> Article {
         if self.is_html_content(content_type) {
-            self.extract_text_from_html(content)
+            html::extract(content, base_url)
         } else {
-            content.to_string()
+            Article {
+                content: content.to_string(),
+                ..Article::default()
+            }
+        }
+    }
+
+    /// Resolves the `offset` and `page` arguments to a character offset
+    fn requested_offset(&self, args: &HashMap<String, Value>) -> ToolResult<usize> {
+        let argument = |name: &str| -> ToolResult<Option<usize>> {
+            match args.get(name) {
+                None | Some(Value::Null) => Ok(None),
+                Some(value) => value.as_u64().map(|n| Some(n as usize)).ok_or_else(|| {
+                    ToolError::InvalidArguments {
+                        tool: self.name().to_string(),
+                        message: format!("Parameter '{}' must be a non-negative integer", name),
+                    }
+                }),
+            }
+        };
+
+        match (argument("offset")?, argument("page")?) {
+            (Some(_), Some(_)) => Err(ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: "Specify either 'offset' or 'page', not both".to_string(),
+            }),
+            (Some(offset), None) => Ok(offset),
+            (None, Some(0)) => Err(ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: "Parameter 'page' starts at 1".to_string(),
+            }),
+            (None, Some(page)) => Ok((page - 1).saturating_mul(PAGE_SIZE)),
+            (None, None) => Ok(0),
         }
     }
 }
 
+/// A fetched HTTP response
+#[derive(Debug)]
+struct FetchedPage {
+    status: u16,
+    content: String,
+    content_type: String,
+    /// URL after redirects, used to resolve relative links
+    url: reqwest::Url,
+    /// Whether the body was cut at `MAX_RESPONSE_SIZE`
+    truncated: bool,
+    /// Whether the body came from the HTTP cache
+    cache_hit: bool,
+}
+
+impl FetchedPage {
+    fn from_cache(page: CachedPage, request_url: &reqwest::Url) -> Self {
+        Self {
+            status: page.status,
+            url: reqwest::Url::parse(&page.url).unwrap_or_else(|_| request_url.clone()),
+            content: page.content,
+            content_type: page.content_type,
+            truncated: false,
+            cache_hit: true,
+        }
+    }
+}
+
+/// Converts a string, number or boolean argument to text
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// One page of a longer text
+#[derive(Debug, PartialEq, Eq)]
+struct ContentPage<'a> {
+    text: &'a str,
+    /// Character offset of the page
+    offset: usize,
+    /// Length of the whole text in characters
+    total_length: usize,
+    /// Character offset of the following page, if any
+    next_offset: Option<usize>,
+}
+
+/// Returns the `PAGE_SIZE` characters of `text` starting at character `offset`
+///
+/// Returns `None` when the offset lies past the end of a non-empty text.
+fn paginate(text: &str, offset: usize) -> Option<ContentPage<'_>> {
+    let total_length = text.chars().count();
+    if offset > 0 && offset >= total_length {
+        return None;
+    }
+    let byte_at = |chars: usize| {
+        text.char_indices()
+            .nth(chars)
+            .map(|(index, _)| index)
+            .unwrap_or(text.len())
+    };
+    let end = offset.saturating_add(PAGE_SIZE).min(total_length);
+    Some(ContentPage {
+        text: &text[byte_at(offset)..byte_at(end)],
+        offset,
+        total_length,
+        next_offset: (end < total_length).then_some(end),
+    })
+}
+
 impl Default for WebTool {
     fn default() -> Self {
         Self::new()
@@ -249,9 +995,7 @@ impl Tool for WebTool {
     }
 
     fn description(&self) -> &str {
-        "Fetches web content from a URL. Supports HTML (strips tags), JSON (returns raw), and plain text. \
-         HTTP/HTTPS only. Follows up to 5 redirects. 30-second timeout. Max 100KB response size. \
-         Assumes UTF-8 encoding for all content."
+        &self.description
     }
 
     fn parameters(&self) -> Value {
@@ -261,6 +1005,40 @@ impl Tool for WebTool {
                 "url": {
                     "type": "string",
                     "description": "The URL to fetch. Must be http:// or https://"
+                },
+                "page": {
+                    "type": "integer",
+                    "description": "Page of the content to return, starting at 1 (default: 1)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Character offset to start reading from (alternative to 'page')"
+                },
+                "method": {
+                    "type": "string",
+                    "enum": METHODS,
+                    "description": "HTTP method (default: GET)"
+                },
+                "headers": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Extra request headers"
+                },
+                "json": {
+                    "description": "JSON request body (not with GET)"
+                },
+                "form": {
+                    "type": "object",
+                    "description": "Form fields sent as application/x-www-form-urlencoded (not with GET)"
+                },
+                "credential": {
+                    "type": "string",
+                    "description": "Name of a configured credential to authenticate the request with"
+                },
+                "max_age": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Accept a cached copy up to this many seconds old, whatever the server's caching headers say; 0 always checks with the server"
                 }
             },
             "required": ["url"]
@@ -272,24 +1050,29 @@ impl Tool for WebTool {
         args: HashMap<String, Value>,
         _ctx: &ToolExecutionContext,
     ) -> ToolResult<String> {
-        // Extract URL parameter
-        let url = args.get("url").and_then(|v| v.as_str()).ok_or_else(|| {
-            ToolError::InvalidArguments {
-                tool: self.name().to_string(),
-                message: "Missing required parameter 'url'".to_string(),
-            }
-        })?;
-
-        // Validate URL
-        self.validate_url(url)?;
+        // Validate the request and pagination
+        let request = self.build_request(&args)?;
+        let offset = self.requested_offset(&args)?;
+        let max_age = match args.get("max_age") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(value.as_u64().ok_or_else(|| {
+                self.invalid_argument("Parameter 'max_age' must be a non-negative integer")
+            })?),
+        };
 
-        // Fetch the content
-        let (status, content, content_type) = self.fetch_url(url).await?;
+        // Send the request
+        let fetched = self.fetch(request, max_age).await?;
+        let status = fetched.status;
+        let content = &fetched.content;
 
         // Check for HTTP errors (4xx, 5xx)
         if status >= 400 {
             let error_body = if content.len() > MAX_ERROR_BODY_SIZE {
-                format!("{}... (truncated)", &content[..MAX_ERROR_BODY_SIZE])
+                let end = (0..=MAX_ERROR_BODY_SIZE)
+                    .rev()
+                    .find(|&i| content.is_char_boundary(i))
+                    .unwrap_or(0);
+                format!("{}... (truncated)", &content[..end])
             } else {
                 content.clone()
             };
@@ -317,13 +1100,40 @@ impl Tool for WebTool {
         }
 
         // Process content based on type
-        let processed_content = self.process_content(&content, &content_type);
+        let article = self.process_content(content, &fetched.content_type, Some(&fetched.url));
+
+        let page =
+            paginate(&article.content, offset).ok_or_else(|| ToolError::InvalidArguments {
+                tool: self.name().to_string(),
+                message: format!(
+                    "Offset {} is past the end of the content ({} characters)",
+                    offset,
+                    article.content.chars().count()
+                ),
+            })?;
+
+        // Only list the links referenced on this page
+        let links: Vec<_> = article
+            .links
+            .iter()
+            .filter(|link| page.text.contains(&format!("][{}]", link.index)))
+            .collect();
 
         // Return JSON response
         let result = serde_json::json!({
             "status": status,
-            "content": processed_content,
-            "content_type": content_type
+            "url": fetched.url.as_str(),
+            "content_type": fetched.content_type,
+            "title": article.title,
+            "content": page.text,
+            "links": links,
+            "offset": page.offset,
+            "page": page.offset / PAGE_SIZE + 1,
+            "total_pages": page.total_length.div_ceil(PAGE_SIZE).max(1),
+            "total_length": page.total_length,
+            "next_offset": page.next_offset,
+            "truncated": fetched.truncated,
+            "cache_hit": fetched.cache_hit
         });
 
         Ok(result.to_string())
@@ -412,7 +1222,7 @@ mod tests {
     fn test_extract_text_from_html_basic() {
         let tool = WebTool::new();
         let html = "<html><body><h1>Title</h1><p>Paragraph text</p></body></html>";
-        let text = tool.extract_text_from_html(html);
+        let text = tool.process_content(html, "text/html", None).content;
         assert!(text.contains("Title"));
         assert!(text.contains("Paragraph text"));
         assert!(!text.contains("<html>"));
@@ -423,7 +1233,7 @@ mod tests {
     fn test_extract_text_from_html_with_br() {
         let tool = WebTool::new();
         let html = "Line 1<br>Line 2<br/>Line 3";
-        let text = tool.extract_text_from_html(html);
+        let text = tool.process_content(html, "text/html", None).content;
         assert!(text.contains("Line 1"));
         assert!(text.contains("Line 2"));
         assert!(text.contains("Line 3"));
@@ -435,7 +1245,7 @@ mod tests {
     fn test_extract_text_from_html_entities() {
         let tool = WebTool::new();
         let html = "Text with &lt;tags&gt; and &amp; symbols";
-        let text = tool.extract_text_from_html(html);
+        let text = tool.process_content(html, "text/html", None).content;
         assert!(text.contains("<tags>"));
         assert!(text.contains("&"));
         assert!(!text.contains("&lt;"));
@@ -447,7 +1257,7 @@ mod tests {
     fn test_extract_text_from_html_nested_tags() {
         let tool = WebTool::new();
         let html = "<div><p><span>Nested content</span></p></div>";
-        let text = tool.extract_text_from_html(html);
+        let text = tool.process_content(html, "text/html", None).content;
         assert!(text.contains("Nested content"));
         assert!(!text.contains("<div>"));
         assert!(!text.contains("<p>"));
@@ -458,7 +1268,7 @@ mod tests {
     fn test_process_content_html() {
         let tool = WebTool::new();
         let html = "<p>Hello <b>World</b></p>";
-        let result = tool.process_content(html, "text/html");
+        let result = tool.process_content(html, "text/html", None).content;
         assert!(result.contains("Hello"));
         assert!(result.contains("World"));
         assert!(!result.contains("<p>"));
@@ -469,7 +1279,7 @@ mod tests {
     fn test_process_content_json() {
         let tool = WebTool::new();
         let json = r#"{"key": "value"}"#;
-        let result = tool.process_content(json, "application/json");
+        let result = tool.process_content(json, "application/json", None).content;
         assert_eq!(result, json);
     }
 
@@ -477,7 +1287,7 @@ mod tests {
     fn test_process_content_plain() {
         let tool = WebTool::new();
         let text = "Plain text content";
-        let result = tool.process_content(text, "text/plain");
+        let result = tool.process_content(text, "text/plain", None).content;
         assert_eq!(result, text);
     }
 
@@ -487,6 +1297,8 @@ mod tests {
         let params = tool.parameters();
         assert_eq!(params["type"], "object");
         assert!(params["properties"]["url"]["type"] == "string");
+        assert!(params["properties"]["page"]["type"] == "integer");
+        assert!(params["properties"]["offset"]["type"] == "integer");
         assert!(
             params["required"]
                 .as_array()
@@ -549,8 +1361,8 @@ mod tests {
     fn test_max_response_size_truncation() {
         let _tool = WebTool::new();
 
-        // Create content larger than MAX_RESPONSE_SIZE (100KB)
-        let large_content = "x".repeat(150 * 1024); // 150KB
+        // Create content larger than MAX_RESPONSE_SIZE (5MB)
+        let large_content = "x".repeat(MAX_RESPONSE_SIZE + 50 * 1024);
 
         // Simulate what happens in fetch_url when content exceeds limit
         let content_bytes = large_content.as_bytes();
@@ -583,4 +1395,561 @@ mod tests {
         assert!(error_body.contains("... (truncated)"));
         assert!(error_body.len() < large_error.len() + 100); // Some buffer for " (truncated)"
     }
+
+    #[test]
+    fn test_process_content_html_links() {
+        let tool = WebTool::new();
+        let base = reqwest::Url::parse("https://example.com/blog/post").unwrap();
+        let html = r#"<html><head><title>Post</title></head>
+            <body><p>Read <a href="../about">about us</a></p></body></html>"#;
+        let article = tool.process_content(html, "text/html; charset=utf-8", Some(&base));
+        assert_eq!(article.title.as_deref(), Some("Post"));
+        assert_eq!(article.content, "Read [about us][1]");
+        assert_eq!(article.links[0].url, "https://example.com/about");
+    }
+
+    #[test]
+    fn test_paginate() {
+        let text = "é".repeat(PAGE_SIZE * 2 + 10);
+
+        let first = paginate(&text, 0).unwrap();
+        assert_eq!(first.text.chars().count(), PAGE_SIZE);
+        assert_eq!(first.total_length, PAGE_SIZE * 2 + 10);
+        assert_eq!(first.next_offset, Some(PAGE_SIZE));
+
+        let last = paginate(&text, PAGE_SIZE * 2).unwrap();
+        assert_eq!(last.text.chars().count(), 10);
+        assert_eq!(last.next_offset, None);
+
+        let middle = paginate(&text, 5).unwrap();
+        assert_eq!(middle.offset, 5);
+        assert_eq!(middle.next_offset, Some(PAGE_SIZE + 5));
+
+        assert!(paginate(&text, PAGE_SIZE * 2 + 10).is_none());
+        assert_eq!(paginate("", 0).unwrap().text, "");
+    }
+
+    #[test]
+    fn test_requested_offset() {
+        let tool = WebTool::new();
+        let args = |pairs: &[(&str, Value)]| -> HashMap<String, Value> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect()
+        };
+
+        assert_eq!(tool.requested_offset(&args(&[])).unwrap(), 0);
+        assert_eq!(
+            tool.requested_offset(&args(&[("offset", serde_json::json!(1234))]))
+                .unwrap(),
+            1234
+        );
+        assert_eq!(
+            tool.requested_offset(&args(&[("page", serde_json::json!(3))]))
+                .unwrap(),
+            PAGE_SIZE * 2
+        );
+        for invalid in [
+            args(&[("page", serde_json::json!(0))]),
+            args(&[("offset", serde_json::json!(-1))]),
+            args(&[("page", serde_json::json!("two"))]),
+            args(&[
+                ("page", serde_json::json!(1)),
+                ("offset", serde_json::json!(0)),
+            ]),
+        ] {
+            assert!(matches!(
+                tool.requested_offset(&invalid),
+                Err(ToolError::InvalidArguments { .. })
+            ));
+        }
+    }
+
+    fn args(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn configured_tool(json: &str) -> WebTool {
+        let config: WebToolConfig = serde_json::from_str(json).unwrap();
+        WebTool::from_config(&config).unwrap()
+    }
+
+    /// Serves a single connection on localhost and returns its base URL
+    async fn serve_once(respond: fn(&str) -> String) -> String {
+        serve(respond, 1).await
+    }
+
+    /// Serves `connections` connections on localhost, one at a time, and
+    /// returns the base URL; later connections are refused
+    async fn serve(respond: fn(&str) -> String, connections: usize) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for _ in 0..connections {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut data = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    data.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&data).to_ascii_lowercase();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .and_then(|len| len.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if data.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+                let response = respond(&String::from_utf8_lossy(&data));
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn echo(request: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            request.len(),
+            request
+        )
+    }
+
+    #[test]
+    fn test_domain_policy() {
+        let policy = DomainPolicy::new(
+            &["example.com".to_string(), "*.internal.test".to_string()],
+            &["secret.example.com".to_string()],
+        )
+        .unwrap();
+        assert!(policy.check("example.com").is_ok());
+        assert!(policy.check("docs.Example.COM.").is_ok());
+        assert!(policy.check("api.internal.test").is_ok());
+        assert!(policy.check("internal.test").is_err());
+        assert!(policy.check("notexample.com").is_err());
+        let denied = policy.check("secret.example.com").unwrap_err();
+        assert!(denied.contains("denylist"));
+        let not_allowed = policy.check("other.org").unwrap_err();
+        assert!(not_allowed.contains("allowlist"));
+
+        // Without an allowlist every host that is not denied is reachable
+        let policy = DomainPolicy::new(&[], &["ads.test".to_string()]).unwrap();
+        assert!(policy.check("anything.org").is_ok());
+        assert!(policy.check("tracker.ads.test").is_err());
+
+        for invalid in [
+            "",
+            "http://example.com",
+            "example.com/path",
+            "example.com:8080",
+            "a*.b",
+        ] {
+            assert!(
+                DomainPolicy::new(&[invalid.to_string()], &[]).is_err(),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_credentials_rejected() {
+        for credential in [
+            r#"{"value": "x", "env": "X", "domains": ["a.test"]}"#,
+            r#"{"domains": ["a.test"]}"#,
+            r#"{"value": "x", "domains": []}"#,
+            r#"{"value": "x", "header": "Bad Header", "domains": ["a.test"]}"#,
+        ] {
+            let config: WebToolConfig =
+                serde_json::from_str(&format!(r#"{{"credentials": {{"api": {}}}}}"#, credential))
+                    .unwrap();
+            assert!(
+                WebTool::from_config(&config).is_err(),
+                "{} should be rejected",
+                credential
+            );
+        }
+
+        let config: WebToolConfig = serde_json::from_str(
+            r#"{"credentials": {"bad name": {"value": "x", "domains": ["a.test"]}}}"#,
+        )
+        .unwrap();
+        assert!(WebTool::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_credential_secrets_not_in_debug_output() {
+        let config: WebToolConfig = serde_json::from_str(
+            r#"{"credentials": {"api": {"value": "hunter2", "domains": ["a.test"]}}}"#,
+        )
+        .unwrap();
+        assert!(!format!("{:?}", config).contains("hunter2"));
+        let tool = WebTool::from_config(&config).unwrap();
+        assert!(!format!("{:?}", tool).contains("hunter2"));
+        assert!(tool.description().contains("Configured credentials: api."));
+    }
+
+    #[test]
+    fn test_build_request_defaults_to_get() {
+        let tool = WebTool::new();
+        let request = tool
+            .build_request(&args(&[("url", serde_json::json!("https://example.com/"))]))
+            .unwrap();
+        assert_eq!(request.method, reqwest::Method::GET);
+        assert!(request.headers.is_empty());
+        assert_eq!(request.body, RequestBody::Empty);
+        assert_eq!(request.credential_host, None);
+    }
+
+    #[test]
+    fn test_build_request_method_headers_and_body() {
+        let tool = WebTool::new();
+        let request = tool
+            .build_request(&args(&[
+                ("url", serde_json::json!("https://example.com/api")),
+                ("method", serde_json::json!("patch")),
+                (
+                    "headers",
+                    serde_json::json!({"X-Trace": "abc", "X-Count": 3}),
+                ),
+                ("json", serde_json::json!({"state": "on"})),
+            ]))
+            .unwrap();
+        assert_eq!(request.method, reqwest::Method::PATCH);
+        assert_eq!(request.headers["x-trace"], "abc");
+        assert_eq!(request.headers["x-count"], "3");
+        assert_eq!(
+            request.body,
+            RequestBody::Json(serde_json::json!({"state": "on"}))
+        );
+
+        let request = tool
+            .build_request(&args(&[
+                ("url", serde_json::json!("https://example.com/login")),
+                ("method", serde_json::json!("POST")),
+                ("form", serde_json::json!({"user": "me", "remember": true})),
+            ]))
+            .unwrap();
+        assert_eq!(
+            request.body,
+            RequestBody::Form(vec![
+                ("remember".to_string(), "true".to_string()),
+                ("user".to_string(), "me".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_build_request_rejects_invalid_arguments() {
+        let tool = WebTool::new();
+        let url = ("url", serde_json::json!("https://example.com/"));
+        for invalid in [
+            args(&[url.clone(), ("method", serde_json::json!("TRACE"))]),
+            args(&[url.clone(), ("headers", serde_json::json!(["X-A"]))]),
+            args(&[
+                url.clone(),
+                ("headers", serde_json::json!({"Host": "other"})),
+            ]),
+            args(&[
+                url.clone(),
+                ("headers", serde_json::json!({"X-A": {"nested": 1}})),
+            ]),
+            args(&[url.clone(), ("json", serde_json::json!({"a": 1}))]),
+            args(&[
+                url.clone(),
+                ("method", serde_json::json!("POST")),
+                ("json", serde_json::json!({})),
+                ("form", serde_json::json!({})),
+            ]),
+            args(&[
+                url.clone(),
+                ("method", serde_json::json!("POST")),
+                ("form", serde_json::json!({"a": [1]})),
+            ]),
+            args(&[url.clone(), ("credential", serde_json::json!("missing"))]),
+        ] {
+            assert!(
+                matches!(
+                    tool.build_request(&invalid),
+                    Err(ToolError::InvalidArguments { .. })
+                ),
+                "{:?} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_request_checks_domain_policy() {
+        let tool = configured_tool(r#"{"allowed_domains": ["example.com"]}"#);
+        assert!(
+            tool.build_request(&args(&[(
+                "url",
+                serde_json::json!("https://www.example.com/")
+            )]))
+            .is_ok()
+        );
+        match tool.build_request(&args(&[("url", serde_json::json!("https://evil.test/"))])) {
+            Err(ToolError::PermissionDenied { message, .. }) => {
+                assert!(message.contains("allowlist"))
+            }
+            other => panic!("expected PermissionDenied, got {:?}", other),
+        }
+        assert!(
+            tool.description()
+                .contains("Only these hosts can be reached: example.com.")
+        );
+    }
+
+    #[test]
+    fn test_build_request_attaches_credential() {
+        let tool = configured_tool(
+            r#"{"credentials": {
+                "home": {"prefix": "Bearer ", "value": "s3cret", "domains": ["homeassistant.local"]},
+                "env_key": {"header": "X-Api-Key", "env": "MINICLAW_TEST_WEB_MISSING_KEY", "domains": ["api.test"]}
+            }}"#,
+        );
+
+        let request = tool
+            .build_request(&args(&[
+                (
+                    "url",
+                    serde_json::json!("http://homeassistant.local:8123/api/states"),
+                ),
+                ("credential", serde_json::json!("home")),
+            ]))
+            .unwrap();
+        let header = &request.headers[reqwest::header::AUTHORIZATION];
+        assert_eq!(header, "Bearer s3cret");
+        assert!(header.is_sensitive());
+        assert_eq!(
+            request.credential_host.as_deref(),
+            Some("homeassistant.local")
+        );
+
+        // Credentials are bound to their domains
+        match tool.build_request(&args(&[
+            ("url", serde_json::json!("https://attacker.test/collect")),
+            ("credential", serde_json::json!("home")),
+        ])) {
+            Err(ToolError::PermissionDenied { message, .. }) => {
+                assert!(message.contains("homeassistant.local"));
+                assert!(!message.contains("s3cret"));
+            }
+            other => panic!("expected PermissionDenied, got {:?}", other),
+        }
+
+        // Missing environment variables are reported without failing the config
+        match tool.build_request(&args(&[
+            ("url", serde_json::json!("https://api.test/")),
+            ("credential", serde_json::json!("env_key")),
+        ])) {
+            Err(ToolError::ExecutionFailed { message, .. }) => {
+                assert!(message.contains("MINICLAW_TEST_WEB_MISSING_KEY"))
+            }
+            other => panic!("expected ExecutionFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_post_with_credential() {
+        let base_url = serve_once(echo).await;
+        let tool = configured_tool(
+            r#"{"credentials": {"local": {"header": "X-Api-Key", "value": "k3y", "domains": ["127.0.0.1"]}}, "local_services": ["127.0.0.1"]}"#,
+        );
+
+        let result = tool
+            .execute(
+                args(&[
+                    ("url", serde_json::json!(format!("{}/api/light", base_url))),
+                    ("method", serde_json::json!("POST")),
+                    ("headers", serde_json::json!({"X-Client": "miniclaw"})),
+                    ("json", serde_json::json!({"on": true})),
+                    ("credential", serde_json::json!("local")),
+                ]),
+                &ToolExecutionContext::default(),
+            )
+            .await
+            .unwrap();
+        let output: Value = serde_json::from_str(&result).unwrap();
+        let request = output["content"].as_str().unwrap();
+        assert!(request.starts_with("POST /api/light HTTP/1.1"));
+        assert!(request.contains("x-api-key: k3y"));
+        assert!(request.contains("x-client: miniclaw"));
+        assert!(request.contains("content-type: application/json"));
+        assert!(request.ends_with(r#"{"on":true}"#));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_denied_host_refused() {
+        fn redirect(_request: &str) -> String {
+            "HTTP/1.1 302 Found\r\nLocation: http://blocked.test/\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string()
+        }
+        let base_url = serve_once(redirect).await;
+        let tool = configured_tool(
+            r#"{"denied_domains": ["blocked.test"], "local_services": ["127.0.0.1"]}"#,
+        );
+
+        let result = tool
+            .execute(
+                args(&[("url", serde_json::json!(base_url))]),
+                &ToolExecutionContext::default(),
+            )
+            .await;
+        match result {
+            Err(ToolError::PermissionDenied { message, .. }) => {
+                assert!(message.contains("denylist"), "{}", message)
+            }
+            other => panic!("expected PermissionDenied, got {:?}", other),
+        }
+    }
+
+    fn expect_blocked(result: ToolResult<String>, kind: &str) {
+        match result {
+            Err(ToolError::PermissionDenied { message, .. }) => {
+                assert!(message.contains(kind), "{}", message);
+                assert!(message.contains("local_services"), "{}", message);
+            }
+            other => panic!("expected PermissionDenied, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_private_addresses_refused() {
+        let tool = WebTool::new();
+        for (url, kind) in [
+            ("http://169.254.169.254/latest/meta-data/", "link-local"),
+            ("http://127.0.0.1:11434/api/delete", "loopback"),
+            ("http://[::1]/", "loopback"),
+            ("http://192.168.1.1/admin", "private"),
+            // Resolved by the guarded resolver rather than parsed as a literal
+            ("http://localhost:9/", "loopback"),
+        ] {
+            let result = tool
+                .execute(
+                    args(&[("url", serde_json::json!(url))]),
+                    &ToolExecutionContext::default(),
+                )
+                .await;
+            expect_blocked(result, kind);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_services_allowlist() {
+        let base_url = serve_once(echo).await;
+        let url = serde_json::json!(format!("{}/status", base_url));
+
+        let tool = configured_tool(r#"{"local_services": ["127.0.0.0/8"]}"#);
+        let result = tool
+            .execute(
+                args(&[("url", url.clone())]),
+                &ToolExecutionContext::default(),
+            )
+            .await
+            .unwrap();
+        let output: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(output["status"], 200);
+
+        let tool = configured_tool(r#"{"local_services": ["10.0.0.0/8"]}"#);
+        let result = tool
+            .execute(args(&[("url", url)]), &ToolExecutionContext::default())
+            .await;
+        expect_blocked(result, "loopback");
+
+        assert!(
+            WebTool::from_config(&WebToolConfig {
+                local_services: vec!["10.0.0.0/40".to_string()],
+                ..WebToolConfig::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_private_address_refused() {
+        fn redirect(_request: &str) -> String {
+            "HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string()
+        }
+        let base_url = serve_once(redirect).await;
+        let tool = configured_tool(r#"{"local_services": ["127.0.0.1"]}"#);
+
+        let result = tool
+            .execute(
+                args(&[("url", serde_json::json!(base_url))]),
+                &ToolExecutionContext::default(),
+            )
+            .await;
+        expect_blocked(result, "link-local");
+    }
+
+    #[tokio::test]
+    async fn test_execute_uses_cache() {
+        fn respond(request: &str) -> String {
+            if request
+                .to_ascii_lowercase()
+                .contains("if-none-match: \"v1\"")
+            {
+                "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n"
+                    .to_string()
+            } else {
+                let body = "<html><body><p>Cached page</p></body></html>";
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nETag: \"v1\"\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            }
+        }
+        // Two requests reach the server: the first fetch and one revalidation
+        let base_url = serve(respond, 2).await;
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let tool = configured_tool(r#"{"local_services": ["127.0.0.1"]}"#)
+            .with_cache(HttpCache::new(cache_dir.path().to_path_buf(), 1024 * 1024));
+
+        let fetch = |max_age: Option<u64>| {
+            let mut call = args(&[("url", serde_json::json!(format!("{}/page", base_url)))]);
+            if let Some(max_age) = max_age {
+                call.insert("max_age".to_string(), serde_json::json!(max_age));
+            }
+            let tool = &tool;
+            async move {
+                let result = tool
+                    .execute(call, &ToolExecutionContext::default())
+                    .await
+                    .unwrap();
+                serde_json::from_str::<Value>(&result).unwrap()
+            }
+        };
+
+        let first = fetch(None).await;
+        assert_eq!(first["cache_hit"], false);
+        assert!(first["content"].as_str().unwrap().contains("Cached page"));
+
+        // no-cache: revalidated with If-None-Match and answered 304
+        let second = fetch(None).await;
+        assert_eq!(second["cache_hit"], true);
+        assert_eq!(second["status"], 200);
+        assert_eq!(second["content"], first["content"]);
+
+        // max_age accepts the stored copy without contacting the server
+        let third = fetch(Some(3600)).await;
+        assert_eq!(third["cache_hit"], true);
+        assert_eq!(third["content"], first["content"]);
+    }
 }
@@ -0,0 +1,1068 @@
+//! Web search tool for the agent
+//!
+//! Queries a configured search backend and normalizes its answers into one
+//! schema (title, URL, snippet) whose URLs can be passed straight to the
+//! `web` tool. Supported backends are a SearXNG instance, the Brave Search
+//! and Tavily APIs, and DuckDuckGo's HTML endpoint, which needs no API key
+//! and doubles as the fallback when the configured backend fails.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agent::tools::html;
+use crate::agent::tools::types::{Tool, ToolError, ToolExecutionContext, ToolResult};
+
+/// Default timeout for search requests in seconds
+const DEFAULT_SEARCH_TIMEOUT_SECS: u64 = 20;
+
+/// Number of results returned when the call does not ask for a count
+const DEFAULT_MAX_RESULTS: usize = 5;
+
+/// Upper bound on the results of a single search
+const MAX_RESULTS_LIMIT: usize = 20;
+
+/// Snippets longer than this many characters are shortened
+const MAX_SNIPPET_CHARS: usize = 400;
+
+/// Public endpoints of the hosted backends
+const BRAVE_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const TAVILY_URL: &str = "https://api.tavily.com/search";
+const DUCKDUCKGO_URL: &str = "https://html.duckduckgo.com/html/";
+
+/// Time ranges a search can be restricted to
+const TIME_RANGES: &[&str] = &["day", "week", "month", "year"];
+
+static TAG_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<[^>]*>").expect("valid regex"));
+
+static ANCHOR_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<a\b([^>]*)>(.*?)</a\s*>").expect("valid regex"));
+
+static ATTRIBUTE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(class|href)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid regex")
+});
+
+/// Search backends that can be configured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchBackendKind {
+    /// A SearXNG instance with the JSON output format enabled
+    Searxng,
+    /// The Brave Search API
+    Brave,
+    /// The Tavily search API
+    Tavily,
+    /// DuckDuckGo's HTML results page
+    #[default]
+    Duckduckgo,
+}
+
+impl SearchBackendKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Searxng => "searxng",
+            Self::Brave => "brave",
+            Self::Tavily => "tavily",
+            Self::Duckduckgo => "duckduckgo",
+        }
+    }
+}
+
+/// Backend and limits of the web search tool.
+///
+/// Missing fields fall back to the built-in defaults.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSearchConfig {
+    /// Backend queried for results
+    pub backend: SearchBackendKind,
+    /// Base URL of the SearXNG instance; for the hosted APIs, overrides the
+    /// public endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// API key of Brave or Tavily
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Environment variable holding the API key, read when searching
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Results returned when a call does not ask for a count
+    pub max_results: usize,
+    /// Retry failed searches on DuckDuckGo
+    pub fallback: bool,
+}
+
+impl Default for WebSearchConfig {
+    fn default() -> Self {
+        Self {
+            backend: SearchBackendKind::default(),
+            url: None,
+            api_key: None,
+            api_key_env: None,
+            max_results: DEFAULT_MAX_RESULTS,
+            fallback: true,
+        }
+    }
+}
+
+impl std::fmt::Debug for WebSearchConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSearchConfig")
+            .field("backend", &self.backend)
+            .field("url", &self.url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("api_key_env", &self.api_key_env)
+            .field("max_results", &self.max_results)
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+/// Errors raised while building the web search tool from its config
+#[derive(Debug, thiserror::Error)]
+pub enum WebSearchConfigError {
+    #[error("The {0} backend needs a 'url'")]
+    MissingUrl(&'static str),
+    #[error("Invalid URL '{0}': expected an http:// or https:// URL")]
+    InvalidUrl(String),
+    #[error("The {0} backend needs exactly one of 'api_key' and 'api_key_env'")]
+    MissingApiKey(&'static str),
+    #[error("max_results must be between 1 and {MAX_RESULTS_LIMIT}")]
+    InvalidMaxResults,
+}
+
+/// One search result, the same whichever backend produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchResult {
+    /// Page title
+    pub title: String,
+    /// Absolute http(s) URL, ready to pass to the `web` tool
+    pub url: String,
+    /// Text excerpt describing the page
+    pub snippet: String,
+    /// Publication date when the backend reports one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+}
+
+/// A search request as seen by the backends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub text: String,
+    /// Number of results wanted; backends may return more or fewer
+    pub limit: usize,
+    /// One of [`TIME_RANGES`]
+    pub time_range: Option<String>,
+}
+
+/// A source of search results
+///
+/// Implementations send `query` to their service and return its results in
+/// order of relevance; the tool cleans, deduplicates and truncates them.
+/// Errors are plain messages shown to the agent.
+#[async_trait]
+pub trait SearchBackend: Send + Sync + std::fmt::Debug {
+    /// Name reported in the tool output
+    fn name(&self) -> &'static str;
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>, String>;
+}
+
+/// An API key given in the config or read from the environment
+#[derive(Clone)]
+enum ApiKey {
+    Value(String),
+    Env(String),
+}
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Value(_) => write!(f, "<redacted>"),
+            Self::Env(var) => write!(f, "${}", var),
+        }
+    }
+}
+
+impl ApiKey {
+    fn from_config(
+        config: &WebSearchConfig,
+        backend: &'static str,
+    ) -> Result<Self, WebSearchConfigError> {
+        match (&config.api_key, &config.api_key_env) {
+            (Some(key), None) if !key.is_empty() => Ok(Self::Value(key.clone())),
+            (None, Some(var)) if !var.is_empty() => Ok(Self::Env(var.clone())),
+            _ => Err(WebSearchConfigError::MissingApiKey(backend)),
+        }
+    }
+
+    fn resolve(&self) -> Result<String, String> {
+        match self {
+            Self::Value(key) => Ok(key.clone()),
+            Self::Env(var) => std::env::var(var)
+                .ok()
+                .filter(|key| !key.is_empty())
+                .ok_or_else(|| format!("environment variable {} is not set", var)),
+        }
+    }
+}
+
+/// Checks that a response succeeded and returns its body
+async fn read_body(response: reqwest::Response) -> Result<String, String> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    if !status.is_success() {
+        let excerpt: String = body.chars().take(200).collect();
+        return Err(format!("HTTP {}: {}", status.as_u16(), excerpt.trim()));
+    }
+    Ok(body)
+}
+
+fn parse_json(body: &str) -> Result<Value, String> {
+    serde_json::from_str(body).map_err(|e| format!("Invalid JSON response: {}", e))
+}
+
+/// Reads a string field, treating missing and non-string values as empty
+fn field<'a>(item: &'a Value, name: &str) -> &'a str {
+    item.get(name).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn optional_field(item: &Value, name: &str) -> Option<String> {
+    item.get(name)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// A self-hosted SearXNG instance
+#[derive(Debug, Clone)]
+pub struct Searxng {
+    url: reqwest::Url,
+}
+
+impl Searxng {
+    pub fn new(url: reqwest::Url) -> Self {
+        Self { url }
+    }
+}
+
+#[async_trait]
+impl SearchBackend for Searxng {
+    fn name(&self) -> &'static str {
+        "searxng"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>, String> {
+        let mut url = self.url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().push("search");
+        }
+        let mut params = vec![("q", query.text.as_str()), ("format", "json")];
+        if let Some(range) = &query.time_range {
+            params.push(("time_range", range));
+        }
+        let response = client
+            .get(url)
+            .query(&params)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        parse_searxng(&parse_json(&read_body(response).await?)?)
+    }
+}
+
+fn parse_searxng(json: &Value) -> Result<Vec<SearchResult>, String> {
+    let results = json
+        .get("results")
+        .and_then(Value::as_array)
+        .ok_or("Response has no 'results' (is the JSON format enabled on the instance?)")?;
+    Ok(results
+        .iter()
+        .map(|item| SearchResult {
+            title: field(item, "title").to_string(),
+            url: field(item, "url").to_string(),
+            snippet: field(item, "content").to_string(),
+            published: optional_field(item, "publishedDate"),
+        })
+        .collect())
+}
+
+/// The Brave Search API
+#[derive(Debug, Clone)]
+pub struct Brave {
+    url: reqwest::Url,
+    api_key: ApiKey,
+}
+
+#[async_trait]
+impl SearchBackend for Brave {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>, String> {
+        let count = query.limit.to_string();
+        let mut params = vec![("q", query.text.as_str()), ("count", count.as_str())];
+        // Brave names the ranges pd, pw, pm and py
+        if let Some(range) = &query.time_range {
+            let freshness = match range.as_str() {
+                "day" => "pd",
+                "week" => "pw",
+                "month" => "pm",
+                _ => "py",
+            };
+            params.push(("freshness", freshness));
+        }
+        let response = client
+            .get(self.url.clone())
+            .query(&params)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .header("X-Subscription-Token", self.api_key.resolve()?)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        Ok(parse_brave(&parse_json(&read_body(response).await?)?))
+    }
+}
+
+fn parse_brave(json: &Value) -> Vec<SearchResult> {
+    json.pointer("/web/results")
+        .and_then(Value::as_array)
+        .map(|results| {
+            results
+                .iter()
+                .map(|item| SearchResult {
+                    title: field(item, "title").to_string(),
+                    url: field(item, "url").to_string(),
+                    snippet: field(item, "description").to_string(),
+                    published: optional_field(item, "page_age")
+                        .or_else(|| optional_field(item, "age")),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The Tavily search API
+#[derive(Debug, Clone)]
+pub struct Tavily {
+    url: reqwest::Url,
+    api_key: ApiKey,
+}
+
+#[async_trait]
+impl SearchBackend for Tavily {
+    fn name(&self) -> &'static str {
+        "tavily"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>, String> {
+        let mut body = serde_json::json!({
+            "query": query.text,
+            "max_results": query.limit,
+        });
+        if let Some(range) = &query.time_range {
+            body["time_range"] = Value::from(range.as_str());
+        }
+        let response = client
+            .post(self.url.clone())
+            .bearer_auth(self.api_key.resolve()?)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        parse_tavily(&parse_json(&read_body(response).await?)?)
+    }
+}
+
+fn parse_tavily(json: &Value) -> Result<Vec<SearchResult>, String> {
+    let results = json
+        .get("results")
+        .and_then(Value::as_array)
+        .ok_or("Response has no 'results'")?;
+    Ok(results
+        .iter()
+        .map(|item| SearchResult {
+            title: field(item, "title").to_string(),
+            url: field(item, "url").to_string(),
+            snippet: field(item, "content").to_string(),
+            published: optional_field(item, "published_date"),
+        })
+        .collect())
+}
+
+/// DuckDuckGo's HTML results page, usable without an API key
+#[derive(Debug, Clone)]
+pub struct DuckDuckGo {
+    url: reqwest::Url,
+}
+
+impl DuckDuckGo {
+    pub fn new(url: reqwest::Url) -> Self {
+        Self { url }
+    }
+}
+
+impl Default for DuckDuckGo {
+    fn default() -> Self {
+        Self::new(reqwest::Url::parse(DUCKDUCKGO_URL).expect("valid URL"))
+    }
+}
+
+#[async_trait]
+impl SearchBackend for DuckDuckGo {
+    fn name(&self) -> &'static str {
+        "duckduckgo"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>, String> {
+        let mut form = vec![("q", query.text.as_str())];
+        if let Some(range) = &query.time_range {
+            form.push(("df", &range[..1]));
+        }
+        let response = client
+            .post(self.url.clone())
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        let body = read_body(response).await?;
+        let results = parse_duckduckgo(&body);
+        if results.is_empty() && body.contains("anomaly") {
+            return Err("DuckDuckGo refused the request (rate limited)".to_string());
+        }
+        Ok(results)
+    }
+}
+
+/// Reads results from the anchors of a DuckDuckGo HTML page
+///
+/// `result__a` anchors start a result and `result__snippet` anchors add its
+/// snippet. Ads link through `duckduckgo.com/y.js` and are dropped.
+fn parse_duckduckgo(page: &str) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = Vec::new();
+    let mut current_is_ad = false;
+    for anchor in ANCHOR_PATTERN.captures_iter(page) {
+        let mut class = "";
+        let mut href = "";
+        for attribute in ATTRIBUTE_PATTERN.captures_iter(&anchor[1]) {
+            let value = attribute
+                .get(2)
+                .or_else(|| attribute.get(3))
+                .map_or("", |m| m.as_str());
+            if attribute[1].eq_ignore_ascii_case("class") {
+                class = value;
+            } else {
+                href = value;
+            }
+        }
+        let classes: Vec<&str> = class.split_whitespace().collect();
+        if classes.contains(&"result__a") {
+            match duckduckgo_target(&html::decode_entities(href)) {
+                Some(url) => {
+                    current_is_ad = false;
+                    results.push(SearchResult {
+                        title: anchor[2].to_string(),
+                        url,
+                        snippet: String::new(),
+                        published: None,
+                    });
+                }
+                None => current_is_ad = true,
+            }
+        } else if classes.contains(&"result__snippet") && !current_is_ad {
+            if let Some(result) = results.last_mut() {
+                result.snippet = anchor[2].to_string();
+            }
+        }
+    }
+    results
+}
+
+/// Unwraps DuckDuckGo's `/l/?uddg=` redirect links; ads yield `None`
+fn duckduckgo_target(href: &str) -> Option<String> {
+    let absolute = match href.strip_prefix("//") {
+        Some(rest) => format!("https://{}", rest),
+        None => href.to_string(),
+    };
+    let url = reqwest::Url::parse(&absolute).ok()?;
+    let host = url.host_str().unwrap_or_default();
+    if host == "duckduckgo.com" || host.ends_with(".duckduckgo.com") {
+        if url.path() != "/l/" {
+            return None;
+        }
+        return url
+            .query_pairs()
+            .find(|(name, _)| name == "uddg")
+            .map(|(_, target)| target.into_owned());
+    }
+    Some(absolute)
+}
+
+/// Removes markup and entities from a title or snippet and collapses spaces
+fn clean_text(text: &str) -> String {
+    let text = html::decode_entities(&TAG_PATTERN.replace_all(text, ""));
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cleans results, drops those without an http(s) URL or seen before and
+/// keeps at most `limit`
+fn normalize(results: Vec<SearchResult>, limit: usize) -> Vec<SearchResult> {
+    let mut seen = HashSet::new();
+    results
+        .into_iter()
+        .filter_map(|result| {
+            let url = reqwest::Url::parse(result.url.trim()).ok()?;
+            if !matches!(url.scheme(), "http" | "https") || !seen.insert(url.to_string()) {
+                return None;
+            }
+            let mut snippet = clean_text(&result.snippet);
+            if snippet.chars().count() > MAX_SNIPPET_CHARS {
+                snippet = snippet.chars().take(MAX_SNIPPET_CHARS).collect();
+                snippet.push('…');
+            }
+            let title = clean_text(&result.title);
+            Some(SearchResult {
+                title: if title.is_empty() {
+                    url.to_string()
+                } else {
+                    title
+                },
+                url: url.to_string(),
+                snippet,
+                published: result.published,
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+fn parse_url(url: &str) -> Result<reqwest::Url, WebSearchConfigError> {
+    reqwest::Url::parse(url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| WebSearchConfigError::InvalidUrl(url.to_string()))
+}
+
+/// Tool for searching the web
+///
+/// Returns titles, URLs and snippets from a [`SearchBackend`]. When the
+/// backend fails and a fallback is set, the search is retried there and the
+/// output says why.
+#[derive(Debug)]
+pub struct WebSearchTool {
+    backend: Arc<dyn SearchBackend>,
+    fallback: Option<Arc<dyn SearchBackend>>,
+    max_results: usize,
+    client: reqwest::Client,
+}
+
+impl WebSearchTool {
+    /// Creates a tool searching `backend`, retrying on `fallback` if it fails
+    pub fn new(backend: Arc<dyn SearchBackend>, fallback: Option<Arc<dyn SearchBackend>>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(DEFAULT_SEARCH_TIMEOUT_SECS))
+            .user_agent("Mozilla/5.0 (compatible; miniclaw/0.1.0)")
+            .build()
+            .unwrap_or_default();
+        Self {
+            backend,
+            fallback,
+            max_results: DEFAULT_MAX_RESULTS,
+            client,
+        }
+    }
+
+    /// Sets the number of results returned when a call does not ask for a count
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results.clamp(1, MAX_RESULTS_LIMIT);
+        self
+    }
+
+    /// Creates the tool for the backend described by `config`
+    pub fn from_config(config: &WebSearchConfig) -> Result<Self, WebSearchConfigError> {
+        if !(1..=MAX_RESULTS_LIMIT).contains(&config.max_results) {
+            return Err(WebSearchConfigError::InvalidMaxResults);
+        }
+        let name = config.backend.as_str();
+        let url = |default: &str| parse_url(config.url.as_deref().unwrap_or(default));
+        let backend: Arc<dyn SearchBackend> = match config.backend {
+            SearchBackendKind::Searxng => {
+                let url = config
+                    .url
+                    .as_deref()
+                    .ok_or(WebSearchConfigError::MissingUrl(name))?;
+                Arc::new(Searxng::new(parse_url(url)?))
+            }
+            SearchBackendKind::Brave => Arc::new(Brave {
+                url: url(BRAVE_URL)?,
+                api_key: ApiKey::from_config(config, name)?,
+            }),
+            SearchBackendKind::Tavily => Arc::new(Tavily {
+                url: url(TAVILY_URL)?,
+                api_key: ApiKey::from_config(config, name)?,
+            }),
+            SearchBackendKind::Duckduckgo => Arc::new(DuckDuckGo::new(url(DUCKDUCKGO_URL)?)),
+        };
+        let fallback = (config.fallback && config.backend != SearchBackendKind::Duckduckgo)
+            .then(|| Arc::new(DuckDuckGo::default()) as Arc<dyn SearchBackend>);
+        Ok(Self::new(backend, fallback).with_max_results(config.max_results))
+    }
+
+    fn invalid_argument(&self, message: impl Into<String>) -> ToolError {
+        ToolError::InvalidArguments {
+            tool: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Reads the query, result count and time range from the arguments
+    fn parse_query(&self, args: &HashMap<String, Value>) -> ToolResult<SearchQuery> {
+        let text = args
+            .get("query")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| self.invalid_argument("Missing required parameter 'query'"))?;
+
+        let limit = match args.get("max_results") {
+            None | Some(Value::Null) => self.max_results,
+            Some(value) => value
+                .as_u64()
+                .filter(|n| (1..=MAX_RESULTS_LIMIT as u64).contains(n))
+                .ok_or_else(|| {
+                    self.invalid_argument(format!(
+                        "'max_results' must be an integer between 1 and {}",
+                        MAX_RESULTS_LIMIT
+                    ))
+                })? as usize,
+        };
+
+        let time_range = match args.get("time_range") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(
+                value
+                    .as_str()
+                    .filter(|range| TIME_RANGES.contains(range))
+                    .ok_or_else(|| {
+                        self.invalid_argument(format!(
+                            "'time_range' must be one of: {}",
+                            TIME_RANGES.join(", ")
+                        ))
+                    })?
+                    .to_string(),
+            ),
+        };
+
+        Ok(SearchQuery {
+            text: text.to_string(),
+            limit,
+            time_range,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Searches the web and returns a list of results with title, url and snippet. \
+         Use it to find pages instead of guessing URLs, then read a result with the 'web' tool. \
+         Optionally limit the number of results (max 20) or restrict them to the past day, week, month or year."
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "The search query"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_RESULTS_LIMIT,
+                    "description": format!("Number of results to return (default: {})", self.max_results)
+                },
+                "time_range": {
+                    "type": "string",
+                    "enum": TIME_RANGES,
+                    "description": "Only return results from this recent period"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, Value>,
+        _ctx: &ToolExecutionContext,
+    ) -> ToolResult<String> {
+        let query = self.parse_query(&args)?;
+
+        let mut backend = &self.backend;
+        let mut fallback_reason = None;
+        let results = match self.backend.search(&self.client, &query).await {
+            Ok(results) => results,
+            Err(error) => {
+                let Some(fallback) = &self.fallback else {
+                    return Err(ToolError::ExecutionFailed {
+                        tool: self.name().to_string(),
+                        message: format!("Search on {} failed: {}", self.backend.name(), error),
+                    });
+                };
+                tracing::warn!(
+                    backend = self.backend.name(),
+                    error = %error,
+                    "Web search failed, retrying on fallback"
+                );
+                let results = fallback.search(&self.client, &query).await.map_err(|e| {
+                    ToolError::ExecutionFailed {
+                        tool: self.name().to_string(),
+                        message: format!(
+                            "Search on {} failed: {}; fallback {} failed too: {}",
+                            self.backend.name(),
+                            error,
+                            fallback.name(),
+                            e
+                        ),
+                    }
+                })?;
+                backend = fallback;
+                fallback_reason = Some(format!("{} failed: {}", self.backend.name(), error));
+                results
+            }
+        };
+
+        let results = normalize(results, query.limit);
+        let mut output = serde_json::json!({
+            "query": query.text,
+            "backend": backend.name(),
+            "results": results,
+        });
+        if let Some(reason) = fallback_reason {
+            output["fallback_reason"] = Value::String(reason);
+        }
+        if results.is_empty() {
+            output["note"] = Value::from("No results. Try different or fewer keywords.");
+        }
+
+        serde_json::to_string_pretty(&output).map_err(|e| ToolError::ExecutionFailed {
+            tool: self.name().to_string(),
+            message: format!("Failed to serialize results: {}", e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    /// Serves `body` as JSON or HTML to `connections` requests on localhost
+    /// and returns the base URL
+    async fn serve(body: &'static str, content_type: &'static str, connections: usize) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for _ in 0..connections {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 8192];
+                let _ = stream.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    content_type,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    const DUCKDUCKGO_PAGE: &str = r#"
+        <div class="result results_links result--ad">
+          <a rel="nofollow" class="result__a" href="https://duckduckgo.com/y.js?ad_provider=x">Buy Rust</a>
+          <a class="result__snippet" href="https://duckduckgo.com/y.js?ad_provider=x">Sponsored</a>
+        </div>
+        <div class="result results_links results_links_deep web-result">
+          <h2 class="result__title">
+            <a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust%2Dlang.org%2F&amp;rut=abc">Rust Programming <b>Language</b></a>
+          </h2>
+          <a class="result__snippet" href="//duckduckgo.com/l/?uddg=x">A language empowering everyone to build <b>reliable</b> &amp; efficient software.</a>
+        </div>
+        <div class="result results_links web-result">
+          <a class="result__a" href="https://doc.rust-lang.org/book/">The Rust Book</a>
+        </div>"#;
+
+    #[test]
+    fn test_parse_duckduckgo() {
+        let results = normalize(parse_duckduckgo(DUCKDUCKGO_PAGE), 10);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(
+            results[0].snippet,
+            "A language empowering everyone to build reliable & efficient software."
+        );
+        assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+        assert_eq!(results[1].snippet, "");
+    }
+
+    #[test]
+    fn test_parse_api_responses() {
+        let searxng = serde_json::json!({"results": [
+            {"title": "SearXNG", "url": "https://docs.searxng.org/", "content": "Privacy-respecting metasearch",
+             "engine": "brave", "publishedDate": "2024-05-01T00:00:00"}
+        ]});
+        let results = parse_searxng(&searxng).unwrap();
+        assert_eq!(results[0].snippet, "Privacy-respecting metasearch");
+        assert_eq!(results[0].published.as_deref(), Some("2024-05-01T00:00:00"));
+        assert!(parse_searxng(&serde_json::json!({"error": "format"})).is_err());
+
+        let brave = serde_json::json!({"web": {"results": [
+            {"title": "Brave", "url": "https://brave.com/search/api/", "description": "The <strong>Brave</strong> Search API", "age": "2 days ago"}
+        ]}});
+        let results = normalize(parse_brave(&brave), 5);
+        assert_eq!(results[0].snippet, "The Brave Search API");
+        assert_eq!(results[0].published.as_deref(), Some("2 days ago"));
+        assert!(parse_brave(&serde_json::json!({"query": {}})).is_empty());
+
+        let tavily = serde_json::json!({"results": [
+            {"title": "Tavily", "url": "https://tavily.com", "content": "Search for agents", "score": 0.9}
+        ]});
+        let results = parse_tavily(&tavily).unwrap();
+        assert_eq!(results[0].url, "https://tavily.com");
+        assert_eq!(results[0].published, None);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let result = |title: &str, url: &str| SearchResult {
+            title: title.to_string(),
+            url: url.to_string(),
+            snippet: "x ".repeat(500),
+            published: None,
+        };
+        let results = normalize(
+            vec![
+                result("A", "https://a.example/"),
+                result("A again", "https://a.example"),
+                result("Mail", "mailto:someone@example.com"),
+                result("Relative", "/path"),
+                result("", "https://b.example/page"),
+                result("C", "https://c.example/"),
+            ],
+            2,
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "A");
+        assert_eq!(results[1].title, "https://b.example/page");
+        assert_eq!(results[0].snippet.chars().count(), MAX_SNIPPET_CHARS + 1);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = |json: &str| serde_json::from_str::<WebSearchConfig>(json).unwrap();
+
+        let tool = WebSearchTool::from_config(&WebSearchConfig::default()).unwrap();
+        assert_eq!(tool.backend.name(), "duckduckgo");
+        assert!(tool.fallback.is_none());
+
+        let tool = WebSearchTool::from_config(&config(
+            r#"{"backend": "searxng", "url": "http://searx.lan:8080", "max_results": 8}"#,
+        ))
+        .unwrap();
+        assert_eq!(tool.backend.name(), "searxng");
+        assert_eq!(tool.fallback.as_ref().unwrap().name(), "duckduckgo");
+        assert_eq!(tool.max_results, 8);
+
+        let tool = WebSearchTool::from_config(&config(
+            r#"{"backend": "brave", "api_key_env": "BRAVE_API_KEY", "fallback": false}"#,
+        ))
+        .unwrap();
+        assert!(tool.fallback.is_none());
+
+        for invalid in [
+            r#"{"backend": "searxng"}"#,
+            r#"{"backend": "searxng", "url": "ftp://searx.lan"}"#,
+            r#"{"backend": "tavily"}"#,
+            r#"{"backend": "brave", "api_key": "a", "api_key_env": "B"}"#,
+            r#"{"max_results": 0}"#,
+            r#"{"max_results": 21}"#,
+        ] {
+            assert!(
+                WebSearchTool::from_config(&config(invalid)).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_api_key_not_in_debug_output() {
+        let config: WebSearchConfig =
+            serde_json::from_str(r#"{"backend": "tavily", "api_key": "tvly-s3cret"}"#).unwrap();
+        let tool = WebSearchTool::from_config(&config).unwrap();
+        assert!(!format!("{:?}", config).contains("s3cret"));
+        assert!(!format!("{:?}", tool).contains("s3cret"));
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let tool = WebSearchTool::from_config(&WebSearchConfig::default()).unwrap();
+        let query = tool
+            .parse_query(&args(&[
+                ("query", serde_json::json!("  rust async  ")),
+                ("time_range", serde_json::json!("week")),
+            ]))
+            .unwrap();
+        assert_eq!(query.text, "rust async");
+        assert_eq!(query.limit, DEFAULT_MAX_RESULTS);
+        assert_eq!(query.time_range.as_deref(), Some("week"));
+
+        for invalid in [
+            args(&[]),
+            args(&[("query", serde_json::json!(" "))]),
+            args(&[
+                ("query", serde_json::json!("x")),
+                ("max_results", serde_json::json!(0)),
+            ]),
+            args(&[
+                ("query", serde_json::json!("x")),
+                ("max_results", serde_json::json!(50)),
+            ]),
+            args(&[
+                ("query", serde_json::json!("x")),
+                ("time_range", serde_json::json!("decade")),
+            ]),
+        ] {
+            assert!(matches!(
+                tool.parse_query(&invalid),
+                Err(ToolError::InvalidArguments { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_searxng() {
+        let base_url = serve(
+            r#"{"results": [
+                {"title": "Rust", "url": "https://www.rust-lang.org/", "content": "A language"},
+                {"title": "Crates", "url": "https://crates.io/", "content": "Packages"}
+            ]}"#,
+            "application/json",
+            1,
+        )
+        .await;
+        let config = WebSearchConfig {
+            backend: SearchBackendKind::Searxng,
+            url: Some(base_url),
+            ..WebSearchConfig::default()
+        };
+        let tool = WebSearchTool::from_config(&config).unwrap();
+
+        let result = tool
+            .execute(
+                args(&[
+                    ("query", serde_json::json!("rust")),
+                    ("max_results", serde_json::json!(1)),
+                ]),
+                &ToolExecutionContext::default(),
+            )
+            .await
+            .unwrap();
+        let output: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(output["backend"], "searxng");
+        assert_eq!(output["results"].as_array().unwrap().len(), 1);
+        assert_eq!(output["results"][0]["url"], "https://www.rust-lang.org/");
+        assert!(output.get("fallback_reason").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_when_backend_fails() {
+        // Nothing listens on the SearXNG URL once the listener is dropped
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let searxng_url = format!("http://{}/", closed.local_addr().unwrap());
+        drop(closed);
+        let duckduckgo_url = serve(DUCKDUCKGO_PAGE, "text/html", 1).await;
+
+        let tool = WebSearchTool::new(
+            Arc::new(Searxng::new(reqwest::Url::parse(&searxng_url).unwrap())),
+            Some(Arc::new(DuckDuckGo::new(
+                reqwest::Url::parse(&duckduckgo_url).unwrap(),
+            ))),
+        );
+        let result = tool
+            .execute(
+                args(&[("query", serde_json::json!("rust"))]),
+                &ToolExecutionContext::default(),
+            )
+            .await
+            .unwrap();
+        let output: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(output["backend"], "duckduckgo");
+        assert!(
+            output["fallback_reason"]
+                .as_str()
+                .unwrap()
+                .starts_with("searxng failed")
+        );
+        assert_eq!(output["results"][0]["title"], "Rust Programming Language");
+
+        let tool = WebSearchTool::new(
+            Arc::new(Searxng::new(reqwest::Url::parse(&searxng_url).unwrap())),
+            None,
+        );
+        let result = tool
+            .execute(
+                args(&[("query", serde_json::json!("rust"))]),
+                &ToolExecutionContext::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed { .. })));
+    }
+}
@@ -0,0 +1,525 @@
+//! Per-turn execution traces for the agent loop
+//!
+//! Every processed message produces a [`TurnTrace`] describing the context that was
+//! sent, each LLM call and each tool execution. Traces are stored as JSON files under
+//! `{workspace}/traces/` and rendered by `miniclaw trace list/show`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::providers::{LlmMessage, LlmResponse, Tokenizer};
+use crate::utils::MiniClawError;
+
+/// Type alias for Results in this module
+type Result<T> = std::result::Result<T, MiniClawError>;
+
+/// Directory (relative to the workspace) where traces are stored
+pub const TRACES_DIR: &str = "traces";
+
+/// Maximum number of trace files kept on disk; oldest are pruned first
+pub const MAX_TRACE_FILES: usize = 500;
+
+/// Maximum number of characters of tool arguments kept in a trace
+const MAX_TRACED_ARGUMENTS_CHARS: usize = 2000;
+
+/// Maximum number of characters used for a context layer label
+const MAX_LAYER_LABEL_CHARS: usize = 60;
+
+/// Size of a single message in the context sent to the LLM
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContextLayerTrace {
+    pub role: String,
+    /// First line of the message, used to identify the layer
+    pub label: String,
+    pub chars: usize,
+    /// Tokens counted with the model's tokenizer, including message overhead
+    pub estimated_tokens: usize,
+}
+
+/// A single LLM call made while processing a message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LlmCallTrace {
+    pub iteration: u32,
+    pub model: String,
+    pub latency_ms: u64,
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    /// Names of the tools requested by this call
+    pub tool_calls: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// A single tool execution made while processing a message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolExecutionTrace {
+    pub iteration: u32,
+    pub tool_call_id: String,
+    pub name: String,
+    pub arguments: String,
+    pub duration_ms: u64,
+    pub result_bytes: usize,
+    pub error: Option<String>,
+}
+
+/// Structured record of everything that happened while processing one message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TurnTrace {
+    pub id: String,
+    pub session_id: String,
+    pub channel: String,
+    pub chat_id: String,
+    pub model: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub iterations: u32,
+    pub context_layers: Vec<ContextLayerTrace>,
+    pub llm_calls: Vec<LlmCallTrace>,
+    pub tool_executions: Vec<ToolExecutionTrace>,
+    pub response_chars: Option<usize>,
+    pub error: Option<String>,
+}
+
+impl TurnTrace {
+    /// Starts a new trace for a message in the given channel/chat
+    pub fn new(channel: &str, chat_id: &str, model: &str) -> Self {
+        let started_at = Utc::now();
+        let session_id = format!("{}_{}", channel, chat_id);
+        let id = format!(
+            "{}-{}",
+            started_at.format("%Y%m%dT%H%M%S%3fZ"),
+            sanitize_id_component(&session_id)
+        );
+
+        Self {
+            id,
+            session_id,
+            channel: channel.to_string(),
+            chat_id: chat_id.to_string(),
+            model: model.to_string(),
+            started_at,
+            duration_ms: 0,
+            iterations: 0,
+            context_layers: Vec::new(),
+            llm_calls: Vec::new(),
+            tool_executions: Vec::new(),
+            response_chars: None,
+            error: None,
+        }
+    }
+
+    /// Records the size of each message in the assembled context
+    pub fn record_context(&mut self, context: &[LlmMessage], tokenizer: &Tokenizer) {
+        self.context_layers = context
+            .iter()
+            .map(|msg| ContextLayerTrace {
+                role: msg.role.as_str().to_string(),
+                label: layer_label(&msg.content),
+                chars: msg.content.chars().count(),
+                estimated_tokens: tokenizer.count_message(msg),
+            })
+            .collect();
+    }
+
+    /// Records an LLM call and its outcome
+    pub fn record_llm_call(
+        &mut self,
+        iteration: u32,
+        latency_ms: u64,
+        outcome: std::result::Result<&LlmResponse, String>,
+    ) {
+        let call = match outcome {
+            Ok(response) => LlmCallTrace {
+                iteration,
+                model: self.model.clone(),
+                latency_ms,
+                prompt_tokens: response.prompt_tokens,
+                completion_tokens: response.completion_tokens,
+                tool_calls: response
+                    .tool_calls
+                    .iter()
+                    .flatten()
+                    .map(|tc| tc.name.clone())
+                    .collect(),
+                error: None,
+            },
+            Err(error) => LlmCallTrace {
+                iteration,
+                model: self.model.clone(),
+                latency_ms,
+                prompt_tokens: None,
+                completion_tokens: None,
+                tool_calls: Vec::new(),
+                error: Some(error),
+            },
+        };
+        self.llm_calls.push(call);
+    }
+
+    /// Records a tool execution; long arguments are truncated
+    pub fn record_tool_execution(&mut self, mut execution: ToolExecutionTrace) {
+        if execution.arguments.chars().count() > MAX_TRACED_ARGUMENTS_CHARS {
+            execution.arguments = execution
+                .arguments
+                .chars()
+                .take(MAX_TRACED_ARGUMENTS_CHARS)
+                .collect::<String>()
+                + "…";
+        }
+        self.tool_executions.push(execution);
+    }
+
+    /// Completes the trace with the final response or error
+    pub fn finish(&mut self, outcome: std::result::Result<&str, String>) {
+        self.duration_ms = (Utc::now() - self.started_at).num_milliseconds().max(0) as u64;
+        match outcome {
+            Ok(response) => self.response_chars = Some(response.chars().count()),
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Total prompt + completion tokens reported by the provider
+    pub fn total_tokens(&self) -> u32 {
+        self.llm_calls
+            .iter()
+            .map(|c| c.prompt_tokens.unwrap_or(0) + c.completion_tokens.unwrap_or(0))
+            .sum()
+    }
+
+    /// Total estimated tokens of the initial context
+    pub fn context_tokens(&self) -> usize {
+        self.context_layers.iter().map(|l| l.estimated_tokens).sum()
+    }
+}
+
+/// Stores and loads [`TurnTrace`] files in `{workspace}/traces/`
+pub struct TraceStore {
+    traces_dir: PathBuf,
+}
+
+impl TraceStore {
+    /// Creates a trace store for the given workspace
+    pub fn new(workspace_path: impl AsRef<Path>) -> Self {
+        Self {
+            traces_dir: workspace_path.as_ref().join(TRACES_DIR),
+        }
+    }
+
+    /// Returns the directory where traces are stored
+    pub fn traces_dir(&self) -> &Path {
+        &self.traces_dir
+    }
+
+    /// Writes a trace to disk and prunes old traces beyond [`MAX_TRACE_FILES`]
+    pub async fn save(&self, trace: &TurnTrace) -> Result<PathBuf> {
+        fs::create_dir_all(&self.traces_dir)
+            .await
+            .map_err(|e| MiniClawError::io(&self.traces_dir, e))?;
+
+        let file_path = self.traces_dir.join(format!("{}.json", trace.id));
+        let temp_path = file_path.with_extension("tmp");
+
+        let json = serde_json::to_string_pretty(trace)
+            .map_err(|e| MiniClawError::serialization(e.to_string()))?;
+
+        if let Err(e) = fs::write(&temp_path, &json).await {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(MiniClawError::io(&temp_path, e));
+        }
+        if let Err(e) = fs::rename(&temp_path, &file_path).await {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(MiniClawError::io(&file_path, e));
+        }
+
+        self.prune(MAX_TRACE_FILES).await?;
+
+        Ok(file_path)
+    }
+
+    /// Loads a trace by id; `latest` returns the most recent trace
+    pub async fn load(&self, id: &str) -> Result<TurnTrace> {
+        if id == "latest" {
+            let ids = self.trace_ids().await?;
+            let latest = ids
+                .last()
+                .ok_or_else(|| MiniClawError::invalid_input("No traces recorded yet"))?;
+            return self.load_file(latest).await;
+        }
+
+        if id.is_empty() || sanitize_id_component(id) != id {
+            return Err(MiniClawError::invalid_input(format!(
+                "Invalid trace id: {}",
+                id
+            )));
+        }
+
+        self.load_file(id).await
+    }
+
+    /// Lists the most recent traces, newest first
+    pub async fn list(&self, limit: usize) -> Result<Vec<TurnTrace>> {
+        let ids = self.trace_ids().await?;
+        let mut traces = Vec::new();
+
+        for id in ids.iter().rev().take(limit) {
+            match self.load_file(id).await {
+                Ok(trace) => traces.push(trace),
+                Err(e) => tracing::warn!(trace_id = %id, error = %e, "Skipping unreadable trace"),
+            }
+        }
+
+        Ok(traces)
+    }
+
+    async fn load_file(&self, id: &str) -> Result<TurnTrace> {
+        let file_path = self.traces_dir.join(format!("{}.json", id));
+        let json = fs::read_to_string(&file_path)
+            .await
+            .map_err(|e| MiniClawError::io(&file_path, e))?;
+        serde_json::from_str(&json).map_err(|e| MiniClawError::serialization(e.to_string()))
+    }
+
+    /// Returns trace ids sorted oldest first (ids start with a UTC timestamp)
+    async fn trace_ids(&self) -> Result<Vec<String>> {
+        let mut entries = match fs::read_dir(&self.traces_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(MiniClawError::io(&self.traces_dir, e)),
+        };
+
+        let mut ids = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| MiniClawError::io(&self.traces_dir, e))?
+        {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("json")
+                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+            {
+                ids.push(stem.to_string());
+            }
+        }
+
+        ids.sort();
+        Ok(ids)
+    }
+
+    async fn prune(&self, keep: usize) -> Result<()> {
+        let ids = self.trace_ids().await?;
+        if ids.len() <= keep {
+            return Ok(());
+        }
+
+        for id in &ids[..ids.len() - keep] {
+            let path = self.traces_dir.join(format!("{}.json", id));
+            if let Err(e) = fs::remove_file(&path).await {
+                tracing::warn!(path = ?path, error = %e, "Failed to prune old trace");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Replaces characters that are unsafe in file names
+fn sanitize_id_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// First non-empty line of a message, truncated
+pub(crate) fn layer_label(content: &str) -> String {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+
+    if line.chars().count() > MAX_LAYER_LABEL_CHARS {
+        line.chars().take(MAX_LAYER_LABEL_CHARS).collect::<String>() + "…"
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::tokenizer::MESSAGE_OVERHEAD_TOKENS;
+    use crate::providers::{LlmRole, LlmToolCall};
+    use tempfile::TempDir;
+
+    fn sample_trace(channel: &str) -> TurnTrace {
+        let mut trace = TurnTrace::new(channel, "42", "test-model");
+        trace.record_context(
+            &[
+                LlmMessage::new(LlmRole::System, "# Soul\nBe helpful"),
+                LlmMessage::new(LlmRole::User, "Hello there"),
+            ],
+            &Tokenizer::default(),
+        );
+        trace
+    }
+
+    #[test]
+    fn test_new_trace_id_is_filename_safe() {
+        let trace = TurnTrace::new("tele/gram", "-100:5", "m");
+        assert_eq!(trace.session_id, "tele/gram_-100:5");
+        assert!(trace.id.ends_with("-tele_gram_-100_5"));
+        assert_eq!(sanitize_id_component(&trace.id), trace.id);
+    }
+
+    #[test]
+    fn test_record_context_layers() {
+        let trace = sample_trace("cli");
+        assert_eq!(trace.context_layers.len(), 2);
+        assert_eq!(trace.context_layers[0].role, "system");
+        assert_eq!(trace.context_layers[0].label, "# Soul");
+        assert_eq!(trace.context_layers[0].chars, 17);
+        let tokenizer = Tokenizer::default();
+        assert_eq!(
+            trace.context_layers[0].estimated_tokens,
+            tokenizer.count("# Soul\nBe helpful") + MESSAGE_OVERHEAD_TOKENS
+        );
+        assert_eq!(trace.context_layers[1].label, "Hello there");
+        assert_eq!(
+            trace.context_tokens(),
+            trace.context_layers[0].estimated_tokens + 2 + MESSAGE_OVERHEAD_TOKENS
+        );
+    }
+
+    #[test]
+    fn test_record_llm_call_success_and_error() {
+        let mut trace = sample_trace("cli");
+        let response = LlmResponse {
+            content: String::new(),
+            tool_calls: Some(vec![LlmToolCall::new("1", "exec", "{}")]),
+            prompt_tokens: Some(100),
+            completion_tokens: Some(20),
+        };
+
+        trace.record_llm_call(1, 250, Ok(&response));
+        trace.record_llm_call(2, 30, Err("boom".to_string()));
+
+        assert_eq!(trace.llm_calls[0].tool_calls, vec!["exec".to_string()]);
+        assert_eq!(trace.llm_calls[0].latency_ms, 250);
+        assert_eq!(trace.llm_calls[1].error.as_deref(), Some("boom"));
+        assert_eq!(trace.total_tokens(), 120);
+    }
+
+    #[test]
+    fn test_record_tool_execution_truncates_arguments() {
+        let mut trace = sample_trace("cli");
+        trace.record_tool_execution(ToolExecutionTrace {
+            iteration: 1,
+            tool_call_id: "1".to_string(),
+            name: "filesystem".to_string(),
+            arguments: "x".repeat(MAX_TRACED_ARGUMENTS_CHARS + 10),
+            duration_ms: 5,
+            result_bytes: 10,
+            error: None,
+        });
+
+        assert_eq!(
+            trace.tool_executions[0].arguments.chars().count(),
+            MAX_TRACED_ARGUMENTS_CHARS + 1
+        );
+    }
+
+    #[test]
+    fn test_finish_records_outcome() {
+        let mut ok = sample_trace("cli");
+        ok.finish(Ok("done"));
+        assert_eq!(ok.response_chars, Some(4));
+        assert!(ok.error.is_none());
+
+        let mut failed = sample_trace("cli");
+        failed.finish(Err("Max iterations (3) reached".to_string()));
+        assert!(failed.response_chars.is_none());
+        assert!(failed.error.is_some());
+    }
+
+    #[test]
+    fn test_layer_label_truncates() {
+        assert_eq!(layer_label("\n\n  first  \nsecond"), "first");
+        assert_eq!(layer_label(""), "");
+        let long = "a".repeat(100);
+        assert_eq!(
+            layer_label(&long).chars().count(),
+            MAX_LAYER_LABEL_CHARS + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_save_list_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TraceStore::new(temp_dir.path());
+
+        let mut first = sample_trace("cli");
+        first.id = format!("20260101T000000000Z-{}", first.session_id);
+        let mut second = sample_trace("telegram");
+        second.id = format!("20260102T000000000Z-{}", second.session_id);
+
+        store.save(&first).await.unwrap();
+        let path = store.save(&second).await.unwrap();
+        assert!(path.starts_with(temp_dir.path().join(TRACES_DIR)));
+
+        let listed = store.list(10).await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, second.id);
+
+        assert_eq!(store.load(&first.id).await.unwrap(), first);
+        assert_eq!(store.load("latest").await.unwrap().id, second.id);
+        assert_eq!(store.list(1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_empty_when_no_traces() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TraceStore::new(temp_dir.path());
+        assert!(store.list(10).await.unwrap().is_empty());
+        assert!(store.load("latest").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_path_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TraceStore::new(temp_dir.path());
+        assert!(matches!(
+            store.load("../config").await,
+            Err(MiniClawError::InvalidInput { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_newest() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TraceStore::new(temp_dir.path());
+
+        for day in 1..=3 {
+            let mut trace = sample_trace("cli");
+            trace.id = format!("2026010{}T000000000Z-cli_42", day);
+            store.save(&trace).await.unwrap();
+        }
+        store.prune(2).await.unwrap();
+
+        let ids = store.trace_ids().await.unwrap();
+        assert_eq!(
+            ids,
+            vec![
+                "20260102T000000000Z-cli_42".to_string(),
+                "20260103T000000000Z-cli_42".to_string()
+            ]
+        );
+    }
+}
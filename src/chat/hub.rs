@@ -1,3 +1,4 @@
+use crate::chat::router::AgentRouter;
 use crate::chat::types::{InboundMessage, OutboundMessage};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -34,6 +35,8 @@ pub struct ChatHub {
     channels: Arc<RwLock<HashMap<String, mpsc::Sender<OutboundMessage>>>>,
     delivery_failure_callback: Option<DeliveryFailureCallback>,
     agent_tx: Option<mpsc::Sender<InboundMessage>>,
+    named_agent_txs: HashMap<String, mpsc::Sender<InboundMessage>>,
+    router: AgentRouter,
 }
 
 impl ChatHub {
@@ -53,6 +56,8 @@ impl ChatHub {
             channels: Arc::new(RwLock::new(HashMap::new())),
             delivery_failure_callback: None,
             agent_tx: None,
+            named_agent_txs: HashMap::new(),
+            router: AgentRouter::new(),
         }
     }
 
@@ -78,6 +83,24 @@ impl ChatHub {
         self.agent_tx = Some(sender);
     }
 
+    /// Register a sender for a named agent profile.
+    /// Messages reach it when the router resolves to `name`.
+    pub fn register_named_agent_sender(
+        &mut self,
+        name: impl Into<String>,
+        sender: mpsc::Sender<InboundMessage>,
+    ) {
+        let name = name.into();
+        tracing::info!(agent = %name, "Named AgentLoop sender registered with ChatHub");
+        self.named_agent_txs.insert(name, sender);
+    }
+
+    /// Set the router used to pick a named agent for each inbound message.
+    /// Unrouted messages go to the sender from [`register_agent_sender`](Self::register_agent_sender).
+    pub fn set_router(&mut self, router: AgentRouter) {
+        self.router = router;
+    }
+
     pub fn inbound_sender(&self) -> mpsc::Sender<InboundMessage> {
         self.inbound_tx.clone()
     }
@@ -229,20 +252,7 @@ impl ChatHub {
                         chat_id = %msg.chat_id,
                         "Received inbound message"
                     );
-                    // Forward to AgentLoop if connected
-                    if let Some(agent_tx) = &self.agent_tx {
-                        match agent_tx.try_send(msg) {
-                            Ok(_) => {
-                                tracing::trace!("Message forwarded to AgentLoop");
-                            }
-                            Err(mpsc::error::TrySendError::Full(_)) => {
-                                tracing::warn!("AgentLoop buffer full, message dropped");
-                            }
-                            Err(mpsc::error::TrySendError::Closed(_)) => {
-                                tracing::error!("AgentLoop receiver closed");
-                            }
-                        }
-                    }
+                    self.forward_to_agent(msg);
                 }
                 Some(msg) = self.recv_outbound() => {
                     tracing::debug!(
@@ -292,6 +302,46 @@ impl ChatHub {
         Ok(())
     }
 
+    /// Forwards an inbound message to the AgentLoop chosen by the router,
+    /// or to the default AgentLoop if no route matches.
+    /// Drops the message when the agent buffer is full.
+    fn forward_to_agent(&self, mut msg: InboundMessage) {
+        let mut agent = "default".to_string();
+        let mut agent_tx = self.agent_tx.as_ref();
+
+        if let Some(route) = self.router.resolve(&msg) {
+            match self.named_agent_txs.get(&route.agent) {
+                Some(tx) => {
+                    if let Some(content) = route.content {
+                        msg.content = content;
+                    }
+                    agent = route.agent;
+                    agent_tx = Some(tx);
+                }
+                None => {
+                    tracing::warn!(
+                        agent = %route.agent,
+                        "Route matched an unregistered agent, using default agent"
+                    );
+                }
+            }
+        }
+
+        if let Some(agent_tx) = agent_tx {
+            match agent_tx.try_send(msg) {
+                Ok(_) => {
+                    tracing::trace!(agent = %agent, "Message forwarded to AgentLoop");
+                }
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    tracing::warn!(agent = %agent, "AgentLoop buffer full, message dropped");
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    tracing::error!(agent = %agent, "AgentLoop receiver closed");
+                }
+            }
+        }
+    }
+
     async fn recv_inbound(&self) -> Option<InboundMessage> {
         let mut rx = self.inbound_rx.lock().await;
         rx.recv().await
@@ -527,9 +577,10 @@ mod tests {
         let inbound_tx = hub.inbound_sender();
         inbound_tx.send(msg).await.unwrap();
 
-        // Simulate ChatHub.run() processing by calling recv_inbound
+        // Simulate ChatHub.run() processing: receive then forward
         let received = hub.recv_inbound().await;
         assert!(received.is_some());
+        hub.forward_to_agent(received.unwrap());
 
         // Verify message was forwarded to agent_rx
         let forwarded = agent_rx.recv().await;
@@ -548,20 +599,62 @@ mod tests {
             let msg = InboundMessage::new("telegram", "123", format!("msg {}", i));
             let inbound_tx = hub.inbound_sender();
             inbound_tx.send(msg).await.unwrap();
-            hub.recv_inbound().await; // Process and forward
+            let received = hub.recv_inbound().await.unwrap();
+            hub.forward_to_agent(received); // Process and forward
         }
 
-        // Verify both messages in agent_rx
-        assert!(agent_rx.recv().await.is_some());
-        assert!(agent_rx.recv().await.is_some());
-
         // Send third message - should drop due to full buffer
         let msg = InboundMessage::new("telegram", "123", "overflow");
         let inbound_tx = hub.inbound_sender();
         inbound_tx.send(msg).await.unwrap();
-        hub.recv_inbound().await; // Process and forward - should drop
+        let received = hub.recv_inbound().await.unwrap();
+        hub.forward_to_agent(received); // Process and forward - should drop
+
+        // Only the first two messages reached agent_rx
+        assert_eq!(agent_rx.recv().await.unwrap().content, "msg 0");
+        assert_eq!(agent_rx.recv().await.unwrap().content, "msg 1");
 
-        // Agent buffer should be empty (message was dropped)
+        // Agent buffer should be empty (overflow message was dropped)
         assert!(agent_rx.try_recv().is_err());
     }
+
+    #[tokio::test]
+    async fn test_message_routing_to_named_agents() {
+        use crate::chat::router::RouteRule;
+
+        let mut hub = ChatHub::new();
+        let (default_tx, mut default_rx) = mpsc::channel(10);
+        let (ops_tx, mut ops_rx) = mpsc::channel(10);
+        hub.register_agent_sender(default_tx);
+        hub.register_named_agent_sender("ops", ops_tx);
+
+        let mut router = AgentRouter::new();
+        router.add_rule(RouteRule {
+            agent: "ops".to_string(),
+            chat_ids: vec!["999".to_string()],
+            command_prefix: Some("/ops".to_string()),
+            ..Default::default()
+        });
+        router.add_rule(RouteRule {
+            agent: "missing".to_string(),
+            channels: vec!["cli".to_string()],
+            ..Default::default()
+        });
+        hub.set_router(router);
+
+        hub.forward_to_agent(InboundMessage::new("telegram", "123", "/ops df -h"));
+        hub.forward_to_agent(InboundMessage::new("telegram", "999", "uptime?"));
+        hub.forward_to_agent(InboundMessage::new("telegram", "123", "hello"));
+        hub.forward_to_agent(InboundMessage::new("cli", "1", "unregistered route"));
+
+        assert_eq!(ops_rx.recv().await.unwrap().content, "df -h");
+        assert_eq!(ops_rx.recv().await.unwrap().content, "uptime?");
+        assert!(ops_rx.try_recv().is_err());
+
+        assert_eq!(default_rx.recv().await.unwrap().content, "hello");
+        assert_eq!(
+            default_rx.recv().await.unwrap().content,
+            "unregistered route"
+        );
+    }
 }
@@ -1,5 +1,7 @@
 pub mod hub;
+pub mod router;
 pub mod types;
 
 pub use hub::{ChatError, ChatHub};
+pub use router::{AgentRouter, RouteMatch, RouteRule};
 pub use types::{InboundMessage, OutboundMessage};
@@ -0,0 +1,188 @@
+//! Routing of inbound messages to named agents
+//!
+//! When the gateway serves several agent profiles, the [`AgentRouter`] decides which
+//! agent receives each inbound message. Rules are matched in priority order:
+//!
+//! 1. Command prefix (e.g. `/ops restart nginx`); the prefix is stripped
+//! 2. Chat id (optionally restricted to the rule's channels)
+//! 3. Channel
+//!
+//! Within a tier the first matching rule wins. Messages that match no rule go to
+//! the default agent.
+
+use crate::chat::types::InboundMessage;
+
+/// Routing rule for a single named agent
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteRule {
+    /// Name of the agent receiving matched messages
+    pub agent: String,
+    /// Channels routed to this agent (e.g. "telegram")
+    pub channels: Vec<String>,
+    /// Chat ids routed to this agent
+    pub chat_ids: Vec<String>,
+    /// Command prefix routed to this agent (e.g. "/ops")
+    pub command_prefix: Option<String>,
+}
+
+/// Result of routing a message
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteMatch {
+    /// Name of the agent that should handle the message
+    pub agent: String,
+    /// Rewritten message content, when a command prefix was stripped
+    pub content: Option<String>,
+}
+
+/// Resolves which named agent handles an inbound message
+#[derive(Debug, Clone, Default)]
+pub struct AgentRouter {
+    rules: Vec<RouteRule>,
+}
+
+impl AgentRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a routing rule. Rules added first win ties within a tier.
+    pub fn add_rule(&mut self, rule: RouteRule) {
+        self.rules.push(rule);
+    }
+
+    /// Returns true if no rules are configured
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Resolves the agent for a message, or `None` for the default agent
+    pub fn resolve(&self, message: &InboundMessage) -> Option<RouteMatch> {
+        // Tier 1: command prefix
+        for rule in &self.rules {
+            if let Some(prefix) = &rule.command_prefix
+                && let Some(rest) = strip_command_prefix(&message.content, prefix)
+            {
+                return Some(RouteMatch {
+                    agent: rule.agent.clone(),
+                    // A bare prefix keeps the original content so the agent gets a message
+                    content: (!rest.is_empty()).then(|| rest.to_string()),
+                });
+            }
+        }
+
+        // Tier 2: chat id, restricted to the rule's channels when given
+        for rule in &self.rules {
+            if rule.chat_ids.contains(&message.chat_id)
+                && (rule.channels.is_empty() || rule.channels.contains(&message.channel))
+            {
+                return Some(RouteMatch {
+                    agent: rule.agent.clone(),
+                    content: None,
+                });
+            }
+        }
+
+        // Tier 3: channel
+        for rule in &self.rules {
+            if rule.chat_ids.is_empty() && rule.channels.contains(&message.channel) {
+                return Some(RouteMatch {
+                    agent: rule.agent.clone(),
+                    content: None,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the remaining content if `content` starts with `prefix` as a whole word
+fn strip_command_prefix<'a>(content: &'a str, prefix: &str) -> Option<&'a str> {
+    let trimmed = content.trim_start();
+    let rest = trimmed.strip_prefix(prefix)?;
+
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router() -> AgentRouter {
+        let mut router = AgentRouter::new();
+        router.add_rule(RouteRule {
+            agent: "ops".to_string(),
+            channels: vec!["telegram".to_string()],
+            chat_ids: vec!["-100200".to_string()],
+            command_prefix: Some("/ops".to_string()),
+        });
+        router.add_rule(RouteRule {
+            agent: "cli_agent".to_string(),
+            channels: vec!["cli".to_string()],
+            ..Default::default()
+        });
+        router
+    }
+
+    #[test]
+    fn test_command_prefix_routes_and_strips() {
+        let msg = InboundMessage::new("cli", "1", "/ops restart nginx");
+        let route = router().resolve(&msg).unwrap();
+        assert_eq!(route.agent, "ops");
+        assert_eq!(route.content.as_deref(), Some("restart nginx"));
+    }
+
+    #[test]
+    fn test_command_prefix_requires_word_boundary() {
+        let msg = InboundMessage::new("discord", "1", "/opsec question");
+        assert!(router().resolve(&msg).is_none());
+    }
+
+    #[test]
+    fn test_bare_command_prefix_keeps_content() {
+        let msg = InboundMessage::new("discord", "1", "/ops");
+        let route = router().resolve(&msg).unwrap();
+        assert_eq!(route.agent, "ops");
+        assert!(route.content.is_none());
+    }
+
+    #[test]
+    fn test_chat_id_routing_respects_channels() {
+        let msg = InboundMessage::new("telegram", "-100200", "status?");
+        assert_eq!(router().resolve(&msg).unwrap().agent, "ops");
+
+        // Same chat id on another channel is not matched
+        let msg = InboundMessage::new("discord", "-100200", "status?");
+        assert!(router().resolve(&msg).is_none());
+    }
+
+    #[test]
+    fn test_chat_id_rule_does_not_claim_whole_channel() {
+        // The ops rule lists telegram only as a filter for its chat ids
+        let msg = InboundMessage::new("telegram", "42", "hello");
+        assert!(router().resolve(&msg).is_none());
+    }
+
+    #[test]
+    fn test_channel_routing() {
+        let msg = InboundMessage::new("cli", "1", "hello");
+        let route = router().resolve(&msg).unwrap();
+        assert_eq!(route.agent, "cli_agent");
+        assert!(route.content.is_none());
+    }
+
+    #[test]
+    fn test_empty_router_routes_to_default() {
+        let router = AgentRouter::new();
+        assert!(router.is_empty());
+        assert!(
+            router
+                .resolve(&InboundMessage::new("cli", "1", "/ops hi"))
+                .is_none()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Context;
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
 
-use crate::config::{Config, load_config, run_onboarding};
+use crate::config::{AgentConfig, Config, load_config, run_onboarding};
 use crate::providers::ProviderError;
 
 #[derive(Parser)]
@@ -96,6 +96,11 @@ pub enum Commands {
     /// ```bash
     /// miniclaw --verbose agent -m "Debug this code"
     /// ```
+    ///
+    /// With runtime limit overrides:
+    /// ```bash
+    /// miniclaw agent --max-iterations 10 --tool-timeout 120 -m "Summarize the logs"
+    /// ```
     Agent {
         /// Message to send to the agent
         #[arg(short, long, help = "Message to send to the agent")]
@@ -104,6 +109,9 @@ pub enum Commands {
         /// Model to use for this request (overrides config)
         #[arg(short = 'M', long, help = "Model to use for this request")]
         model: Option<String>,
+
+        #[command(flatten)]
+        limits: AgentLimitOverrides,
     },
 
     /// Memory management commands
@@ -131,6 +139,103 @@ pub enum Commands {
         command: MemoryCommands,
     },
 
+    /// Inspect per-message execution traces
+    ///
+    /// Every processed message records a trace in the workspace (traces/)
+    /// with context sizes, LLM calls and tool executions.
+    ///
+    /// # Examples
+    ///
+    /// List recent traces:
+    /// ```bash
+    /// miniclaw trace list
+    /// ```
+    ///
+    /// Show the most recent trace:
+    /// ```bash
+    /// miniclaw trace show latest
+    /// ```
+    Trace {
+        #[command(subcommand)]
+        command: TraceCommands,
+    },
+
+    /// Check the RSS and Atom feeds the workspace is subscribed to
+    ///
+    /// Subscriptions are managed by the agent's feeds tool and stored in the
+    /// workspace (feeds/). Scheduled digests run `miniclaw feeds check`.
+    ///
+    /// # Examples
+    ///
+    /// Print the items published since the last check:
+    /// ```bash
+    /// miniclaw feeds check
+    /// ```
+    ///
+    /// List subscriptions:
+    /// ```bash
+    /// miniclaw feeds list
+    /// ```
+    Feeds {
+        #[command(subcommand)]
+        command: FeedsCommands,
+    },
+
+    /// Show the context the agent would send for a message
+    ///
+    /// Assembles the exact messages the context builder would send to the LLM
+    /// for a session and a hypothetical message, with the token count of each
+    /// layer and the history messages dropped to fit the context budget.
+    ///
+    /// # Examples
+    ///
+    /// Inspect a Telegram chat:
+    /// ```bash
+    /// miniclaw context --channel telegram --chat-id 123456789 -m "What's my locker code?"
+    /// ```
+    ///
+    /// Print each layer in full with a smaller budget:
+    /// ```bash
+    /// miniclaw context --channel cli --chat-id oneshot -m "Hello" --full --max-context-tokens 2000
+    /// ```
+    Context {
+        /// Channel of the session (e.g. telegram, cli)
+        #[arg(long, value_name = "CHANNEL")]
+        channel: String,
+
+        /// Chat id of the session
+        #[arg(long, value_name = "ID")]
+        chat_id: String,
+
+        /// Hypothetical message to build the context for
+        #[arg(short, long, value_name = "MESSAGE")]
+        message: String,
+
+        /// Agent profile whose workspace is used (default: the default agent)
+        #[arg(long, value_name = "NAME")]
+        agent: Option<String>,
+
+        /// Print the full content of each layer
+        #[arg(long)]
+        full: bool,
+
+        #[command(flatten)]
+        limits: AgentLimitOverrides,
+    },
+
+    /// Inspect the command policy used by exec, spawn and cron
+    ///
+    /// # Examples
+    ///
+    /// Check whether a command would be allowed for a Telegram user:
+    /// ```bash
+    /// miniclaw policy explain --channel telegram --user-id 42 -- find . -delete
+    /// ```
+    Policy {
+        #[command(subcommand)]
+        command: PolicyCommands,
+    },
+
     /// Start the gateway daemon
     ///
     /// Runs miniclaw as a background daemon with automatic session persistence.
@@ -178,6 +283,65 @@ pub enum Commands {
     Models,
 }
 
+/// Per-invocation overrides for the `agent` config section
+#[derive(Args, Debug, Default, Clone, PartialEq)]
+pub struct AgentLimitOverrides {
+    /// Maximum LLM→Tools iterations for this request
+    #[arg(long, value_name = "N")]
+    pub max_iterations: Option<u32>,
+
+    /// Maximum retries for a failed LLM call
+    #[arg(long, value_name = "N")]
+    pub max_llm_retries: Option<u32>,
+
+    /// Timeout for a single tool execution, in seconds
+    #[arg(long = "tool-timeout", value_name = "SECS")]
+    pub tool_timeout_secs: Option<u64>,
+
+    /// Maximum tokens allowed in context (approximate)
+    #[arg(long, value_name = "N")]
+    pub max_context_tokens: Option<usize>,
+
+    /// Maximum history messages to include in context
+    #[arg(long, value_name = "N")]
+    pub max_history_messages: Option<usize>,
+
+    /// Maximum memory entries to include in context
+    #[arg(long, value_name = "N")]
+    pub max_memory_entries: Option<usize>,
+
+    /// Token budget for memories injected into context
+    #[arg(long, value_name = "N")]
+    pub max_memory_tokens: Option<usize>,
+}
+
+impl AgentLimitOverrides {
+    /// Applies the overrides that were given on top of `agent_config`
+    pub fn apply(&self, agent_config: &mut AgentConfig) {
+        if let Some(v) = self.max_iterations {
+            agent_config.max_iterations = v;
+        }
+        if let Some(v) = self.max_llm_retries {
+            agent_config.max_llm_retries = v;
+        }
+        if let Some(v) = self.tool_timeout_secs {
+            agent_config.tool_timeout_secs = v;
+        }
+        if let Some(v) = self.max_context_tokens {
+            agent_config.max_context_tokens = v;
+        }
+        if let Some(v) = self.max_history_messages {
+            agent_config.max_history_messages = v;
+        }
+        if let Some(v) = self.max_memory_entries {
+            agent_config.max_memory_entries = v;
+        }
+        if let Some(v) = self.max_memory_tokens {
+            agent_config.max_memory_tokens = v;
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum MemoryCommands {
     /// Read memory entries
@@ -239,6 +403,76 @@ pub enum MemoryCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum TraceCommands {
+    /// List recent traces, newest first
+    List {
+        /// Maximum number of traces to show (default: 20)
+        #[arg(short = 'n', long, default_value = "20", value_name = "N")]
+        limit: usize,
+    },
+    /// Show a single trace
+    Show {
+        /// Trace id from `miniclaw trace list`, or `latest`
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FeedsCommands {
+    /// Print new items and mark them as seen; prints nothing when there are none
+    Check {
+        /// Only check this feed
+        #[arg(long, value_name = "NAME")]
+        feed: Option<String>,
+
+        /// Maximum number of items per feed (default: 20)
+        #[arg(short = 'n', long, default_value = "20", value_name = "N")]
+        limit: usize,
+
+        /// Workspace holding the subscriptions (default: ~/.miniclaw/workspace)
+        #[arg(long, value_name = "PATH")]
+        workspace: Option<std::path::PathBuf>,
+    },
+    /// List subscribed feeds
+    List {
+        /// Workspace holding the subscriptions (default: ~/.miniclaw/workspace)
+        #[arg(long, value_name = "PATH")]
+        workspace: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PolicyCommands {
+    /// Explain whether a command would be allowed, without running it
+    Explain {
+        /// Channel of the caller (e.g. telegram, cli)
+        #[arg(long, value_name = "CHANNEL")]
+        channel: Option<String>,
+
+        /// Chat id of the caller
+        #[arg(long, value_name = "ID")]
+        chat_id: Option<String>,
+
+        /// User id of the caller
+        #[arg(long, value_name = "ID")]
+        user_id: Option<String>,
+
+        /// Command to check
+        #[arg(value_name = "COMMAND")]
+        command: String,
+
+        /// Arguments of the command
+        #[arg(
+            value_name = "ARGS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        args: Vec<String>,
+    },
+}
+
 pub fn run(cli: Cli) -> anyhow::Result<()> {
     tracing::debug!("CLI parsing complete, processing command");
 
@@ -261,14 +495,48 @@ pub fn run(cli: Cli) -> anyhow::Result<()> {
             handle_help(command)?;
             Ok(())
         }
-        Some(Commands::Agent { message, model }) => {
+        Some(Commands::Agent {
+            message,
+            model,
+            limits,
+        }) => {
             tracing::debug!("Executing agent command");
-            handle_agent(message, model, &config, cli.verbose)
+            handle_agent(message, model, limits, &config, cli.verbose)
         }
         Some(Commands::Memory { command }) => {
             tracing::debug!("Executing memory command");
             handle_memory_command(command, &config)
         }
+        Some(Commands::Trace { command }) => {
+            tracing::debug!("Executing trace command");
+            handle_trace_command(command, &config)
+        }
+        Some(Commands::Feeds { command }) => {
+            tracing::debug!("Executing feeds command");
+            handle_feeds_command(command, &config)
+        }
+        Some(Commands::Context {
+            channel,
+            chat_id,
+            message,
+            agent,
+            full,
+            limits,
+        }) => {
+            tracing::debug!("Executing context command");
+            let request = ContextRequest {
+                channel,
+                chat_id,
+                message,
+                agent,
+                full,
+            };
+            handle_context(request, limits, &config)
+        }
+        Some(Commands::Policy { command }) => {
+            tracing::debug!("Executing policy command");
+            handle_policy_command(command, &config)
+        }
         Some(Commands::Gateway { pid_file }) => {
             tracing::debug!("Executing gateway command");
             handle_gateway(&config, pid_file)
@@ -308,12 +576,22 @@ fn handle_onboard(yes: bool, path: Option<String>, config: &Config) -> anyhow::R
 fn handle_agent(
     message: String,
     model: Option<String>,
+    limits: AgentLimitOverrides,
     config: &Config,
     verbose: bool,
 ) -> anyhow::Result<()> {
     use crate::agent::execute_one_shot;
 
-    tracing::info!(message = %message, model = ?model, "Starting agent one-shot command");
+    tracing::info!(message = %message, model = ?model, limits = ?limits, "Starting agent one-shot command");
+
+    // Apply per-invocation limit overrides on top of the configured agent section
+    let mut config = config.clone();
+    limits.apply(&mut config.agent);
+    config
+        .agent
+        .validate()
+        .context("Invalid agent limit override")?;
+    let config = &config;
 
     // Create a tokio runtime for the async execution
     let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
@@ -613,6 +891,478 @@ async fn handle_memory_rank(query: String, limit: usize, _config: &Config) -> an
     Ok(())
 }
 
+fn handle_trace_command(command: TraceCommands, _config: &Config) -> anyhow::Result<()> {
+    use crate::agent::TraceStore;
+
+    tracing::info!("Starting trace command");
+
+    let workspace_path = dirs::home_dir()
+        .map(|home| home.join(".miniclaw").join("workspace"))
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+
+    let trace_store = TraceStore::new(workspace_path);
+
+    // Create a tokio runtime for the async execution
+    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+
+    let result = rt.block_on(async {
+        match command {
+            TraceCommands::List { limit } => handle_trace_list(&trace_store, limit).await,
+            TraceCommands::Show { id } => handle_trace_show(&trace_store, &id).await,
+        }
+    });
+
+    rt.shutdown_timeout(std::time::Duration::from_secs(5));
+
+    result
+}
+
+fn handle_feeds_command(command: FeedsCommands, config: &Config) -> anyhow::Result<()> {
+    use crate::agent::tools::feeds::FeedStore;
+
+    tracing::info!("Starting feeds command");
+
+    let workspace = match &command {
+        FeedsCommands::Check { workspace, .. } | FeedsCommands::List { workspace } => {
+            workspace.clone()
+        }
+    };
+    let workspace_path = match workspace {
+        Some(path) => path,
+        None => dirs::home_dir()
+            .map(|home| home.join(".miniclaw").join("workspace"))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?,
+    };
+
+    let store = FeedStore::new(&workspace_path, &config.tools.web)
+        .map_err(|e| anyhow::anyhow!("Invalid tools.web: {}", e))?;
+
+    // Create a tokio runtime for the async execution
+    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+
+    let result = rt.block_on(async {
+        match command {
+            FeedsCommands::Check { feed, limit, .. } => {
+                handle_feeds_check(&store, feed.as_deref(), limit).await
+            }
+            FeedsCommands::List { .. } => handle_feeds_list(&store).await,
+        }
+    });
+
+    rt.shutdown_timeout(std::time::Duration::from_secs(5));
+
+    result
+}
+
+/// Prints the digest as plain text, since scheduled digests send it to a chat
+async fn handle_feeds_check(
+    store: &crate::agent::tools::feeds::FeedStore,
+    feed: Option<&str>,
+    limit: usize,
+) -> anyhow::Result<()> {
+    if limit == 0 {
+        anyhow::bail!("Limit must be a positive integer");
+    }
+
+    let updates = store
+        .check(feed, limit, true)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to check feeds: {}", e))?;
+
+    let digest = crate::agent::tools::feeds::format_digest(&updates);
+    if !digest.is_empty() {
+        println!("{}", digest);
+    }
+
+    Ok(())
+}
+
+async fn handle_feeds_list(store: &crate::agent::tools::feeds::FeedStore) -> anyhow::Result<()> {
+    let feeds = store
+        .list()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list feeds: {}", e))?;
+
+    if feeds.is_empty() {
+        println!("\x1b[33m📝 No feed subscriptions yet.\x1b[0m");
+        return Ok(());
+    }
+
+    println!("\x1b[1;36m## 📰 Feeds\x1b[0m\n");
+
+    for (name, subscription) in &feeds {
+        let title = subscription.title.as_deref().unwrap_or("(untitled)");
+        println!("\x1b[1m{}\x1b[0m — {}", name, title);
+        let checked = subscription
+            .last_checked
+            .map(|date| date.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| "never".to_string());
+        println!(
+            "   \x1b[90m{} | last checked {}\x1b[0m",
+            subscription.url, checked
+        );
+    }
+
+    println!("\n\x1b[90mTotal: {} feed(s)\x1b[0m", feeds.len());
+
+    Ok(())
+}
+
+async fn handle_trace_list(
+    trace_store: &crate::agent::TraceStore,
+    limit: usize,
+) -> anyhow::Result<()> {
+    if limit == 0 {
+        anyhow::bail!("Limit must be a positive integer");
+    }
+
+    let traces = trace_store
+        .list(limit)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list traces: {}", e))?;
+
+    if traces.is_empty() {
+        println!("\x1b[33m📝 No traces recorded yet.\x1b[0m");
+        return Ok(());
+    }
+
+    println!("\x1b[1;36m## 🔎 Recent Traces\x1b[0m\n");
+
+    for trace in &traces {
+        let status = match &trace.error {
+            Some(_) => "\x1b[31m✗\x1b[0m",
+            None => "\x1b[32m✓\x1b[0m",
+        };
+        println!("{} \x1b[1m{}\x1b[0m", status, trace.id);
+        println!(
+            "   \x1b[90m{} | {} | {}ms | {} LLM call(s) | {} tool call(s) | {} tokens\x1b[0m",
+            trace.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            trace.session_id,
+            trace.duration_ms,
+            trace.llm_calls.len(),
+            trace.tool_executions.len(),
+            trace.total_tokens()
+        );
+    }
+
+    println!("\n\x1b[90mTotal: {} trace(s)\x1b[0m", traces.len());
+
+    Ok(())
+}
+
+async fn handle_trace_show(trace_store: &crate::agent::TraceStore, id: &str) -> anyhow::Result<()> {
+    let trace = trace_store
+        .load(id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load trace '{}': {}", id, e))?;
+
+    println!("\x1b[1;36m## 🔎 Trace {}\x1b[0m\n", trace.id);
+    println!("Session:    {}", trace.session_id);
+    println!("Model:      {}", trace.model);
+    println!(
+        "Started:    {}",
+        trace.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!("Duration:   {}ms", trace.duration_ms);
+    println!("Iterations: {}", trace.iterations);
+    match (&trace.error, trace.response_chars) {
+        (Some(error), _) => println!("Outcome:    \x1b[31merror: {}\x1b[0m", error),
+        (None, Some(chars)) => println!("Outcome:    \x1b[32m{} chars response\x1b[0m", chars),
+        (None, None) => println!("Outcome:    unknown"),
+    }
+
+    println!(
+        "\n\x1b[1;32m### Context ({} layers, ~{} tokens)\x1b[0m",
+        trace.context_layers.len(),
+        trace.context_tokens()
+    );
+    for layer in &trace.context_layers {
+        println!(
+            "  {:>6} tok  {:<9} \x1b[90m{}\x1b[0m",
+            layer.estimated_tokens, layer.role, layer.label
+        );
+    }
+
+    println!(
+        "\n\x1b[1;32m### LLM Calls ({})\x1b[0m",
+        trace.llm_calls.len()
+    );
+    for call in &trace.llm_calls {
+        let tokens = match (call.prompt_tokens, call.completion_tokens) {
+            (Some(prompt), Some(completion)) => format!("{} in / {} out", prompt, completion),
+            _ => "tokens n/a".to_string(),
+        };
+        print!(
+            "  #{:<3} {:>6}ms  {}  \x1b[90m{}\x1b[0m",
+            call.iteration, call.latency_ms, call.model, tokens
+        );
+        if !call.tool_calls.is_empty() {
+            print!("  → {}", call.tool_calls.join(", "));
+        }
+        if let Some(error) = &call.error {
+            print!("  \x1b[31merror: {}\x1b[0m", error);
+        }
+        println!();
+    }
+
+    println!(
+        "\n\x1b[1;32m### Tool Executions ({})\x1b[0m",
+        trace.tool_executions.len()
+    );
+    for execution in &trace.tool_executions {
+        let status = if execution.error.is_some() {
+            "\x1b[31m✗\x1b[0m"
+        } else {
+            "\x1b[32m✓\x1b[0m"
+        };
+        println!(
+            "  {} #{:<3} {} {:>6}ms  {} bytes",
+            status,
+            execution.iteration,
+            execution.name,
+            execution.duration_ms,
+            execution.result_bytes
+        );
+        println!("      \x1b[90margs: {}\x1b[0m", execution.arguments);
+        if let Some(error) = &execution.error {
+            println!("      \x1b[31merror: {}\x1b[0m", error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Arguments of the `context` command
+struct ContextRequest {
+    channel: String,
+    chat_id: String,
+    message: String,
+    agent: Option<String>,
+    full: bool,
+}
+
+fn handle_context(
+    request: ContextRequest,
+    limits: AgentLimitOverrides,
+    config: &Config,
+) -> anyhow::Result<()> {
+    tracing::info!(channel = %request.channel, chat_id = %request.chat_id, agent = ?request.agent, "Starting context command");
+
+    let mut config = config.clone();
+    limits.apply(&mut config.agent);
+    config
+        .agent
+        .validate()
+        .context("Invalid agent limit override")?;
+
+    // Resolve the agent profile: explicit name, else the default profile if any
+    let profile = match &request.agent {
+        Some(name) => Some(
+            config
+                .agents
+                .iter()
+                .find(|p| &p.name == name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown agent profile '{}'", name))?,
+        ),
+        None => config.default_agent_profile(),
+    };
+    let workspace_path = match profile {
+        Some(profile) => profile.workspace_path(),
+        None => dirs::home_dir().map(|home| home.join(".miniclaw").join("workspace")),
+    }
+    .ok_or_else(|| anyhow::anyhow!("Could not determine workspace directory"))?;
+    if !workspace_path.exists() {
+        anyhow::bail!(
+            "Workspace does not exist: {}. Run 'miniclaw onboard' first.",
+            workspace_path.display()
+        );
+    }
+
+    let model = profile.and_then(|p| p.model.clone()).or_else(|| {
+        config
+            .provider_config
+            .as_ref()
+            .map(|pc| pc.default_model().to_string())
+    });
+    let tools = profile.and_then(|p| p.tools.clone());
+
+    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+    let result = rt.block_on(async {
+        print_context_report(
+            &request,
+            &workspace_path,
+            model.as_deref(),
+            tools.as_deref(),
+            &config,
+        )
+        .await
+    });
+    rt.shutdown_timeout(std::time::Duration::from_secs(5));
+
+    result
+}
+
+async fn print_context_report(
+    request: &ContextRequest,
+    workspace_path: &std::path::Path,
+    model: Option<&str>,
+    tools: Option<&[String]>,
+    config: &Config,
+) -> anyhow::Result<()> {
+    use crate::agent::ContextBuilderImpl;
+    use crate::agent::tools::ToolRegistry;
+    use crate::chat::InboundMessage;
+    use crate::providers::Tokenizer;
+    use crate::session::{Persistence, Session};
+
+    // Tool names are needed to render {{tools}} exactly as the agent would;
+    // the tools themselves are not built, so no scheduler or server starts
+    let tool_names = ToolRegistry::default_tool_names()
+        .into_iter()
+        .filter(|name| tools.is_none_or(|allowlist| allowlist.contains(name)))
+        .collect();
+
+    let tokenizer = model.map(Tokenizer::for_model).unwrap_or_default();
+    let builder =
+        ContextBuilderImpl::with_config(workspace_path, config.agent.context_builder_config())
+            .map_err(|e| anyhow::anyhow!("Failed to create context builder: {}", e))?
+            .with_tokenizer(tokenizer)
+            .with_tool_names(tool_names);
+
+    // MCP tool names are only known once the servers answer tools/list
+    if !config.tools.mcp.servers.is_empty() {
+        let servers: Vec<&str> = config
+            .tools
+            .mcp
+            .servers
+            .iter()
+            .map(|server| server.name.as_str())
+            .collect();
+        println!(
+            "\x1b[33m🔌 {{{{tools}}}} leaves out the tools of the MCP servers ({}), which are not started for this report.\x1b[0m\n",
+            servers.join(", ")
+        );
+    }
+
+    let persistence = Persistence::new(workspace_path.join("sessions"));
+    let session_id = format!("{}_{}", request.channel, request.chat_id);
+    let session = match persistence.load_session(&session_id).await {
+        Ok(session) => session,
+        Err(e) => {
+            println!(
+                "\x1b[33m📝 No saved session '{}' ({}), using an empty history.\x1b[0m\n",
+                session_id, e
+            );
+            Session::new(request.channel.clone(), request.chat_id.clone())
+        }
+    };
+
+    let inbound = InboundMessage::new(&request.channel, &request.chat_id, &request.message);
+    let report = builder
+        .build_context_report(&session, &inbound)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to build context: {}", e))?;
+
+    let total: usize = report
+        .messages
+        .iter()
+        .map(|m| builder.tokenizer().count_message(m))
+        .sum();
+    println!(
+        "\x1b[1;36m## 🧩 Context for {} ({} messages, {} / {} tokens, {})\x1b[0m\n",
+        session_id,
+        report.messages.len(),
+        total,
+        builder.config().max_context_tokens,
+        builder.tokenizer().kind()
+    );
+    print_context_messages(&report.messages, builder.tokenizer(), request.full);
+
+    if report.dropped.is_empty() {
+        println!("\n\x1b[90mNothing was dropped to fit the context budget.\x1b[0m");
+    } else {
+        println!(
+            "\n\x1b[1;33m### Dropped to fit the budget ({})\x1b[0m",
+            report.dropped.len()
+        );
+        print_context_messages(&report.dropped, builder.tokenizer(), request.full);
+    }
+
+    Ok(())
+}
+
+/// Prints one line per message (role, tokens, first line), or the full content
+fn print_context_messages(
+    messages: &[crate::providers::LlmMessage],
+    tokenizer: &crate::providers::Tokenizer,
+    full: bool,
+) {
+    for (i, msg) in messages.iter().enumerate() {
+        let mut label = crate::agent::trace::layer_label(&msg.content);
+        if let Some(calls) = &msg.tool_calls {
+            let names: Vec<&str> = calls.iter().map(|c| c.name.as_str()).collect();
+            label = format!("{} [tool calls: {}]", label, names.join(", "))
+                .trim()
+                .to_string();
+        }
+        println!(
+            "  {:>3}. {:>6} tok  {:<9} \x1b[90m{}\x1b[0m",
+            i + 1,
+            tokenizer.count_message(msg),
+            msg.role.as_str(),
+            label
+        );
+        if full {
+            for line in msg.content.lines() {
+                println!("         │ {}", line);
+            }
+        }
+    }
+}
+
+fn handle_policy_command(command: PolicyCommands, config: &Config) -> anyhow::Result<()> {
+    use crate::agent::tools::policy::PolicyScope;
+
+    match command {
+        PolicyCommands::Explain {
+            channel,
+            chat_id,
+            user_id,
+            command,
+            args,
+        } => {
+            let policy = config.tools.command_policy()?;
+            let scope = PolicyScope {
+                channel,
+                chat_id,
+                user_id,
+            };
+            let decision = policy.evaluate(&command, &args, &scope);
+
+            let invocation = std::iter::once(command.as_str())
+                .chain(args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if decision.allowed {
+                println!("\x1b[1;32mALLOWED\x1b[0m {}", invocation);
+            } else {
+                println!("\x1b[1;31mDENIED\x1b[0m {}", invocation);
+            }
+            println!("Reason: {}", decision.reason);
+            if decision.profiles.is_empty() {
+                println!("Profiles: (none)");
+            } else {
+                println!("Profiles: {}", decision.profiles.join(", "));
+            }
+            println!("Steps:");
+            for step in &decision.steps {
+                println!("  - {}", step);
+            }
+            Ok(())
+        }
+    }
+}
+
 fn handle_gateway(config: &Config, pid_file: Option<std::path::PathBuf>) -> anyhow::Result<()> {
     use crate::gateway::run_gateway;
 
@@ -839,7 +1589,8 @@ mod tests {
             cli.command,
             Some(Commands::Agent {
                 message,
-                model: None
+                model: None,
+                ..
             }) if message == "Hello"
         ));
     }
@@ -851,7 +1602,8 @@ mod tests {
             cli.command,
             Some(Commands::Agent {
                 message,
-                model: None
+                model: None,
+                ..
             }) if message == "Test message"
         ));
     }
@@ -863,7 +1615,8 @@ mod tests {
             cli.command,
             Some(Commands::Agent {
                 message,
-                model: Some(m)
+                model: Some(m),
+                ..
             }) if message == "Hello" && m == "custom-model"
         ));
     }
@@ -882,7 +1635,8 @@ mod tests {
             cli.command,
             Some(Commands::Agent {
                 message,
-                model: Some(m)
+                model: Some(m),
+                ..
             }) if message == "Test" && m == "google/gemini-2.5-flash"
         ));
     }
@@ -895,7 +1649,8 @@ mod tests {
             cli.command,
             Some(Commands::Agent {
                 message,
-                model: None
+                model: None,
+                ..
             }) if message == "Hello"
         ));
     }
@@ -907,11 +1662,180 @@ mod tests {
             cli.command,
             Some(Commands::Agent {
                 message,
-                model: None
+                model: None,
+                ..
             }) if message == "What is 2 + 2?"
         ));
     }
 
+    #[test]
+    fn test_agent_command_limit_overrides() {
+        let cli = Cli::parse_from([
+            "miniclaw",
+            "agent",
+            "-m",
+            "Hello",
+            "--max-iterations",
+            "10",
+            "--tool-timeout",
+            "120",
+            "--max-history-messages",
+            "5",
+        ]);
+
+        let Some(Commands::Agent { limits, .. }) = cli.command else {
+            panic!("expected agent command");
+        };
+        assert_eq!(limits.max_iterations, Some(10));
+        assert_eq!(limits.tool_timeout_secs, Some(120));
+        assert_eq!(limits.max_history_messages, Some(5));
+        assert_eq!(limits.max_llm_retries, None);
+
+        let mut agent_config = AgentConfig::default();
+        limits.apply(&mut agent_config);
+        assert_eq!(agent_config.max_iterations, 10);
+        assert_eq!(agent_config.tool_timeout_secs, 120);
+        assert_eq!(agent_config.max_history_messages, 5);
+        assert_eq!(
+            agent_config.max_llm_retries,
+            AgentConfig::default().max_llm_retries
+        );
+    }
+
+    #[test]
+    fn test_agent_command_without_overrides_keeps_config() {
+        let cli = Cli::parse_from(["miniclaw", "agent", "-m", "Hello"]);
+        let Some(Commands::Agent { limits, .. }) = cli.command else {
+            panic!("expected agent command");
+        };
+        assert_eq!(limits, AgentLimitOverrides::default());
+
+        let mut agent_config = AgentConfig::default();
+        limits.apply(&mut agent_config);
+        assert_eq!(agent_config, AgentConfig::default());
+    }
+
+    #[test]
+    fn test_trace_list_command_parsing() {
+        let cli = Cli::parse_from(["miniclaw", "trace", "list"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Trace {
+                command: TraceCommands::List { limit: 20 }
+            })
+        ));
+
+        let cli = Cli::parse_from(["miniclaw", "trace", "list", "-n", "5"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Trace {
+                command: TraceCommands::List { limit: 5 }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_trace_show_command_parsing() {
+        let cli = Cli::parse_from(["miniclaw", "trace", "show", "latest"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Trace {
+                command: TraceCommands::Show { id }
+            }) if id == "latest"
+        ));
+    }
+
+    #[test]
+    fn test_feeds_command_parsing() {
+        let cli = Cli::parse_from([
+            "miniclaw",
+            "feeds",
+            "check",
+            "--feed",
+            "hn",
+            "--workspace",
+            "/tmp/ws",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Feeds {
+                command: FeedsCommands::Check { feed: Some(feed), limit: 20, workspace: Some(ws) }
+            }) if feed == "hn" && ws == std::path::Path::new("/tmp/ws")
+        ));
+
+        let cli = Cli::parse_from(["miniclaw", "feeds", "list"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Feeds {
+                command: FeedsCommands::List { workspace: None }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_policy_explain_parsing() {
+        let cli = Cli::parse_from([
+            "miniclaw",
+            "policy",
+            "explain",
+            "--user-id",
+            "42",
+            "find",
+            ".",
+            "-delete",
+        ]);
+        let Some(Commands::Policy {
+            command:
+                PolicyCommands::Explain {
+                    channel,
+                    user_id,
+                    command,
+                    args,
+                    ..
+                },
+        }) = cli.command
+        else {
+            panic!("expected policy explain command");
+        };
+        assert_eq!(channel, None);
+        assert_eq!(user_id.as_deref(), Some("42"));
+        assert_eq!(command, "find");
+        assert_eq!(args, vec![".", "-delete"]);
+    }
+
+    #[test]
+    fn test_context_command_parsing() {
+        let cli = Cli::parse_from([
+            "miniclaw",
+            "context",
+            "--channel",
+            "telegram",
+            "--chat-id",
+            "42",
+            "-m",
+            "Hello",
+            "--max-context-tokens",
+            "2000",
+        ]);
+        let Some(Commands::Context {
+            channel,
+            chat_id,
+            message,
+            agent,
+            full,
+            limits,
+        }) = cli.command
+        else {
+            panic!("expected context command");
+        };
+        assert_eq!(channel, "telegram");
+        assert_eq!(chat_id, "42");
+        assert_eq!(message, "Hello");
+        assert_eq!(agent, None);
+        assert!(!full);
+        assert_eq!(limits.max_context_tokens, Some(2000));
+    }
+
     #[test]
     fn test_memory_rank_command_parsing() {
         let cli = Cli::parse_from(["miniclaw", "memory", "rank", "-q", "project meeting"]);
@@ -26,6 +26,19 @@ pub fn load_config(cli_model: Option<String>, cli_config_path: Option<PathBuf>)
 
     let mut config = Config::default();
 
+    // An explicit --config path must at least point into an existing directory;
+    // a missing file there is fine (defaults apply until onboarding writes it)
+    if let Some(ref path) = cli_config_path {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !parent.is_dir() {
+                anyhow::bail!(
+                    "Config directory does not exist: {}. Check the --config path.",
+                    parent.display()
+                );
+            }
+        }
+    }
+
     // Layer 1: Load from config file (~/.miniclaw/config.json)
     let config_file = cli_config_path.clone().or_else(get_default_config_path);
 
@@ -61,6 +74,14 @@ pub fn load_config(cli_model: Option<String>, cli_config_path: Option<PathBuf>)
         tracing::debug!("Applied CLI config path override");
     }
 
+    config
+        .validate_agents()
+        .context("Invalid agent configuration")?;
+    config
+        .tools
+        .validate()
+        .context("Invalid tool configuration")?;
+
     let final_summary = config.get_safe_summary();
     tracing::debug!(
         api_key_configured = final_summary.api_key_configured,
@@ -152,6 +173,9 @@ fn merge_config_from_file(config: Config, path: &PathBuf) -> Result<Config> {
         default_channel: file_config.default_channel,
         provider_type: None, // Deprecated, ignored
         provider_config: file_config.provider_config.or(config.provider_config),
+        agent: file_config.agent,
+        tools: file_config.tools,
+        agents: file_config.agents,
     })
 }
 
@@ -193,6 +217,9 @@ fn merge_env_variables(config: Config) -> Config {
         default_channel: config.default_channel,
         provider_type: None, // Deprecated, ignored
         provider_config: env_provider_config.or(config.provider_config),
+        agent: config.agent,
+        tools: config.tools,
+        agents: config.agents,
     }
 }
 
@@ -283,6 +310,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None, // Deprecated, should be ignored
             provider_config: Some(crate::providers::ProviderConfig::openrouter("file-api-key")),
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
         };
 
         save_config(&test_config, &config_path).unwrap();
@@ -315,6 +345,37 @@ mod tests {
         assert!(err_msg.contains("invalid JSON") || err_msg.to_lowercase().contains("json"));
     }
 
+    #[test]
+    fn test_load_config_invalid_command_policy() {
+        let _lock = CONFIG_TEST_ENV_LOCK.lock().unwrap();
+        let temp_dir = setup();
+        let config_path = temp_dir.path().join("config.json");
+
+        let mut test_config = Config::default();
+        test_config.tools.commands.rules = vec![crate::agent::tools::policy::ArgumentRule::deny(
+            "find", "(", "broken",
+        )];
+        save_config(&test_config, &config_path).unwrap();
+
+        let err = load_config(None, Some(config_path)).unwrap_err();
+        assert!(format!("{:#}", err).contains("tools.commands"));
+    }
+
+    #[test]
+    fn test_load_config_invalid_sandbox() {
+        let _lock = CONFIG_TEST_ENV_LOCK.lock().unwrap();
+        let temp_dir = setup();
+        let config_path = temp_dir.path().join("config.json");
+
+        let mut test_config = Config::default();
+        test_config.tools.sandbox.enabled = true;
+        test_config.tools.sandbox.max_open_files = Some(0);
+        save_config(&test_config, &config_path).unwrap();
+
+        let err = load_config(None, Some(config_path)).unwrap_err();
+        assert!(format!("{:#}", err).contains("tools.sandbox"));
+    }
+
     #[test]
     fn test_env_variable_override() {
         let _lock = CONFIG_TEST_ENV_LOCK.lock().unwrap();
@@ -331,6 +392,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: Some(crate::providers::ProviderConfig::openai("file-key")),
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
         };
         save_config(&file_config, &config_path).unwrap();
 
@@ -378,6 +442,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: Some(ProviderConfig::OpenAi(OpenAiConfig::new("file-key"))),
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
         };
         save_config(&file_config, &config_path).unwrap();
 
@@ -415,6 +482,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: Some(ProviderConfig::OpenAi(OpenAiConfig::new("file-key"))),
+            agent: crate::config::AgentConfig::default(),
+            tools: crate::config::ToolsConfig::default(),
+            agents: Vec::new(),
         };
         save_config(&file_config, &config_path).unwrap();
 
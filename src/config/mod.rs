@@ -4,4 +4,7 @@ pub mod schema;
 
 pub use loader::{get_config_path, load_config, save_config};
 pub use onboard::run_onboarding;
-pub use schema::Config;
+pub use schema::{
+    AgentConfig, AgentProfile, AgentRoutes, Config, FilesystemToolConfig, ShellToolConfig,
+    ToolsConfig,
+};
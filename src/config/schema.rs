@@ -1,7 +1,38 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
 
+use crate::agent::ContextBuilderConfig;
+use crate::agent::tools::filesystem::{DEFAULT_MAX_BASE64_BYTES, DEFAULT_MAX_READ_BYTES};
+use crate::agent::tools::mcp::McpConfig;
+use crate::agent::tools::policy::{CommandPolicy, CommandPolicyConfig};
+use crate::agent::tools::sandbox::{Sandbox, SandboxConfig};
+use crate::agent::tools::web::{WebTool, WebToolConfig};
+use crate::agent::tools::web_search::{WebSearchConfig, WebSearchTool};
+use crate::agent::tools::{
+    DEFAULT_MAX_SHELL_SESSIONS, DEFAULT_SHELL_IDLE_TIMEOUT_SECS, DEFAULT_TOOL_TIMEOUT_SECS,
+};
 use crate::providers::ProviderConfig;
 
+/// Default for `agent.max_iterations`: iterations before the agent loop
+/// stops to prevent infinite loops
+pub const MAX_ITERATIONS: u32 = 200;
+
+/// Default for `agent.max_llm_retries`: retries for transient LLM errors
+pub const MAX_LLM_RETRIES: u32 = 3;
+
+/// Upper bound for `agent.max_iterations`
+pub const MAX_ITERATIONS_LIMIT: u32 = 1000;
+
+/// Upper bound for `agent.max_llm_retries`
+pub const MAX_LLM_RETRIES_LIMIT: u32 = 10;
+
+/// Upper bound for `agent.tool_timeout_secs` (1 hour)
+pub const MAX_TOOL_TIMEOUT_SECS: u64 = 3600;
+
+/// Lower bound for `agent.max_context_tokens`
+pub const MIN_CONTEXT_TOKENS: usize = 256;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -31,11 +62,344 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider_config: Option<ProviderConfig>,
 
+    /// Agent runtime limits (iterations, retries, timeouts, context sizes)
+    #[serde(default)]
+    pub agent: AgentConfig,
+
+    /// Tool-specific settings
+    #[serde(default)]
+    pub tools: ToolsConfig,
+
+    /// Named agent profiles served by the gateway.
+    /// Empty = a single agent using ~/.miniclaw/workspace
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<AgentProfile>,
+
     /// DEPRECATED: Legacy model field - kept for deserialization warning only
     #[serde(skip_serializing)]
     pub model: Option<String>,
 }
 
+/// Runtime limits for the agent loop and context builder.
+///
+/// Missing fields fall back to the built-in defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentConfig {
+    /// Maximum LLM→Tools iterations per message
+    pub max_iterations: u32,
+    /// Maximum retries for a failed LLM call
+    pub max_llm_retries: u32,
+    /// Timeout for a single tool execution, in seconds
+    pub tool_timeout_secs: u64,
+    /// Maximum tokens allowed in context (approximate)
+    pub max_context_tokens: usize,
+    /// Maximum history messages to include in context
+    pub max_history_messages: usize,
+    /// Maximum memory entries to include in context
+    pub max_memory_entries: usize,
+    /// Token budget for injected memories and daily-note highlights
+    pub max_memory_tokens: usize,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        let context = ContextBuilderConfig::default();
+        Self {
+            max_iterations: MAX_ITERATIONS,
+            max_llm_retries: MAX_LLM_RETRIES,
+            tool_timeout_secs: DEFAULT_TOOL_TIMEOUT_SECS,
+            max_context_tokens: context.max_context_tokens,
+            max_history_messages: context.max_history_messages,
+            max_memory_entries: context.max_memory_entries,
+            max_memory_tokens: context.max_memory_tokens,
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Validate the agent limits.
+    ///
+    /// Checks:
+    /// - max_iterations is between 1 and [`MAX_ITERATIONS_LIMIT`]
+    /// - max_llm_retries is at most [`MAX_LLM_RETRIES_LIMIT`]
+    /// - tool_timeout_secs is between 1 and [`MAX_TOOL_TIMEOUT_SECS`]
+    /// - max_context_tokens is at least [`MIN_CONTEXT_TOKENS`]
+    /// - max_history_messages is between 1 and the session capacity
+    /// - max_memory_tokens is between 1 and max_context_tokens
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(1..=MAX_ITERATIONS_LIMIT).contains(&self.max_iterations) {
+            anyhow::bail!(
+                "Invalid agent.max_iterations: {}. Must be between 1 and {}.",
+                self.max_iterations,
+                MAX_ITERATIONS_LIMIT
+            );
+        }
+
+        if self.max_llm_retries > MAX_LLM_RETRIES_LIMIT {
+            anyhow::bail!(
+                "Invalid agent.max_llm_retries: {}. Must be at most {}.",
+                self.max_llm_retries,
+                MAX_LLM_RETRIES_LIMIT
+            );
+        }
+
+        if !(1..=MAX_TOOL_TIMEOUT_SECS).contains(&self.tool_timeout_secs) {
+            anyhow::bail!(
+                "Invalid agent.tool_timeout_secs: {}. Must be between 1 and {}.",
+                self.tool_timeout_secs,
+                MAX_TOOL_TIMEOUT_SECS
+            );
+        }
+
+        if self.max_context_tokens < MIN_CONTEXT_TOKENS {
+            anyhow::bail!(
+                "Invalid agent.max_context_tokens: {}. Must be at least {}.",
+                self.max_context_tokens,
+                MIN_CONTEXT_TOKENS
+            );
+        }
+
+        if !(1..=crate::session::MAX_MESSAGES).contains(&self.max_history_messages) {
+            anyhow::bail!(
+                "Invalid agent.max_history_messages: {}. Must be between 1 and {}.",
+                self.max_history_messages,
+                crate::session::MAX_MESSAGES
+            );
+        }
+
+        if !(1..=self.max_context_tokens).contains(&self.max_memory_tokens) {
+            anyhow::bail!(
+                "Invalid agent.max_memory_tokens: {}. Must be between 1 and max_context_tokens ({}).",
+                self.max_memory_tokens,
+                self.max_context_tokens
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Tool execution timeout as a [`Duration`](std::time::Duration)
+    pub fn tool_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.tool_timeout_secs)
+    }
+
+    /// Context builder settings derived from these limits
+    pub fn context_builder_config(&self) -> ContextBuilderConfig {
+        ContextBuilderConfig {
+            max_context_tokens: self.max_context_tokens,
+            max_history_messages: self.max_history_messages,
+            max_memory_entries: self.max_memory_entries,
+            max_memory_tokens: self.max_memory_tokens,
+            ..ContextBuilderConfig::default()
+        }
+    }
+}
+
+/// Settings for individual tools
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolsConfig {
+    /// Filesystem tool limits
+    pub filesystem: FilesystemToolConfig,
+    /// Command policy shared by the exec, spawn and cron executors
+    pub commands: CommandPolicyConfig,
+    /// Execution sandbox shared by the exec, spawn and cron executors
+    pub sandbox: SandboxConfig,
+    /// Shell session limits
+    pub shell: ShellToolConfig,
+    /// Web tool domain policy and credentials
+    pub web: WebToolConfig,
+    /// Web search backend
+    pub web_search: WebSearchConfig,
+    /// MCP servers whose tools are offered to the agent
+    pub mcp: McpConfig,
+}
+
+impl ToolsConfig {
+    /// Validate the tool settings.
+    ///
+    /// Checks:
+    /// - The filesystem limits are valid (see [`FilesystemToolConfig::validate`])
+    /// - The shell limits are valid (see [`ShellToolConfig::validate`])
+    /// - The command policy compiles (patterns, profile names and criteria)
+    /// - The sandbox limits are non-zero and its syscall names are known
+    /// - The web domain patterns parse and every credential is complete
+    /// - The web search backend has its URL or API key
+    /// - The MCP servers are well-formed (see [`McpConfig::validate`])
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.filesystem.validate()?;
+        self.shell.validate()?;
+        self.command_policy()?;
+        self.sandbox()?;
+        self.web_tool()?;
+        self.web_search_tool()?;
+        self.mcp
+            .validate()
+            .map_err(|e| anyhow::anyhow!("Invalid tools.mcp: {}", e))?;
+        Ok(())
+    }
+
+    /// Builds the command policy described by `commands`
+    pub fn command_policy(&self) -> anyhow::Result<CommandPolicy> {
+        CommandPolicy::from_config(&self.commands)
+            .map_err(|e| anyhow::anyhow!("Invalid tools.commands: {}", e))
+    }
+
+    /// Builds the execution sandbox described by `sandbox`
+    pub fn sandbox(&self) -> anyhow::Result<Sandbox> {
+        Sandbox::from_config(&self.sandbox)
+            .map_err(|e| anyhow::anyhow!("Invalid tools.sandbox: {}", e))
+    }
+
+    /// Builds the web tool described by `web`
+    pub fn web_tool(&self) -> anyhow::Result<WebTool> {
+        WebTool::from_config(&self.web).map_err(|e| anyhow::anyhow!("Invalid tools.web: {}", e))
+    }
+
+    /// Builds the web search tool described by `web_search`
+    pub fn web_search_tool(&self) -> anyhow::Result<WebSearchTool> {
+        WebSearchTool::from_config(&self.web_search)
+            .map_err(|e| anyhow::anyhow!("Invalid tools.web_search: {}", e))
+    }
+}
+
+/// Read limits for the filesystem tool.
+///
+/// Missing fields fall back to the built-in defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilesystemToolConfig {
+    /// Maximum number of bytes of text returned by a single read, and the size
+    /// limit for files searched by grep or edited in place
+    pub max_read_bytes: u64,
+    /// Maximum size of a file that can be read as base64
+    pub max_base64_bytes: u64,
+}
+
+impl Default for FilesystemToolConfig {
+    fn default() -> Self {
+        Self {
+            max_read_bytes: DEFAULT_MAX_READ_BYTES,
+            max_base64_bytes: DEFAULT_MAX_BASE64_BYTES,
+        }
+    }
+}
+
+impl FilesystemToolConfig {
+    /// Validate the read limits.
+    ///
+    /// Checks:
+    /// - max_read_bytes is at least 1
+    /// - max_base64_bytes is at least 1
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.max_read_bytes == 0 {
+            anyhow::bail!("Invalid tools.filesystem.max_read_bytes: 0. Must be at least 1.");
+        }
+        if self.max_base64_bytes == 0 {
+            anyhow::bail!("Invalid tools.filesystem.max_base64_bytes: 0. Must be at least 1.");
+        }
+        Ok(())
+    }
+}
+
+/// Limits for the shell session tool.
+///
+/// Missing fields fall back to the built-in defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellToolConfig {
+    /// Maximum number of open sessions per conversation
+    pub max_sessions: usize,
+    /// Seconds after which an unused session is closed
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for ShellToolConfig {
+    fn default() -> Self {
+        Self {
+            max_sessions: DEFAULT_MAX_SHELL_SESSIONS,
+            idle_timeout_secs: DEFAULT_SHELL_IDLE_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl ShellToolConfig {
+    /// Validate the session limits.
+    ///
+    /// Checks:
+    /// - max_sessions is at least 1
+    /// - idle_timeout_secs is at least 1
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.max_sessions == 0 {
+            anyhow::bail!("Invalid tools.shell.max_sessions: 0. Must be at least 1.");
+        }
+        if self.idle_timeout_secs == 0 {
+            anyhow::bail!("Invalid tools.shell.idle_timeout_secs: 0. Must be at least 1.");
+        }
+        Ok(())
+    }
+}
+
+/// A named agent with its own workspace, model and tools
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentProfile {
+    /// Unique profile name (letters, digits, '-' and '_')
+    pub name: String,
+
+    /// Workspace directory (SOUL.md, AGENTS.md, skills, sessions).
+    /// Defaults to ~/.miniclaw/agents/{name}/workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
+
+    /// Model for this agent; defaults to the provider's default model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Tool allowlist; all default tools when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+
+    /// Which inbound messages are routed to this agent
+    #[serde(default)]
+    pub routes: AgentRoutes,
+
+    /// Receives messages that match no route (defaults to the first profile)
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl AgentProfile {
+    /// Resolves the workspace directory, expanding a leading `~/`
+    pub fn workspace_path(&self) -> Option<PathBuf> {
+        match &self.workspace {
+            Some(path) => match path.strip_prefix("~") {
+                Ok(rest) => dirs::home_dir().map(|home| home.join(rest)),
+                Err(_) => Some(path.clone()),
+            },
+            None => dirs::home_dir().map(|home| {
+                home.join(".miniclaw")
+                    .join("agents")
+                    .join(&self.name)
+                    .join("workspace")
+            }),
+        }
+    }
+}
+
+/// Routing criteria for an agent profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentRoutes {
+    /// Channels routed to this agent (e.g. "telegram")
+    pub channels: Vec<String>,
+    /// Chat ids routed to this agent (restricted to `channels` when both are set)
+    pub chat_ids: Vec<String>,
+    /// Command prefix routed to this agent, e.g. "/ops"; stripped from the message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command_prefix: Option<String>,
+}
+
 fn default_spawn_log_output() -> bool {
     false
 }
@@ -54,6 +418,9 @@ impl Default for Config {
             default_channel: default_channel(),
             provider_type: None,
             provider_config: None,
+            agent: AgentConfig::default(),
+            tools: ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         }
     }
@@ -64,6 +431,8 @@ impl Config {
     ///
     /// Checks:
     /// - All user IDs in allow_from are positive integers
+    /// - The agent section and profiles are valid (see [`Config::validate_agents`])
+    /// - The tool settings are valid (see [`ToolsConfig::validate`])
     pub fn validate(&self) -> anyhow::Result<()> {
         // Validate allow_from entries are positive integers
         for user_id in &self.allow_from {
@@ -75,9 +444,73 @@ impl Config {
             }
         }
 
+        self.validate_agents()?;
+        self.tools.validate()?;
+
+        Ok(())
+    }
+
+    /// Validate the agent limits and agent profiles.
+    ///
+    /// Checks:
+    /// - The agent section is within bounds (see [`AgentConfig::validate`])
+    /// - Profile names are non-empty, unique and contain only letters, digits, '-' and '_'
+    /// - At most one profile is marked as default
+    /// - Command prefixes are non-empty, contain no whitespace and are unique
+    pub fn validate_agents(&self) -> anyhow::Result<()> {
+        self.agent.validate()?;
+
+        let mut names = HashSet::new();
+        let mut prefixes = HashSet::new();
+
+        for profile in &self.agents {
+            if profile.name.is_empty()
+                || !profile
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                anyhow::bail!(
+                    "Invalid agent profile name: '{}'. Use letters, digits, '-' and '_'.",
+                    profile.name
+                );
+            }
+
+            if !names.insert(profile.name.as_str()) {
+                anyhow::bail!("Duplicate agent profile name: '{}'", profile.name);
+            }
+
+            if let Some(prefix) = &profile.routes.command_prefix {
+                if prefix.is_empty() || prefix.chars().any(char::is_whitespace) {
+                    anyhow::bail!(
+                        "Invalid command_prefix '{}' for agent '{}'. Prefixes must be a single word.",
+                        prefix,
+                        profile.name
+                    );
+                }
+
+                if !prefixes.insert(prefix.as_str()) {
+                    anyhow::bail!("Duplicate command_prefix '{}' in agent profiles", prefix);
+                }
+            }
+        }
+
+        if self.agents.iter().filter(|p| p.default).count() > 1 {
+            anyhow::bail!("Only one agent profile can be marked as default");
+        }
+
         Ok(())
     }
 
+    /// Returns the profile that receives unrouted messages:
+    /// the one marked default, otherwise the first profile
+    pub fn default_agent_profile(&self) -> Option<&AgentProfile> {
+        self.agents
+            .iter()
+            .find(|p| p.default)
+            .or_else(|| self.agents.first())
+    }
+
     /// Check if OpenAI-compatible API key is configured (for logging purposes).
     /// Returns true if set, without exposing the actual key value.
     pub fn is_api_key_configured(&self) -> bool {
@@ -144,6 +577,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: None,
+            agent: AgentConfig::default(),
+            tools: ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -208,6 +644,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: None,
+            agent: AgentConfig::default(),
+            tools: ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -224,6 +663,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: None,
+            agent: AgentConfig::default(),
+            tools: ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -240,6 +682,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: None,
+            agent: AgentConfig::default(),
+            tools: ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -258,6 +703,9 @@ mod tests {
             default_channel: "telegram".to_string(),
             provider_type: None,
             provider_config: Some(ProviderConfig::OpenAi(OpenAiConfig::new("test-key"))),
+            agent: AgentConfig::default(),
+            tools: ToolsConfig::default(),
+            agents: Vec::new(),
             model: None,
         };
 
@@ -266,6 +714,364 @@ mod tests {
         assert!(summary.provider_configured);
     }
 
+    #[test]
+    fn test_agent_config_defaults_match_constants() {
+        let agent = AgentConfig::default();
+        assert_eq!(agent.max_iterations, MAX_ITERATIONS);
+        assert_eq!(agent.max_llm_retries, MAX_LLM_RETRIES);
+        assert_eq!(agent.tool_timeout_secs, DEFAULT_TOOL_TIMEOUT_SECS);
+        assert_eq!(agent.max_context_tokens, 4000);
+        assert_eq!(agent.max_history_messages, 50);
+        assert_eq!(agent.max_memory_entries, 20);
+        assert_eq!(agent.max_memory_tokens, 1000);
+        assert!(agent.validate().is_ok());
+    }
+
+    #[test]
+    fn test_agent_config_partial_deserialization() {
+        let json = r#"{
+            "agent": { "max_iterations": 25, "tool_timeout_secs": 120 }
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.agent.max_iterations, 25);
+        assert_eq!(config.agent.tool_timeout_secs, 120);
+        assert_eq!(config.agent.max_llm_retries, MAX_LLM_RETRIES);
+        assert_eq!(config.agent.max_history_messages, 50);
+    }
+
+    #[test]
+    fn test_config_without_agent_section_uses_defaults() {
+        let config: Config = serde_json::from_str(r#"{"allow_from": [1]}"#).unwrap();
+        assert_eq!(config.agent, AgentConfig::default());
+    }
+
+    #[test]
+    fn test_tools_config_partial_deserialization() {
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"filesystem": {"max_read_bytes": 4096}}}"#).unwrap();
+        assert_eq!(config.tools.filesystem.max_read_bytes, 4096);
+        assert_eq!(
+            config.tools.filesystem.max_base64_bytes,
+            DEFAULT_MAX_BASE64_BYTES
+        );
+        assert!(config.validate().is_ok());
+
+        let config: Config = serde_json::from_str(r#"{"allow_from": [1]}"#).unwrap();
+        assert_eq!(config.tools, ToolsConfig::default());
+    }
+
+    #[test]
+    fn test_command_policy_config() {
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"commands": {"mode": "allowlist", "allow": ["ls"]}}}"#,
+        )
+        .unwrap();
+        let policy = config.tools.command_policy().unwrap();
+        let scope = crate::agent::tools::policy::PolicyScope::default();
+        assert!(policy.is_allowed("ls", &[], &scope));
+        assert!(!policy.is_allowed("cat", &[], &scope));
+        // Unset fields keep their defaults
+        assert!(!config.tools.commands.rules.is_empty());
+
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"commands": {"rules": [{"command": "git", "args": "("}]}}}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_shell_config() {
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"shell": {"max_sessions": 2}}}"#).unwrap();
+        assert_eq!(config.tools.shell.max_sessions, 2);
+        assert_eq!(
+            config.tools.shell.idle_timeout_secs,
+            DEFAULT_SHELL_IDLE_TIMEOUT_SECS
+        );
+
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"shell": {"idle_timeout_secs": 0}}}"#).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_web_config() {
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"web": {
+                "allowed_domains": ["homeassistant.local", "*.example.com"],
+                "credentials": {
+                    "home_assistant": {"prefix": "Bearer ", "env": "HA_TOKEN", "domains": ["homeassistant.local"]}
+                }
+            }}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.tools.web.allowed_domains.len(), 2);
+        let credential = &config.tools.web.credentials["home_assistant"];
+        assert_eq!(credential.header, "Authorization");
+        assert_eq!(credential.env.as_deref(), Some("HA_TOKEN"));
+        assert!(config.validate().is_ok());
+
+        // A credential must name the hosts it may be sent to
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"web": {"credentials": {"api": {"value": "secret", "domains": []}}}}}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"web": {"denied_domains": ["http://x/"]}}}"#)
+                .unwrap();
+        assert!(config.validate().is_err());
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"web": {"local_services": ["homeassistant.local", "192.168.1.0/24"]}}}"#,
+        )
+        .unwrap();
+        assert!(!config.tools.web.allow_private_networks);
+        assert!(config.tools.web.cache.enabled);
+        assert_eq!(config.tools.web.cache.max_size_mb, 50);
+        assert!(config.validate().is_ok());
+
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"web": {"cache": {"max_size_mb": 0}}}}"#).unwrap();
+        assert!(config.validate().is_err());
+
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"web": {"local_services": ["10.0.0.0/99"]}}}"#)
+                .unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_web_search_config() {
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"web_search": {"backend": "searxng", "url": "http://searx.lan:8080"}}}"#,
+        )
+        .unwrap();
+        assert!(config.tools.web_search.fallback);
+        assert!(config.validate().is_ok());
+
+        // The hosted APIs need a key
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"web_search": {"backend": "brave"}}}"#).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_mcp_config() {
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"mcp": {"servers": [{"name": "docs", "url": "https://docs.example.com/mcp"}]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.tools.mcp.servers.len(), 1);
+        assert!(config.validate().is_ok());
+
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"mcp": {"servers": [{"name": "docs"}]}}}"#).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_sandbox_config() {
+        let config: Config = serde_json::from_str(
+            r#"{"tools": {"sandbox": {"memory_mb": null, "network": false}}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.tools.sandbox.memory_mb, None);
+        assert!(!config.tools.sandbox.network);
+        // Unset fields keep their defaults
+        assert_eq!(config.tools.sandbox.cpu_time_secs, Some(300));
+        assert!(config.tools.sandbox.seccomp.enabled);
+
+        let config: Config =
+            serde_json::from_str(r#"{"tools": {"sandbox": {"max_open_files": 0}}}"#).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validate_rejects_zero_read_limits() {
+        let mut config = Config::default();
+        config.tools.filesystem.max_read_bytes = 0;
+        assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        config.tools.filesystem.max_base64_bytes = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validate_rejects_invalid_agent_limits() {
+        let invalid = [
+            AgentConfig {
+                max_iterations: 0,
+                ..Default::default()
+            },
+            AgentConfig {
+                max_iterations: MAX_ITERATIONS_LIMIT + 1,
+                ..Default::default()
+            },
+            AgentConfig {
+                max_llm_retries: MAX_LLM_RETRIES_LIMIT + 1,
+                ..Default::default()
+            },
+            AgentConfig {
+                tool_timeout_secs: 0,
+                ..Default::default()
+            },
+            AgentConfig {
+                max_context_tokens: MIN_CONTEXT_TOKENS - 1,
+                ..Default::default()
+            },
+            AgentConfig {
+                max_history_messages: 0,
+                ..Default::default()
+            },
+            AgentConfig {
+                max_history_messages: crate::session::MAX_MESSAGES + 1,
+                ..Default::default()
+            },
+            AgentConfig {
+                max_memory_tokens: 0,
+                ..Default::default()
+            },
+            AgentConfig {
+                max_context_tokens: 1000,
+                max_memory_tokens: 1001,
+                ..Default::default()
+            },
+        ];
+
+        for agent in invalid {
+            let config = Config {
+                agent: agent.clone(),
+                tools: ToolsConfig::default(),
+                ..Default::default()
+            };
+            assert!(config.validate().is_err(), "{:?} should be rejected", agent);
+        }
+    }
+
+    fn profile(name: &str) -> AgentProfile {
+        AgentProfile {
+            name: name.to_string(),
+            workspace: None,
+            model: None,
+            tools: None,
+            routes: AgentRoutes::default(),
+            default: false,
+        }
+    }
+
+    #[test]
+    fn test_agent_profiles_deserialization() {
+        let json = r#"{
+            "agents": [
+                { "name": "home", "default": true },
+                {
+                    "name": "ops",
+                    "workspace": "/srv/ops",
+                    "model": "gpt-4o-mini",
+                    "tools": ["exec", "filesystem"],
+                    "routes": { "chat_ids": ["-100200"], "command_prefix": "/ops" }
+                }
+            ]
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.agents.len(), 2);
+        assert!(config.validate().is_ok());
+
+        let ops = &config.agents[1];
+        assert_eq!(ops.workspace_path(), Some(PathBuf::from("/srv/ops")));
+        assert_eq!(ops.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(ops.tools.as_ref().unwrap().len(), 2);
+        assert_eq!(ops.routes.command_prefix.as_deref(), Some("/ops"));
+        assert!(ops.routes.channels.is_empty());
+
+        assert_eq!(config.default_agent_profile().unwrap().name, "home");
+    }
+
+    #[test]
+    fn test_agent_profile_workspace_defaults() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+
+        assert_eq!(
+            profile("ops").workspace_path(),
+            Some(home.join(".miniclaw/agents/ops/workspace"))
+        );
+
+        let mut tilde = profile("ops");
+        tilde.workspace = Some(PathBuf::from("~/bots/ops"));
+        assert_eq!(tilde.workspace_path(), Some(home.join("bots/ops")));
+    }
+
+    #[test]
+    fn test_default_agent_profile_falls_back_to_first() {
+        let config = Config {
+            agents: vec![profile("home"), profile("ops")],
+            ..Default::default()
+        };
+        assert_eq!(config.default_agent_profile().unwrap().name, "home");
+        assert!(Config::default().default_agent_profile().is_none());
+    }
+
+    #[test]
+    fn test_config_validate_rejects_invalid_agent_profiles() {
+        let mut two_defaults = vec![profile("a"), profile("b")];
+        two_defaults[0].default = true;
+        two_defaults[1].default = true;
+
+        let mut same_prefix = vec![profile("a"), profile("b")];
+        same_prefix[0].routes.command_prefix = Some("/x".to_string());
+        same_prefix[1].routes.command_prefix = Some("/x".to_string());
+
+        let mut spaced_prefix = profile("a");
+        spaced_prefix.routes.command_prefix = Some("/x y".to_string());
+
+        let invalid = [
+            vec![profile("ops team")],
+            vec![profile("")],
+            vec![profile("ops"), profile("ops")],
+            two_defaults,
+            same_prefix,
+            vec![spaced_prefix],
+        ];
+
+        for agents in invalid {
+            let config = Config {
+                agents: agents.clone(),
+                ..Default::default()
+            };
+            assert!(
+                config.validate().is_err(),
+                "{:?} should be rejected",
+                agents
+            );
+        }
+    }
+
+    #[test]
+    fn test_agent_config_to_context_builder_config() {
+        let agent = AgentConfig {
+            max_context_tokens: 8000,
+            max_history_messages: 10,
+            max_memory_entries: 5,
+            max_memory_tokens: 300,
+            ..Default::default()
+        };
+
+        let context = agent.context_builder_config();
+        assert_eq!(context.max_context_tokens, 8000);
+        assert_eq!(context.max_history_messages, 10);
+        assert_eq!(context.max_memory_entries, 5);
+        assert_eq!(context.max_memory_tokens, 300);
+        assert!(context.cache_workspace_files);
+        assert_eq!(agent.tool_timeout(), std::time::Duration::from_secs(30));
+    }
+
     #[test]
     fn test_safe_summary_without_provider_config() {
         let config = Config::default();
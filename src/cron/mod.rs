@@ -6,6 +6,10 @@
 //! - Recurring tasks (Interval) that execute every N minutes (minimum 2)
 //!
 //! The scheduler runs as a background task and checks for due jobs every minute.
+//! Job commands are checked against the shared command policy when they run,
+//! using the scope of the caller that scheduled them, and run inside the
+//! shared execution sandbox. A job can name a chat that receives its output
+//! after each run.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -17,9 +21,13 @@ use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info};
 
+use crate::agent::tools::policy::{CommandPolicy, PolicyScope};
+use crate::agent::tools::sandbox::Sandbox;
+use crate::chat::{ChatHub, OutboundMessage};
+
 pub mod types;
 
-use types::{CancelResult, Job, JobStatus, ListResult, ScheduleResult};
+use types::{CancelResult, Job, JobNotification, JobStatus, ListResult, ScheduleResult};
 
 /// Global counter for unique job IDs
 static JOB_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -28,16 +36,81 @@ static JOB_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 ///
 /// Uses Arc<RwLock<HashMap>> for thread-safe concurrent access to jobs.
 /// The scheduler can be cloned to share the same job storage across tasks.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct CronScheduler {
     jobs: Arc<RwLock<HashMap<String, Job>>>,
+    policy: Arc<CommandPolicy>,
+    sandbox: Arc<Sandbox>,
+    chat_hub: Option<Arc<ChatHub>>,
+}
+
+impl std::fmt::Debug for CronScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CronScheduler")
+            .field("jobs", &self.jobs)
+            .field("policy", &self.policy)
+            .field("sandbox", &self.sandbox)
+            .field("notifications", &self.chat_hub.is_some())
+            .finish()
+    }
 }
 
 impl CronScheduler {
-    /// Creates a new CronScheduler with empty job storage
+    /// Creates a new CronScheduler with empty job storage, the default command
+    /// policy and the default sandbox
     pub fn new() -> Self {
         Self {
             jobs: Arc::new(RwLock::new(HashMap::new())),
+            policy: Arc::new(CommandPolicy::default()),
+            sandbox: Arc::new(Sandbox::default()),
+            chat_hub: None,
+        }
+    }
+
+    /// Replaces the command policy applied to job commands
+    pub fn with_policy(mut self, policy: Arc<CommandPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the command policy applied to job commands
+    pub fn policy(&self) -> &CommandPolicy {
+        &self.policy
+    }
+
+    /// Replaces the sandbox job commands run in
+    pub fn with_sandbox(mut self, sandbox: Arc<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Delivers the output of jobs that name a chat through `chat_hub`
+    pub fn with_chat_hub(mut self, chat_hub: Arc<ChatHub>) -> Self {
+        self.chat_hub = Some(chat_hub);
+        self
+    }
+
+    /// Whether job output can be sent to chats
+    pub fn can_notify(&self) -> bool {
+        self.chat_hub.is_some()
+    }
+
+    /// Sends a job's output, or its error, to the chat it names
+    ///
+    /// Empty output sends nothing, so a job can stay quiet when it has
+    /// nothing to report.
+    async fn notify(&self, job: &Job, result: &Result<String, String>) {
+        let (Some(notify), Some(hub)) = (&job.notify, &self.chat_hub) else {
+            return;
+        };
+        let content = match result {
+            Ok(output) if output.trim().is_empty() => return,
+            Ok(output) => output.trim().to_string(),
+            Err(e) => format!("Scheduled job {} failed: {}", job.id, e),
+        };
+        let message = OutboundMessage::new(&notify.channel, &notify.chat_id, content);
+        if let Err(e) = hub.send_outbound(message).await {
+            error!(job_id = %job.id, error = %e, "Failed to send job notification");
         }
     }
 
@@ -54,6 +127,8 @@ impl CronScheduler {
     /// * `command` - The command to execute
     /// * `execute_at` - ISO 8601 datetime string when to execute
     /// * `args` - Optional command arguments
+    /// * `scope` - Caller scheduling the job, for the command policy
+    /// * `notify` - Chat to send the job's output to
     ///
     /// # Returns
     /// * `Ok(ScheduleResult)` - Job scheduled successfully
@@ -63,6 +138,8 @@ impl CronScheduler {
         command: String,
         execute_at: String,
         args: Option<Vec<String>>,
+        scope: PolicyScope,
+        notify: Option<JobNotification>,
     ) -> Result<ScheduleResult, String> {
         // Parse the ISO 8601 datetime
         let execute_at = chrono::DateTime::parse_from_rfc3339(&execute_at)
@@ -80,7 +157,9 @@ impl CronScheduler {
         }
 
         let job_id = Self::generate_job_id();
-        let job = Job::new_fire_at(job_id.clone(), command, execute_at, args);
+        let job = Job::new_fire_at(job_id.clone(), command, execute_at, args)
+            .with_scope(scope)
+            .with_notification(notify);
 
         let next_execution = job.next_execution().unwrap();
 
@@ -109,6 +188,8 @@ impl CronScheduler {
     /// * `command` - The command to execute
     /// * `minutes` - Interval in minutes (must be >= 2)
     /// * `args` - Optional command arguments
+    /// * `scope` - Caller scheduling the job, for the command policy
+    /// * `notify` - Chat to send the job's output to
     ///
     /// # Returns
     /// * `Ok(ScheduleResult)` - Job scheduled successfully
@@ -118,6 +199,8 @@ impl CronScheduler {
         command: String,
         minutes: u32,
         args: Option<Vec<String>>,
+        scope: PolicyScope,
+        notify: Option<JobNotification>,
     ) -> Result<ScheduleResult, String> {
         // Validate minimum interval
         if minutes < types::MIN_INTERVAL_MINUTES {
@@ -129,7 +212,9 @@ impl CronScheduler {
         }
 
         let job_id = Self::generate_job_id();
-        let job = Job::new_interval(job_id.clone(), command, minutes, args);
+        let job = Job::new_interval(job_id.clone(), command, minutes, args)
+            .with_scope(scope)
+            .with_notification(notify);
 
         let next_execution = job.next_execution().unwrap();
 
@@ -347,7 +432,10 @@ impl CronScheduler {
                             );
 
                             // Execute the command
-                            match execute_job(&job).await {
+                            let result =
+                                execute_job(&job, &scheduler.policy, &scheduler.sandbox).await;
+                            scheduler.notify(&job, &result).await;
+                            match result {
                                 Ok(output) => {
                                     info!(
                                         job_id = %job_id,
@@ -401,45 +489,29 @@ impl Default for CronScheduler {
 /// Executes a job command
 ///
 /// This is a helper function that executes the job's command using
-/// tokio::process::Command. It applies the same security blacklist
-/// as the exec tool.
+/// tokio::process::Command. It applies the same command policy as the
+/// exec tool, evaluated for the caller that scheduled the job, and runs the
+/// command inside the sandbox.
 ///
 /// # Returns
 /// The stdout output of the command, or an error message
-async fn execute_job(job: &Job) -> Result<String, String> {
+async fn execute_job(
+    job: &Job,
+    policy: &CommandPolicy,
+    sandbox: &Sandbox,
+) -> Result<String, String> {
     use tokio::process::Command;
 
-    // Security: Check command against blacklist
-    const BLACKLIST: &[&str] = &[
-        "rm", "sudo", "dd", "mkfs", "shutdown", "reboot", "passwd", "visudo",
-    ];
-
-    // Extract basename for blacklist check
-    let base_cmd = std::path::Path::new(&job.command)
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or(&job.command)
-        .to_lowercase();
-
-    if BLACKLIST.contains(&base_cmd.as_str()) {
+    // Security: Check command against the policy
+    let args = job.args.clone().unwrap_or_default();
+    let decision = policy.evaluate(&job.command, &args, &job.scope);
+    if !decision.allowed {
         return Err(format!(
-            "Command '{}' is blacklisted for security reasons",
-            job.command
+            "Command not allowed: {} ({})",
+            job.command, decision.reason
         ));
     }
 
-    // Additional security: Check if command contains absolute paths to blacklisted commands
-    // This prevents bypassing with /bin/rm, /usr/bin/sudo, etc.
-    let cmd_lower = job.command.to_lowercase();
-    for blacklisted in BLACKLIST {
-        if cmd_lower.contains(&format!("/{}", blacklisted)) {
-            return Err(format!(
-                "Command '{}' contains blacklisted command '{}' and is blocked for security reasons",
-                job.command, blacklisted
-            ));
-        }
-    }
-
     // Build the command
     let mut cmd = Command::new(&job.command);
 
@@ -447,6 +519,10 @@ async fn execute_job(job: &Job) -> Result<String, String> {
         cmd.args(args);
     }
 
+    let _sandbox_guard = sandbox
+        .prepare(&mut cmd)
+        .map_err(|e| format!("Failed to prepare sandbox: {}", e))?;
+
     // Execute with timeout
     match tokio::time::timeout(Duration::from_secs(30), cmd.output()).await {
         Ok(Ok(output)) => {
@@ -494,6 +570,8 @@ mod tests {
                 "echo".to_string(),
                 execute_at,
                 Some(vec!["hello".to_string()]),
+                PolicyScope::default(),
+                None,
             )
             .await;
 
@@ -509,7 +587,13 @@ mod tests {
         let execute_at = (Utc::now() - Duration::hours(1)).to_rfc3339();
 
         let result = scheduler
-            .schedule_fire_at("echo".to_string(), execute_at, None)
+            .schedule_fire_at(
+                "echo".to_string(),
+                execute_at,
+                None,
+                PolicyScope::default(),
+                None,
+            )
             .await;
 
         assert!(result.is_err());
@@ -521,7 +605,13 @@ mod tests {
         let scheduler = CronScheduler::new();
 
         let result = scheduler
-            .schedule_interval("echo".to_string(), 5, Some(vec!["test".to_string()]))
+            .schedule_interval(
+                "echo".to_string(),
+                5,
+                Some(vec!["test".to_string()]),
+                PolicyScope::default(),
+                None,
+            )
             .await;
 
         assert!(result.is_ok());
@@ -535,7 +625,7 @@ mod tests {
         let scheduler = CronScheduler::new();
 
         let result = scheduler
-            .schedule_interval("echo".to_string(), 1, None)
+            .schedule_interval("echo".to_string(), 1, None, PolicyScope::default(), None)
             .await;
 
         assert!(result.is_err());
@@ -549,7 +639,13 @@ mod tests {
         // Schedule FireAt job with time far in the future
         let execute_at = (Utc::now() + Duration::days(1)).to_rfc3339();
         let result1 = scheduler
-            .schedule_fire_at("echo".to_string(), execute_at.clone(), None)
+            .schedule_fire_at(
+                "echo".to_string(),
+                execute_at.clone(),
+                None,
+                PolicyScope::default(),
+                None,
+            )
             .await;
         assert!(
             result1.is_ok(),
@@ -559,7 +655,7 @@ mod tests {
 
         // Schedule Interval job
         let result2 = scheduler
-            .schedule_interval("ls".to_string(), 10, None)
+            .schedule_interval("ls".to_string(), 10, None, PolicyScope::default(), None)
             .await;
         assert!(
             result2.is_ok(),
@@ -582,7 +678,13 @@ mod tests {
 
         let execute_at = (Utc::now() + Duration::hours(1)).to_rfc3339();
         let result = scheduler
-            .schedule_fire_at("echo".to_string(), execute_at, None)
+            .schedule_fire_at(
+                "echo".to_string(),
+                execute_at,
+                None,
+                PolicyScope::default(),
+                None,
+            )
             .await
             .unwrap();
 
@@ -689,11 +791,47 @@ mod tests {
             Some(vec!["hello".to_string()]),
         );
 
-        let result = execute_job(&job).await;
+        let result = execute_job(&job, &CommandPolicy::default(), &Sandbox::default()).await;
         assert!(result.is_ok());
         assert!(result.unwrap().contains("hello"));
     }
 
+    #[tokio::test]
+    async fn test_notify_sends_output_to_chat() {
+        let hub = Arc::new(ChatHub::new());
+        let scheduler = CronScheduler::new().with_chat_hub(Arc::clone(&hub));
+        assert!(scheduler.can_notify());
+        let job = crate::cron::types::Job::new_fire_at(
+            "test-notify".to_string(),
+            "echo".to_string(),
+            Utc::now() + Duration::hours(1),
+            None,
+        )
+        .with_notification(Some(JobNotification {
+            channel: "telegram".to_string(),
+            chat_id: "42".to_string(),
+        }));
+
+        scheduler.notify(&job, &Ok("  \n".to_string())).await;
+        assert!(hub.test_try_recv_outbound().await.is_none());
+
+        scheduler
+            .notify(&job, &Ok("3 new items\n".to_string()))
+            .await;
+        let message = hub.test_try_recv_outbound().await.unwrap();
+        assert_eq!(message.chat_id, "42");
+        assert_eq!(message.content, "3 new items");
+
+        scheduler
+            .notify(&job, &Err("exit status 1".to_string()))
+            .await;
+        let message = hub.test_try_recv_outbound().await.unwrap();
+        assert_eq!(
+            message.content,
+            "Scheduled job test-notify failed: exit status 1"
+        );
+    }
+
     #[tokio::test]
     async fn test_execute_job_blacklisted() {
         let job = crate::cron::types::Job::new_fire_at(
@@ -703,9 +841,9 @@ mod tests {
             Some(vec!["-rf".to_string(), "/".to_string()]),
         );
 
-        let result = execute_job(&job).await;
+        let result = execute_job(&job, &CommandPolicy::default(), &Sandbox::default()).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("blacklisted"));
+        assert!(result.unwrap_err().contains("not allowed"));
     }
 
     #[tokio::test]
@@ -717,9 +855,9 @@ mod tests {
             Some(vec!["-rf".to_string()]),
         );
 
-        let result = execute_job(&job).await;
+        let result = execute_job(&job, &CommandPolicy::default(), &Sandbox::default()).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("blacklisted"));
+        assert!(result.unwrap_err().contains("not allowed"));
     }
 
     #[tokio::test]
@@ -731,7 +869,7 @@ mod tests {
             None,
         );
 
-        let result = execute_job(&job).await;
+        let result = execute_job(&job, &CommandPolicy::default(), &Sandbox::default()).await;
         assert!(result.is_err());
     }
 
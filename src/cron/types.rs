@@ -8,6 +8,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::agent::tools::policy::PolicyScope;
+
 /// Minimum interval in minutes for recurring jobs
 pub const MIN_INTERVAL_MINUTES: u32 = 2;
 
@@ -82,6 +84,13 @@ pub enum JobStatus {
     Cancelled,
 }
 
+/// Chat that receives the output of a job
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobNotification {
+    pub channel: String,
+    pub chat_id: String,
+}
+
 /// A scheduled job
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Job {
@@ -104,6 +113,12 @@ pub struct Job {
     /// Last error message if job failed
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_error: Option<String>,
+    /// Caller that scheduled the job, used to evaluate the command policy
+    #[serde(default, skip_serializing_if = "PolicyScope::is_empty")]
+    pub scope: PolicyScope,
+    /// Chat sent the job's output after each run, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<JobNotification>,
 }
 
 impl Job {
@@ -132,6 +147,8 @@ impl Job {
             created_at: Utc::now(),
             execution_count: 0,
             last_error: None,
+            scope: PolicyScope::default(),
+            notify: None,
         }
     }
 
@@ -176,9 +193,23 @@ impl Job {
             created_at: now,
             execution_count: 0,
             last_error: None,
+            scope: PolicyScope::default(),
+            notify: None,
         }
     }
 
+    /// Records the caller that scheduled the job
+    pub fn with_scope(mut self, scope: PolicyScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Sends the job's output to a chat after each run
+    pub fn with_notification(mut self, notify: Option<JobNotification>) -> Self {
+        self.notify = notify;
+        self
+    }
+
     /// Get the next execution time for this job
     pub fn next_execution(&self) -> Option<DateTime<Utc>> {
         match &self.job_type {
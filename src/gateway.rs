@@ -2,58 +2,81 @@
 //!
 //! The gateway runs as a background daemon, managing the ChatHub and SessionManager
 //! with automatic session persistence every 30 seconds.
+//!
+//! Each configured agent profile gets its own workspace, SessionManager and AgentLoop;
+//! the ChatHub routes inbound messages between them.
 
 use crate::agent::tools::ToolRegistry;
-use crate::agent::{AgentLoop, ContextBuilderImpl};
+use crate::agent::tools::mcp::{self, McpTool};
+use crate::agent::{AgentLoop, ContextBuilderImpl, TraceStore};
 use crate::channels::{Channel, TelegramChannel};
-use crate::chat::ChatHub;
-use crate::config::Config;
-use crate::providers::LlmProvider;
+use crate::chat::{AgentRouter, ChatHub, InboundMessage, RouteRule};
+use crate::config::{AgentProfile, Config};
+use crate::providers::{LlmProvider, Tokenizer};
 use crate::session::SessionManager;
 use anyhow::{Context, Result};
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, error, info, warn};
 
 /// Runs the gateway daemon with auto-persistence and graceful shutdown.
 ///
 /// This function:
-/// 1. Initializes the SessionManager and loads existing sessions
-/// 2. Starts the auto-persistence background task (every 30 seconds)
-/// 3. Initializes the ChatHub and channels (Telegram, etc.)
-/// 4. Initializes the AgentLoop for message processing
-/// 5. Starts memory monitoring background task
-/// 6. Handles SIGTERM/SIGINT for graceful shutdown
-/// 7. Flushes all sessions to disk before exiting
+/// 1. Resolves the agent profiles (a single default agent if none are configured)
+/// 2. Initializes a SessionManager per agent and loads existing sessions
+/// 3. Starts the auto-persistence background tasks (every 30 seconds)
+/// 4. Initializes the ChatHub, agent routing and channels (Telegram, etc.)
+/// 5. Initializes an AgentLoop per agent for message processing
+/// 6. Starts memory monitoring background task
+/// 7. Handles SIGTERM/SIGINT for graceful shutdown
+/// 8. Flushes all sessions to disk before exiting
 pub async fn run_gateway(config: &Config) -> Result<()> {
     info!(
         version = env!("CARGO_PKG_VERSION"),
         "Starting miniclaw gateway daemon"
     );
 
-    // Determine sessions directory
-    let sessions_dir = dirs::home_dir()
-        .map(|home| home.join(".miniclaw").join("workspace").join("sessions"))
-        .context("Could not determine sessions directory")?;
-
-    // Initialize SessionManager
-    let session_manager = Arc::new(SessionManager::new(sessions_dir.clone()));
-    session_manager
-        .initialize()
-        .await
-        .context("Failed to initialize SessionManager")?;
-
+    // Resolve agent profiles and their workspaces
+    let profiles = resolve_agent_profiles(config)?;
     info!(
-        "SessionManager initialized with {} sessions",
-        session_manager.session_count().await
+        agents = ?profiles.iter().map(|p| p.profile.name.as_str()).collect::<Vec<_>>(),
+        "Agent profiles resolved"
     );
 
-    // Start auto-persistence background task
-    let (persistence_handle, persistence_shutdown) = session_manager.start_auto_persistence();
-    info!("Auto-persistence background task started (30s interval)");
+    // Initialize a SessionManager per agent, stored in the agent's workspace
+    let mut session_runtimes = Vec::with_capacity(profiles.len());
+    for gateway_profile in &profiles {
+        let name = &gateway_profile.profile.name;
+        let session_manager = Arc::new(SessionManager::new(
+            gateway_profile.workspace_path.join("sessions"),
+        ));
+        session_manager
+            .initialize()
+            .await
+            .with_context(|| format!("Failed to initialize SessionManager for agent '{}'", name))?;
+
+        info!(
+            agent = %name,
+            "SessionManager initialized with {} sessions",
+            session_manager.session_count().await
+        );
 
-    // Start session cleanup background task
-    let (cleanup_handle, cleanup_shutdown) = session_manager.start_cleanup_task();
-    info!("Session cleanup background task started (24h interval)");
+        // Start auto-persistence and session cleanup background tasks
+        let (persistence_handle, persistence_shutdown) = session_manager.start_auto_persistence();
+        let (cleanup_handle, cleanup_shutdown) = session_manager.start_cleanup_task();
+
+        session_runtimes.push(SessionRuntime {
+            agent: name.clone(),
+            session_manager,
+            persistence_handle,
+            persistence_shutdown,
+            cleanup_handle,
+            cleanup_shutdown,
+        });
+    }
+    info!("Auto-persistence background tasks started (30s interval)");
+    info!("Session cleanup background tasks started (24h interval)");
 
     // Create shutdown channel for coordination
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
@@ -96,84 +119,69 @@ pub async fn run_gateway(config: &Config) -> Result<()> {
         let _ = shutdown_tx_signal.send(()).await;
     });
 
-    // Initialize ChatHub
+    // Initialize ChatHub with one agent channel per profile
     let mut chat_hub = ChatHub::new();
+    let mut router = AgentRouter::new();
+    let mut agent_receivers = Vec::with_capacity(profiles.len());
 
-    // Create channel for ChatHub -> AgentLoop communication
-    let (agent_tx, agent_rx) = tokio::sync::mpsc::channel(100);
+    for gateway_profile in &profiles {
+        let profile = &gateway_profile.profile;
+        let (agent_tx, agent_rx) = mpsc::channel(100);
 
-    // Register the sender with ChatHub
-    chat_hub.register_agent_sender(agent_tx);
-    info!("ChatHub connected to AgentLoop via agent channel");
+        if profile.default {
+            chat_hub.register_agent_sender(agent_tx.clone());
+        }
+        chat_hub.register_named_agent_sender(&profile.name, agent_tx);
+
+        router.add_rule(RouteRule {
+            agent: profile.name.clone(),
+            channels: profile.routes.channels.clone(),
+            chat_ids: profile.routes.chat_ids.clone(),
+            command_prefix: profile.routes.command_prefix.clone(),
+        });
+        agent_receivers.push(agent_rx);
+    }
+
+    chat_hub.set_router(router);
+    info!("ChatHub connected to AgentLoops via agent channels");
 
     // Wrap in Arc for shared access
     let chat_hub = Arc::new(chat_hub);
     info!("ChatHub initialized");
 
-    // Determine workspace directory for context builder
-    let workspace_path = dirs::home_dir()
-        .map(|home| home.join(".miniclaw").join("workspace"))
-        .context("Could not determine workspace directory")?;
-
-    // Create LLM provider
+    // Create LLM provider (shared by all agents)
     let llm_provider = create_provider(config)?;
 
     // Determine model from provider_config or use provider default
-    let model = config
+    let default_model = config
         .provider_config
         .as_ref()
         .map(|pc| pc.default_model().to_string())
         .unwrap_or_else(|| llm_provider.default_model());
 
-    info!("LLM provider initialized with model: {}", model);
-
-    // Create tool registry with all default tools
-    // The "telegram" channel is used as default for the message tool
-    let tool_registry = Arc::new(
-        ToolRegistry::with_all_default_tools(
-            workspace_path.clone(),
-            Arc::clone(&chat_hub),
-            config,
-            &config.default_channel,
-        )
-        .await,
-    );
-    info!("Tool registry initialized with all default tools");
-
-    // Create context builder
-    let context_builder = Arc::new(
-        ContextBuilderImpl::new(workspace_path).context("Failed to create context builder")?,
-    );
-    info!("Context builder initialized");
+    info!("LLM provider initialized with model: {}", default_model);
 
-    // Initialize AgentLoop for message processing with inbound receiver
-    // Share the same session_manager instance (already Arc<SessionManager>)
-    let agent_loop = AgentLoop::builder(
-        Arc::clone(&chat_hub),
+    let shared = SharedAgentResources {
+        chat_hub: Arc::clone(&chat_hub),
         llm_provider,
-        context_builder,
-        tool_registry,
-        Arc::clone(&session_manager),
-    )
-    .with_model(model.clone())
-    .with_inbound_receiver(agent_rx)
-    .build();
-    info!("AgentLoop initialized with inbound receiver");
+        default_model,
+        // Start the MCP servers once; every agent gets proxies sharing the connections
+        mcp_tools: mcp::connect_servers(&config.tools.mcp).await,
+    };
 
-    // Spawn AgentLoop processing task
-    // Note: AgentLoop.run() can only be called once as it takes ownership of the inbound receiver
-    tokio::spawn(async move {
-        info!("AgentLoop processing task started");
-        match agent_loop.run().await {
-            Ok(()) => {
-                info!("AgentLoop processing task stopped normally");
-            }
-            Err(e) => {
-                error!("AgentLoop error: {}. Task terminated.", e);
-            }
-        }
-        info!("AgentLoop processing task terminated");
-    });
+    // Initialize and spawn an AgentLoop per profile
+    for ((gateway_profile, agent_rx), runtime) in
+        profiles.iter().zip(agent_receivers).zip(&session_runtimes)
+    {
+        start_agent_loop(
+            gateway_profile,
+            config,
+            &shared,
+            Arc::clone(&runtime.session_manager),
+            agent_rx,
+        )
+        .await?;
+    }
 
     // Initialize Telegram channel if configured
     let telegram_channel = if let Some(token) = &config.telegram_token {
@@ -279,40 +287,8 @@ pub async fn run_gateway(config: &Config) -> Result<()> {
         }
     }
 
-    // Signal persistence task to stop
-    info!("Signaling persistence task to stop...");
-    let _ = persistence_shutdown.send(()).await;
-
-    // Wait for persistence task to complete with timeout (5s as per spec)
-    let timeout_duration = std::time::Duration::from_secs(5);
-    match tokio::time::timeout(timeout_duration, persistence_handle).await {
-        Ok(Ok(())) => {
-            info!("Persistence task completed gracefully");
-        }
-        Ok(Err(e)) => {
-            error!("Persistence task panicked: {}", e);
-        }
-        Err(_) => {
-            error!("Persistence task did not complete within 5s timeout");
-        }
-    }
-
-    // Signal cleanup task to stop
-    info!("Signaling cleanup task to stop...");
-    let _ = cleanup_shutdown.send(()).await;
-
-    // Wait for cleanup task to complete with shorter timeout (it may not be actively running)
-    let cleanup_timeout = std::time::Duration::from_secs(5);
-    match tokio::time::timeout(cleanup_timeout, cleanup_handle).await {
-        Ok(Ok(())) => {
-            info!("Cleanup task completed gracefully");
-        }
-        Ok(Err(e)) => {
-            error!("Cleanup task panicked: {}", e);
-        }
-        Err(_) => {
-            error!("Cleanup task did not complete within 5s timeout");
-        }
+    for runtime in session_runtimes.iter_mut() {
+        runtime.stop_background_tasks().await;
     }
 
     // Signal memory monitoring task to stop
@@ -327,16 +303,256 @@ pub async fn run_gateway(config: &Config) -> Result<()> {
 
     // Final persistence flush
     info!("Flushing all sessions to disk...");
-    if let Err(e) = session_manager.save_all_sessions().await {
-        error!("Failed to save sessions during shutdown: {}", e);
-    } else {
-        info!("All sessions flushed to disk successfully");
+    for runtime in &session_runtimes {
+        if let Err(e) = runtime.session_manager.save_all_sessions().await {
+            error!(agent = %runtime.agent, "Failed to save sessions during shutdown: {}", e);
+        } else {
+            info!(agent = %runtime.agent, "All sessions flushed to disk successfully");
+        }
     }
 
     info!("Gateway daemon stopped gracefully");
     Ok(())
 }
 
+/// An agent profile resolved to an existing workspace directory
+struct GatewayProfile {
+    profile: AgentProfile,
+    workspace_path: PathBuf,
+}
+
+/// What the agent loops of all profiles share
+struct SharedAgentResources {
+    chat_hub: Arc<ChatHub>,
+    llm_provider: Arc<dyn LlmProvider>,
+    default_model: String,
+    /// Proxies for the tools of the MCP servers
+    mcp_tools: Vec<McpTool>,
+}
+
+/// Session persistence state and background tasks for one agent
+struct SessionRuntime {
+    agent: String,
+    session_manager: Arc<SessionManager>,
+    persistence_handle: tokio::task::JoinHandle<()>,
+    persistence_shutdown: mpsc::Sender<()>,
+    cleanup_handle: tokio::task::JoinHandle<()>,
+    cleanup_shutdown: mpsc::Sender<()>,
+}
+
+impl SessionRuntime {
+    /// Signals the persistence and cleanup tasks to stop and waits for them (5s each)
+    async fn stop_background_tasks(&mut self) {
+        let timeout_duration = std::time::Duration::from_secs(5);
+
+        // Signal persistence task to stop
+        info!(agent = %self.agent, "Signaling persistence task to stop...");
+        let _ = self.persistence_shutdown.send(()).await;
+
+        // Wait for persistence task to complete with timeout (5s as per spec)
+        match tokio::time::timeout(timeout_duration, &mut self.persistence_handle).await {
+            Ok(Ok(())) => {
+                info!(agent = %self.agent, "Persistence task completed gracefully");
+            }
+            Ok(Err(e)) => {
+                error!(agent = %self.agent, "Persistence task panicked: {}", e);
+            }
+            Err(_) => {
+                error!(agent = %self.agent, "Persistence task did not complete within 5s timeout");
+            }
+        }
+
+        // Signal cleanup task to stop
+        info!(agent = %self.agent, "Signaling cleanup task to stop...");
+        let _ = self.cleanup_shutdown.send(()).await;
+
+        // Wait for cleanup task to complete (it may not be actively running)
+        match tokio::time::timeout(timeout_duration, &mut self.cleanup_handle).await {
+            Ok(Ok(())) => {
+                info!(agent = %self.agent, "Cleanup task completed gracefully");
+            }
+            Ok(Err(e)) => {
+                error!(agent = %self.agent, "Cleanup task panicked: {}", e);
+            }
+            Err(_) => {
+                error!(agent = %self.agent, "Cleanup task did not complete within 5s timeout");
+            }
+        }
+    }
+}
+
+/// Resolves the configured agent profiles to workspaces.
+///
+/// Without configured profiles a single "default" agent using ~/.miniclaw/workspace
+/// is returned. Exactly one returned profile is marked as default. Profiles without
+/// an explicit workspace get one initialized under ~/.miniclaw/agents/{name}/.
+fn resolve_agent_profiles(config: &Config) -> Result<Vec<GatewayProfile>> {
+    if config.agents.is_empty() {
+        let workspace_path = dirs::home_dir()
+            .map(|home| home.join(".miniclaw").join("workspace"))
+            .context("Could not determine workspace directory")?;
+
+        return Ok(vec![GatewayProfile {
+            profile: AgentProfile {
+                name: "default".to_string(),
+                workspace: Some(workspace_path.clone()),
+                model: None,
+                tools: None,
+                routes: Default::default(),
+                default: true,
+            },
+            workspace_path,
+        }]);
+    }
+
+    let default_name = config
+        .default_agent_profile()
+        .map(|p| p.name.clone())
+        .unwrap_or_default();
+
+    let mut profiles = Vec::with_capacity(config.agents.len());
+    for profile in &config.agents {
+        let workspace_path = profile.workspace_path().with_context(|| {
+            format!(
+                "Could not determine workspace directory for agent '{}'",
+                profile.name
+            )
+        })?;
+
+        if !workspace_path.exists() {
+            match (&profile.workspace, workspace_path.parent()) {
+                // Default location: create it with the standard workspace files
+                (None, Some(base_path)) => {
+                    info!(agent = %profile.name, path = %workspace_path.display(), "Initializing agent workspace");
+                    crate::workspace::initialize_workspace(base_path, false).with_context(
+                        || {
+                            format!(
+                                "Failed to initialize workspace for agent '{}'",
+                                profile.name
+                            )
+                        },
+                    )?;
+                }
+                _ => anyhow::bail!(
+                    "Workspace for agent '{}' does not exist: {}. Create it or run 'miniclaw onboard --path <dir>'.",
+                    profile.name,
+                    workspace_path.display()
+                ),
+            }
+        }
+
+        let mut profile = profile.clone();
+        profile.default = profile.name == default_name;
+        profiles.push(GatewayProfile {
+            profile,
+            workspace_path,
+        });
+    }
+
+    Ok(profiles)
+}
+
+/// Builds the tools, context builder and AgentLoop for one profile and spawns it.
+async fn start_agent_loop(
+    gateway_profile: &GatewayProfile,
+    config: &Config,
+    shared: &SharedAgentResources,
+    session_manager: Arc<SessionManager>,
+    agent_rx: mpsc::Receiver<InboundMessage>,
+) -> Result<()> {
+    let profile = &gateway_profile.profile;
+    let workspace_path = &gateway_profile.workspace_path;
+    let name = profile.name.clone();
+
+    // Create tool registry with the default tools in the profile allowlist, so
+    // tools the profile leaves out start no scheduler or other background work
+    let tool_registry = Arc::new(
+        ToolRegistry::with_allowed_default_tools(
+            workspace_path.clone(),
+            Arc::clone(&shared.chat_hub),
+            config,
+            &config.default_channel,
+            profile.tools.as_deref(),
+        )
+        .await,
+    );
+    mcp::register_tools(&tool_registry, &shared.mcp_tools).await;
+
+    if let Some(allowlist) = &profile.tools {
+        let removed = tool_registry.retain_tools(allowlist).await;
+        for tool in allowlist {
+            if !tool_registry.contains(tool).await {
+                warn!(agent = %name, tool = %tool, "Unknown tool in agent allowlist");
+            }
+        }
+        debug!(agent = %name, removed = ?removed, "Tool allowlist applied");
+    }
+    info!(agent = %name, tools = tool_registry.len().await, "Tool registry initialized");
+
+    // Release per-conversation tool state, such as shell sessions, with expired sessions
+    let mut expired = session_manager.subscribe_expired();
+    let registry = Arc::clone(&tool_registry);
+    tokio::spawn(async move {
+        loop {
+            match expired.recv().await {
+                Ok(session_id) => registry.session_expired(&session_id).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Missed expired session notifications");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let model = profile.model.as_deref().unwrap_or(&shared.default_model);
+    let tool_names = tool_registry
+        .list_tools()
+        .await
+        .into_iter()
+        .map(|(name, _, _)| name)
+        .collect();
+
+    // Create context builder, budgeting with the model's tokenizer
+    let context_builder = Arc::new(
+        ContextBuilderImpl::with_config(workspace_path, config.agent.context_builder_config())
+            .with_context(|| format!("Failed to create context builder for agent '{}'", name))?
+            .with_tokenizer(Tokenizer::for_model(model))
+            .with_tool_names(tool_names),
+    );
+
+    // Initialize AgentLoop for message processing with inbound receiver
+    let agent_loop = AgentLoop::builder(
+        Arc::clone(&shared.chat_hub),
+        Arc::clone(&shared.llm_provider),
+        context_builder,
+        tool_registry,
+        session_manager,
+    )
+    .with_model(model)
+    .with_agent_config(&config.agent)
+    .with_trace_store(TraceStore::new(workspace_path))
+    .with_inbound_receiver(agent_rx)
+    .build();
+    info!(agent = %name, model = %model, "AgentLoop initialized with inbound receiver");
+
+    // Spawn AgentLoop processing task
+    // Note: AgentLoop.run() can only be called once as it takes ownership of the inbound receiver
+    tokio::spawn(async move {
+        info!(agent = %name, "AgentLoop processing task started");
+        match agent_loop.run().await {
+            Ok(()) => {
+                info!(agent = %name, "AgentLoop processing task stopped normally");
+            }
+            Err(e) => {
+                error!(agent = %name, "AgentLoop error: {}. Task terminated.", e);
+            }
+        }
+        info!(agent = %name, "AgentLoop processing task terminated");
+    });
+
+    Ok(())
+}
+
 /// Runs the ChatHub with a timeout for graceful shutdown coordination.
 async fn run_chat_hub(chat_hub: Arc<ChatHub>) -> Result<()> {
     // Run ChatHub with a periodic check for shutdown
@@ -376,6 +592,58 @@ fn create_provider(config: &Config) -> Result<Arc<dyn LlmProvider>> {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn profile(name: &str, workspace: &std::path::Path) -> AgentProfile {
+        AgentProfile {
+            name: name.to_string(),
+            workspace: Some(workspace.to_path_buf()),
+            model: None,
+            tools: None,
+            routes: Default::default(),
+            default: false,
+        }
+    }
+
+    #[test]
+    fn test_resolve_agent_profiles_without_config_uses_single_default() {
+        let profiles = resolve_agent_profiles(&Config::default()).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].profile.name, "default");
+        assert!(profiles[0].profile.default);
+        assert!(profiles[0].workspace_path.ends_with(".miniclaw/workspace"));
+    }
+
+    #[test]
+    fn test_resolve_agent_profiles_marks_single_default() {
+        let home = tempfile::TempDir::new().unwrap();
+        let ops = tempfile::TempDir::new().unwrap();
+
+        let mut ops_profile = profile("ops", ops.path());
+        ops_profile.default = true;
+        let config = Config {
+            agents: vec![profile("home", home.path()), ops_profile],
+            ..Default::default()
+        };
+
+        let profiles = resolve_agent_profiles(&config).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert!(!profiles[0].profile.default);
+        assert!(profiles[1].profile.default);
+        assert_eq!(profiles[1].workspace_path, ops.path());
+    }
+
+    #[test]
+    fn test_resolve_agent_profiles_rejects_missing_explicit_workspace() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            agents: vec![profile("ops", &temp_dir.path().join("missing"))],
+            ..Default::default()
+        };
+
+        let err = resolve_agent_profiles(&config).err().unwrap();
+        assert!(err.to_string().contains("does not exist"));
+    }
 
     #[tokio::test]
     async fn test_gateway_command_available() {
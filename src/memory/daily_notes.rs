@@ -9,6 +9,7 @@ use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
 use crate::memory::types::{MemoryEntry, MemoryError, MemoryType};
+use crate::workspace::WorkspaceFileCache;
 
 /// Default number of days to show for `memory recent` command
 pub const DEFAULT_RECENT_DAYS: usize = 7;
@@ -133,6 +134,23 @@ where
 pub async fn read_recent_days(
     workspace_path: &Path,
     days: usize,
+) -> Result<Vec<DailyNoteSection>, MemoryError> {
+    read_recent_days_with(workspace_path, days, None).await
+}
+
+/// Same as [`read_recent_days`], reading and parsing the files through `cache`
+pub async fn read_recent_days_cached(
+    workspace_path: &Path,
+    days: usize,
+    cache: &WorkspaceFileCache,
+) -> Result<Vec<DailyNoteSection>, MemoryError> {
+    read_recent_days_with(workspace_path, days, Some(cache)).await
+}
+
+async fn read_recent_days_with(
+    workspace_path: &Path,
+    days: usize,
+    cache: Option<&WorkspaceFileCache>,
 ) -> Result<Vec<DailyNoteSection>, MemoryError> {
     // Validate days parameter
     if days == 0 {
@@ -153,7 +171,18 @@ pub async fn read_recent_days(
         let file_path = memory_dir.join(&filename);
 
         if file_path.exists() {
-            match parse_daily_note_file(&file_path).await {
+            let parsed = match cache {
+                Some(cache) => cache
+                    .read_parsed(&file_path, |content| parse_daily_note(content, date))
+                    .await
+                    .map(|entries| entries.as_ref().clone())
+                    .map_err(|e| MemoryError::StorageFailed {
+                        operation: format!("read daily note file {}", file_path.display()),
+                        source: e,
+                    }),
+                None => parse_daily_note_file(&file_path).await,
+            };
+            match parsed {
                 Ok(entries) => {
                     if !entries.is_empty() {
                         sections.push(DailyNoteSection {
@@ -172,7 +201,7 @@ pub async fn read_recent_days(
     }
 
     // Sort chronologically (oldest first)
-    sections.sort_by(|a, b| a.date.cmp(&b.date));
+    sections.sort_by_key(|a| a.date);
 
     tracing::info!(
         days = days,
@@ -210,6 +239,11 @@ async fn parse_daily_note_file(file_path: &Path) -> Result<Vec<DailyNoteEntry>,
             source: std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid filename format"),
         })?;
 
+    Ok(parse_daily_note(&content, file_date))
+}
+
+/// Extracts the entries of a daily note written on `file_date`
+fn parse_daily_note(content: &str, file_date: NaiveDate) -> Vec<DailyNoteEntry> {
     let mut entries = Vec::new();
     let mut current_timestamp: Option<DateTime<Utc>> = None;
     let mut current_content = String::new();
@@ -250,7 +284,7 @@ async fn parse_daily_note_file(file_path: &Path) -> Result<Vec<DailyNoteEntry>,
         }
     }
 
-    Ok(entries)
+    entries
 }
 
 /// Parses a time string in format "HH:MM:SS UTC" with a given date
@@ -2,6 +2,7 @@
 //!
 //! This module provides functions and structures for managing long-term memory stored in MEMORY.md
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -18,6 +19,10 @@ const MEMORY_FILE_SIZE_LIMIT: u64 = 1024 * 1024;
 /// Timestamp format suffix in memory entries: "(added at YYYY-MM-DDTHH:MM:SSZ)"
 const TIMESTAMP_SUFFIX: &str = "(added at ";
 
+/// Marker holding an entry's identifier at the end of its line: "<!-- id:1a2b3c4d -->"
+const ID_MARKER_PREFIX: &str = "<!-- id:";
+const ID_MARKER_SUFFIX: &str = "-->";
+
 /// Checks file size and logs warning if exceeds limit
 ///
 /// # Arguments
@@ -51,6 +56,8 @@ async fn check_file_size(file_path: &Path) -> Result<(), MemoryError> {
 /// A long-term memory entry with content and timestamp
 #[derive(Debug, Clone)]
 pub struct LongTermMemoryEntry {
+    /// Stable identifier, kept in MEMORY.md as an HTML comment
+    pub id: String,
     /// The content of the memory entry
     pub content: String,
     /// When the entry was created (ISO 8601 UTC)
@@ -63,10 +70,7 @@ impl LongTermMemoryEntry {
     /// # Arguments
     /// * `content` - The content to store
     pub fn new(content: String) -> Self {
-        Self {
-            content,
-            timestamp: Utc::now(),
-        }
+        Self::with_timestamp(content, Utc::now())
     }
 
     /// Creates a long-term memory entry with a specific timestamp
@@ -75,12 +79,20 @@ impl LongTermMemoryEntry {
     /// * `content` - The content to store
     /// * `timestamp` - The timestamp to use
     pub fn with_timestamp(content: String, timestamp: DateTime<Utc>) -> Self {
-        Self { content, timestamp }
+        Self {
+            id: derive_id(&entry_text(&content, timestamp)),
+            content,
+            timestamp,
+        }
     }
 
     /// Parses a memory entry from a bullet point line
     ///
-    /// Extracts content and timestamp from format: "- Content (added at 2026-02-16T10:30:00Z)"
+    /// Extracts content, timestamp and id from format:
+    /// "- Content (added at 2026-02-16T10:30:00Z) <!-- id:1a2b3c4d -->"
+    ///
+    /// Lines without an id marker (written before ids existed) get one derived
+    /// from the line, so it stays the same until the line is edited.
     ///
     /// # Arguments
     /// * `line` - The bullet point line to parse
@@ -89,30 +101,153 @@ impl LongTermMemoryEntry {
     /// * `Some(LongTermMemoryEntry)` - If parsing succeeds
     /// * `None` - If format is invalid
     fn parse_from_line(line: &str) -> Self {
-        let content_str = line.trim();
-
-        // Try to extract timestamp from "(added at ...)" suffix
-        if let Some(timestamp_start) = content_str.rfind(TIMESTAMP_SUFFIX) {
-            let content = content_str[..timestamp_start].trim().to_string();
-            let timestamp_str = &content_str[timestamp_start + TIMESTAMP_SUFFIX.len()..];
-
-            // Extract timestamp until closing ')'
-            if let Some(end_paren) = timestamp_str.find(')') {
-                let timestamp_value = &timestamp_str[..end_paren];
-
-                // Try to parse ISO 8601 timestamp
-                if let Ok(timestamp) = DateTime::parse_from_rfc3339(timestamp_value) {
-                    return Self {
-                        content,
-                        timestamp: timestamp.with_timezone(&Utc),
-                    };
-                }
+        let (text, id) = split_id_marker(line);
+        let id = id.map_or_else(|| derive_id(text), str::to_string);
+        let (content, timestamp) = split_timestamp_suffix(text);
+        Self {
+            id,
+            content: content.to_string(),
+            // Fallback: use current timestamp
+            timestamp: timestamp.unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+/// Formats an entry as stored, without its id: "Content (added at ...)"
+fn entry_text(content: &str, timestamp: DateTime<Utc>) -> String {
+    format!(
+        "{} {}{})",
+        content,
+        TIMESTAMP_SUFFIX,
+        timestamp.to_rfc3339()
+    )
+}
+
+/// Formats a complete entry line, bullet and id included
+fn entry_line(content: &str, timestamp: DateTime<Utc>, id: &str) -> String {
+    format!(
+        "- {} {}{} {}",
+        entry_text(content, timestamp),
+        ID_MARKER_PREFIX,
+        id,
+        ID_MARKER_SUFFIX
+    )
+}
+
+/// Derives an 8 hex digit identifier from an entry's text
+fn derive_id(text: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, text.as_bytes());
+    digest.as_ref()[..4]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Derives an identifier for `text` that is not in `taken`
+fn unique_id(text: &str, taken: &HashSet<String>) -> String {
+    let mut id = derive_id(text);
+    let mut attempt = 1;
+    while taken.contains(&id) {
+        id = derive_id(&format!("{}#{}", text, attempt));
+        attempt += 1;
+    }
+    id
+}
+
+/// Joins the lines of `content` so an entry stays on its bullet line
+fn single_line(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits an entry line into its text and the id from its "<!-- id:... -->" marker, if any
+pub(crate) fn split_id_marker(line: &str) -> (&str, Option<&str>) {
+    let line = line.trim();
+    if let Some(rest) = line.strip_suffix(ID_MARKER_SUFFIX) {
+        if let Some(start) = rest.rfind(ID_MARKER_PREFIX) {
+            let id = rest[start + ID_MARKER_PREFIX.len()..].trim();
+            if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return (rest[..start].trim_end(), Some(id));
             }
         }
+    }
+    (line, None)
+}
 
-        // Fallback: use entire line as content with current timestamp
-        Self::new(content_str.to_string())
+/// Splits an entry line into its content and the "(added at ...)" timestamp, if any
+///
+/// Returns the trimmed line unchanged when the suffix is missing or malformed.
+pub(crate) fn split_timestamp_suffix(line: &str) -> (&str, Option<DateTime<Utc>>) {
+    let content_str = line.trim();
+
+    // Try to extract timestamp from "(added at ...)" suffix
+    if let Some(timestamp_start) = content_str.rfind(TIMESTAMP_SUFFIX) {
+        let timestamp_str = &content_str[timestamp_start + TIMESTAMP_SUFFIX.len()..];
+
+        // Extract timestamp until closing ')'
+        if let Some(end_paren) = timestamp_str.find(')') {
+            // Try to parse ISO 8601 timestamp
+            if let Ok(timestamp) = DateTime::parse_from_rfc3339(timestamp_str[..end_paren].trim()) {
+                return (
+                    content_str[..timestamp_start].trim(),
+                    Some(timestamp.with_timezone(&Utc)),
+                );
+            }
+        }
     }
+
+    (content_str, None)
+}
+
+/// A line of MEMORY.md, as classified by [`parse_lines`]
+enum MemoryLine {
+    /// A "## " header; `None` when it is not a date, which ends the current section
+    Section(Option<NaiveDate>),
+    /// A bullet entry in a dated section; `marked` when the line carries its id
+    Entry {
+        entry: LongTermMemoryEntry,
+        marked: bool,
+    },
+    /// Anything else (title, blank lines, bullets outside dated sections)
+    Other,
+}
+
+/// Classifies the lines of MEMORY.md, giving every entry a unique id
+///
+/// When two entries share an id (e.g. duplicated lines), the later one gets
+/// a new id, which is written back on the next edit.
+fn parse_lines(content: &str) -> Vec<(&str, MemoryLine)> {
+    let mut in_section = false;
+    let mut ids = HashSet::new();
+
+    content
+        .lines()
+        .map(|raw| {
+            if let Some(date_str) = raw.strip_prefix("## ") {
+                let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok();
+                in_section = date.is_some();
+                return (raw, MemoryLine::Section(date));
+            }
+            match raw.strip_prefix("- ") {
+                Some(entry_line) if in_section => {
+                    let (text, marker) = split_id_marker(entry_line);
+                    let mut entry = LongTermMemoryEntry::parse_from_line(entry_line);
+                    let mut marked = marker.is_some();
+                    if ids.contains(&entry.id) {
+                        entry.id = unique_id(text, &ids);
+                        marked = false;
+                    }
+                    ids.insert(entry.id.clone());
+                    (raw, MemoryLine::Entry { entry, marked })
+                }
+                _ => (raw, MemoryLine::Other),
+            }
+        })
+        .collect()
 }
 
 /// A dated section in the memory file
@@ -209,18 +344,19 @@ impl LongTermMemory {
 
     /// Appends content to long-term memory
     ///
-    /// Groups entries by date (## YYYY-MM-DD) and adds bullet points.
-    /// Creates the file if it doesn't exist.
+    /// Groups entries by date (## YYYY-MM-DD) and adds bullet points, each
+    /// tagged with a new id. Creates the file if it doesn't exist.
     /// Thread-safe: uses write lock to prevent concurrent modifications.
     ///
     /// # Arguments
     /// * `content` - The content to append
     ///
     /// # Returns
-    /// * `Ok(())` - Content appended successfully
+    /// * `Ok(LongTermMemoryEntry)` - The entry written, with its id
     /// * `Err(MemoryError)` - If append fails
-    pub async fn append_entry(&self, content: &str) -> Result<(), MemoryError> {
-        if content.trim().is_empty() {
+    pub async fn append_entry(&self, content: &str) -> Result<LongTermMemoryEntry, MemoryError> {
+        let content = single_line(content);
+        if content.is_empty() {
             return Err(MemoryError::InvalidContent(
                 "Content cannot be empty".to_string(),
             ));
@@ -236,16 +372,28 @@ impl LongTermMemory {
             tracing::warn!("Memory file size check: {}", e);
         }
 
-        let today = Utc::now().format("%Y-%m-%d").to_string();
-        let timestamp = Utc::now().to_rfc3339();
-        let entry_line = format!("- {} {} {})\n", content.trim(), TIMESTAMP_SUFFIX, timestamp);
-
         // Read existing content
         let existing = fs::read_to_string(&self.file_path).await.map_err(|e| {
             MemoryError::storage_failed(format!("read memory file {}", self.file_path.display()), e)
         })?;
 
+        let timestamp = Utc::now();
+        let taken: HashSet<String> = parse_lines(&existing)
+            .into_iter()
+            .filter_map(|(_, line)| match line {
+                MemoryLine::Entry { entry, .. } => Some(entry.id),
+                _ => None,
+            })
+            .collect();
+        let entry = LongTermMemoryEntry {
+            id: unique_id(&entry_text(&content, timestamp), &taken),
+            content,
+            timestamp,
+        };
+        let entry_line = format!("{}\n", entry_line(&entry.content, timestamp, &entry.id));
+
         // Check if today's section exists
+        let today = timestamp.format("%Y-%m-%d").to_string();
         let today_header = format!("## {}", today);
         let new_content = if existing.contains(&today_header) {
             // Append to existing today section - use replacen to replace only first occurrence
@@ -259,8 +407,153 @@ impl LongTermMemory {
             format!("{}\n{}\n{}", existing.trim(), today_header, entry_line)
         };
 
-        // Write updated content
-        fs::write(&self.file_path, new_content).await.map_err(|e| {
+        self.write_file(&new_content).await?;
+
+        tracing::debug!(
+            file = %self.file_path.display(),
+            date = %today,
+            id = %entry.id,
+            "Added entry to long-term memory"
+        );
+
+        Ok(entry)
+    }
+
+    /// Replaces the content of the entry with the given id
+    ///
+    /// The entry keeps its id and date. Entries without an id marker get
+    /// theirs written along the way.
+    ///
+    /// # Returns
+    /// * `Ok(LongTermMemoryEntry)` - The updated entry
+    /// * `Err(MemoryError::EntryNotFound)` - If no entry has this id
+    pub async fn update_entry(
+        &self,
+        id: &str,
+        content: &str,
+    ) -> Result<LongTermMemoryEntry, MemoryError> {
+        let content = single_line(content);
+        if content.is_empty() {
+            return Err(MemoryError::InvalidContent(
+                "Content cannot be empty".to_string(),
+            ));
+        }
+        self.edit_entry(id, Some(&content)).await
+    }
+
+    /// Removes the entry with the given id
+    ///
+    /// A dated section left without entries is removed as well.
+    ///
+    /// # Returns
+    /// * `Ok(LongTermMemoryEntry)` - The removed entry
+    /// * `Err(MemoryError::EntryNotFound)` - If no entry has this id
+    pub async fn delete_entry(&self, id: &str) -> Result<LongTermMemoryEntry, MemoryError> {
+        self.edit_entry(id, None).await
+    }
+
+    /// Rewrites MEMORY.md with the entry `id` replaced by `replacement`, or
+    /// removed when it is `None`
+    async fn edit_entry(
+        &self,
+        id: &str,
+        replacement: Option<&str>,
+    ) -> Result<LongTermMemoryEntry, MemoryError> {
+        let _lock = self.write_lock.lock().await;
+
+        let existing = match fs::read_to_string(&self.file_path).await {
+            Ok(existing) => existing,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(MemoryError::EntryNotFound(id.to_string()));
+            }
+            Err(e) => {
+                return Err(MemoryError::storage_failed(
+                    format!("read memory file {}", self.file_path.display()),
+                    e,
+                ));
+            }
+        };
+
+        let lines = parse_lines(&existing);
+        let target = lines
+            .iter()
+            .position(|(_, line)| matches!(line, MemoryLine::Entry { entry, .. } if entry.id == id))
+            .ok_or_else(|| MemoryError::EntryNotFound(id.to_string()))?;
+
+        // Header of a section that only holds the deleted entry
+        let empty_section = if replacement.is_none() {
+            let header = lines[..target]
+                .iter()
+                .rposition(|(_, line)| matches!(line, MemoryLine::Section(_)));
+            let next_header = lines[target + 1..]
+                .iter()
+                .position(|(_, line)| matches!(line, MemoryLine::Section(_)))
+                .map_or(lines.len(), |offset| target + 1 + offset);
+            let entries = header.map_or(0, |header| {
+                lines[header + 1..next_header]
+                    .iter()
+                    .filter(|(_, line)| matches!(line, MemoryLine::Entry { .. }))
+                    .count()
+            });
+            header.filter(|_| entries == 1)
+        } else {
+            None
+        };
+
+        let mut output: Vec<String> = Vec::with_capacity(lines.len());
+        let mut edited = None;
+        for (index, (raw, line)) in lines.into_iter().enumerate() {
+            match line {
+                MemoryLine::Entry { entry, .. } if index == target => {
+                    edited = Some(match replacement {
+                        Some(content) => {
+                            output.push(entry_line(content, entry.timestamp, &entry.id));
+                            LongTermMemoryEntry {
+                                content: content.to_string(),
+                                ..entry
+                            }
+                        }
+                        None => entry,
+                    });
+                }
+                MemoryLine::Entry {
+                    entry,
+                    marked: false,
+                } => {
+                    let (text, _) = split_id_marker(raw.strip_prefix("- ").unwrap_or(raw));
+                    output.push(format!(
+                        "- {} {}{} {}",
+                        text, ID_MARKER_PREFIX, entry.id, ID_MARKER_SUFFIX
+                    ));
+                }
+                _ if Some(index) == empty_section => {
+                    if output.last().is_some_and(|line| line.trim().is_empty()) {
+                        output.pop();
+                    }
+                }
+                _ => output.push(raw.to_string()),
+            }
+        }
+
+        let mut new_content = output.join("\n");
+        if existing.ends_with('\n') {
+            new_content.push('\n');
+        }
+        self.write_file(&new_content).await?;
+
+        let edited = edited.ok_or_else(|| MemoryError::EntryNotFound(id.to_string()))?;
+        tracing::debug!(
+            file = %self.file_path.display(),
+            id = %id,
+            deleted = replacement.is_none(),
+            "Edited long-term memory entry"
+        );
+        Ok(edited)
+    }
+
+    /// Writes MEMORY.md, restores its permissions and invalidates the cache
+    async fn write_file(&self, content: &str) -> Result<(), MemoryError> {
+        fs::write(&self.file_path, content).await.map_err(|e| {
             MemoryError::storage_failed(
                 format!("write memory file {}", self.file_path.display()),
                 e,
@@ -279,12 +572,6 @@ impl LongTermMemory {
         let mut cache = self.cache.lock().await;
         *cache = None;
 
-        tracing::debug!(
-            file = %self.file_path.display(),
-            date = %today,
-            "Added entry to long-term memory"
-        );
-
         Ok(())
     }
 
@@ -347,31 +634,29 @@ impl LongTermMemory {
 
     /// Parses memory content from Markdown format
     ///
-    /// Extracts ## YYYY-MM-DD sections and bullet points with timestamps.
+    /// Extracts ## YYYY-MM-DD sections and bullet points with timestamps and ids.
     fn parse_memory_content(content: &str) -> Result<Vec<MemorySection>, MemoryError> {
         let mut sections = Vec::new();
         let mut current_section: Option<MemorySection> = None;
 
-        for line in content.lines() {
-            if let Some(date_str) = line.strip_prefix("## ") {
-                // Save previous section if exists
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-
-                // Parse date from header
-                if let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                    current_section = Some(MemorySection {
+        for (_, line) in parse_lines(content) {
+            match line {
+                MemoryLine::Section(date) => {
+                    // Save previous section if exists
+                    if let Some(section) = current_section.take() {
+                        sections.push(section);
+                    }
+                    current_section = date.map(|date| MemorySection {
                         date,
                         entries: Vec::new(),
                     });
                 }
-            } else if let Some(entry_content) = line.strip_prefix("- ") {
-                if let Some(ref mut section) = current_section {
-                    // Parse bullet point entry with timestamp extraction
-                    let entry = LongTermMemoryEntry::parse_from_line(entry_content);
-                    section.entries.push(entry);
+                MemoryLine::Entry { entry, .. } => {
+                    if let Some(ref mut section) = current_section {
+                        section.entries.push(entry);
+                    }
                 }
+                MemoryLine::Other => {}
             }
         }
 
@@ -893,4 +1178,81 @@ mod tests {
         assert!(content.contains("Entry 2"));
         assert!(content.contains("Entry 3"));
     }
+
+    #[tokio::test]
+    async fn test_append_entry_writes_id() {
+        let temp_dir = tempdir().unwrap();
+        let memory = LongTermMemory::new(temp_dir.path());
+
+        let entry = memory
+            .append_entry("Likes tea\nand biscuits")
+            .await
+            .unwrap();
+        assert_eq!(entry.id.len(), 8);
+        assert_eq!(entry.content, "Likes tea and biscuits");
+
+        let content = fs::read_to_string(&memory.file_path).await.unwrap();
+        assert!(content.contains(&format!("<!-- id:{} -->", entry.id)));
+
+        let sections = memory.read_all().await.unwrap();
+        assert_eq!(sections[0].entries[0].id, entry.id);
+        assert_eq!(sections[0].entries[0].content, "Likes tea and biscuits");
+        assert_eq!(sections[0].entries[0].timestamp, entry.timestamp);
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_entry() {
+        let temp_dir = tempdir().unwrap();
+        let memory = LongTermMemory::new(temp_dir.path());
+        fs::create_dir_all(temp_dir.path().join("memory"))
+            .await
+            .unwrap();
+        fs::write(
+            &memory.file_path,
+            "# Memory\n\n## 2026-02-15\n- Lives in Paris (added at 2026-02-15T09:00:00Z)\n\n## 2026-02-16\n- Prefers tea\n- Prefers tea\n",
+        )
+        .await
+        .unwrap();
+
+        // Legacy entries get ids derived from their line, unique even for duplicates
+        let sections = memory.read_all().await.unwrap();
+        let paris = sections[0].entries[0].id.clone();
+        let (tea, tea_copy) = (
+            sections[1].entries[0].id.clone(),
+            sections[1].entries[1].id.clone(),
+        );
+        assert_ne!(tea, tea_copy);
+        assert_eq!(memory.read_all().await.unwrap()[0].entries[0].id, paris);
+
+        let updated = memory.update_entry(&paris, "Lives in Lyon").await.unwrap();
+        assert_eq!(updated.id, paris);
+        assert_eq!(updated.timestamp.to_rfc3339(), "2026-02-15T09:00:00+00:00");
+
+        // The edit wrote every id, so the other entries kept theirs
+        let content = fs::read_to_string(&memory.file_path).await.unwrap();
+        assert!(content.contains(&format!("- Prefers tea <!-- id:{} -->", tea_copy)));
+        let sections = memory.read_all().await.unwrap();
+        assert_eq!(sections[0].entries[0].content, "Lives in Lyon");
+        assert_eq!(sections[1].entries[1].id, tea_copy);
+
+        // Deleting the last entry of a section removes its header
+        memory.delete_entry(&paris).await.unwrap();
+        let content = fs::read_to_string(&memory.file_path).await.unwrap();
+        assert!(!content.contains("2026-02-15"));
+        assert!(content.starts_with("# Memory\n\n## 2026-02-16\n"));
+
+        memory.delete_entry(&tea).await.unwrap();
+        let sections = memory.read_all().await.unwrap();
+        assert_eq!(sections[0].entries.len(), 1);
+        assert_eq!(sections[0].entries[0].id, tea_copy);
+
+        assert!(matches!(
+            memory.delete_entry(&tea).await,
+            Err(MemoryError::EntryNotFound(_))
+        ));
+        assert!(matches!(
+            memory.update_entry(&tea_copy, "  ").await,
+            Err(MemoryError::InvalidContent(_))
+        ));
+    }
 }
@@ -19,7 +19,8 @@ pub use daily_notes::{
 };
 pub use long_term::{LongTermMemory, LongTermMemoryEntry, MemorySection};
 pub use ranker::{
-    DEFAULT_SEARCH_LIMIT, MAX_SEARCH_RESULTS, MemoryRanker, MemorySource, RankedMemory,
+    ContextMemories, DEFAULT_HIGHLIGHT_DAYS, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_RESULTS,
+    MemoryRanker, MemorySource, RankedMemory,
 };
 pub use short_term::{
     MAX_SHORT_TERM_ENTRIES, MemoryEntry as ShortTermMemoryEntry, ShortTermMemory,
@@ -73,18 +74,24 @@ impl MemoryStore {
     /// * `content` - The content to store
     ///
     /// # Returns
-    /// * `Ok(String)` - Path to the memory file
+    /// * `Ok(LongTermMemoryEntry)` - The entry written, with its id
     /// * `Err(MemoryError)` - If storage fails
-    pub async fn append_to_memory(&self, content: String) -> Result<String, MemoryError> {
+    pub async fn append_to_memory(
+        &self,
+        content: String,
+    ) -> Result<LongTermMemoryEntry, MemoryError> {
         // Use the unified LongTermMemory::append_entry method
-        self.long_term.append_entry(&content).await?;
+        let entry = self.long_term.append_entry(&content).await?;
 
         // Add to short-term memory
         self.short_term.add_entry(content).await;
 
-        // Return file path
-        let file_path = self.workspace_path.join("memory").join("MEMORY.md");
-        Ok(file_path.to_string_lossy().to_string())
+        Ok(entry)
+    }
+
+    /// Returns the path of the long-term memory file (MEMORY.md)
+    pub fn memory_file_path(&self) -> PathBuf {
+        self.workspace_path.join("memory").join("MEMORY.md")
     }
 
     /// Creates a daily note
@@ -5,10 +5,13 @@
 //! and returns ranked results based on keyword match counts.
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 
+use crate::memory::long_term::{split_id_marker, split_timestamp_suffix};
 use crate::memory::types::MemoryError;
+use crate::workspace::WorkspaceFileCache;
 
 /// Default number of search results to return
 pub const DEFAULT_SEARCH_LIMIT: usize = 5;
@@ -19,6 +22,9 @@ pub const MAX_SEARCH_RESULTS: usize = 20;
 /// Default number of days to search in daily notes
 pub const DEFAULT_DAILY_NOTE_SEARCH_DAYS: usize = 30;
 
+/// Default number of days of daily notes surfaced as recent highlights
+pub const DEFAULT_HIGHLIGHT_DAYS: usize = 2;
+
 /// Source of a memory entry
 #[derive(Debug, Clone, PartialEq)]
 pub enum MemorySource {
@@ -40,6 +46,8 @@ impl std::fmt::Display for MemorySource {
 /// A ranked memory result
 #[derive(Debug, Clone, PartialEq)]
 pub struct RankedMemory {
+    /// Identifier of a long-term entry, for updating or deleting it
+    pub id: Option<String>,
     /// Full content of the memory
     pub content: String,
     /// Relevance score (number of matching keywords)
@@ -52,11 +60,30 @@ pub struct RankedMemory {
     pub excerpt: String,
 }
 
+/// Memory candidates for a conversation turn, ordered by priority
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextMemories {
+    /// Matching entries (best first), followed by the remaining long-term
+    /// entries (newest first)
+    pub memories: Vec<RankedMemory>,
+    /// Recent daily-note entries that did not match, newest first
+    pub daily_highlights: Vec<RankedMemory>,
+}
+
+impl ContextMemories {
+    /// Returns true if there are no candidates at all
+    pub fn is_empty(&self) -> bool {
+        self.memories.is_empty() && self.daily_highlights.is_empty()
+    }
+}
+
 /// Memory ranker for searching and ranking memories
 #[derive(Debug, Clone)]
 pub struct MemoryRanker {
     /// Workspace path for file operations
     workspace_path: PathBuf,
+    /// Cache serving parsed memory files when set
+    file_cache: Option<Arc<WorkspaceFileCache>>,
 }
 
 impl MemoryRanker {
@@ -65,7 +92,17 @@ impl MemoryRanker {
     /// # Arguments
     /// * `workspace_path` - The workspace directory containing memory files
     pub fn new(workspace_path: PathBuf) -> Self {
-        Self { workspace_path }
+        Self {
+            workspace_path,
+            file_cache: None,
+        }
+    }
+
+    /// Reads and parses MEMORY.md and daily notes through `cache` in
+    /// [`rank_for_context`](Self::rank_for_context)
+    pub fn with_file_cache(mut self, cache: Arc<WorkspaceFileCache>) -> Self {
+        self.file_cache = Some(cache);
+        self
     }
 
     /// Tokenizes text into searchable keywords
@@ -78,11 +115,7 @@ impl MemoryRanker {
     /// # Returns
     /// Vector of lowercase tokens without punctuation
     pub fn tokenize(text: &str) -> Vec<String> {
-        text.to_lowercase()
-            .split_whitespace()
-            .map(|s| s.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
-            .filter(|s| !s.is_empty())
-            .collect()
+        crate::utils::text::tokenize(text)
     }
 
     /// Calculates relevance score for content against query tokens
@@ -159,6 +192,7 @@ impl MemoryRanker {
                 let score = Self::calculate_score(&content_lower, query_tokens);
                 if score > 0 {
                     results.push(RankedMemory {
+                        id: Some(entry.id.clone()),
                         content: entry.content.clone(),
                         score,
                         source: MemorySource::LongTerm,
@@ -209,6 +243,7 @@ impl MemoryRanker {
                 let score = Self::calculate_score(&content_lower, query_tokens);
                 if score > 0 {
                     results.push(RankedMemory {
+                        id: None,
                         content: entry.content.clone(),
                         score,
                         source: MemorySource::DailyNote,
@@ -282,6 +317,146 @@ impl MemoryRanker {
         );
         Ok(final_results)
     }
+
+    /// Ranks all memories against a conversation message for context injection
+    ///
+    /// Unlike [`search_all`](Self::search_all), entries that do not match are kept
+    /// so recent memories still reach the model: matching entries come first, then
+    /// unmatched long-term entries newest first. Unmatched daily-note entries from the
+    /// last `highlight_days` days are returned separately as highlights.
+    ///
+    /// MEMORY.md is read leniently: bullet entries in dated sections and free-form
+    /// lines are both included. Stop words and tokens shorter than three characters
+    /// are ignored for scoring.
+    pub async fn rank_for_context(
+        &self,
+        message: &str,
+        highlight_days: usize,
+    ) -> Result<ContextMemories, MemoryError> {
+        use crate::memory::daily_notes::{read_recent_days, read_recent_days_cached};
+
+        let query_tokens = crate::utils::text::keywords(message);
+
+        let memory_file = self.workspace_path.join("memory").join("MEMORY.md");
+        let long_term = match &self.file_cache {
+            Some(cache) => cache
+                .read_parsed(&memory_file, parse_long_term_entries)
+                .await
+                .map(|entries| entries.as_ref().clone()),
+            None => tokio::fs::read_to_string(&memory_file)
+                .await
+                .map(|content| parse_long_term_entries(&content)),
+        };
+        let long_term = match long_term {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(MemoryError::storage_failed(
+                    format!("read memory file {}", memory_file.display()),
+                    e,
+                ));
+            }
+        };
+
+        let daily = match &self.file_cache {
+            Some(cache) => {
+                read_recent_days_cached(&self.workspace_path, DEFAULT_DAILY_NOTE_SEARCH_DAYS, cache)
+                    .await?
+            }
+            None => read_recent_days(&self.workspace_path, DEFAULT_DAILY_NOTE_SEARCH_DAYS).await?,
+        };
+        let daily = daily
+            .into_iter()
+            .flat_map(|section| section.entries)
+            .map(|entry| RankedMemory {
+                id: None,
+                excerpt: Self::create_excerpt(&entry.content),
+                content: entry.content,
+                score: 0,
+                source: MemorySource::DailyNote,
+                date: Some(entry.timestamp),
+            });
+
+        // Baseline order is newest first; later lines in MEMORY.md count as newer
+        let mut candidates: Vec<RankedMemory> = long_term.into_iter().chain(daily).collect();
+        candidates.reverse();
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.date));
+
+        for candidate in &mut candidates {
+            candidate.score =
+                Self::calculate_score(&candidate.content.to_lowercase(), &query_tokens);
+        }
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.score));
+
+        let highlight_start = (Utc::now() - Duration::days(highlight_days as i64 - 1))
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .map(|start| start.and_utc());
+
+        let mut result = ContextMemories::default();
+        for candidate in candidates {
+            match candidate.source {
+                MemorySource::DailyNote if candidate.score == 0 => {
+                    if highlight_days > 0 && candidate.date >= highlight_start {
+                        result.daily_highlights.push(candidate);
+                    }
+                }
+                _ => result.memories.push(candidate),
+            }
+        }
+
+        tracing::debug!(
+            token_count = query_tokens.len(),
+            memories = result.memories.len(),
+            daily_highlights = result.daily_highlights.len(),
+            "Ranked memories for context"
+        );
+        Ok(result)
+    }
+}
+
+/// Extracts entries from MEMORY.md content, in file order
+///
+/// Bullet entries take their date from the "(added at ...)" suffix or their
+/// `## YYYY-MM-DD` section. Headings, separators and blank lines are skipped.
+fn parse_long_term_entries(content: &str) -> Vec<RankedMemory> {
+    let mut section_date: Option<DateTime<Utc>> = None;
+    let mut entries = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix("## ") {
+            section_date = NaiveDate::parse_from_str(header.trim(), "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc());
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with("---") {
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .unwrap_or(line);
+        let (line, id) = split_id_marker(line);
+        let (entry, timestamp) = split_timestamp_suffix(line);
+        if entry.is_empty() {
+            continue;
+        }
+
+        entries.push(RankedMemory {
+            id: id.map(str::to_string),
+            content: entry.to_string(),
+            score: 0,
+            source: MemorySource::LongTerm,
+            date: timestamp.or(section_date),
+            excerpt: MemoryRanker::create_excerpt(entry),
+        });
+    }
+
+    entries
 }
 
 #[cfg(test)]
@@ -390,6 +565,80 @@ mod tests {
         assert_eq!(MemorySource::DailyNote.to_string(), "Daily Note");
     }
 
+    #[test]
+    fn test_parse_long_term_entries() {
+        let content = "# Long-term Memory\n\nUser likes Rust.\n\n## 2026-02-16\n- Prefers tea (added at 2026-02-16T10:30:00Z)\n- Lives in Lyon\n";
+        let entries = parse_long_term_entries(content);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].content, "User likes Rust.");
+        assert!(entries[0].date.is_none());
+        assert_eq!(entries[1].content, "Prefers tea");
+        assert_eq!(
+            entries[1].date.unwrap().to_rfc3339(),
+            "2026-02-16T10:30:00+00:00"
+        );
+        assert_eq!(entries[2].content, "Lives in Lyon");
+        assert_eq!(
+            entries[2].date.unwrap().to_rfc3339(),
+            "2026-02-16T00:00:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rank_for_context_orders_matches_then_recency() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let memory_dir = temp_dir.path().join("memory");
+        tokio::fs::create_dir_all(&memory_dir).await.unwrap();
+        tokio::fs::write(
+            memory_dir.join("MEMORY.md"),
+            "Oldest note\nProduction database is Postgres 16\nNewest note\n",
+        )
+        .await
+        .unwrap();
+
+        let today = Utc::now().format("%Y-%m-%d");
+        tokio::fs::write(
+            memory_dir.join(format!("{}.md", today)),
+            format!("# Daily Notes - {today}\n\n## 09:00:00 UTC\n\nShipped the release\n"),
+        )
+        .await
+        .unwrap();
+
+        let ranker = MemoryRanker::new(temp_dir.path().to_path_buf());
+        let ranked = ranker
+            .rank_for_context("Which database do we use in production?", 2)
+            .await
+            .unwrap();
+
+        let contents: Vec<&str> = ranked.memories.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "Production database is Postgres 16",
+                "Newest note",
+                "Oldest note"
+            ]
+        );
+        assert_eq!(ranked.memories[0].score, 2);
+        assert_eq!(ranked.daily_highlights.len(), 1);
+        assert_eq!(ranked.daily_highlights[0].content, "Shipped the release");
+
+        // Highlights are disabled with zero days
+        let ranked = ranker.rank_for_context("hello", 0).await.unwrap();
+        assert!(ranked.daily_highlights.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rank_for_context_empty_workspace() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ranker = MemoryRanker::new(temp_dir.path().to_path_buf());
+        let ranked = ranker.rank_for_context("anything", 2).await.unwrap();
+        assert!(ranked.is_empty());
+    }
+
     #[test]
     fn test_constants() {
         assert_eq!(DEFAULT_SEARCH_LIMIT, 5);
@@ -400,6 +649,7 @@ mod tests {
     #[test]
     fn test_ranked_memory_creation() {
         let ranked = RankedMemory {
+            id: Some("1a2b3c4d".to_string()),
             content: "Test content".to_string(),
             score: 5,
             source: MemorySource::LongTerm,
@@ -47,6 +47,8 @@ pub struct MemoryEntry {
 pub enum MemoryError {
     /// File not found
     FileNotFound(String),
+    /// No long-term memory entry with this id
+    EntryNotFound(String),
     /// File too large
     FileTooLarge { path: String, size: u64, limit: u64 },
     /// Invalid content
@@ -66,6 +68,9 @@ impl fmt::Display for MemoryError {
             MemoryError::FileNotFound(path) => {
                 write!(f, "Memory file not found: {}", path)
             }
+            MemoryError::EntryNotFound(id) => {
+                write!(f, "Memory entry not found: {}", id)
+            }
             MemoryError::FileTooLarge { path, size, limit } => {
                 write!(
                     f,
@@ -35,6 +35,7 @@ pub mod factory;
 pub mod mock;
 pub mod ollama;
 pub mod openai;
+pub mod tokenizer;
 
 // Export error types
 pub use error::ProviderError;
@@ -51,6 +52,9 @@ pub use openai::{GenericOpenAiProvider, KimiProvider, OpenAiProvider, OpenRouter
 // Export Ollama provider
 pub use ollama::OllamaProvider;
 
+// Export offline tokenizer
+pub use tokenizer::{Tokenizer, TokenizerKind};
+
 /// Represents a message in the conversation for LLM context
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LlmMessage {
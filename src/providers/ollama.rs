@@ -32,8 +32,9 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
 use crate::providers::factory::OllamaConfig;
+use crate::providers::tokenizer::MESSAGE_OVERHEAD_TOKENS;
 use crate::providers::{
-    LlmMessage, LlmProvider, LlmResponse, LlmToolCall, ModelInfo, ProviderError,
+    LlmMessage, LlmProvider, LlmResponse, LlmToolCall, ModelInfo, ProviderError, Tokenizer,
 };
 
 /// Ollama API request body format
@@ -297,21 +298,22 @@ impl OllamaProvider {
         Ok(())
     }
 
-    /// Estimates token count from character count (rough approximation: 4 chars ≈ 1 token)
-    fn estimate_tokens(&self, text: &str) -> u32 {
-        (text.len() as f32 / 4.0).ceil() as u32
+    /// Estimates token count with the BPE tokenizer closest to the model family
+    fn estimate_tokens(&self, model: &str, text: &str) -> u32 {
+        Tokenizer::for_model(model).count(text) as u32
     }
 
     /// Estimates token count for a list of messages
-    fn estimate_message_tokens(&self, messages: &[OllamaMessage]) -> u32 {
+    fn estimate_message_tokens(&self, model: &str, messages: &[OllamaMessage]) -> u32 {
+        let tokenizer = Tokenizer::for_model(model);
         messages
             .iter()
             .map(|msg| {
-                // Count role, content, and basic message overhead
-                let role_tokens = self.estimate_tokens(&msg.role);
-                let content_tokens = self.estimate_tokens(&msg.content);
-                // Add small overhead for message structure (approximately 4 tokens per message)
-                role_tokens + content_tokens + 4
+                // Count role, content, and the per-message format overhead
+                let tokens = tokenizer.count(&msg.role)
+                    + tokenizer.count(&msg.content)
+                    + MESSAGE_OVERHEAD_TOKENS;
+                tokens as u32
             })
             .sum()
     }
@@ -452,8 +454,8 @@ impl LlmProvider for OllamaProvider {
             );
         } else {
             // Estimate tokens if not provided
-            let estimated_prompt = self.estimate_message_tokens(&request.messages);
-            let estimated_completion = self.estimate_tokens(&accumulated.content);
+            let estimated_prompt = self.estimate_message_tokens(&request.model, &request.messages);
+            let estimated_completion = self.estimate_tokens(&request.model, &accumulated.content);
             warn!(
                 estimated_prompt_tokens = estimated_prompt,
                 estimated_completion_tokens = estimated_completion,
@@ -607,11 +609,21 @@ mod tests {
         let config = create_test_config();
         let provider = OllamaProvider::new(config);
 
-        // 4 chars ≈ 1 token
-        assert_eq!(provider.estimate_tokens("abcd"), 1);
-        assert_eq!(provider.estimate_tokens("abcdefgh"), 2);
-        assert_eq!(provider.estimate_tokens("abcdefghijkl"), 3);
-        assert_eq!(provider.estimate_tokens(""), 0);
+        // Llama models are counted with cl100k_base
+        assert_eq!(provider.estimate_tokens("llama3.2", "hello world"), 2);
+        assert_eq!(provider.estimate_tokens("llama3.2", ""), 0);
+        assert_eq!(provider.estimate_tokens("gpt-oss:20b", "hello world"), 2);
+
+        let messages = vec![OllamaMessage {
+            role: "user".to_string(),
+            content: "hello world".to_string(),
+            tool_calls: None,
+            tool_call_id: None,
+        }];
+        assert_eq!(
+            provider.estimate_message_tokens("llama3.2", &messages),
+            1 + 2 + MESSAGE_OVERHEAD_TOKENS as u32
+        );
     }
 
     #[test]
@@ -0,0 +1,219 @@
+//! Offline BPE token counting
+//!
+//! Uses the cl100k_base and o200k_base tables embedded by `tiktoken-rs`, so no
+//! network access is needed. The encoding is chosen per model family; models from
+//! other vendors (Llama, Mistral, Claude, Gemini, ...) are counted with cl100k_base,
+//! which is far closer than a character heuristic for non-English text and code.
+
+use std::fmt;
+use tiktoken_rs::CoreBPE;
+
+use super::LlmMessage;
+
+/// Tokens added per message by the chat format (role and separators)
+pub const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Model name prefixes that use the o200k_base encoding
+const O200K_MODEL_PREFIXES: &[&str] = &[
+    "gpt-4o",
+    "chatgpt-4o",
+    "gpt-4.1",
+    "gpt-4.5",
+    "gpt-5",
+    "gpt-oss",
+    "o1",
+    "o3",
+    "o4",
+];
+
+/// BPE encoding used to count tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerKind {
+    /// cl100k_base (GPT-4, GPT-3.5, and the default for other vendors)
+    Cl100k,
+    /// o200k_base (GPT-4o, GPT-4.1, GPT-5, o-series)
+    O200k,
+}
+
+impl TokenizerKind {
+    /// Picks the encoding for a model name.
+    ///
+    /// Provider prefixes such as `openai/gpt-4o` (OpenRouter) are ignored.
+    pub fn for_model(model: &str) -> Self {
+        let name = model
+            .rsplit('/')
+            .next()
+            .unwrap_or(model)
+            .to_ascii_lowercase();
+
+        if O200K_MODEL_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        {
+            TokenizerKind::O200k
+        } else {
+            TokenizerKind::Cl100k
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenizerKind::Cl100k => "cl100k_base",
+            TokenizerKind::O200k => "o200k_base",
+        }
+    }
+}
+
+impl fmt::Display for TokenizerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Counts tokens with an embedded BPE table
+///
+/// Cheap to copy; the tables are loaded once per process on first use.
+#[derive(Clone, Copy)]
+pub struct Tokenizer {
+    kind: TokenizerKind,
+    bpe: &'static CoreBPE,
+}
+
+impl Tokenizer {
+    pub fn new(kind: TokenizerKind) -> Self {
+        let bpe = match kind {
+            TokenizerKind::Cl100k => tiktoken_rs::cl100k_base_singleton(),
+            TokenizerKind::O200k => tiktoken_rs::o200k_base_singleton(),
+        };
+        Self { kind, bpe }
+    }
+
+    /// Creates the tokenizer for a model family (see [`TokenizerKind::for_model`])
+    pub fn for_model(model: &str) -> Self {
+        Self::new(TokenizerKind::for_model(model))
+    }
+
+    pub fn kind(&self) -> TokenizerKind {
+        self.kind
+    }
+
+    /// Counts the tokens in `text`. Special-token markers are counted as plain text.
+    pub fn count(&self, text: &str) -> usize {
+        self.bpe.count_ordinary(text)
+    }
+
+    /// Counts the tokens of a chat message: content, tool calls and format overhead
+    pub fn count_message(&self, message: &LlmMessage) -> usize {
+        let tool_call_tokens: usize = message
+            .tool_calls
+            .iter()
+            .flatten()
+            .map(|tc| self.count(&tc.name) + self.count(&tc.arguments))
+            .sum();
+
+        self.count(&message.content) + tool_call_tokens + MESSAGE_OVERHEAD_TOKENS
+    }
+
+    /// Counts the tokens of a list of chat messages
+    pub fn count_messages(&self, messages: &[LlmMessage]) -> usize {
+        messages.iter().map(|m| self.count_message(m)).sum()
+    }
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new(TokenizerKind::Cl100k)
+    }
+}
+
+impl fmt::Debug for Tokenizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tokenizer")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{LlmRole, LlmToolCall};
+
+    #[test]
+    fn test_kind_for_model_families() {
+        assert_eq!(TokenizerKind::for_model("gpt-4o"), TokenizerKind::O200k);
+        assert_eq!(
+            TokenizerKind::for_model("gpt-4o-mini"),
+            TokenizerKind::O200k
+        );
+        assert_eq!(
+            TokenizerKind::for_model("openai/gpt-4.1-mini"),
+            TokenizerKind::O200k
+        );
+        assert_eq!(TokenizerKind::for_model("o3-mini"), TokenizerKind::O200k);
+        assert_eq!(TokenizerKind::for_model("GPT-5"), TokenizerKind::O200k);
+
+        assert_eq!(TokenizerKind::for_model("gpt-4"), TokenizerKind::Cl100k);
+        assert_eq!(
+            TokenizerKind::for_model("gpt-3.5-turbo"),
+            TokenizerKind::Cl100k
+        );
+        assert_eq!(
+            TokenizerKind::for_model("google/gemini-2.5-flash"),
+            TokenizerKind::Cl100k
+        );
+        assert_eq!(TokenizerKind::for_model("llama3.2"), TokenizerKind::Cl100k);
+        assert_eq!(TokenizerKind::for_model(""), TokenizerKind::Cl100k);
+    }
+
+    #[test]
+    fn test_count_known_values() {
+        // Reference counts from OpenAI's tiktoken
+        let cl100k = Tokenizer::new(TokenizerKind::Cl100k);
+        assert_eq!(cl100k.count("hello world"), 2);
+        assert_eq!(cl100k.count(""), 0);
+
+        let o200k = Tokenizer::for_model("gpt-4o");
+        assert_eq!(o200k.kind(), TokenizerKind::O200k);
+        assert_eq!(o200k.count("hello world"), 2);
+    }
+
+    #[test]
+    fn test_count_non_english_exceeds_char_heuristic() {
+        let tokenizer = Tokenizer::default();
+        let french = "Pourriez-vous vérifier l'état du déploiement d'hier soir ? Merci beaucoup !";
+        let japanese = "昨日のデプロイの状態を確認してください";
+
+        // The old len / 4 heuristic underestimates accented and CJK text
+        assert!(tokenizer.count(french) > french.len() / 4);
+        assert!(tokenizer.count(japanese) > japanese.len() / 4);
+    }
+
+    #[test]
+    fn test_count_message_includes_tool_calls_and_overhead() {
+        let tokenizer = Tokenizer::default();
+        let mut message = LlmMessage::new(LlmRole::Assistant, "hello world");
+        assert_eq!(
+            tokenizer.count_message(&message),
+            2 + MESSAGE_OVERHEAD_TOKENS
+        );
+
+        message.tool_calls = Some(vec![LlmToolCall::new("1", "exec", r#"{"cmd":"ls"}"#)]);
+        assert!(tokenizer.count_message(&message) > 2 + MESSAGE_OVERHEAD_TOKENS);
+
+        let messages = vec![message.clone(), message.clone()];
+        assert_eq!(
+            tokenizer.count_messages(&messages),
+            2 * tokenizer.count_message(&message)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(TokenizerKind::Cl100k.to_string(), "cl100k_base");
+        assert_eq!(
+            format!("{:?}", Tokenizer::for_model("gpt-4o")),
+            "Tokenizer { kind: O200k }"
+        );
+    }
+}
@@ -9,6 +9,7 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio::sync::broadcast;
 use tracing::{debug, error, info};
 
 /// TTL for session expiration (30 days)
@@ -20,12 +21,23 @@ pub const CLEANUP_INTERVAL_SECS: u64 = 24 * 60 * 60;
 /// Manages session cleanup based on TTL policy
 pub struct Cleanup {
     sessions_dir: PathBuf,
+    /// Receives the id of every session deleted as expired
+    expired_tx: Option<broadcast::Sender<String>>,
 }
 
 impl Cleanup {
     /// Creates a new Cleanup instance
     pub fn new(sessions_dir: PathBuf) -> Self {
-        Self { sessions_dir }
+        Self {
+            sessions_dir,
+            expired_tx: None,
+        }
+    }
+
+    /// Announces the id of every session deleted as expired on `expired_tx`
+    pub fn with_expiry_notifier(mut self, expired_tx: broadcast::Sender<String>) -> Self {
+        self.expired_tx = Some(expired_tx);
+        self
     }
 
     /// Checks if a session is expired based on last_accessed timestamp
@@ -190,6 +202,10 @@ impl Cleanup {
                     if bytes > 0 {
                         bytes_freed += bytes;
                         sessions_deleted += 1;
+                        if let Some(tx) = &self.expired_tx {
+                            // No receivers is fine: nothing holds state for the session
+                            let _ = tx.send(session_id);
+                        }
                     }
                 }
                 Err(e) => {
@@ -219,11 +235,13 @@ impl Cleanup {
     pub fn start_cleanup_task(
         &self,
     ) -> (tokio::task::JoinHandle<()>, tokio::sync::mpsc::Sender<()>) {
-        let sessions_dir = self.sessions_dir.clone();
+        let cleanup = Cleanup {
+            sessions_dir: self.sessions_dir.clone(),
+            expired_tx: self.expired_tx.clone(),
+        };
         let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
 
         let handle = tokio::spawn(async move {
-            let cleanup = Cleanup::new(sessions_dir);
             let mut interval =
                 tokio::time::interval(tokio::time::Duration::from_secs(CLEANUP_INTERVAL_SECS));
 
@@ -411,6 +429,33 @@ mod tests {
         assert!(sessions_dir.join("telegram_new.json").exists());
     }
 
+    #[tokio::test]
+    async fn test_run_cleanup_announces_expired_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let sessions_dir = temp_dir.path().join("sessions");
+        fs::create_dir_all(&sessions_dir).await.unwrap();
+
+        let (tx, mut rx) = broadcast::channel(16);
+        let cleanup = Cleanup::new(sessions_dir.clone()).with_expiry_notifier(tx);
+
+        for (chat_id, age_days) in [("old", 31), ("new", 1)] {
+            let mut session = Session::new("telegram".to_string(), chat_id.to_string());
+            session.last_accessed = Utc::now() - Duration::days(age_days);
+            let json = serde_json::to_string(&session).unwrap();
+            fs::write(
+                sessions_dir.join(format!("telegram_{}.json", chat_id)),
+                json,
+            )
+            .await
+            .unwrap();
+        }
+
+        cleanup.run().await.unwrap();
+
+        assert_eq!(rx.try_recv().unwrap(), "telegram_old");
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_run_cleanup_no_expired() {
         let temp_dir = TempDir::new().unwrap();
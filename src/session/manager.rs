@@ -4,7 +4,7 @@ use crate::utils::MiniClawError;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 use tokio::time::{Duration, interval};
 use tracing::{debug, error, info, warn};
 
@@ -13,6 +13,9 @@ type Result<T> = std::result::Result<T, MiniClawError>;
 
 pub const PERSISTENCE_INTERVAL_SECS: u64 = 30;
 
+/// Expired session ids buffered per subscriber before the oldest are dropped
+const EXPIRED_CHANNEL_CAPACITY: usize = 256;
+
 pub struct SessionManager {
     sessions: Arc<RwLock<HashMap<String, Session>>>,
     /// Set of session IDs that have been modified since last save.
@@ -21,18 +24,30 @@ pub struct SessionManager {
     /// avoiding unnecessary I/O for unchanged sessions.
     dirty_sessions: Arc<RwLock<HashSet<String>>>,
     persistence: Arc<Persistence>,
+    /// Announces the ids of sessions deleted by the cleanup task
+    expired_tx: broadcast::Sender<String>,
 }
 
 impl SessionManager {
     pub fn new(sessions_dir: PathBuf) -> Self {
         let persistence = Arc::new(Persistence::new(sessions_dir));
+        let (expired_tx, _) = broadcast::channel(EXPIRED_CHANNEL_CAPACITY);
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             dirty_sessions: Arc::new(RwLock::new(HashSet::new())),
             persistence,
+            expired_tx,
         }
     }
 
+    /// Subscribes to the ids of sessions that expire
+    ///
+    /// The cleanup task sends the id of every session it deletes, so that
+    /// state kept for the conversation elsewhere can be released with it.
+    pub fn subscribe_expired(&self) -> broadcast::Receiver<String> {
+        self.expired_tx.subscribe()
+    }
+
     pub async fn initialize(&self) -> Result<()> {
         // Create sessions directory
         self.persistence.create_sessions_dir().await?;
@@ -298,7 +313,8 @@ impl SessionManager {
         &self,
     ) -> (tokio::task::JoinHandle<()>, tokio::sync::mpsc::Sender<()>) {
         use crate::session::cleanup::Cleanup;
-        let cleanup = Cleanup::new(self.persistence.sessions_dir.clone());
+        let cleanup = Cleanup::new(self.persistence.sessions_dir.clone())
+            .with_expiry_notifier(self.expired_tx.clone());
         cleanup.start_cleanup_task()
     }
 }
@@ -18,4 +18,5 @@ pub const BUILT_IN_TOOLS: &[&str] = &[
     "list_skills",
     "read_skill",
     "delete_skill",
+    "run_skill",
 ];
@@ -7,7 +7,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use tracing;
 
-use super::types::{Skill, SkillError, SkillParameter, SkillSummary};
+use super::types::{Skill, SkillError, SkillParameter, SkillScript, SkillSummary};
 
 /// Name of the skill definition file
 const SKILL_FILENAME: &str = "SKILL.md";
@@ -243,15 +243,65 @@ fn parse_skill(content: &str, skill_path: &Path) -> Result<Skill> {
     // Parse parameters from "## Parameters" section
     let parameters = parse_parameters(content, &directory_name)?;
 
+    // Parse the bundled script from "## Script" section
+    let script = parse_script(content, &directory_name)?;
+
     Ok(Skill {
         name,
         description,
         parameters,
         content: content.to_string(),
         directory_name,
+        script,
     })
 }
 
+/// Parse the script declaration from the SKILL.md content
+///
+/// Expected format:
+/// ```markdown
+/// ## Script
+/// - `weather.py` (python): Fetches the forecast
+/// ```
+///
+/// The language may be `sh`/`shell`, `bash` or `python`.
+fn parse_script(content: &str, directory_name: &str) -> Result<Option<SkillScript>> {
+    let mut in_script = false;
+
+    for line in content.lines() {
+        if line.trim() == "## Script" {
+            in_script = true;
+            continue;
+        }
+        if in_script {
+            if line.starts_with("## ") {
+                break;
+            }
+            if !line.trim().starts_with("- `") {
+                continue;
+            }
+            // Same shape as a parameter line: - `path` (language): description
+            let Some(declared) = parse_parameter_line(line) else {
+                return Err(SkillError::InvalidFormat(
+                    directory_name.to_string(),
+                    format!("invalid script declaration: {}", line.trim()),
+                )
+                .into());
+            };
+            let interpreter =
+                SkillScript::interpreter_for(&declared.param_type).ok_or_else(|| {
+                    SkillError::InvalidFormat(
+                        directory_name.to_string(),
+                        format!("unsupported script language '{}'", declared.param_type),
+                    )
+                })?;
+            return Ok(Some(SkillScript::new(&declared.name, interpreter)));
+        }
+    }
+
+    Ok(None)
+}
+
 /// Parse parameters from the SKILL.md content
 ///
 /// Expected format:
@@ -712,6 +762,49 @@ No header here.
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_skill_with_script() {
+        let content = r#"# Skill: Weather
+
+## Description
+Get the forecast.
+
+## Parameters
+- `city` (string, required): The city name
+
+## Script
+- `weather.py` (python): Prints the forecast as JSON
+"#;
+
+        let temp_dir = TempDir::new().unwrap();
+        let skill_path = temp_dir.path().join("weather");
+
+        let skill = parse_skill(content, &skill_path).unwrap();
+        assert_eq!(
+            skill.script,
+            Some(SkillScript::new("weather.py", "python3"))
+        );
+        assert_eq!(skill.parameters.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_skill_with_unsupported_script_language() {
+        let content = r#"# Skill: Weather
+
+## Description
+Get the forecast.
+
+## Script
+- `weather.rb` (ruby): Prints the forecast
+"#;
+
+        let temp_dir = TempDir::new().unwrap();
+        let skill_path = temp_dir.path().join("weather");
+
+        let err = parse_skill(content, &skill_path).unwrap_err();
+        assert!(err.to_string().contains("unsupported script language"));
+    }
+
     #[test]
     fn test_parse_parameter_line() {
         let line = "- `city` (string, required): The city name";
@@ -1068,6 +1068,7 @@ Manual implementation
     }
 
     #[tokio::test]
+    #[ignore = "requires non-root: privileged users bypass file modes"]
     async fn test_read_skill_permission_denied() {
         use std::os::unix::fs::PermissionsExt;
 
@@ -6,6 +6,7 @@
 pub mod constants;
 pub mod loader;
 pub mod manager;
+pub mod selection;
 pub mod types;
 
 // Re-export commonly used types and functions
@@ -15,7 +16,8 @@ pub use loader::{
     load_all_skills, load_skill, skill_exists, skills_directory_exists,
 };
 pub use manager::{ManagedSkill, SkillManagerError, SkillMetadata, SkillsManager};
-pub use types::{Skill, SkillError, SkillParameter, SkillSummary};
+pub use selection::{DEFAULT_MAX_ACTIVE_SKILLS, SkillIndexEntry, select_skills};
+pub use types::{Skill, SkillError, SkillParameter, SkillScript, SkillSummary};
 
 use anyhow::Result;
 use std::path::Path;
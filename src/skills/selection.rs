@@ -0,0 +1,229 @@
+//! Skill selection for context injection
+//!
+//! Instead of injecting every SKILL.md into the system context, the context
+//! builder injects a compact index of skill names and descriptions, plus the full
+//! instructions of the few skills that match the current message. Skills can be
+//! tuned through their `## Metadata` section:
+//!
+//! ```markdown
+//! ## Metadata
+//! - **Always-on**: true
+//! - **Keywords**: forecast, rain, temperature
+//! ```
+//!
+//! Always-on skills are injected in full on every turn. Keywords add explicit
+//! triggers on top of the skill name and description.
+
+use std::collections::HashSet;
+
+use crate::utils::text::keywords;
+
+/// Default number of matching skills injected in full per turn
+pub const DEFAULT_MAX_ACTIVE_SKILLS: usize = 3;
+
+/// Description keywords a message must share with a skill when neither its
+/// name nor its explicit keywords are mentioned
+const MIN_DESCRIPTION_MATCHES: usize = 2;
+
+/// A skill as seen by the context builder
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillIndexEntry {
+    /// Directory name, as accepted by `read_skill`
+    pub name: String,
+    /// One-line description for the index
+    pub description: String,
+    /// Explicit trigger keywords from the metadata (lowercase)
+    pub keywords: Vec<String>,
+    /// Whether the skill is injected in full on every turn
+    pub always_on: bool,
+    /// Full SKILL.md content
+    pub content: String,
+}
+
+impl SkillIndexEntry {
+    /// Builds an index entry from a skill directory name and its SKILL.md content
+    ///
+    /// The description is the `## Description` section when present, otherwise
+    /// the first line of the file without its heading markers.
+    pub fn parse(name: &str, content: &str) -> Self {
+        let description = section_lines(content, "## Description")
+            .find(|line| !line.is_empty())
+            .or_else(|| content.lines().map(str::trim).find(|line| !line.is_empty()))
+            .map(|line| {
+                line.trim_start_matches('#')
+                    .trim()
+                    .trim_start_matches("Skill:")
+                    .trim()
+            })
+            .filter(|line| !line.is_empty())
+            .unwrap_or("(no description)")
+            .to_string();
+
+        let mut always_on = false;
+        let mut skill_keywords = Vec::new();
+        for line in section_lines(content, "## Metadata") {
+            let Some((key, value)) = parse_metadata_line(line) else {
+                continue;
+            };
+            match key.to_lowercase().as_str() {
+                "always-on" | "always on" | "always_on" => {
+                    always_on = matches!(value.to_lowercase().as_str(), "true" | "yes");
+                }
+                "keywords" => {
+                    skill_keywords = value
+                        .split(',')
+                        .map(|k| k.trim().to_lowercase())
+                        .filter(|k| !k.is_empty())
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            description,
+            keywords: skill_keywords,
+            always_on,
+            content: content.to_string(),
+        }
+    }
+
+    /// Scores the skill against the keywords of a message (0 means no match)
+    ///
+    /// Mentioning a word of the skill name or an explicit keyword is a match on
+    /// its own; description words only count once several of them are present.
+    pub fn relevance(&self, message_keywords: &HashSet<String>) -> usize {
+        let name_hits = self
+            .name
+            .split(['-', '_', ' '])
+            .map(str::to_lowercase)
+            .filter(|word| message_keywords.contains(word))
+            .count();
+        let keyword_hits = self
+            .keywords
+            .iter()
+            .filter(|keyword| message_keywords.contains(*keyword))
+            .count();
+        let description_hits = keywords(&self.description)
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .intersection(message_keywords)
+            .count();
+
+        if name_hits + keyword_hits == 0 && description_hits < MIN_DESCRIPTION_MATCHES {
+            return 0;
+        }
+        2 * (name_hits + keyword_hits) + description_hits
+    }
+}
+
+/// Selects the skills to inject in full for a message
+///
+/// Returns always-on skills first, then up to `max_matches` matching skills,
+/// best match first. Ties keep the order of `skills`.
+pub fn select_skills<'a>(
+    skills: &'a [SkillIndexEntry],
+    message: &str,
+    max_matches: usize,
+) -> Vec<&'a SkillIndexEntry> {
+    let message_keywords: HashSet<String> = keywords(message).into_iter().collect();
+
+    let mut matches: Vec<(usize, &SkillIndexEntry)> = skills
+        .iter()
+        .filter(|skill| !skill.always_on)
+        .map(|skill| (skill.relevance(&message_keywords), skill))
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    skills
+        .iter()
+        .filter(|skill| skill.always_on)
+        .chain(
+            matches
+                .into_iter()
+                .take(max_matches)
+                .map(|(_, skill)| skill),
+        )
+        .collect()
+}
+
+/// Iterates over the trimmed lines of a `## ` section, excluding its header
+fn section_lines<'a>(content: &'a str, header: &'a str) -> impl Iterator<Item = &'a str> {
+    content
+        .lines()
+        .map(str::trim)
+        .skip_while(move |line| *line != header)
+        .skip(1)
+        .take_while(|line| !line.starts_with("## "))
+}
+
+/// Parses a `- **Key**: value` metadata line
+fn parse_metadata_line(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix("- ")?.trim();
+    let (key, value) = rest.split_once(':')?;
+    let key = key.trim().trim_matches('*').trim();
+    let value = value.trim().trim_start_matches("**").trim();
+    Some((key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEATHER_SKILL: &str = "# Skill: weather\n\n## Description\nGet weather forecasts for a city\n\n## Implementation\nCall the API.\n\n## Metadata\n- **Created**: 2026-02-16T10:00:00Z\n- **Keywords**: rain, umbrella\n";
+
+    #[test]
+    fn test_parse_description_and_metadata() {
+        let entry = SkillIndexEntry::parse("weather", WEATHER_SKILL);
+        assert_eq!(entry.description, "Get weather forecasts for a city");
+        assert_eq!(entry.keywords, vec!["rain", "umbrella"]);
+        assert!(!entry.always_on);
+
+        let entry = SkillIndexEntry::parse(
+            "style",
+            "# House Style\nAlways answer in French.\n\n## Metadata\n- **Always-on**: true\n",
+        );
+        assert_eq!(entry.description, "House Style");
+        assert!(entry.always_on);
+    }
+
+    #[test]
+    fn test_select_skills_by_name_and_keywords() {
+        let skills = vec![
+            SkillIndexEntry::parse("weather", WEATHER_SKILL),
+            SkillIndexEntry::parse("git_helper", "# Git helper\nCommit and push changes"),
+        ];
+
+        let selected = select_skills(&skills, "Do I need an umbrella today?", 3);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "weather");
+
+        let selected = select_skills(&skills, "Run git status", 3);
+        assert_eq!(selected[0].name, "git_helper");
+
+        assert!(select_skills(&skills, "Hello there", 3).is_empty());
+    }
+
+    #[test]
+    fn test_select_skills_always_on_and_limit() {
+        let skills = vec![
+            SkillIndexEntry::parse("alpha", "# Alpha\nalpha tasks"),
+            SkillIndexEntry::parse("beta", "# Beta\nbeta tasks"),
+            SkillIndexEntry::parse("style", "# Style\n\n## Metadata\n- **Always-on**: yes\n"),
+        ];
+
+        let selected = select_skills(&skills, "alpha and beta", 1);
+        let names: Vec<&str> = selected.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["style", "alpha"]);
+    }
+
+    #[test]
+    fn test_description_needs_several_matches() {
+        let skills = vec![SkillIndexEntry::parse("notes", WEATHER_SKILL)];
+
+        assert!(select_skills(&skills, "forecasts please", 3).is_empty());
+        assert_eq!(select_skills(&skills, "city weather forecasts", 3).len(), 1);
+    }
+}
@@ -55,6 +55,35 @@ impl SkillParameter {
     }
 }
 
+/// A script bundled in a skill directory and declared in its `## Script` section
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillScript {
+    /// Path of the script relative to the skill directory
+    pub path: String,
+    /// Program that runs the script (sh, bash or python3)
+    pub interpreter: String,
+}
+
+impl SkillScript {
+    /// Create a new skill script
+    pub fn new(path: &str, interpreter: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            interpreter: interpreter.to_string(),
+        }
+    }
+
+    /// Map the language named in SKILL.md to the program that runs it
+    pub fn interpreter_for(language: &str) -> Option<&'static str> {
+        match language.trim().to_lowercase().as_str() {
+            "sh" | "shell" => Some("sh"),
+            "bash" => Some("bash"),
+            "python" | "python3" => Some("python3"),
+            _ => None,
+        }
+    }
+}
+
 /// Represents a skill package
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Skill {
@@ -68,6 +97,9 @@ pub struct Skill {
     pub content: String,
     /// Directory name (may differ from skill name)
     pub directory_name: String,
+    /// Script run by the `run_skill` tool, if the skill bundles one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<SkillScript>,
 }
 
 impl Skill {
@@ -85,9 +117,16 @@ impl Skill {
             parameters,
             content: content.to_string(),
             directory_name: directory_name.to_string(),
+            script: None,
         }
     }
 
+    /// Attach a bundled script to the skill
+    pub fn with_script(mut self, script: SkillScript) -> Self {
+        self.script = Some(script);
+        self
+    }
+
     /// Check if the skill has valid required fields
     pub fn is_valid(&self) -> bool {
         !self.name.is_empty() && !self.description.is_empty()
@@ -115,6 +154,13 @@ impl Skill {
             context.push('\n');
         }
 
+        if self.script.is_some() {
+            context.push_str(&format!(
+                "Run with the `run_skill` tool (skill: \"{}\").\n\n",
+                self.name
+            ));
+        }
+
         context
     }
 }
@@ -213,6 +259,25 @@ mod tests {
         assert!(!context.contains("Parameters:"));
     }
 
+    #[test]
+    fn test_skill_context_string_with_script() {
+        let skill = Skill::new("Weather", "Get weather info", vec![], "", "weather")
+            .with_script(SkillScript::new("weather.py", "python3"));
+        let context = skill.to_context_string();
+        assert!(context.contains("run_skill"));
+
+        let plain = Skill::new("Simple", "Simple skill", vec![], "", "simple");
+        assert!(!plain.to_context_string().contains("run_skill"));
+    }
+
+    #[test]
+    fn test_skill_script_interpreter_for() {
+        assert_eq!(SkillScript::interpreter_for("shell"), Some("sh"));
+        assert_eq!(SkillScript::interpreter_for("Python"), Some("python3"));
+        assert_eq!(SkillScript::interpreter_for("bash"), Some("bash"));
+        assert_eq!(SkillScript::interpreter_for("ruby"), None);
+    }
+
     #[test]
     fn test_skill_summary_new() {
         let summary = SkillSummary::new("Test", "Test desc", true, "test");
@@ -5,6 +5,7 @@ pub mod error;
 pub mod fs;
 pub mod paths;
 pub mod security;
+pub mod text;
 
 // Re-export commonly used items
 pub use circuit_breaker::{CircuitBreaker, CircuitState};
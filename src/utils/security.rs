@@ -2,9 +2,12 @@
 //!
 //! Provides security-related functionality including:
 //! - Whitelist checking for Telegram user IDs
-//! - Exec command blacklist validation
+//! - Command checks against the default command policy
 
 use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use crate::agent::tools::policy::{CommandPolicy, PolicyScope};
 
 /// Special value that represents "allow all users" wildcard
 const ALLOW_ALL_WILDCARD: i64 = -1;
@@ -93,21 +96,18 @@ impl WhitelistChecker {
     }
 }
 
-/// Blacklisted commands that cannot be executed via exec tool (NFR-S4).
-const BLACKLISTED_COMMANDS: &[&str] = &[
-    "rm", "sudo", "dd", "mkfs", "shutdown", "reboot", "passwd", "visudo",
-];
+/// The built-in command policy, used when no config is available (NFR-S4).
+static DEFAULT_COMMAND_POLICY: LazyLock<CommandPolicy> = LazyLock::new(CommandPolicy::default);
 
-/// Check if a command is allowed to be executed.
+/// Check if a command (without arguments) is allowed by the default command policy.
 ///
 /// # Arguments
 /// * `command` - The command to check
 ///
 /// # Returns
-/// `true` if command is allowed, `false` if blacklisted
+/// `true` if command is allowed, `false` if denied
 pub fn is_command_allowed(command: &str) -> bool {
-    let command_lower = command.to_lowercase();
-    !BLACKLISTED_COMMANDS.contains(&command_lower.as_str())
+    DEFAULT_COMMAND_POLICY.is_allowed(command, &[], &PolicyScope::default())
 }
 
 #[cfg(test)]
@@ -0,0 +1,55 @@
+//! Text helpers for matching workspace content against conversation messages
+
+/// Common words ignored when matching content against a conversation message
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "your", "all", "any", "can", "had", "has",
+    "have", "her", "was", "one", "our", "out", "his", "how", "its", "who", "did", "does", "get",
+    "got", "let", "she", "too", "use", "this", "that", "with", "from", "they", "them", "then",
+    "than", "what", "when", "where", "which", "will", "would", "could", "should", "about", "there",
+    "their", "these", "those", "into", "just", "like", "some", "been", "were", "also", "please",
+    "thanks",
+];
+
+/// Minimum length (in characters) of a keyword
+const MIN_KEYWORD_CHARS: usize = 3;
+
+/// Splits text into lowercase words, trimming surrounding punctuation
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split_whitespace()
+        .map(|s| s.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Extracts the meaningful words of a message
+///
+/// Stop words and words shorter than three characters are dropped.
+pub fn keywords(text: &str) -> Vec<String> {
+    tokenize(text)
+        .into_iter()
+        .filter(|t| t.chars().count() >= MIN_KEYWORD_CHARS && !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_strips_punctuation() {
+        assert_eq!(
+            tokenize("Hello, World! it's"),
+            vec!["hello", "world", "it's"]
+        );
+        assert!(tokenize("  \n ").is_empty());
+    }
+
+    #[test]
+    fn test_keywords_drop_stop_words_and_short_words() {
+        assert_eq!(
+            keywords("What is the weather in Paris?"),
+            vec!["weather", "paris"]
+        );
+    }
+}
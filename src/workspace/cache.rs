@@ -0,0 +1,228 @@
+//! In-memory cache for workspace files
+//!
+//! The context builder reads the same markdown files on every turn. This cache
+//! keeps their content in memory and only re-reads a file when its modification
+//! time or size changes, which turns each read into a single `stat` call. That
+//! matters on slow storage such as the SD card of a Raspberry Pi.
+//!
+//! Size is compared alongside mtime because some filesystems only store mtime
+//! with a one-second resolution.
+//!
+//! Callers that parse a file (daily notes, SKILL.md) can cache the parsed value
+//! too with [`WorkspaceFileCache::read_parsed`]; it is dropped together with the
+//! file content whenever the file changes.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use tokio::fs;
+
+/// A cached file with the metadata it was read at
+#[derive(Debug, Clone)]
+struct CachedFile {
+    modified: SystemTime,
+    len: u64,
+    content: Arc<str>,
+    /// Values parsed from `content`, one per parsed type
+    parsed: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+/// Thread-safe cache of file contents keyed by path, invalidated by mtime and size
+#[derive(Debug, Default)]
+pub struct WorkspaceFileCache {
+    entries: Mutex<HashMap<PathBuf, CachedFile>>,
+}
+
+impl WorkspaceFileCache {
+    /// Creates an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a file, serving it from memory if it has not changed on disk
+    ///
+    /// Errors (including a missing file) evict any cached entry for the path.
+    pub async fn read(&self, path: &Path) -> io::Result<Arc<str>> {
+        let metadata = match fs::metadata(path).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                self.evict(path);
+                return Err(e);
+            }
+        };
+        let modified = metadata.modified()?;
+        let len = metadata.len();
+
+        let cached = self
+            .lock()
+            .get(path)
+            .filter(|cached| cached.modified == modified && cached.len == len)
+            .map(|cached| Arc::clone(&cached.content));
+        if let Some(content) = cached {
+            return Ok(content);
+        }
+
+        let content: Arc<str> = match fs::read_to_string(path).await {
+            Ok(content) => content.into(),
+            Err(e) => {
+                self.evict(path);
+                return Err(e);
+            }
+        };
+        tracing::trace!(path = %path.display(), "Workspace file cache miss");
+
+        self.lock().insert(
+            path.to_path_buf(),
+            CachedFile {
+                modified,
+                len,
+                content: Arc::clone(&content),
+                parsed: HashMap::new(),
+            },
+        );
+        Ok(content)
+    }
+
+    /// Reads a file and parses it, serving both from memory if it has not
+    /// changed on disk
+    ///
+    /// The parsed value is cached per type `T` and dropped with the content
+    /// when the file changes, so `parse` runs once per version of the file.
+    pub async fn read_parsed<T, F>(&self, path: &Path, parse: F) -> io::Result<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(&str) -> T,
+    {
+        let content = self.read(path).await?;
+
+        let cached = self
+            .lock()
+            .get(path)
+            .filter(|cached| Arc::ptr_eq(&cached.content, &content))
+            .and_then(|cached| cached.parsed.get(&TypeId::of::<T>()).cloned())
+            .and_then(|value| value.downcast::<T>().ok());
+        if let Some(value) = cached {
+            return Ok(value);
+        }
+
+        let value = Arc::new(parse(&content));
+        // Only attach the value to the version of the file it was parsed from
+        if let Some(cached) = self
+            .lock()
+            .get_mut(path)
+            .filter(|cached| Arc::ptr_eq(&cached.content, &content))
+        {
+            let parsed: Arc<dyn Any + Send + Sync> = value.clone();
+            cached.parsed.insert(TypeId::of::<T>(), parsed);
+        }
+        Ok(value)
+    }
+
+    /// Removes a path from the cache
+    pub fn evict(&self, path: &Path) {
+        self.lock().remove(path);
+    }
+
+    /// Drops every cached entry
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the number of cached files
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no files are cached
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, CachedFile>> {
+        // A poisoned lock only means another reader panicked; the map is still valid
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_read_caches_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("SOUL.md");
+        fs::write(&path, "soul").await.unwrap();
+
+        let cache = WorkspaceFileCache::new();
+        let first = cache.read(&path).await.unwrap();
+        let second = cache.read(&path).await.unwrap();
+
+        assert_eq!(&*first, "soul");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_read_invalidates_on_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("SOUL.md");
+        fs::write(&path, "v1").await.unwrap();
+
+        let cache = WorkspaceFileCache::new();
+        assert_eq!(&*cache.read(&path).await.unwrap(), "v1");
+
+        // Same length, so only the mtime tells the versions apart
+        fs::write(&path, "v2").await.unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(&*cache.read(&path).await.unwrap(), "v2");
+    }
+
+    #[tokio::test]
+    async fn test_read_parsed_reparses_only_on_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("SKILL.md");
+        fs::write(&path, "one two").await.unwrap();
+
+        let cache = WorkspaceFileCache::new();
+        let parses = std::sync::atomic::AtomicUsize::new(0);
+        let count_words = |content: &str| {
+            parses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            content.split_whitespace().count()
+        };
+
+        assert_eq!(*cache.read_parsed(&path, count_words).await.unwrap(), 2);
+        assert_eq!(*cache.read_parsed(&path, count_words).await.unwrap(), 2);
+        assert_eq!(parses.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        fs::write(&path, "one two three").await.unwrap();
+        assert_eq!(*cache.read_parsed(&path, count_words).await.unwrap(), 3);
+        assert_eq!(parses.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_missing_file_evicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("TOOLS.md");
+        fs::write(&path, "tools").await.unwrap();
+
+        let cache = WorkspaceFileCache::new();
+        cache.read(&path).await.unwrap();
+        fs::remove_file(&path).await.unwrap();
+
+        let err = cache.read(&path).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(cache.is_empty());
+    }
+}
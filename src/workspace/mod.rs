@@ -3,13 +3,18 @@
 //! This module handles the creation, management, and loading of the workspace structure
 //! including all markdown configuration files (SOUL.md, AGENTS.md, USER.md, TOOLS.md, HEARTBEAT.md).
 
+pub mod cache;
 pub mod templates;
+pub mod variables;
 
 use anyhow::Result;
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+pub use cache::WorkspaceFileCache;
+pub use variables::TemplateVariables;
+
 #[derive(Error, Debug)]
 pub enum WorkspaceError {
     #[error("Workspace directory does not exist: {0:?}")]
@@ -62,29 +62,33 @@ pub const DEFAULT_AGENTS: &str = r#"# Agent Guidelines
 - **read**: Read files from the workspace
 - **write**: Write or modify files
 - **list**: List directory contents
+- **edit / append / insert_at_line**: Make targeted changes without rewriting whole files
+- **delete / move / mkdir**: Manage files and directories
+- **glob / grep**: Find files by pattern and search their contents
 
 ### Execution Tools
 - **exec**: Execute shell commands (with safety restrictions)
-- **spawn**: Run background tasks
+- **spawn**: Run and manage background processes
+- **shell**: Keep an interactive shell open across calls (cd, virtualenvs, REPLs)
 
 ### Communication Tools
 - **message**: Send messages through configured channels
 - **web**: Fetch content from URLs
 
 ### Memory Tools
-- **read_memory**: Access stored memories and context
 - **write_memory**: Store new information
-- **recent**: Retrieve recent memories
-- **rank**: Find relevant memories by query
+- **manage_memory**: Search, read back, correct or forget memories
 
 ### Skill Tools
 - **list_skills**: View available skill packages
 - **read_skill**: Load a specific skill
 - **create_skill**: Package new functionality as a skill
 - **delete_skill**: Remove unused skills
+- **run_skill**: Run the script bundled with a skill
 
 ### Scheduling Tools
 - **cron**: Schedule future tasks (one-time or recurring)
+- **feeds**: Follow RSS/Atom feeds and send periodic digests
 
 ## Safety Guidelines
 - Never execute destructive operations without confirmation
@@ -215,19 +219,48 @@ Execute a shell command with safety restrictions.
 ```
 
 ### spawn
-Launch a background task.
+Launch and manage background processes. Output is captured to log files under `processes/`.
 
 **Parameters:**
-- `command` (string, required): Command to run in background
-- `name` (string, optional): Task identifier
+- `action` (string, optional): spawn (default), list, status, tail_output or kill
+- `command` (string): Command to run in background (spawn)
+- `args` (array): Command arguments (spawn)
+- `notify` (boolean, optional): Message this chat when the process finishes (spawn)
+- `pid` (integer): Process ID (status, tail_output, kill)
+- `lines` (integer, optional): Output lines to return (tail_output, default: 50)
 
 **Example:**
 ```json
 {
   "tool": "spawn",
   "params": {
-    "command": "cargo build --release",
-    "name": "release-build"
+    "command": "cargo",
+    "args": ["build", "--release"],
+    "notify": true
+  }
+}
+```
+
+### shell
+Open a persistent shell session whose state (directory, environment) survives between calls.
+
+**Parameters:**
+- `operation` (string, required): open, send, read, close or list
+- `session` (string): Session name
+- `input` (string, optional): Text to type (send)
+- `key` (string, optional): Control key such as ctrl-c (send)
+- `wait_for` (string, optional): Regex to wait for in the output
+- `timeout` (integer, optional): Maximum wait in seconds (default: 10)
+
+**Example:**
+```json
+{
+  "tool": "shell",
+  "params": {
+    "operation": "send",
+    "session": "build",
+    "input": "cargo test",
+    "wait_for": "test result"
   }
 }
 ```
@@ -254,97 +287,123 @@ Send a message through the configured channel (Telegram, etc.).
 ```
 
 ### web
-Fetch content from a URL.
+Fetch content from a URL. HTML pages are reduced to their main content as Markdown, with links listed separately.
 
 **Parameters:**
 - `url` (string, required): URL to fetch
-- `method` (string, optional): HTTP method (default: GET)
-- `headers` (object, optional): Custom headers
+- `page` (integer, optional): Page of long content to return, starting at 1
+- `offset` (integer, optional): Character offset to read from (use `next_offset` from the previous call)
+- `method` (string, optional): GET, POST, PUT, PATCH or DELETE (default: GET)
+- `headers` (object, optional): Extra request headers
+- `json` / `form` (optional): Request body, sent as JSON or as form fields
+- `credential` (string, optional): Name of a configured credential; secrets never appear in tool calls
+- `max_age` (integer, optional): Accept a cached copy up to this many seconds old; 0 always checks with the server. The result's `cache_hit` tells whether the cache answered
 
 **Example:**
 ```json
 {
   "tool": "web",
   "params": {
-    "url": "https://api.example.com/data"
+    "url": "http://homeassistant.local:8123/api/services/light/turn_on",
+    "method": "POST",
+    "json": {"entity_id": "light.kitchen"},
+    "credential": "home_assistant"
   }
 }
 ```
 
-## Memory Tools
+Hosts on loopback, private, link-local or multicast addresses are refused, including on redirects, unless listed in `tools.web.local_services` (as in the example above).
 
-### read_memory
-Read from memory (short-term, long-term, or daily notes).
+### web_search
+Search the web and get a list of results (title, url, snippet). Use it to find pages instead of guessing URLs, then read a result with `web`.
 
 **Parameters:**
-- `scope` (string, required): "short", "long", or "daily"
-- `date` (string, optional): Date for daily notes (YYYY-MM-DD)
-- `query` (string, optional): Search query for filtering
+- `query` (string, required): Search query
+- `max_results` (integer, optional): Number of results, 1-20 (default: 5)
+- `time_range` (string, optional): "day", "week", "month" or "year"
 
 **Example:**
 ```json
 {
-  "tool": "read_memory",
+  "tool": "web_search",
   "params": {
-    "scope": "daily",
-    "date": "2026-02-15"
+    "query": "tokio graceful shutdown",
+    "max_results": 3
   }
 }
 ```
 
-### write_memory
-Write to memory.
+### feeds
+Follow RSS and Atom feeds. Prefer it over scraping news pages with `web`. Subscriptions and the items already seen are kept in the workspace (`feeds/`).
 
 **Parameters:**
-- `scope` (string, required): "short", "long", or "daily"
-- `content` (string, required): Content to store
+- `action` (string, required): "subscribe", "unsubscribe", "list", "check" or "schedule_digest"
+- `url` (string): Feed URL (subscribe)
+- `feed` (string, optional): Feed name; limits check and schedule_digest to one feed
+- `limit` (integer, optional): New items per feed, 1-100 (check, default: 20)
+- `peek` (boolean, optional): Don't mark the returned items as seen (check)
+- `minutes` (integer): Interval between digests (schedule_digest)
 
 **Example:**
 ```json
 {
-  "tool": "write_memory",
+  "tool": "feeds",
   "params": {
-    "scope": "long",
-    "content": "User prefers dark mode interface"
+    "action": "schedule_digest",
+    "minutes": 360
   }
 }
 ```
 
-### recent
-Retrieve recent memories.
+A digest is a cron job running `miniclaw feeds check`; new items are sent to the chat that scheduled it, and runs with nothing new stay silent.
+
+## Memory Tools
+
+### write_memory
+Write to memory.
 
 **Parameters:**
-- `days` (integer, required): Number of days to look back
-- `scope` (string, optional): Memory scope to search
+- `content` (string, required): Content to store
+- `type` (string, optional): "long_term" (MEMORY.md, default) or "daily" (today's daily note)
 
 **Example:**
 ```json
 {
-  "tool": "recent",
+  "tool": "write_memory",
   "params": {
-    "days": 7
+    "content": "User prefers dark mode interface"
   }
 }
 ```
 
-### rank
-Find memories by relevance to a query.
+Long-term entries are returned with an `id` that `manage_memory` uses to update or delete them.
+
+### manage_memory
+Search, read back, correct or forget memories.
 
 **Parameters:**
-- `query` (string, required): Search query
-- `limit` (integer, optional): Maximum results (default: 10)
+- `action` (string, required): "search", "recent", "sections", "update" or "delete"
+- `query` (string): Keywords to look for (search)
+- `limit` (integer, optional): Number of results, 1-20 (search, default: 5)
+- `days` (integer, optional): Days of daily notes to read, 1-30 (recent, default: 7)
+- `date` (string, optional): List the entries of the MEMORY.md section for this date, YYYY-MM-DD (sections)
+- `id` (string): Id of a long-term entry (update, delete)
+- `content` (string): New content (update)
 
 **Example:**
 ```json
 {
-  "tool": "rank",
+  "tool": "manage_memory",
   "params": {
-    "query": "project requirements",
-    "limit": 5
+    "action": "update",
+    "id": "1a2b3c4d",
+    "content": "User moved to Lyon"
   }
 }
 ```
 
+Each entry in MEMORY.md keeps its id in a trailing `<!-- id:... -->` comment; leave it in place when editing the file by hand.
+
 ## Skill Tools
 
 ### list_skills
@@ -412,6 +471,34 @@ Delete a skill.
 }
 ```
 
+### run_skill
+Run the script bundled with a skill. The skill's SKILL.md declares the script in a
+`## Script` section, written like a parameter line with the language as its type
+(shell, bash or python):
+
+```markdown
+## Script
+- `weather.py` (python): Prints the forecast
+```
+
+Arguments are checked against the skill's parameters and passed as `--name value`
+pairs; the script runs sandboxed under the exec command policy.
+
+**Parameters:**
+- `skill` (string, required): Directory name of the skill
+- `arguments` (object, optional): Values for the skill's parameters
+
+**Example:**
+```json
+{
+  "tool": "run_skill",
+  "params": {
+    "skill": "weather",
+    "arguments": {"city": "Berlin"}
+  }
+}
+```
+
 ## Scheduling Tools
 
 ### cron
@@ -421,6 +508,7 @@ Schedule a task for future execution.
 - `schedule` (string, required): Cron expression or special value ("@daily", "@hourly")
 - `command` (string, required): Command to execute
 - `one_time` (boolean, optional): If true, run once at scheduled time
+- `notify` (boolean, optional): Send the job's output (or its error) to this chat
 
 **Example - Recurring:**
 ```json
@@ -445,6 +533,13 @@ Schedule a task for future execution.
 }
 ```
 
+## MCP Tools
+
+Servers declared under `tools.mcp.servers` in the config add their own tools, named
+`<server>__<tool>` (e.g. `github__create_issue`). Their parameters and description come
+from the server; calls are forwarded to it and its text output is returned as is.
+The servers are started once by the gateway and shared by all agents.
+
 ## Tool Response Format
 
 All tools return a JSON response:
@@ -0,0 +1,190 @@
+//! Template variables for workspace markdown files
+//!
+//! Workspace files (SOUL.md, AGENTS.md, USER.md, TOOLS.md, skills) may contain
+//! `{{name}}` placeholders that are rendered per turn from the inbound message:
+//!
+//! | Variable         | Value                                              |
+//! |------------------|----------------------------------------------------|
+//! | `{{date}}`       | Local date (`YYYY-MM-DD`)                          |
+//! | `{{time}}`       | Local time (`HH:MM`)                               |
+//! | `{{channel}}`    | Channel the message arrived on                     |
+//! | `{{chat_id}}`    | Chat identifier                                    |
+//! | `{{user.name}}`  | `username` (or `user_name`) message metadata       |
+//! | `{{user.id}}`    | `user_id` message metadata                         |
+//! | `{{hostname}}`   | Host name of the machine running miniclaw          |
+//! | `{{tools}}`      | Comma-separated names of the available tools       |
+//! | `{{meta.<key>}}` | Any other message metadata value                   |
+//!
+//! Unknown or unavailable variables are left untouched so that literal braces in
+//! documentation survive rendering.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::chat::InboundMessage;
+
+/// Opening delimiter of a template variable
+const OPEN: &str = "{{";
+/// Closing delimiter of a template variable
+const CLOSE: &str = "}}";
+
+/// Values available to workspace templates for a single turn
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVariables {
+    values: HashMap<String, String>,
+}
+
+impl TemplateVariables {
+    /// Creates an empty set of variables
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the variables for a turn from the inbound message and tool names
+    pub fn for_message(message: &InboundMessage, tool_names: &[String]) -> Self {
+        let now = chrono::Local::now();
+        let mut vars = Self::new()
+            .with("date", now.format("%Y-%m-%d").to_string())
+            .with("time", now.format("%H:%M").to_string())
+            .with("channel", message.channel.clone())
+            .with("chat_id", message.chat_id.clone());
+
+        if let Some(hostname) = sysinfo::System::host_name() {
+            vars.set("hostname", hostname);
+        }
+
+        if !tool_names.is_empty() {
+            let mut names = tool_names.to_vec();
+            names.sort();
+            vars.set("tools", names.join(", "));
+        }
+
+        for (key, value) in &message.metadata {
+            if let Some(value) = metadata_to_string(value) {
+                vars.set(format!("meta.{}", key), value);
+            }
+        }
+
+        let user_name = ["username", "user_name"]
+            .iter()
+            .find_map(|key| message.metadata.get(*key).and_then(metadata_to_string));
+        if let Some(name) = user_name {
+            vars.set("user.name", name);
+        }
+        if let Some(id) = message.metadata.get("user_id").and_then(metadata_to_string) {
+            vars.set("user.id", id);
+        }
+
+        vars
+    }
+
+    /// Sets a variable, returning self for chaining
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Sets a variable, replacing any previous value
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    /// Returns the value of a variable, if set
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Replaces every known `{{name}}` placeholder in `template`
+    ///
+    /// Whitespace inside the braces is ignored (`{{ date }}` works too).
+    pub fn render(&self, template: &str) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find(OPEN) {
+            let after_open = &rest[start + OPEN.len()..];
+            let Some(end) = after_open.find(CLOSE) else {
+                break;
+            };
+
+            output.push_str(&rest[..start]);
+            match self.get(after_open[..end].trim()) {
+                Some(value) => output.push_str(value),
+                None => output.push_str(&rest[start..start + OPEN.len() + end + CLOSE.len()]),
+            }
+            rest = &after_open[end + CLOSE.len()..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+}
+
+/// Converts a scalar metadata value to text; arrays, objects and null are skipped
+fn metadata_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_known_and_unknown_variables() {
+        let vars = TemplateVariables::new()
+            .with("channel", "telegram")
+            .with("user.name", "alice");
+
+        assert_eq!(
+            vars.render("Hi {{user.name}} on {{ channel }}, {{unknown}} stays"),
+            "Hi alice on telegram, {{unknown}} stays"
+        );
+    }
+
+    #[test]
+    fn test_render_unterminated_placeholder() {
+        let vars = TemplateVariables::new().with("date", "2026-01-01");
+        assert_eq!(vars.render("{{date}} and {{date"), "2026-01-01 and {{date");
+        assert_eq!(vars.render("no placeholders"), "no placeholders");
+    }
+
+    #[test]
+    fn test_for_message_uses_metadata() {
+        let message = InboundMessage::new("telegram", "42", "hi")
+            .with_metadata("username", json!("alice"))
+            .with_metadata("user_id", json!(1234))
+            .with_metadata("tags", json!(["a", "b"]));
+        let tools = vec!["web".to_string(), "exec".to_string()];
+
+        let vars = TemplateVariables::for_message(&message, &tools);
+
+        assert_eq!(vars.get("channel"), Some("telegram"));
+        assert_eq!(vars.get("chat_id"), Some("42"));
+        assert_eq!(vars.get("user.name"), Some("alice"));
+        assert_eq!(vars.get("user.id"), Some("1234"));
+        assert_eq!(vars.get("meta.username"), Some("alice"));
+        assert_eq!(vars.get("meta.tags"), None);
+        assert_eq!(vars.get("tools"), Some("exec, web"));
+        assert_eq!(
+            vars.get("date"),
+            Some(chrono::Local::now().format("%Y-%m-%d").to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn test_for_message_without_user_metadata() {
+        let message = InboundMessage::new("cli", "1", "hi");
+        let vars = TemplateVariables::for_message(&message, &[]);
+
+        assert_eq!(vars.get("user.name"), None);
+        assert_eq!(vars.get("tools"), None);
+        assert_eq!(vars.render("{{user.name}}"), "{{user.name}}");
+    }
+}
@@ -77,19 +77,18 @@ async fn test_end_to_end_message_flow() {
     let llm_provider: Arc<dyn miniclaw::providers::LlmProvider> = Arc::new(MockLlmProvider);
     let context_builder: Arc<dyn ContextBuilder> = Arc::new(MockContextBuilder);
     let tool_registry = Arc::new(miniclaw::agent::tools::ToolRegistry::new());
-    let session_manager = Arc::new(tokio::sync::RwLock::new(SessionManager::new(
-        std::env::temp_dir(),
-    )));
+    let session_manager = Arc::new(SessionManager::new(std::env::temp_dir()));
 
-    let agent_loop = AgentLoop::with_model_and_receiver(
+    let agent_loop = AgentLoop::builder(
         Arc::clone(&chat_hub),
         llm_provider,
         context_builder,
         tool_registry,
         session_manager,
-        "test-model",
-        agent_rx,
-    );
+    )
+    .with_model("test-model")
+    .with_inbound_receiver(agent_rx)
+    .build();
 
     // Spawn AgentLoop
     let agent_handle = tokio::spawn(async move {
@@ -136,7 +135,7 @@ async fn test_allow_all_wildcard_integration() {
 async fn test_chat_hub_agent_channel_buffer() {
     // Test that messages are forwarded correctly even with buffer limits
     let mut chat_hub = ChatHub::new();
-    let (agent_tx, mut agent_rx) = mpsc::channel(5); // Small buffer for testing
+    let (agent_tx, _agent_rx) = mpsc::channel(5); // Small buffer for testing
     chat_hub.register_agent_sender(agent_tx);
 
     let chat_hub = Arc::new(chat_hub);
@@ -175,7 +175,7 @@ fn test_help_goes_to_stdout() {
 /// with a non-zero code and print an actionable error message to stderr.
 #[test]
 fn test_agent_without_provider_exits_nonzero() {
-    use assert_cmd::Command as AssertCommand;
+    use assert_cmd::cargo::cargo_bin_cmd;
 
     // Point config to a temp dir that has no provider configured so the
     // binary cannot find an LLM provider. The important thing is we get a
@@ -186,8 +186,7 @@ fn test_agent_without_provider_exits_nonzero() {
     // Write a minimal config with no provider_config
     std::fs::write(&config_path, r#"{"default_channel":"cli"}"#).unwrap();
 
-    let output = AssertCommand::cargo_bin("miniclaw")
-        .unwrap()
+    let output = cargo_bin_cmd!("miniclaw")
         .args(["--config", config_path.to_str().unwrap(), "agent"])
         .output()
         .unwrap();
@@ -210,10 +209,9 @@ fn test_agent_without_provider_exits_nonzero() {
 /// to stderr and exit with a non-zero code.
 #[test]
 fn test_invalid_config_path_exits_nonzero() {
-    use assert_cmd::Command as AssertCommand;
+    use assert_cmd::cargo::cargo_bin_cmd;
 
-    let output = AssertCommand::cargo_bin("miniclaw")
-        .unwrap()
+    let output = cargo_bin_cmd!("miniclaw")
         .args(["--config", "/nonexistent/path/to/config.json", "version"])
         .output()
         .unwrap();
@@ -233,3 +231,52 @@ fn test_invalid_config_path_exits_nonzero() {
         "stderr should contain config error message, got: {stderr}"
     );
 }
+
+/// `context` cannot list MCP tools without starting the servers, so it says
+/// which servers it left out of `{{tools}}`.
+#[test]
+fn test_context_notes_omitted_mcp_tools() {
+    use assert_cmd::cargo::cargo_bin_cmd;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let workspace = temp_dir.path().join("workspace");
+    std::fs::create_dir(&workspace).unwrap();
+    let config_path = temp_dir.path().join("config.json");
+    let config = serde_json::json!({
+        "agents": [{"name": "main", "workspace": workspace}],
+        "tools": {"mcp": {"servers": [{"name": "docs", "url": "https://docs.example.com/mcp"}]}}
+    });
+    std::fs::write(&config_path, config.to_string()).unwrap();
+    // Config files readable by others are skipped
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&config_path, std::fs::Permissions::from_mode(0o600)).unwrap();
+    }
+
+    let output = cargo_bin_cmd!("miniclaw")
+        .args([
+            "--config",
+            config_path.to_str().unwrap(),
+            "context",
+            "--channel",
+            "cli",
+            "--chat-id",
+            "1",
+            "-m",
+            "hi",
+        ])
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "context failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("leaves out the tools of the MCP servers (docs)"),
+        "stdout: {stdout}"
+    );
+}
@@ -108,6 +108,9 @@ async fn test_gateway_initialization_with_valid_config() {
         spawn_log_output: false,
         provider_type: None,
         provider_config: None,
+        agent: miniclaw::config::AgentConfig::default(),
+        tools: miniclaw::config::ToolsConfig::default(),
+        agents: Vec::new(),
         default_channel: "cli".to_string(),
     };
 
@@ -490,10 +493,9 @@ fn test_agent_loop_integration() {
 /// `--help` correctly (does not start the daemon).
 #[test]
 fn test_gateway_help_smoke() {
-    use assert_cmd::Command;
+    use assert_cmd::cargo::cargo_bin_cmd;
 
-    let output = Command::cargo_bin("miniclaw")
-        .unwrap()
+    let output = cargo_bin_cmd!("miniclaw")
         .args(["gateway", "--help"])
         .output()
         .unwrap();
@@ -1,8 +1,9 @@
 use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 
 fn cmd() -> Command {
-    Command::cargo_bin("miniclaw").unwrap()
+    cargo_bin_cmd!("miniclaw")
 }
 
 #[test]
@@ -2,13 +2,13 @@
 //!
 //! These tests verify the CLI behavior for listing available models.
 
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 
 /// Test that `models --help` shows the help message
 #[test]
 fn test_models_help() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("models").arg("--help");
 
     cmd.assert()
@@ -31,7 +31,7 @@ fn test_models_without_provider() {
     )
     .unwrap();
 
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--config").arg(&config_path).arg("models");
 
     cmd.assert()
@@ -42,7 +42,7 @@ fn test_models_without_provider() {
 /// Test that `models` command exists and accepts the command
 #[test]
 fn test_models_command_exists() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("models").arg("--help");
 
     // Should succeed (exit code 0) and show help
@@ -2,7 +2,7 @@
 //!
 //! These tests verify CLI integration and end-to-end onboarding flows.
 
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
@@ -10,7 +10,7 @@ use tempfile::TempDir;
 /// Test that onboard command shows help correctly
 #[test]
 fn test_onboard_help_shows_options() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("onboard").arg("--help");
 
     cmd.assert()
@@ -26,7 +26,7 @@ fn test_onboard_yes_creates_workspace() {
     let temp_dir = TempDir::new().unwrap();
     let workspace_path = temp_dir.path().join("test_miniclaw");
 
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("onboard")
         .arg("--yes")
         .arg("--path")
@@ -42,7 +42,7 @@ fn test_onboard_yes_creates_workspace() {
 /// Test onboard fails with relative path
 #[test]
 fn test_onboard_rejects_relative_path() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("onboard")
         .arg("--yes")
         .arg("--path")
@@ -63,7 +63,7 @@ fn test_onboard_warns_existing_workspace() {
     fs::create_dir_all(&workspace_path).unwrap();
 
     // Then try to onboard again (with --yes to skip prompt)
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("onboard")
         .arg("--yes")
         .arg("--path")
@@ -79,7 +79,7 @@ fn test_onboard_accepts_verbose_flag() {
     let temp_dir = TempDir::new().unwrap();
     let workspace_path = temp_dir.path().join("test_miniclaw");
 
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose")
         .arg("onboard")
         .arg("--yes")
@@ -95,7 +95,7 @@ fn test_onboard_creates_workspace_structure() {
     let temp_dir = TempDir::new().unwrap();
     let workspace_path = temp_dir.path().join("test_miniclaw");
 
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("onboard")
         .arg("--yes")
         .arg("--path")
@@ -114,7 +114,7 @@ fn test_onboard_custom_absolute_path() {
     let temp_dir = TempDir::new().unwrap();
     let workspace_path = temp_dir.path().join("custom_workspace");
 
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("onboard")
         .arg("--yes")
         .arg("-p")
@@ -132,7 +132,7 @@ fn test_onboard_with_global_flags() {
     let temp_dir = TempDir::new().unwrap();
     let workspace_path = temp_dir.path().join("test_miniclaw");
 
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose")
         .arg("onboard")
         .arg("--yes")
@@ -7,13 +7,12 @@
 //! 4. Verbose mode: DEBUG logs to stderr, output to stdout
 //! 5. Piping: only stdout is piped, stderr shown on terminal
 
-use assert_cmd::prelude::*;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
-use std::process::Command;
 
 #[test]
 fn test_version_stdout_only() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("version");
 
     // AC#2: version string goes to stdout, no stderr output on success
@@ -25,7 +24,7 @@ fn test_version_stdout_only() {
 
 #[test]
 fn test_version_stderr_redirect_shows_output() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("version");
     // Simulate: miniclaw version 2>/dev/null
     // Should show version on stdout
@@ -37,7 +36,7 @@ fn test_version_stderr_redirect_shows_output() {
 
 #[test]
 fn test_unknown_command_stderr_output() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("invalid-command-that-does-not-exist");
 
     // AC#3: error message goes to stderr, non-zero exit code
@@ -48,7 +47,7 @@ fn test_unknown_command_stderr_output() {
 
 #[test]
 fn test_verbose_mode_stream_separation() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose").arg("version");
 
     // AC#4: DEBUG logs to stderr, version output to stdout
@@ -61,7 +60,7 @@ fn test_verbose_mode_stream_separation() {
 
 #[test]
 fn test_verbose_mode_no_log_leak_to_stdout() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose").arg("version");
 
     // Verify stdout contains only version, no log artifacts
@@ -81,7 +80,7 @@ fn test_verbose_mode_no_log_leak_to_stdout() {
 
 #[test]
 fn test_default_mode_no_stderr_output() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("version");
 
     // AC#2: no stderr output on success in default mode
@@ -93,7 +92,7 @@ fn test_default_mode_no_stderr_output() {
 
 #[test]
 fn test_piping_stdout_only() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("version");
 
     // AC#5: only stdout is piped (captured), stderr separate
@@ -110,7 +109,7 @@ fn test_piping_stdout_only() {
 
 #[test]
 fn test_version_with_model_info() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("version");
 
     // Version command should show version and optionally model
@@ -129,7 +128,7 @@ fn test_version_with_model_info() {
 
 #[test]
 fn test_help_stdout_only() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--help");
 
     // Help should go to stdout with no stderr
@@ -142,7 +141,7 @@ fn test_help_stdout_only() {
 
 #[test]
 fn test_error_exit_code() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("nonexistent-command");
 
     // AC#3: non-zero exit code for errors
@@ -151,7 +150,7 @@ fn test_error_exit_code() {
 
 #[test]
 fn test_invalid_flag_exit_code() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--invalid-flag-that-does-not-exist");
 
     // AC#3: exit code 2 for CLI argument errors
@@ -160,7 +159,7 @@ fn test_invalid_flag_exit_code() {
 
 #[test]
 fn test_verbose_logs_to_stderr_not_stdout() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.args(["--verbose", "version"]);
 
     let output = cmd.output().expect("Failed to execute command");
@@ -99,10 +99,10 @@ async fn test_tool_registration_and_execution_flow() {
     let tool1 = TestTool::new("multiply", "Multiplies input");
     let tool2 = TestTool::new("echo", "Echoes input");
 
-    registry.register(Box::new(tool1)).unwrap();
-    registry.register(Box::new(tool2)).unwrap();
+    registry.register(Box::new(tool1)).await.unwrap();
+    registry.register(Box::new(tool2)).await.unwrap();
 
-    assert_eq!(registry.len(), 2);
+    assert_eq!(registry.len().await, 2);
 
     // Execute first tool
     let mut args1 = HashMap::new();
@@ -124,22 +124,25 @@ async fn test_tool_registration_and_execution_flow() {
 }
 
 /// Integration test: Tool listing and discovery
-#[test]
-fn test_tool_discovery_via_listing() {
+#[tokio::test]
+async fn test_tool_discovery_via_listing() {
     let registry = ToolRegistry::new();
 
     registry
         .register(Box::new(TestTool::new("tool_a", "First tool")))
+        .await
         .unwrap();
     registry
         .register(Box::new(TestTool::new("tool_b", "Second tool")))
+        .await
         .unwrap();
     registry
         .register(Box::new(TestTool::new("tool_c", "Third tool")))
+        .await
         .unwrap();
 
     // Test list_tools()
-    let tools = registry.list_tools();
+    let tools = registry.list_tools().await;
     assert_eq!(tools.len(), 3);
 
     // Verify all tools are listed
@@ -149,15 +152,15 @@ fn test_tool_discovery_via_listing() {
     assert!(names.contains(&"tool_c".to_string()));
 
     // Test contains()
-    assert!(registry.contains("tool_a"));
-    assert!(registry.contains("tool_b"));
-    assert!(registry.contains("tool_c"));
-    assert!(!registry.contains("tool_d"));
+    assert!(registry.contains("tool_a").await);
+    assert!(registry.contains("tool_b").await);
+    assert!(registry.contains("tool_c").await);
+    assert!(!registry.contains("tool_d").await);
 }
 
 /// Integration test: Tool definitions for LLM
-#[test]
-fn test_tool_definitions_for_llm() {
+#[tokio::test]
+async fn test_tool_definitions_for_llm() {
     let registry = ToolRegistry::new();
 
     registry
@@ -165,10 +168,11 @@ fn test_tool_definitions_for_llm() {
             "calculator",
             "Performs calculations",
         )))
+        .await
         .unwrap();
 
     // Get JSON definitions
-    let definitions = registry.get_tool_definitions();
+    let definitions = registry.get_tool_definitions().await;
     assert_eq!(definitions.len(), 1);
 
     let def = &definitions[0];
@@ -182,7 +186,7 @@ fn test_tool_definitions_for_llm() {
     );
 
     // Get typed definitions
-    let typed_defs = registry.get_definitions();
+    let typed_defs = registry.get_definitions().await;
     assert_eq!(typed_defs.len(), 1);
     assert_eq!(typed_defs[0].name(), "calculator");
     assert_eq!(typed_defs[0].r#type, "function");
@@ -196,6 +200,7 @@ async fn test_tool_error_propagation() {
     // Register a failing tool
     registry
         .register(Box::new(TestTool::failing("failing_tool", "Always fails")))
+        .await
         .unwrap();
 
     // Try to execute it
@@ -234,18 +239,18 @@ async fn test_tool_not_found_error() {
 }
 
 /// Integration test: Tool registration conflict
-#[test]
-fn test_tool_registration_conflict() {
+#[tokio::test]
+async fn test_tool_registration_conflict() {
     let registry = ToolRegistry::new();
 
     let tool1 = TestTool::new("unique_tool", "First registration");
     let tool2 = TestTool::new("unique_tool", "Duplicate name");
 
     // First registration succeeds
-    registry.register(Box::new(tool1)).unwrap();
+    registry.register(Box::new(tool1)).await.unwrap();
 
     // Second registration fails with same name
-    let result = registry.register(Box::new(tool2));
+    let result = registry.register(Box::new(tool2)).await;
     assert!(result.is_err());
 
     match result.unwrap_err() {
@@ -264,18 +269,19 @@ async fn test_tool_unregistration() {
 
     registry
         .register(Box::new(TestTool::new("temp_tool", "Temporary tool")))
+        .await
         .unwrap();
-    assert_eq!(registry.len(), 1);
-    assert!(registry.contains("temp_tool"));
+    assert_eq!(registry.len().await, 1);
+    assert!(registry.contains("temp_tool").await);
 
     // Unregister the tool
-    let removed = registry.unregister("temp_tool");
+    let removed = registry.unregister("temp_tool").await;
     assert!(removed);
-    assert_eq!(registry.len(), 0);
-    assert!(!registry.contains("temp_tool"));
+    assert_eq!(registry.len().await, 0);
+    assert!(!registry.contains("temp_tool").await);
 
     // Trying to unregister again returns false
-    let removed_again = registry.unregister("temp_tool");
+    let removed_again = registry.unregister("temp_tool").await;
     assert!(!removed_again);
 
     // Trying to execute unregistered tool fails
@@ -286,36 +292,35 @@ async fn test_tool_unregistration() {
 }
 
 /// Integration test: Concurrent tool registration (thread safety)
-#[test]
-fn test_concurrent_tool_access() {
+#[tokio::test]
+async fn test_concurrent_tool_access() {
     use std::sync::Arc;
-    use std::thread;
 
     // ToolRegistry is now thread-safe with internal RwLock, no need for external Mutex
     let registry = Arc::new(ToolRegistry::new());
 
     let mut handles = vec![];
 
-    // Spawn multiple threads to register tools
+    // Spawn multiple tasks to register tools
     for i in 0..5 {
         let reg = Arc::clone(&registry);
-        let handle = thread::spawn(move || {
+        let handle = tokio::spawn(async move {
             let tool = TestTool::new(format!("thread_tool_{}", i), "Concurrent tool");
-            reg.register(Box::new(tool)).unwrap();
+            reg.register(Box::new(tool)).await.unwrap();
         });
         handles.push(handle);
     }
 
-    // Wait for all threads
+    // Wait for all tasks
     for handle in handles {
-        handle.join().unwrap();
+        handle.await.unwrap();
     }
 
     // Verify all tools were registered
-    assert_eq!(registry.len(), 5);
+    assert_eq!(registry.len().await, 5);
 
     for i in 0..5 {
-        assert!(registry.contains(&format!("thread_tool_{}", i)));
+        assert!(registry.contains(&format!("thread_tool_{}", i)).await);
     }
 }
 
@@ -350,12 +355,13 @@ async fn test_tool_execution_with_context() {
     }
 
     let registry = ToolRegistry::new();
-    registry.register(Box::new(ContextAwareTool)).unwrap();
+    registry.register(Box::new(ContextAwareTool)).await.unwrap();
 
     // Execute with context
     let ctx = ToolExecutionContext {
         channel: Some("telegram".to_string()),
         chat_id: Some("123456".to_string()),
+        user_id: None,
     };
 
     let result = registry
@@ -437,7 +443,7 @@ async fn test_complex_parameter_handling() {
     }
 
     let registry = ToolRegistry::new();
-    registry.register(Box::new(ComplexTool)).unwrap();
+    registry.register(Box::new(ComplexTool)).await.unwrap();
 
     let mut args = HashMap::new();
     args.insert("string_field".to_string(), json!("test"));
@@ -454,21 +460,21 @@ async fn test_complex_parameter_handling() {
 }
 
 /// Integration test: Empty registry behavior
-#[test]
-fn test_empty_registry_behavior() {
+#[tokio::test]
+async fn test_empty_registry_behavior() {
     let registry = ToolRegistry::new();
 
-    assert!(registry.is_empty());
-    assert_eq!(registry.len(), 0);
-    assert!(!registry.contains("anything"));
+    assert!(registry.is_empty().await);
+    assert_eq!(registry.len().await, 0);
+    assert!(!registry.contains("anything").await);
 
-    let tools = registry.list_tools();
+    let tools = registry.list_tools().await;
     assert!(tools.is_empty());
 
-    let definitions = registry.get_tool_definitions();
+    let definitions = registry.get_tool_definitions().await;
     assert!(definitions.is_empty());
 
-    let typed_defs = registry.get_definitions();
+    let typed_defs = registry.get_definitions().await;
     assert!(typed_defs.is_empty());
 }
 
@@ -507,6 +513,7 @@ async fn test_error_messages_are_clear() {
 
     registry
         .register(Box::new(TestTool::new("test", "Test tool")))
+        .await
         .unwrap();
 
     // Missing required parameter
@@ -529,6 +536,7 @@ async fn test_tool_result_formatting() {
 
     registry
         .register(Box::new(TestTool::new("formatter", "Formats results")))
+        .await
         .unwrap();
 
     let mut args = HashMap::new();
@@ -577,7 +585,7 @@ async fn test_tool_result_format_consistency() {
     }
 
     let registry = ToolRegistry::new();
-    registry.register(Box::new(JsonResultTool)).unwrap();
+    registry.register(Box::new(JsonResultTool)).await.unwrap();
 
     let result = registry
         .execute_tool(
@@ -597,22 +605,22 @@ async fn test_tool_result_format_consistency() {
 }
 
 /// Integration test: Verify WebTool is registered in oneshot mode
-#[test]
-fn test_web_tool_registration() {
+#[tokio::test]
+async fn test_web_tool_registration() {
     use miniclaw::agent::tools::{ToolRegistry, web::WebTool};
 
     let registry = ToolRegistry::new();
 
     // Register WebTool as done in oneshot.rs
     let web_tool = WebTool::new();
-    registry.register(Box::new(web_tool)).unwrap();
+    registry.register(Box::new(web_tool)).await.unwrap();
 
     // Verify tool is registered
-    assert!(registry.contains("web"));
-    assert_eq!(registry.len(), 1);
+    assert!(registry.contains("web").await);
+    assert_eq!(registry.len().await, 1);
 
     // Verify it appears in listings
-    let tools = registry.list_tools();
+    let tools = registry.list_tools().await;
     assert_eq!(tools.len(), 1);
 
     let (name, desc, _params) = &tools[0];
@@ -621,7 +629,7 @@ fn test_web_tool_registration() {
     assert!(desc.contains("HTTP/HTTPS only"));
 
     // Verify tool definition is correct
-    let definitions = registry.get_tool_definitions();
+    let definitions = registry.get_tool_definitions().await;
     assert_eq!(definitions.len(), 1);
     assert_eq!(definitions[0]["function"]["name"], "web");
     assert!(definitions[0]["function"]["parameters"]["properties"]["url"].is_object());
@@ -642,20 +650,20 @@ async fn test_spawn_tool_integration() {
 
     // Register SpawnTool as done in oneshot.rs
     let spawn_tool = SpawnTool::new(temp_dir.path().to_path_buf(), false).unwrap();
-    registry.register(Box::new(spawn_tool)).unwrap();
+    registry.register(Box::new(spawn_tool)).await.unwrap();
 
     // Verify tool is registered
-    assert!(registry.contains("spawn"));
-    assert_eq!(registry.len(), 1);
+    assert!(registry.contains("spawn").await);
+    assert_eq!(registry.len().await, 1);
 
     // Verify it appears in listings
-    let tools = registry.list_tools();
+    let tools = registry.list_tools().await;
     assert_eq!(tools.len(), 1);
 
     let (name, desc, _params) = &tools[0];
     assert_eq!(name, "spawn");
     assert!(desc.contains("background"));
-    assert!(desc.contains("blacklist"));
+    assert!(desc.contains("command policy"));
 
     // Execute spawn tool with a simple command
     let mut args = HashMap::new();
@@ -689,7 +697,7 @@ async fn test_spawn_tool_blacklist_integration() {
     let registry = ToolRegistry::new();
 
     let spawn_tool = SpawnTool::new(temp_dir.path().to_path_buf(), false).unwrap();
-    registry.register(Box::new(spawn_tool)).unwrap();
+    registry.register(Box::new(spawn_tool)).await.unwrap();
 
     // Try to execute blacklisted command
     let mut args = HashMap::new();
@@ -711,8 +719,8 @@ async fn test_spawn_tool_blacklist_integration() {
 }
 
 /// Integration test: Memory tool basic functionality
-#[tokio::test]
-async fn test_memory_tool_basic_functionality() {
+#[test]
+fn test_memory_tool_basic_functionality() {
     use miniclaw::agent::tools::memory::MemoryTool;
     use tempfile::tempdir;
 
@@ -1,10 +1,10 @@
 #![allow(deprecated)]
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 
 #[test]
 fn test_verbose_flag_enables_debug_logging() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose").arg("version");
 
     // In verbose mode, DEBUG logs go to stderr, version string to stdout
@@ -16,7 +16,7 @@ fn test_verbose_flag_enables_debug_logging() {
 
 #[test]
 fn test_short_verbose_flag_works() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("-v").arg("version");
 
     // -v should behave the same as --verbose
@@ -28,7 +28,7 @@ fn test_short_verbose_flag_works() {
 
 #[test]
 fn test_default_mode_shows_only_info() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("version");
 
     // Default mode should NOT show DEBUG logs in stdout or stderr
@@ -41,7 +41,7 @@ fn test_default_mode_shows_only_info() {
 
 #[test]
 fn test_log_format_includes_timestamp() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose").arg("version");
 
     // Log in stderr should contain timestamp in RFC 3339 format (contains T)
@@ -50,7 +50,7 @@ fn test_log_format_includes_timestamp() {
 
 #[test]
 fn test_log_format_includes_level() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose").arg("version");
 
     // Log in stderr should contain log level (DEBUG)
@@ -61,7 +61,7 @@ fn test_log_format_includes_level() {
 
 #[test]
 fn test_log_format_includes_module() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--verbose").arg("version");
 
     // Log in stderr should contain module path (miniclaw::)
@@ -72,7 +72,7 @@ fn test_log_format_includes_module() {
 
 #[test]
 fn test_help_shows_verbose_option() {
-    let mut cmd = Command::cargo_bin("miniclaw").unwrap();
+    let mut cmd = cargo_bin_cmd!("miniclaw");
     cmd.arg("--help");
 
     cmd.assert()
@@ -12,7 +12,7 @@ fn test_onboard_creates_workspace_directory() {
     let base_path = temp_dir.path().join("miniclaw_test");
 
     // Run onboard command with custom path and --yes flag
-    let output = Command::new("cargo")
+    let _output = Command::new("cargo")
         .args([
             "run",
             "--",